@@ -18,6 +18,9 @@ cmd-error-script-recv = Recv error: { $error }
 cmd-error-script-communication = Send/recv error: { $error }
 cmd-error-blocking-task = Blocking task failed: { $error }
 
+# Confirmations
+cmd-confirm-concurrency-adjustment = Max per folder ({ $per_folder }) × max active folders ({ $folders }) exceeds max concurrent downloads ({ $max })
+
 # Success messages
 cmd-success-scripts-reloaded = Scripts reloaded successfully
 cmd-success-config-reloaded = Config reloaded successfully
@@ -7,8 +7,12 @@ dialog-details = Details
 dialog-edit-field = Edit Field
 dialog-edit-label = Edit {$label}
 dialog-add-download = Add Download (Shift+Enter to expand [n-m] pattern)
+dialog-change-folder = Move to Folder (Tab to complete, ↑/↓ to choose, Enter to confirm, Esc to cancel)
 dialog-change-save-path = Change Save Path (Enter to confirm, Esc to cancel)
 dialog-confirm-delete = Confirm Delete
+dialog-confirm-concurrency = Adjust Concurrency Limits
+dialog-confirm-circuit-reset = Circuit Breaker Open
+dialog-confirm-large-download = Large Download
 dialog-switch-folder = 📁 Switch Folder (j/k to navigate, Enter to select)
 dialog-actions = Actions (j/k to navigate, Enter to select)
 dialog-folder-actions = Folder Actions
@@ -21,21 +25,42 @@ folder-menu-delete-all = Delete All
 folder-menu-clear-history = Clear History
 dialog-download-preview = 📋 Download Preview
 dialog-help = Help
+dialog-stats = Statistics
+dialog-script-errors = Script Errors
 dialog-settings = Settings
 dialog-folders = Folders
 dialog-folder-details = Folder Details
 
 # Input prompts
 prompt-url = URL:
+prompt-referer = Referer (optional):
 prompt-save-path = Save Path:
 prompt-value = Value:
 
+# Validation messages
+validation-no-folder-match = No folder matches '{ $input }'
+
 # Confirmation messages
 confirm-delete-single = Delete this download?
 confirm-delete-multiple = Delete {$count} downloads?
+confirm-folder-delete-all = Delete all { $count } downloads in '{ $folder }'?
+confirm-folder-delete-all-note = A single undo restores them all.
+confirm-clear-history = Clear all { $count } items from the history?
+confirm-clear-history-note = This cannot be undone.
+confirm-concurrency-proposed = Apply these values instead?
+confirm-concurrency-per-folder = Max per folder: { $from } → { $to }
+confirm-concurrency-folders = Max active folders: { $from } → { $to }
+confirm-circuit-reset-blocked = Downloads from { $key } are paused after repeated failures.
+confirm-circuit-reset-question = Reset the circuit and start the download?
+confirm-large-download-size = { $filename } is { $size }, over the confirmation threshold.
+confirm-large-download-question = Download it anyway?
 
 # Help text in dialogs
 help-edit-field = Enter: Edit value
 help-folder-edit-navigate = j/k: navigate | Enter: edit field | Esc: back to settings
 help-folder-list = Enter: edit folder | s: save config
 help-script-toggle = j/k: Navigate  Space/Enter: Toggle  r: Reload all
+
+# Download preview
+preview-label-headers = 📨 Request Headers:
+preview-over-confirm-threshold = ⚠ Larger than { $limit }; adding it confirms this size
@@ -12,6 +12,7 @@ help-key-ctrl-z = Ctrl+Z     - Undo last delete
 help-key-m = m          - Open context menu (actions)
 help-key-e = e          - Edit (change folder)
 help-key-r = r          - Retry failed download
+help-key-ctrl-r = Ctrl+R     - Retry all failed downloads
 help-key-shift-s = S          - Resume all paused downloads
 help-key-shift-p = P          - Pause all active downloads
 
@@ -31,6 +32,7 @@ help-key-ctrl-u = Ctrl+u     - Page up
 help-section-panes = Pane Switching:
 help-key-prev-pane = Shift+Tab  - Previous pane
 help-key-next-pane = Tab        - Next pane
+help-key-brackets = [ / ]      - Previous / next folder
 
 help-section-search = Search/Filter:
 help-key-slash = /          - Start search
@@ -39,7 +41,10 @@ help-key-esc-search = Esc        - Clear search/cancel
 help-section-ui = UI:
 help-key-question = ?          - Help screen
 help-key-x = x          - Settings
+help-key-t-shift = T          - Statistics
+help-key-e-shift = E          - Script errors
 help-key-i = i          - Toggle details panel
+help-key-b-shift = B          - Toggle bandwidth graph
 help-key-r-shift = R          - Refresh
 
 help-section-settings = Settings:
@@ -16,6 +16,7 @@ tree-completed-node = History
 
 # Download list columns
 column-sel = Sel
+column-id = ID
 column-status = Status
 column-filename = Filename
 column-size = Size
@@ -23,11 +24,21 @@ column-progress = Progress
 column-speed = Speed
 column-eta = ETA
 
+# Bandwidth graph
+bandwidth-graph-title = Throughput (last minute): {$current}, peak {$peak}
+
 # Status bar - Normal mode
 status-normal-folder = 📁 [{$folder}]
 status-normal-actions = ⏯ Space:toggle | ✓ v:select | ➕ a:add | 🗑 d:delete | 📋 m:menu
 status-normal-undo = Ctrl+Z: undo({$count})
+status-script-errors = ⚠ Script errors: {$count} (E)
+status-insecure-tls = ⚠ TLS verification off
+status-config-problems = ⚠ Config problems: {$count} (ggg config validate)
+status-schedule-paused = ⏸ Quiet hours until {$until}
+status-schedule-limited = ⏬ Limited to {$speed} until {$until}
 status-normal-right = 🔄 F:folder | ❓ ?:help | ❌ q:quit
+status-queue-eta = ⏱ All done in {$eta}
+status-queue-eta-partial = ⏱ All done in {$eta}+ (some sizes unknown)
 
 # Status bar - Other modes
 status-add-download = 📥 Enter URL and press Enter to add
@@ -43,15 +54,26 @@ status-confirm-delete = ⚠️  Confirm Delete
 status-context-menu = 📋 Actions Menu
 
 # Status bar - Right side hints
+status-hint-add-download = Tab: switch field | Enter: add | Esc: cancel
 status-hint-cancel = Esc: cancel
 status-hint-confirm-cancel = Enter: confirm | Esc: cancel
 status-hint-finish = Enter/Esc: finish
 status-hint-navigate = j/k: navigate | Enter: select | Esc: cancel
 status-hint-close = Esc/q: close
+status-hint-stats = Tab: change range | Esc/q: close
+status-hint-script-errors = c: clear | Esc/q: close
 status-hint-settings = Esc/q: close | Shift+R: reload config
 status-hint-folder-edit = j/k: navigate | Enter: edit | Esc: back
 status-hint-confirm-yn = Y: confirm | N/Esc: cancel
 status-hint-menu = j/k: navigate | Enter: execute | Esc: cancel
 
+# Transient status messages (failed actions)
+toast-add-failed = Failed to add download: { $error }
+toast-change-folder-failed = Failed to move download: { $error }
+toast-change-path-failed = Failed to change path: { $error }
+toast-reload-failed = Failed to reload config: { $error }
+toast-save-config-failed = Failed to save configuration: { $error }
+toast-trash-failed = Failed to save deleted downloads for undo: { $error }
+
 # Quick Actions bar title
 quick-actions-title = ⚡ Quick Actions
@@ -8,6 +8,8 @@ settings-app-max-concurrent-per-folder = Max Concurrent Per Folder
 settings-app-max-concurrent-per-folder-desc = Default concurrent downloads per folder
 settings-app-max-active-folders = Max Active Folders
 settings-app-max-active-folders-desc = Maximum folders active simultaneously
+settings-app-max-connections = Max Connections
+settings-app-max-connections-desc = Maximum HTTP connections across all downloads (empty = same as Max Concurrent Downloads)
 settings-app-max-redirects = Max Redirects
 settings-app-max-redirects-desc = Maximum number of HTTP redirects to follow
 settings-app-retry-count = Retry Count
@@ -28,6 +30,7 @@ settings-app-referrer-policy-desc = Default Referrer header policy for downloads
 # Folder Settings Fields
 settings-folder-save-path = Save Path
 settings-folder-save-path-desc = Directory where downloaded files are saved
+settings-folder-free-space = Free Space
 settings-folder-auto-date = Auto-Date Directory
 settings-folder-auto-date-desc = Automatically create date-based subdirectories
 settings-folder-auto-start = Auto-Start Downloads
@@ -57,6 +60,7 @@ context-menu-cancel = Cancel
 settings-value-enabled = Enabled
 settings-value-disabled = Disabled
 settings-value-not-set = Not set
+settings-value-unknown = Unknown
 settings-value-inherit = Inherit from app
 settings-value-enabled-override = Enabled (override)
 settings-value-disabled-override = Disabled (override)
@@ -4,9 +4,23 @@
 # Details panel labels
 details-label-status = Status:
 details-label-url = URL:
+details-label-resolved-url = Resolved URL:
+details-label-routed = Routed:
 details-label-save-path = Save Path:
 details-label-size = Size:
 details-label-downloaded = Downloaded:
+details-label-completed-at = Completed:
+details-label-elapsed = Elapsed:
+details-label-total-time = Total time:
+details-label-average-speed = Average speed:
+details-label-peak-speed = Peak speed:
+details-label-held = Not started:
+details-label-confirm = Confirm:
+details-confirm-size = { $size } is over download.confirm_over_bytes; start it to confirm
+details-label-scripts = Scripts:
+details-script-modified = modified
+details-script-unchanged = unchanged
+details-script-failed = failed
 details-label-filename = 📄 Filename:
 details-label-size-icon = 📊 Size:
 
@@ -14,6 +28,7 @@ details-label-size-icon = 📊 Size:
 status-pending = ⏳ Pending
 status-downloading = 📥 Downloading
 status-paused = ⏸️  Paused
+status-awaiting-confirmation = ❓ Confirm size
 status-completed = ✅ Completed
 status-error = ❌ Error
 status-deleted = 🗑️  Deleted
@@ -21,3 +36,26 @@ status-deleted = 🗑️  Deleted
 # Messages
 message-no-download-selected = No download selected
 message-no-logs = No logs yet
+
+# Statistics panel
+stats-range-all = All time
+stats-range-today = Today
+stats-range-week = Last 7 days
+stats-range-month = Last 30 days
+stats-label-completed = Completed:
+stats-label-failed = Failed:
+stats-label-success-rate = Success rate:
+stats-label-total-bytes = Total:
+stats-label-avg-speed = Avg speed:
+stats-label-lifetime = Lifetime:
+stats-lifetime-files = files
+stats-label-pending = Pending:
+stats-label-downloading = Downloading:
+stats-label-paused = Paused:
+stats-label-error = Error:
+stats-section-queue = Queue
+stats-section-folders = By folder
+stats-section-daily = Daily completions
+stats-empty = No downloads in this period
+script-errors-empty = No script errors
+script-errors-load = load
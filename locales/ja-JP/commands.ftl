@@ -18,6 +18,9 @@ cmd-error-script-recv = 受信エラー: { $error }
 cmd-error-script-communication = 送受信エラー: { $error }
 cmd-error-blocking-task = ブロッキングタスク失敗: { $error }
 
+# Confirmations
+cmd-confirm-concurrency-adjustment = フォルダ毎の最大数 ({ $per_folder }) × 最大アクティブフォルダ数 ({ $folders }) が最大同時ダウンロード数 ({ $max }) を超えている
+
 # Success messages
 cmd-success-scripts-reloaded = スクリプトを再読み込みした
 cmd-success-config-reloaded = 設定を正常にリロードした
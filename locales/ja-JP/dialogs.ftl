@@ -7,8 +7,12 @@ dialog-details = 詳細
 dialog-edit-field = フィールドを編集
 dialog-edit-label = {$label}を編集
 dialog-add-download = ダウンロードを追加 (Shift+Enterで[n-m]を展開)
+dialog-change-folder = フォルダへ移動 (Tabで補完、↑/↓で選択、Enterで確定、Escでキャンセル)
 dialog-change-save-path = 保存パスを変更 (Enterで確定、Escでキャンセル)
 dialog-confirm-delete = 削除の確認
+dialog-confirm-concurrency = 同時実行数の調整
+dialog-confirm-circuit-reset = サーキットブレーカー作動中
+dialog-confirm-large-download = 大きなダウンロード
 dialog-switch-folder = 📁 フォルダを選択 (j/kで移動、Enterで選択)
 dialog-actions = アクションメニュー (j/kで移動、Enterで選択)
 dialog-folder-actions = フォルダアクション
@@ -21,21 +25,42 @@ folder-menu-delete-all = すべて削除
 folder-menu-clear-history = 履歴をクリア
 dialog-download-preview = 📋 ダウンロードプレビュー
 dialog-help = ヘルプ
+dialog-stats = 統計
+dialog-script-errors = スクリプトエラー
 dialog-settings = 設定
 dialog-folders = フォルダ
 dialog-folder-details = フォルダ詳細
 
 # Input prompts
 prompt-url = URL:
+prompt-referer = リファラー (任意):
 prompt-save-path = 保存パス:
 prompt-value = 値:
 
+# Validation messages
+validation-no-folder-match = '{ $input }' に一致するフォルダがありません
+
 # Confirmation messages
 confirm-delete-single = このダウンロードを削除しますか？
 confirm-delete-multiple = {$count}個のダウンロードを削除しますか？
+confirm-folder-delete-all = '{ $folder }' の { $count } 個のダウンロードをすべて削除しますか？
+confirm-folder-delete-all-note = 元に戻す操作でまとめて復元できます。
+confirm-clear-history = 履歴の { $count } 件をすべて消去しますか？
+confirm-clear-history-note = この操作は元に戻せません。
+confirm-concurrency-proposed = 代わりに次の値を適用しますか？
+confirm-concurrency-per-folder = フォルダ毎の最大数: { $from } → { $to }
+confirm-concurrency-folders = 最大アクティブフォルダ数: { $from } → { $to }
+confirm-circuit-reset-blocked = { $key } は失敗が続いたためダウンロードを停止しています。
+confirm-circuit-reset-question = サーキットをリセットしてダウンロードを開始しますか？
+confirm-large-download-size = { $filename } は { $size } で、確認が必要なサイズを超えています。
+confirm-large-download-question = ダウンロードを開始しますか？
 
 # Help text in dialogs
 help-edit-field = Enter: 値を編集
 help-folder-edit-navigate = j/k: 移動 | Enter: フィールドを編集 | Esc: 設定に戻る
 help-folder-list = Enter: フォルダを編集 | s: 設定を保存
 help-script-toggle = j/k: 移動  Space/Enter: 切替  r: すべて再読み込み
+
+# Download preview
+preview-label-headers = 📨 リクエストヘッダー:
+preview-over-confirm-threshold = ⚠ { $limit } を超えています。追加するとこのサイズを承認したことになります
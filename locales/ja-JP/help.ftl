@@ -12,6 +12,7 @@ help-key-ctrl-z = Ctrl+Z     - 削除を取り消し
 help-key-m = m          - コンテキストメニューを開く
 help-key-e = e          - 編集（フォルダ変更）
 help-key-r = r          - 失敗したダウンロードを再試行
+help-key-ctrl-r = Ctrl+R     - 失敗したダウンロードをすべて再試行
 help-key-shift-s = S          - すべて再開
 help-key-shift-p = P          - すべて一時停止
 
@@ -31,6 +32,7 @@ help-key-ctrl-u = Ctrl+u     - 1ページ上へ
 help-section-panes = ペイン切り替え:
 help-key-prev-pane = Shift+Tab  - 前のペイン
 help-key-next-pane = Tab        - 次のペイン
+help-key-brackets = [ / ]      - 前 / 次のフォルダ
 
 help-section-search = 検索/フィルタ:
 help-key-slash = /          - 検索開始
@@ -39,7 +41,10 @@ help-key-esc-search = Esc        - 検索をクリア/キャンセル
 help-section-ui = UI:
 help-key-question = ?          - ヘルプ画面
 help-key-x = x          - 設定
+help-key-t-shift = T          - 統計
+help-key-e-shift = E          - スクリプトエラー
 help-key-i = i          - 詳細パネルの表示/非表示
+help-key-b-shift = B          - 帯域グラフの表示/非表示
 help-key-r-shift = R          - 再読み込み
 
 help-section-settings = 設定:
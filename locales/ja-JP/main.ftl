@@ -16,6 +16,7 @@ tree-completed-node = 履歴
 
 # Download list columns
 column-sel = 選択
+column-id = ID
 column-status = 状態
 column-filename = ファイル名
 column-size = サイズ
@@ -23,11 +24,21 @@ column-progress = 進捗
 column-speed = 速度
 column-eta = 残り
 
+# Bandwidth graph
+bandwidth-graph-title = スループット (直近1分): {$current}、ピーク {$peak}
+
 # Status bar - Normal mode
 status-normal-folder = 📁 [{$folder}]
 status-normal-actions = ⏯ Space:切替 | ✓ v:選択 | ➕ a:追加 | 🗑 d:削除 | 📋 m:メニュー
 status-normal-undo = Ctrl+Z: 元に戻す({$count})
+status-script-errors = ⚠ スクリプトエラー: {$count} (E)
+status-insecure-tls = ⚠ TLS 証明書検証オフ
+status-config-problems = ⚠ 設定の問題: {$count} (ggg config validate)
+status-schedule-paused = ⏸ 静音時間 ({$until} まで)
+status-schedule-limited = ⏬ {$speed} に制限中 ({$until} まで)
 status-normal-right = 🔄 F:フォルダ | ❓ ?:ヘルプ | ❌ q:終了
+status-queue-eta = ⏱ 全体の残り {$eta}
+status-queue-eta-partial = ⏱ 全体の残り {$eta}+ (サイズ不明あり)
 
 # Status bar - Other modes
 status-add-download = 📥 URLを入力してEnterで追加
@@ -43,15 +54,26 @@ status-confirm-delete = ⚠️  削除の確認
 status-context-menu = 📋 アクションメニュー
 
 # Status bar - Right side hints
+status-hint-add-download = Tab: 項目切替 | Enter: 追加 | Esc: キャンセル
 status-hint-cancel = Esc: キャンセル
 status-hint-confirm-cancel = Enter: 確定 | Esc: キャンセル
 status-hint-finish = Enter/Esc: 終了
 status-hint-navigate = j/k: 移動 | Enter: 選択 | Esc: キャンセル
 status-hint-close = Esc/q: 閉じる
+status-hint-stats = Tab: 期間切替 | Esc/q: 閉じる
+status-hint-script-errors = c: クリア | Esc/q: 閉じる
 status-hint-settings = Esc/q: 閉じる | Shift+R: 設定を再読み込み
 status-hint-folder-edit = j/k: 移動 | Enter: 編集 | Esc: 戻る
 status-hint-confirm-yn = Y: 確定 | N/Esc: キャンセル
 status-hint-menu = j/k: 移動 | Enter: 実行 | Esc: キャンセル
 
+# Transient status messages (failed actions)
+toast-add-failed = ダウンロードを追加できませんでした: { $error }
+toast-change-folder-failed = ダウンロードを移動できませんでした: { $error }
+toast-change-path-failed = 保存パスを変更できませんでした: { $error }
+toast-reload-failed = 設定を再読み込みできませんでした: { $error }
+toast-save-config-failed = 設定を保存できませんでした: { $error }
+toast-trash-failed = 削除したダウンロードを元に戻し用に保存できませんでした: { $error }
+
 # Quick Actions bar title
 quick-actions-title = ⚡ クイックアクション
@@ -8,6 +8,8 @@ settings-app-max-concurrent-per-folder = フォルダごとの最大同時数
 settings-app-max-concurrent-per-folder-desc = フォルダごとのデフォルト同時ダウンロード数
 settings-app-max-active-folders = 最大アクティブフォルダ数
 settings-app-max-active-folders-desc = 同時にアクティブにできるフォルダの最大数
+settings-app-max-connections = 最大接続数
+settings-app-max-connections-desc = 全ダウンロード合計のHTTP接続の最大数（空欄 = 最大同時ダウンロード数と同じ）
 settings-app-max-redirects = 最大リダイレクト数
 settings-app-max-redirects-desc = HTTPリダイレクトを追跡する最大回数
 settings-app-retry-count = リトライ回数
@@ -28,6 +30,7 @@ settings-app-referrer-policy-desc = ダウンロードのデフォルトReferrer
 # Folder Settings Fields
 settings-folder-save-path = 保存パス
 settings-folder-save-path-desc = ダウンロードファイルの保存先ディレクトリ
+settings-folder-free-space = 空き容量
 settings-folder-auto-date = 日付ディレクトリ自動作成
 settings-folder-auto-date-desc = 日付ベースのサブディレクトリを自動作成
 settings-folder-auto-start = ダウンロード自動開始
@@ -57,6 +60,7 @@ context-menu-cancel = キャンセル
 settings-value-enabled = 有効
 settings-value-disabled = 無効
 settings-value-not-set = 未設定
+settings-value-unknown = 不明
 settings-value-inherit = アプリから継承
 settings-value-enabled-override = 有効 (上書き)
 settings-value-disabled-override = 無効 (上書き)
@@ -4,9 +4,23 @@
 # Details panel labels
 details-label-status = 状態:
 details-label-url = URL:
+details-label-resolved-url = リダイレクト先:
+details-label-routed = 振り分け:
 details-label-save-path = 保存パス:
 details-label-size = サイズ:
 details-label-downloaded = ダウンロード済み:
+details-label-completed-at = 完了日時:
+details-label-elapsed = 経過時間:
+details-label-total-time = 所要時間:
+details-label-average-speed = 平均速度:
+details-label-peak-speed = 最高速度:
+details-label-held = 開始保留:
+details-label-confirm = 確認待ち:
+details-confirm-size = { $size } は download.confirm_over_bytes を超えています。開始すると確認します
+details-label-scripts = スクリプト:
+details-script-modified = 変更あり
+details-script-unchanged = 変更なし
+details-script-failed = 失敗
 details-label-filename = 📄 ファイル名:
 details-label-size-icon = 📊 サイズ:
 
@@ -14,6 +28,7 @@ details-label-size-icon = 📊 サイズ:
 status-pending = ⏳ 待機中
 status-downloading = 📥 ダウンロード中
 status-paused = ⏸️  一時停止
+status-awaiting-confirmation = ❓ サイズ確認待ち
 status-completed = ✅ 完了
 status-error = ❌ エラー
 status-deleted = 🗑️  削除済み
@@ -21,3 +36,26 @@ status-deleted = 🗑️  削除済み
 # Messages
 message-no-download-selected = ダウンロードが選択されていません
 message-no-logs = ログがありません
+
+# 統計パネル
+stats-range-all = 全期間
+stats-range-today = 今日
+stats-range-week = 過去7日間
+stats-range-month = 過去30日間
+stats-label-completed = 完了:
+stats-label-failed = 失敗:
+stats-label-success-rate = 成功率:
+stats-label-total-bytes = 合計:
+stats-label-avg-speed = 平均速度:
+stats-label-lifetime = 累計:
+stats-lifetime-files = ファイル
+stats-label-pending = 待機中:
+stats-label-downloading = ダウンロード中:
+stats-label-paused = 一時停止:
+stats-label-error = エラー:
+stats-section-queue = キュー
+stats-section-folders = フォルダ別
+stats-section-daily = 日別完了数
+stats-empty = この期間のダウンロードはありません
+script-errors-empty = スクリプトエラーはありません
+script-errors-load = 読み込み
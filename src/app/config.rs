@@ -132,6 +132,14 @@ pub struct ApplicationConfig {
     pub scripts: ScriptConfig,
     #[serde(default)]
     pub keybindings: KeybindingsConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
 }
 
 /// Complete configuration (Application settings + Folder settings)
@@ -144,7 +152,31 @@ pub struct Config {
     #[serde(default)]
     pub keybindings: KeybindingsConfig,
     #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    #[serde(default)]
     pub folders: HashMap<String, FolderConfig>,
+    /// Settings `Config::load` took from the environment (see `ENV_OVERRIDES`)
+    #[serde(skip)]
+    pub(crate) env_overridden: Option<Box<EnvOverridden>>,
+}
+
+/// What environment overrides replaced, so saving can keep them out of the files
+#[derive(Debug, Clone)]
+pub(crate) struct EnvOverridden {
+    /// `ENV_OVERRIDES` keys that were applied
+    keys: Vec<&'static str>,
+    /// Why each skipped variable was not applied
+    rejected: Vec<String>,
+    /// The configuration as read from the files
+    file: Config,
+    /// The configuration right after the overrides were applied
+    applied: Config,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -158,6 +190,21 @@ pub struct GeneralConfig {
     /// Auto-launch ggg-dnd GUI on startup (Windows only)
     #[serde(default)]
     pub auto_launch_dnd: bool,
+    /// Pause active downloads while running on battery (Windows/macOS)
+    #[serde(default)]
+    pub pause_on_battery: bool,
+    /// Pause active downloads while on a metered connection (Windows)
+    #[serde(default)]
+    pub pause_on_metered: bool,
+    /// Sound played when a download completes (unset = off, empty = built-in beep)
+    #[serde(default)]
+    pub completion_sound: Option<PathBuf>,
+    /// Show an ID column with short unique ID prefixes in the download list
+    #[serde(default)]
+    pub show_short_ids: bool,
+    /// Show a graph of total throughput over the last minute below the download list
+    #[serde(default)]
+    pub show_bandwidth_graph: bool,
 }
 
 fn default_skip_download_preview() -> bool {
@@ -170,22 +217,266 @@ pub struct DownloadConfig {
     pub max_concurrent: usize,
     pub retry_count: u32,
     pub retry_delay: u64,
+    /// Full string or `@preset` (see `app::user_agent`)
+    #[serde(with = "crate::app::user_agent")]
     pub user_agent: String,
+    /// User agents picked at random per request when no task, folder or script UA applies
+    #[serde(default, with = "crate::app::user_agent::list")]
+    pub user_agent_rotate: Vec<String>,
     pub bandwidth_limit: u64,
+    /// Share `bandwidth_limit` by task priority (weight 1 + priority) instead of evenly
+    #[serde(default)]
+    pub bandwidth_by_priority: bool,
     #[serde(default)]
     pub max_concurrent_per_folder: Option<usize>,
     #[serde(default)]
     pub parallel_folder_count: Option<usize>,
     #[serde(default = "default_max_redirects")]
     pub max_redirects: u32,
+    /// Allow redirects that downgrade from HTTPS to HTTP
+    #[serde(default)]
+    pub allow_insecure_redirect: bool,
     #[serde(default)]
     pub referrer_policy: ReferrerPolicy,
+    /// Append an extension derived from Content-Type to extensionless filenames
+    #[serde(default)]
+    pub infer_extension: bool,
+    /// Write/throttle granularity of the download loop in bytes (see `MIN_CHUNK_SIZE_BYTES`)
+    #[serde(default = "default_chunk_size_bytes")]
+    pub chunk_size_bytes: usize,
+    /// Seconds an idle pooled connection is kept open (0 = no limit)
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+    /// Idle connections kept per host for reuse (0 = no pooling)
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// Speak HTTP/2 without negotiation (fails against HTTP/1-only servers)
+    #[serde(default)]
+    pub http2_prior_knowledge: bool,
+    /// Minimum TLS version (None = library default)
+    #[serde(default)]
+    pub min_tls_version: Option<TlsVersion>,
+    /// Skip TLS certificate verification (insecure; warned about at startup)
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+    /// Confirm resume support with a 1-byte Range request instead of trusting Accept-Ranges
+    #[serde(default)]
+    pub deep_resume_probe: bool,
+    /// Re-fetch the last bytes of a partial file on resume and restart from scratch if they differ
+    #[serde(default)]
+    pub verify_resume_overlap: bool,
+    /// Order in which pending downloads are started by start-all and folder starts
+    #[serde(default)]
+    pub schedule_policy: SchedulePolicy,
+    /// Simultaneous HTTP connections across all downloads (None = same as `max_concurrent`)
+    #[serde(default)]
+    pub max_connections: Option<usize>,
+    /// HTTP statuses retried even though they are normally final (e.g. 404 from a lagging CDN)
+    #[serde(default)]
+    pub retry_on: Vec<u16>,
+    /// Longest `Retry-After` wait honored on 429/503, in seconds
+    #[serde(default = "default_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+    /// URL schemes that can be added as downloads
+    #[serde(default = "default_allowed_schemes")]
+    pub allowed_schemes: Vec<String>,
+    /// Directory for partial files during transfer (None = write straight to the target folder)
+    #[serde(default)]
+    pub temp_dir: Option<PathBuf>,
+    /// What to do with a completed file whose content matches one already in its folder (None = no check)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_duplicate_content: Option<DuplicateContentAction>,
+    /// Downloads larger than this many bytes pause until confirmed (None = never ask)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_over_bytes: Option<u64>,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
 }
 
 fn default_max_redirects() -> u32 {
     5
 }
 
+/// Default `download.chunk_size_bytes` (64 KiB)
+pub const DEFAULT_CHUNK_SIZE_BYTES: usize = 64 * 1024;
+
+/// Smallest accepted `download.chunk_size_bytes`; smaller values are raised to this
+pub const MIN_CHUNK_SIZE_BYTES: usize = 4 * 1024;
+
+/// Largest accepted `download.chunk_size_bytes` (16 MiB); larger values are
+/// rejected by validation and lowered to this by the HTTP client
+pub const MAX_CHUNK_SIZE_BYTES: usize = 16 * 1024 * 1024;
+
+fn default_chunk_size_bytes() -> usize {
+    DEFAULT_CHUNK_SIZE_BYTES
+}
+
+fn default_max_retry_after_secs() -> u64 {
+    300
+}
+
+/// Default `download.allowed_schemes`
+pub const DEFAULT_ALLOWED_SCHEMES: &[&str] = &["http", "https", "ftp", "ftps"];
+
+fn default_allowed_schemes() -> Vec<String> {
+    DEFAULT_ALLOWED_SCHEMES.iter().map(|scheme| scheme.to_string()).collect()
+}
+
+impl DownloadConfig {
+    /// Reject `url` unless it parses, its scheme is in `allowed_schemes` and
+    /// ggg can download it (FTP is allowed by default but not supported yet)
+    pub fn check_url_scheme(&self, url: &str) -> anyhow::Result<()> {
+        let parsed = url::Url::parse(url).map_err(|e| anyhow::anyhow!("'{}' is not a valid URL ({})", url, e))?;
+        if self.allowed_schemes.iter().any(|scheme| scheme.eq_ignore_ascii_case(parsed.scheme())) {
+            return crate::download::http_client::HttpClient::check_supported_scheme(url);
+        }
+        anyhow::bail!(
+            "URL scheme '{}' is not allowed (download.allowed_schemes: {})",
+            parsed.scheme(),
+            self.allowed_schemes.join(", ")
+        )
+    }
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    10
+}
+
+/// Minimum TLS version for `download.min_tls_version`
+///
+/// Older versions are not offered because the TLS backend (rustls) does not
+/// support them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TlsVersion {
+    #[serde(rename = "1.2")]
+    Tls12,
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+impl TlsVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tls12 => "1.2",
+            Self::Tls13 => "1.3",
+        }
+    }
+}
+
+impl std::str::FromStr for TlsVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "1.2" => Ok(Self::Tls12),
+            "1.3" => Ok(Self::Tls13),
+            _ => Err(anyhow::anyhow!("Invalid TLS version '{}' (expected 1.2 or 1.3)", s)),
+        }
+    }
+}
+
+/// Circuit breaker settings (`[download.circuit_breaker]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CircuitBreakerSettings {
+    #[serde(default)]
+    pub scope: CircuitScope,
+}
+
+/// Handling of a completed file with the same content as an earlier one (`download.on_duplicate_content`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateContentAction {
+    /// Keep both files; only note the match in the download log
+    Keep,
+    /// Replace the new file with a hard link to the earlier one
+    Hardlink,
+    /// Delete the new file
+    Delete,
+}
+
+/// Which pending download starts next (`download.schedule_policy`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulePolicy {
+    /// Queue order
+    #[default]
+    Fifo,
+    /// Highest `priority` first
+    Priority,
+    /// Smallest known size first (quick wins); unknown sizes last
+    SmallestFirst,
+    /// Largest known size first; unknown sizes last
+    LargestFirst,
+}
+
+impl SchedulePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fifo => "fifo",
+            Self::Priority => "priority",
+            Self::SmallestFirst => "smallest_first",
+            Self::LargestFirst => "largest_first",
+        }
+    }
+}
+
+impl std::str::FromStr for SchedulePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "priority" => Ok(Self::Priority),
+            "smallest_first" => Ok(Self::SmallestFirst),
+            "largest_first" => Ok(Self::LargestFirst),
+            _ => Err(anyhow::anyhow!(
+                "Invalid schedule policy '{}' (expected fifo, priority, smallest_first or largest_first)",
+                s
+            )),
+        }
+    }
+}
+
+/// What the circuit breaker groups failures by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitScope {
+    /// Every URL on a host shares one circuit
+    #[default]
+    #[serde(rename = "domain")]
+    Domain,
+    /// URLs in the same directory of a host share one circuit
+    #[serde(rename = "host+path-prefix")]
+    HostPathPrefix,
+}
+
+impl CircuitScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Domain => "domain",
+            Self::HostPathPrefix => "host+path-prefix",
+        }
+    }
+}
+
+impl std::str::FromStr for CircuitScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "domain" => Ok(Self::Domain),
+            "host+path-prefix" => Ok(Self::HostPathPrefix),
+            _ => Err(anyhow::anyhow!(
+                "Invalid circuit breaker scope '{}' (expected domain or host+path-prefix)",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
     pub proxy_enabled: bool,
@@ -202,12 +493,209 @@ pub struct ScriptConfig {
     pub enabled: bool,
     pub directory: PathBuf,
     pub timeout: u64,
+    /// Reload scripts automatically when files in `directory` change (TUI only)
+    #[serde(default)]
+    pub auto_reload: bool,
+    /// Bytes of the response body passed to headersReceived/completed as
+    /// `bodyPeek` (0 = disabled, no extra request)
+    #[serde(default)]
+    pub body_peek_bytes: usize,
     /// Per-script file enable/disable settings
     /// Maps filename (without path) to enabled status
     #[serde(default)]
     pub script_files: HashMap<String, bool>,
 }
 
+/// Retention policy for download history and completion logs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Maximum number of entries to keep (0 = unlimited, the default)
+    #[serde(default)]
+    pub max_entries: usize,
+    /// Remove entries older than this many days (0 = unlimited)
+    #[serde(default)]
+    pub max_age_days: u32,
+    /// Days to keep deleted downloads restorable (0 = never expire)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+}
+
+fn default_trash_retention_days() -> u32 {
+    7
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 0,
+            max_age_days: 0,
+            trash_retention_days: default_trash_retention_days(),
+        }
+    }
+}
+
+/// Auto-folder routing rules, evaluated in order (see `download::routing`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// Application log output (see `main.rs`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Minimum level written (error, warn, info, debug, trace); `--verbose` forces trace
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// How often a new log file is started
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Log files kept; older ones are deleted at startup (0 = keep all)
+    #[serde(default)]
+    pub max_files: usize,
+    /// Format of log lines written to stderr in daemon mode
+    ///
+    /// The log files are always JSON.
+    #[serde(default)]
+    pub console_format: ConsoleFormat,
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            rotation: LogRotation::default(),
+            max_files: 0,
+            console_format: ConsoleFormat::default(),
+        }
+    }
+}
+
+/// Log file rotation policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// `app.jsonl.YYYY-MM-DD`
+    #[default]
+    Daily,
+    /// `app.jsonl.YYYY-MM-DD-HH`
+    Hourly,
+    /// A single `app.jsonl`
+    Never,
+}
+
+impl LogRotation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Hourly => "hourly",
+            Self::Never => "never",
+        }
+    }
+}
+
+impl std::str::FromStr for LogRotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "hourly" => Ok(Self::Hourly),
+            "never" => Ok(Self::Never),
+            _ => Err(anyhow::anyhow!("Invalid log rotation '{}' (expected daily, hourly or never)", s)),
+        }
+    }
+}
+
+/// Console log line format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleFormat {
+    /// One JSON object per line, like the log files
+    Json,
+    /// Compact, colored human-readable lines
+    #[default]
+    Pretty,
+}
+
+impl ConsoleFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Pretty => "pretty",
+        }
+    }
+}
+
+impl std::str::FromStr for ConsoleFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            _ => Err(anyhow::anyhow!("Invalid console format '{}' (expected json or pretty)", s)),
+        }
+    }
+}
+
+/// Quiet windows that pause or throttle downloads (see `util::schedule`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    #[serde(default)]
+    pub windows: Vec<ScheduleWindow>,
+}
+
+/// A recurring local-time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleWindow {
+    /// Start time, "HH:MM"
+    pub start: String,
+    /// End time, "HH:MM" (earlier than `start` = ends the next day)
+    pub end: String,
+    /// Days the window starts on ("mon" .. "sun"); empty = every day
+    #[serde(default)]
+    pub days: Vec<String>,
+    #[serde(default)]
+    pub action: ScheduleAction,
+    /// Combined bandwidth cap in bytes/sec while `action = "limit"`
+    #[serde(default)]
+    pub max_bytes_per_sec: u64,
+}
+
+/// What a schedule window does while active
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleAction {
+    /// Pause active downloads and resume them when the window ends
+    #[default]
+    Pause,
+    /// Cap the combined bandwidth at `max_bytes_per_sec`
+    Limit,
+}
+
+/// Routes matching downloads to `folder`
+///
+/// Every non-empty criterion must match; an entry in any list is enough.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingRule {
+    /// Target folder ID
+    pub folder: String,
+    /// File extensions, case-insensitive (`"jpg"`)
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Content types from the server response (`"image/*"` matches any image)
+    #[serde(default)]
+    pub content_types: Vec<String>,
+    /// URL hosts, subdomains included (`"example.com"`)
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FolderConfig {
     /// Display name for the folder (user-visible)
@@ -216,6 +704,10 @@ pub struct FolderConfig {
     pub save_path: PathBuf,
     #[serde(default)]
     pub auto_date_directory: bool,
+    /// Subdirectory template below `save_path`, e.g. `{base}/{yyyy}/{mm}/{host}`
+    /// (see `file::path_template`). Takes precedence over `auto_date_directory`.
+    #[serde(default)]
+    pub save_path_template: Option<String>,
     #[serde(default)]
     pub auto_start_downloads: bool,
     #[serde(default)]
@@ -224,12 +716,18 @@ pub struct FolderConfig {
     pub script_files: Option<HashMap<String, bool>>,
     #[serde(default)]
     pub max_concurrent: Option<usize>,
-    #[serde(default)]
+    #[serde(default, with = "crate::app::user_agent::option")]
     pub user_agent: Option<String>,
     #[serde(default)]
     pub referrer_policy: Option<ReferrerPolicy>,
     #[serde(default)]
     pub default_headers: HashMap<String, String>,
+    /// Maximum number of queued downloads (pending or running); further ones are held
+    #[serde(default)]
+    pub max_queued: Option<usize>,
+    /// Maximum bytes still to transfer across running downloads; further starts are held
+    #[serde(default)]
+    pub max_inflight_bytes: Option<u64>,
 }
 
 impl Default for FolderConfig {
@@ -238,6 +736,7 @@ impl Default for FolderConfig {
             name: String::new(),
             save_path: crate::util::paths::resolve_default_download_directory(),
             auto_date_directory: false,
+            save_path_template: None,
             auto_start_downloads: false,
             scripts_enabled: None,
             script_files: None,
@@ -245,6 +744,8 @@ impl Default for FolderConfig {
             user_agent: None,
             referrer_policy: None,
             default_headers: HashMap::new(),
+            max_queued: None,
+            max_inflight_bytes: None,
         }
     }
 }
@@ -257,6 +758,18 @@ impl FolderConfig {
             ..Self::default()
         }
     }
+
+    /// Save-path template in effect for this folder
+    ///
+    /// `auto_date_directory` is shorthand for `{base}/{date}`.
+    pub fn effective_save_path_template(&self) -> Option<&str> {
+        self.save_path_template
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .or(self
+                .auto_date_directory
+                .then_some(crate::file::path_template::AUTO_DATE_TEMPLATE))
+    }
 }
 
 impl Default for Config {
@@ -269,18 +782,44 @@ impl Default for Config {
                 start_minimized: false,
                 skip_download_preview: true,
                 auto_launch_dnd: false,
+                pause_on_battery: false,
+                pause_on_metered: false,
+                completion_sound: None,
+                show_short_ids: false,
+                show_bandwidth_graph: false,
             },
             download: DownloadConfig {
                 default_directory: crate::util::paths::resolve_default_download_directory(),
                 max_concurrent: 3,
                 retry_count: 3,
                 retry_delay: 5,
-                user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
+                user_agent: crate::app::user_agent::DEFAULT_USER_AGENT.to_string(),
+                user_agent_rotate: Vec::new(),
                 bandwidth_limit: 0,
+                bandwidth_by_priority: false,
                 max_concurrent_per_folder: None,
                 parallel_folder_count: None,
                 max_redirects: 5,
+                allow_insecure_redirect: false,
                 referrer_policy: ReferrerPolicy::default(),
+                infer_extension: false,
+                chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
+                pool_idle_timeout_secs: 90,
+                pool_max_idle_per_host: 10,
+                http2_prior_knowledge: false,
+                min_tls_version: None,
+                danger_accept_invalid_certs: false,
+                deep_resume_probe: false,
+                verify_resume_overlap: false,
+                schedule_policy: SchedulePolicy::default(),
+                max_connections: None,
+                retry_on: Vec::new(),
+                max_retry_after_secs: 300,
+                allowed_schemes: default_allowed_schemes(),
+                temp_dir: None,
+                on_duplicate_content: None,
+                confirm_over_bytes: None,
+                circuit_breaker: CircuitBreakerSettings::default(),
             },
             network: NetworkConfig {
                 proxy_enabled: false,
@@ -295,10 +834,99 @@ impl Default for Config {
                 enabled: true,
                 directory: crate::util::paths::resolve_default_scripts_directory(),
                 timeout: 30,
+                auto_reload: false,
+                body_peek_bytes: 0,
                 script_files: HashMap::new(),
             },
             keybindings: KeybindingsConfig::default(),
+            history: HistoryConfig::default(),
+            routing: RoutingConfig::default(),
+            logging: LoggingConfig::default(),
+            schedule: ScheduleConfig::default(),
             folders: HashMap::new(),
+            env_overridden: None,
+        }
+    }
+}
+
+/// Environment variables that override settings, with the setting each replaces
+///
+/// `Config::load` applies them after reading the files (env > file > default).
+/// `GGG_CONFIG_DIR` is not listed: it chooses the files (see `util::paths`).
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("GGG_MAX_CONCURRENT", "download.max_concurrent"),
+    ("GGG_MAX_CONCURRENT_PER_FOLDER", "download.max_concurrent_per_folder"),
+    ("GGG_DOWNLOAD_DIR", "download.default_directory"),
+    ("GGG_BANDWIDTH_LIMIT", "download.bandwidth_limit"),
+    ("GGG_RETRY_COUNT", "download.retry_count"),
+    ("GGG_USER_AGENT", "download.user_agent"),
+    ("GGG_LANGUAGE", "general.language"),
+    ("GGG_LOG_LEVEL", "logging.level"),
+    ("GGG_SCRIPTS_ENABLED", "scripts.enabled"),
+];
+
+/// Problems found by `Config::check_files`
+#[derive(Debug, Default)]
+pub struct ConfigIssues {
+    /// Files that could not be read or parsed, with the line and column where possible
+    pub errors: Vec<String>,
+    /// Ignored keys as `file: dotted.key` (usually typos)
+    pub unknown_keys: Vec<String>,
+}
+
+impl ConfigIssues {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.unknown_keys.is_empty()
+    }
+
+    /// Parse `path` as `T` and record errors and unknown keys under `label`
+    fn check_file<T: Serialize + serde::de::DeserializeOwned>(&mut self, path: &std::path::Path, label: &str) {
+        match std::fs::read_to_string(path) {
+            Ok(content) => self.check_content::<T>(&content, label),
+            Err(e) => self.errors.push(format!("{}: {}", label, e)),
+        }
+    }
+
+    /// Parse `content` as `T` and record errors and unknown keys under `label`
+    pub fn check_content<T: Serialize + serde::de::DeserializeOwned>(&mut self, content: &str, label: &str) {
+        // Parse the text (not a table) so errors keep their line and column
+        let (table, parsed) = match (toml::from_str::<toml::Table>(content), toml::from_str::<T>(content)) {
+            (Ok(table), Ok(parsed)) => (table, parsed),
+            (Err(e), _) | (_, Err(e)) => {
+                self.errors.push(format!("{}: {}", label, e.to_string().trim_end()));
+                return;
+            }
+        };
+        let Ok(known) = toml::Table::try_from(&parsed) else {
+            return;
+        };
+
+        let mut unknown = Vec::new();
+        collect_unknown_keys(&table, &known, "", &mut unknown);
+        self.unknown_keys.extend(unknown.into_iter().map(|key| format!("{}: {}", label, key)));
+    }
+}
+
+/// Dotted paths of keys in `file` that are missing from `known`
+///
+/// `known` is the parsed file serialized back, so it has every key serde
+/// understood; whatever else the file has was ignored.
+fn collect_unknown_keys(file: &toml::Table, known: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in file {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match (value, known.get(key)) {
+            (_, None) => out.push(path),
+            (toml::Value::Table(file), Some(toml::Value::Table(known))) => {
+                collect_unknown_keys(file, known, &path, out);
+            }
+            (toml::Value::Array(file), Some(toml::Value::Array(known))) => {
+                for (i, (file, known)) in file.iter().zip(known).enumerate() {
+                    if let (toml::Value::Table(file), toml::Value::Table(known)) = (file, known) {
+                        collect_unknown_keys(file, known, &format!("{}[{}]", path, i), out);
+                    }
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -373,6 +1001,7 @@ impl Config {
                     name: "default".to_string(),
                     save_path: app_config.download.default_directory.clone(),
                     auto_date_directory: false,
+                    save_path_template: None,
                     auto_start_downloads: false,
                     scripts_enabled: None,
                     script_files: None,
@@ -380,6 +1009,8 @@ impl Config {
                     user_agent: None,
                     referrer_policy: None,
                     default_headers: HashMap::new(),
+                    max_queued: None,
+                    max_inflight_bytes: None,
                 },
             );
         }
@@ -391,30 +1022,187 @@ impl Config {
             network: app_config.network,
             scripts: app_config.scripts,
             keybindings: app_config.keybindings,
+            history: app_config.history,
+            routing: app_config.routing,
+            logging: app_config.logging,
+            schedule: app_config.schedule,
             folders,
+            env_overridden: None,
         };
 
         // Step 6: Validate
-        if let Err(errors) = crate::app::settings::validate_folder_config(&config) {
-            return Err(anyhow::anyhow!(
+        config.validate()?;
+
+        // Step 7: Auto-save after migration to persist new UUID keys
+        if migrated {
+            tracing::info!("Saving migrated folder configs with UUID keys");
+            if let Err(e) = config.save() {
+                tracing::error!("Failed to save migrated config: {}", e);
+            }
+        }
+
+        // Step 8: Environment overrides (env > file > default), applied after
+        // the migration save so they are not written to the files. The file
+        // values are kept so later saves write those instead.
+        let file = config;
+        let mut config = file.clone();
+        let (applied, rejected) = config.apply_env_overrides();
+        if !applied.is_empty() || !rejected.is_empty() {
+            let keys = ENV_OVERRIDES
+                .iter()
+                .filter(|(name, _)| applied.contains(name))
+                .map(|&(_, key)| key)
+                .collect();
+            config.env_overridden = Some(Box::new(EnvOverridden {
+                keys,
+                rejected,
+                file,
+                applied: config.clone(),
+            }));
+        }
+
+        Ok(config)
+    }
+
+    /// Check cross-setting constraints (see `settings::validate_folder_config`)
+    fn validate(&self) -> anyhow::Result<()> {
+        crate::app::settings::validate_folder_config(self).map_err(|errors| {
+            anyhow::anyhow!(
                 "Invalid configuration: {}",
                 errors
                     .iter()
                     .map(|e| e.to_string())
                     .collect::<Vec<_>>()
                     .join(", ")
-            ));
+            )
+        })
+    }
+
+    /// Apply `ENV_OVERRIDES` set in the process environment
+    ///
+    /// Returns the variables that were applied and a message for each one
+    /// that was skipped. A value that does not parse, or that would leave the
+    /// configuration invalid, is skipped and the current value kept.
+    pub fn apply_env_overrides(&mut self) -> (Vec<&'static str>, Vec<String>) {
+        self.apply_overrides(|name| std::env::var(name).ok())
+    }
+
+    fn apply_overrides(&mut self, lookup: impl Fn(&str) -> Option<String>) -> (Vec<&'static str>, Vec<String>) {
+        let mut applied = Vec::new();
+        let mut rejected = Vec::new();
+        let mut invalid = Vec::new();
+        for &(name, key) in ENV_OVERRIDES {
+            let Some(value) = lookup(name) else {
+                continue;
+            };
+            let mut candidate = self.clone();
+            if let Err(e) = candidate.apply_override(key, value.trim()) {
+                rejected.push(format!("Ignored {} (overrides {}): {:#}", name, key, e));
+                continue;
+            }
+            invalid.push((name, key, candidate));
         }
 
-        // Step 7: Auto-save after migration to persist new UUID keys
-        if migrated {
-            tracing::info!("Saving migrated folder configs with UUID keys");
-            if let Err(e) = config.save() {
-                tracing::error!("Failed to save migrated config: {}", e);
+        // Overrides that only validate together (e.g. a lower max_concurrent
+        // with a lower max_concurrent_per_folder) get another pass
+        loop {
+            let before = invalid.len();
+            for (name, key, candidate) in std::mem::take(&mut invalid) {
+                let mut merged = self.clone();
+                merged.copy_override(key, &candidate);
+                if merged.validate().is_ok() {
+                    *self = merged;
+                    applied.push(name);
+                } else {
+                    invalid.push((name, key, candidate));
+                }
             }
+            if invalid.is_empty() || invalid.len() == before {
+                break;
+            }
+        }
+        for (name, key, candidate) in invalid {
+            let mut merged = self.clone();
+            merged.copy_override(key, &candidate);
+            let reason = merged.validate().err().map(|e| format!("{:#}", e)).unwrap_or_default();
+            rejected.push(format!("Ignored {} (overrides {}): {}", name, key, reason));
         }
 
-        Ok(config)
+        applied.sort_by_key(|name| ENV_OVERRIDES.iter().position(|(n, _)| n == name));
+        (applied, rejected)
+    }
+
+    /// Environment variables `Config::load` skipped, with the reason for each
+    pub fn env_override_problems(&self) -> &[String] {
+        self.env_overridden.as_ref().map_or(&[], |overridden| &overridden.rejected)
+    }
+
+    fn apply_override(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "download.max_concurrent" => self.download.max_concurrent = value.parse()?,
+            "download.max_concurrent_per_folder" => self.download.max_concurrent_per_folder = Some(value.parse()?),
+            "download.default_directory" => self.download.default_directory = PathBuf::from(value),
+            "download.bandwidth_limit" => self.download.bandwidth_limit = value.parse()?,
+            "download.retry_count" => self.download.retry_count = value.parse()?,
+            "download.user_agent" => self.download.user_agent = crate::app::user_agent::expand(value)?,
+            "general.language" => self.general.language = value.to_string(),
+            "logging.level" => self.logging.level = value.to_string(),
+            "scripts.enabled" => self.scripts.enabled = value.parse()?,
+            _ => anyhow::bail!("no such setting"),
+        }
+        Ok(())
+    }
+
+    /// This configuration as it belongs in the files
+    ///
+    /// Settings still holding an environment override's value get their file
+    /// value back; settings changed since loading keep the new value.
+    pub fn without_env_overrides(&self) -> std::borrow::Cow<'_, Self> {
+        let Some(overridden) = &self.env_overridden else {
+            return std::borrow::Cow::Borrowed(self);
+        };
+        let mut config = self.clone();
+        config.env_overridden = None;
+        for &key in &overridden.keys {
+            if self.override_value(key) == overridden.applied.override_value(key) {
+                config.copy_override(key, &overridden.file);
+            }
+        }
+        std::borrow::Cow::Owned(config)
+    }
+
+    /// The value of an `ENV_OVERRIDES` setting, for comparison
+    fn override_value(&self, key: &str) -> String {
+        match key {
+            "download.max_concurrent" => self.download.max_concurrent.to_string(),
+            "download.max_concurrent_per_folder" => format!("{:?}", self.download.max_concurrent_per_folder),
+            "download.default_directory" => format!("{:?}", self.download.default_directory),
+            "download.bandwidth_limit" => self.download.bandwidth_limit.to_string(),
+            "download.retry_count" => self.download.retry_count.to_string(),
+            "download.user_agent" => self.download.user_agent.clone(),
+            "general.language" => self.general.language.clone(),
+            "logging.level" => self.logging.level.clone(),
+            "scripts.enabled" => self.scripts.enabled.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Copy an `ENV_OVERRIDES` setting from `from`
+    fn copy_override(&mut self, key: &str, from: &Config) {
+        match key {
+            "download.max_concurrent" => self.download.max_concurrent = from.download.max_concurrent,
+            "download.max_concurrent_per_folder" => {
+                self.download.max_concurrent_per_folder = from.download.max_concurrent_per_folder
+            }
+            "download.default_directory" => self.download.default_directory = from.download.default_directory.clone(),
+            "download.bandwidth_limit" => self.download.bandwidth_limit = from.download.bandwidth_limit,
+            "download.retry_count" => self.download.retry_count = from.download.retry_count,
+            "download.user_agent" => self.download.user_agent = from.download.user_agent.clone(),
+            "general.language" => self.general.language = from.general.language.clone(),
+            "logging.level" => self.logging.level = from.logging.level.clone(),
+            "scripts.enabled" => self.scripts.enabled = from.scripts.enabled,
+            _ => {}
+        }
     }
 
     /// Save configuration to multi-file structure
@@ -431,8 +1219,8 @@ impl Config {
             ));
         }
 
-        // Step 2: Save application-level config
-        Self::save_application_config(self)?;
+        // Step 2: Save application-level config, minus environment overrides
+        Self::save_application_config(&self.without_env_overrides())?;
 
         // Step 3: Save each folder config
         for (folder_name, folder_config) in &self.folders {
@@ -442,6 +1230,35 @@ impl Config {
         Ok(())
     }
 
+    /// Check the configuration files for problems that loading works around
+    ///
+    /// `Config::load` fails on an unreadable `settings.toml` (and the caller
+    /// falls back to defaults), skips folder configs that do not parse and
+    /// ignores keys it does not know, so a typo silently loses a setting.
+    /// This reports all three without changing anything.
+    pub fn check_files() -> anyhow::Result<ConfigIssues> {
+        let config_dir = crate::util::paths::find_config_directory()?;
+        let mut issues = ConfigIssues::default();
+
+        let app_path = crate::util::paths::get_app_config_path()?;
+        if app_path.exists() {
+            issues.check_file::<ApplicationConfig>(&app_path, "settings.toml");
+        }
+
+        let mut folder_dirs: Vec<PathBuf> = std::fs::read_dir(&config_dir)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("settings.toml").is_file())
+            .collect();
+        folder_dirs.sort();
+        for dir in folder_dirs {
+            let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            issues.check_file::<FolderConfig>(&dir.join("settings.toml"), &format!("{}/settings.toml", name));
+        }
+
+        Ok(issues)
+    }
+
     // --- Helper Methods ---
 
     fn load_application_config() -> anyhow::Result<ApplicationConfig> {
@@ -470,18 +1287,44 @@ impl Config {
                     start_minimized: false,
                     skip_download_preview: true,
                     auto_launch_dnd: false,
+                    pause_on_battery: false,
+                    pause_on_metered: false,
+                    completion_sound: None,
+                    show_short_ids: false,
+                    show_bandwidth_graph: false,
                 },
                 download: DownloadConfig {
                     default_directory: crate::util::paths::resolve_default_download_directory(),
                     max_concurrent: 3,
                     retry_count: 3,
                     retry_delay: 5,
-                    user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
+                    user_agent: crate::app::user_agent::DEFAULT_USER_AGENT.to_string(),
+                    user_agent_rotate: Vec::new(),
                     bandwidth_limit: 0,
+                    bandwidth_by_priority: false,
                     max_concurrent_per_folder: None,
                     parallel_folder_count: None,
                     max_redirects: 5,
+                    allow_insecure_redirect: false,
                     referrer_policy: ReferrerPolicy::default(),
+                    infer_extension: false,
+                    chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
+                    pool_idle_timeout_secs: 90,
+                    pool_max_idle_per_host: 10,
+                    http2_prior_knowledge: false,
+                    min_tls_version: None,
+                    danger_accept_invalid_certs: false,
+                    deep_resume_probe: false,
+                    verify_resume_overlap: false,
+                    schedule_policy: SchedulePolicy::default(),
+                    max_connections: None,
+                    retry_on: Vec::new(),
+                    max_retry_after_secs: 300,
+                    allowed_schemes: default_allowed_schemes(),
+                    temp_dir: None,
+                    on_duplicate_content: None,
+                    confirm_over_bytes: None,
+                    circuit_breaker: CircuitBreakerSettings::default(),
                 },
                 network: NetworkConfig {
                     proxy_enabled: false,
@@ -496,9 +1339,15 @@ impl Config {
                     enabled: true,
                     directory: crate::util::paths::resolve_default_scripts_directory(),
                     timeout: 30,
+                    auto_reload: false,
+                    body_peek_bytes: 0,
                     script_files: HashMap::new(),
                 },
                 keybindings: KeybindingsConfig::default(),
+                history: HistoryConfig::default(),
+                routing: RoutingConfig::default(),
+                logging: LoggingConfig::default(),
+                schedule: ScheduleConfig::default(),
             })
         }
     }
@@ -519,6 +1368,10 @@ impl Config {
             network: self.network.clone(),
             scripts: self.scripts.clone(),
             keybindings: self.keybindings.clone(),
+            history: self.history.clone(),
+            routing: self.routing.clone(),
+            logging: self.logging.clone(),
+            schedule: self.schedule.clone(),
         };
 
         let content = toml::to_string_pretty(&app_config)?;
@@ -915,6 +1768,11 @@ timeout = 60
                 start_minimized: true,
                 skip_download_preview: true,
                 auto_launch_dnd: false,
+                pause_on_battery: false,
+                pause_on_metered: false,
+                completion_sound: None,
+                show_short_ids: false,
+                show_bandwidth_graph: false,
             },
             download: DownloadConfig {
                 default_directory: PathBuf::from("C:\\Downloads"),
@@ -922,11 +1780,32 @@ timeout = 60
                 retry_count: 3,
                 retry_delay: 5,
                 user_agent: "TestAgent".to_string(),
+                user_agent_rotate: Vec::new(),
                 bandwidth_limit: 0,
+                bandwidth_by_priority: false,
                 max_concurrent_per_folder: Some(2),
                 parallel_folder_count: Some(2),
                 max_redirects: 10,
+                allow_insecure_redirect: false,
                 referrer_policy: ReferrerPolicy::default(),
+                infer_extension: false,
+                chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
+                pool_idle_timeout_secs: 90,
+                pool_max_idle_per_host: 10,
+                http2_prior_knowledge: false,
+                min_tls_version: None,
+                danger_accept_invalid_certs: false,
+                deep_resume_probe: false,
+                verify_resume_overlap: false,
+                schedule_policy: SchedulePolicy::default(),
+                max_connections: None,
+                retry_on: Vec::new(),
+                max_retry_after_secs: 300,
+                allowed_schemes: default_allowed_schemes(),
+                temp_dir: None,
+                on_duplicate_content: None,
+                confirm_over_bytes: None,
+                circuit_breaker: CircuitBreakerSettings::default(),
             },
             network: NetworkConfig {
                 proxy_enabled: false,
@@ -941,9 +1820,15 @@ timeout = 60
                 enabled: true,
                 directory: PathBuf::from("./scripts"),
                 timeout: 30,
+                auto_reload: false,
+                body_peek_bytes: 0,
                 script_files: HashMap::new(),
             },
             keybindings: KeybindingsConfig::default(),
+            history: HistoryConfig::default(),
+            routing: RoutingConfig::default(),
+            logging: LoggingConfig::default(),
+            schedule: ScheduleConfig::default(),
         };
 
         // Should serialize and deserialize correctly
@@ -976,6 +1861,7 @@ timeout = 60
             name: "test".to_string(),
             save_path: PathBuf::from("C:\\Test"),
             auto_date_directory: true,
+            save_path_template: None,
             auto_start_downloads: false,
             scripts_enabled: None, // Should inherit from app
             script_files: None,     // Should inherit from app
@@ -983,6 +1869,8 @@ timeout = 60
             user_agent: None,       // Should inherit from app
             referrer_policy: None,  // Should inherit from app
             default_headers: HashMap::new(),
+            max_queued: None,
+            max_inflight_bytes: None,
         };
 
         let serialized = toml::to_string_pretty(&folder_config).unwrap();
@@ -1021,6 +1909,122 @@ timeout = 60
         assert!(result.is_err());
     }
 
+    #[test]
+    #[serial]
+    fn test_check_files_reports_unknown_keys_and_parse_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        unsafe { std::env::set_var("GGG_TEST_MODE", "1") };
+
+        // A saved config round-trips without unknown keys; add a typo and a stray section
+        Config::default().save().unwrap();
+        let app_path = temp_dir.path().join("settings.toml");
+        let mut table: toml::Table = toml::from_str(&std::fs::read_to_string(&app_path).unwrap()).unwrap();
+        let download = table.get_mut("download").and_then(|v| v.as_table_mut()).unwrap();
+        download.insert("max_concurent".to_string(), toml::Value::Integer(5));
+        table.insert("extras".to_string(), toml::Value::Table(toml::Table::new()));
+        std::fs::write(&app_path, toml::to_string(&table).unwrap()).unwrap();
+
+        // A folder config that does not parse is skipped by load, and reported here
+        std::fs::create_dir_all(temp_dir.path().join("broken")).unwrap();
+        std::fs::write(temp_dir.path().join("broken").join("settings.toml"), "save_path = \n").unwrap();
+
+        let issues = Config::check_files().unwrap();
+        crate::util::paths::set_config_dir_override(None);
+        unsafe { std::env::remove_var("GGG_TEST_MODE") };
+
+        assert_eq!(
+            issues.unknown_keys,
+            ["settings.toml: download.max_concurent", "settings.toml: extras"]
+        );
+        assert_eq!(issues.errors.len(), 1);
+        assert!(issues.errors[0].starts_with("broken/settings.toml: "));
+        assert!(issues.errors[0].contains("line 1"));
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars: HashMap<&str, &str> = HashMap::from([
+            ("GGG_MAX_CONCURRENT", "8"),
+            ("GGG_RETRY_COUNT", "many"),
+            ("GGG_SCRIPTS_ENABLED", " false "),
+        ]);
+        let mut config = Config::default();
+        let (applied, rejected) = config.apply_overrides(|name| vars.get(name).map(|v| v.to_string()));
+
+        // A bad value is skipped with a message naming the variable; the rest still apply
+        assert_eq!(applied, ["GGG_MAX_CONCURRENT", "GGG_SCRIPTS_ENABLED"]);
+        assert_eq!(config.download.max_concurrent, 8);
+        assert!(!config.scripts.enabled);
+        assert_eq!(config.download.retry_count, Config::default().download.retry_count);
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected[0].contains("GGG_RETRY_COUNT"));
+    }
+
+    #[test]
+    fn test_env_overrides_that_fail_validation_are_skipped() {
+        let mut config = Config::default();
+        config.download.max_concurrent = 6;
+        config.download.max_concurrent_per_folder = Some(3);
+        config.download.parallel_folder_count = Some(2);
+
+        // Too low for the file's per-folder limit on its own
+        let (applied, rejected) =
+            config.clone().apply_overrides(|name| (name == "GGG_MAX_CONCURRENT").then(|| "2".to_string()));
+        assert!(applied.is_empty());
+        assert!(rejected[0].contains("GGG_MAX_CONCURRENT"));
+
+        // Valid once the per-folder override is applied as well
+        let vars = HashMap::from([("GGG_MAX_CONCURRENT", "2"), ("GGG_MAX_CONCURRENT_PER_FOLDER", "1")]);
+        let (applied, rejected) = config.apply_overrides(|name| vars.get(name).map(|v| v.to_string()));
+        assert_eq!(applied, ["GGG_MAX_CONCURRENT", "GGG_MAX_CONCURRENT_PER_FOLDER"]);
+        assert!(rejected.is_empty());
+        assert_eq!((config.download.max_concurrent, config.download.max_concurrent_per_folder), (2, Some(1)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_overrides_win_over_file_on_load() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        unsafe { std::env::set_var("GGG_TEST_MODE", "1") };
+
+        let mut file_config = Config::default();
+        file_config.download.max_concurrent = 3;
+        file_config.download.user_agent = "FromFile/1.0".to_string();
+        file_config.save().unwrap();
+
+        unsafe { std::env::set_var("GGG_MAX_CONCURRENT", "7") };
+        unsafe { std::env::set_var("GGG_RETRY_COUNT", "-1") };
+        let config = Config::load();
+        unsafe { std::env::remove_var("GGG_MAX_CONCURRENT") };
+        unsafe { std::env::remove_var("GGG_RETRY_COUNT") };
+        // A bad override does not fail the load; it is reported and skipped
+        let mut config = config.unwrap();
+        let problems = config.env_override_problems().to_vec();
+        // The overrides are not written back to the file, not even by a later save
+        let after_load = Config::load().unwrap();
+        config.save().unwrap();
+        let after_save = Config::load().unwrap();
+        // A setting changed after loading is saved like any other
+        config.download.user_agent = "Changed/2.0".to_string();
+        config.save().unwrap();
+        let after_change = Config::load().unwrap();
+
+        crate::util::paths::set_config_dir_override(None);
+        unsafe { std::env::remove_var("GGG_TEST_MODE") };
+
+        assert_eq!(config.download.max_concurrent, 7);
+        assert_eq!(config.download.retry_count, file_config.download.retry_count);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("GGG_RETRY_COUNT"));
+        assert_eq!(after_load.download.max_concurrent, 3);
+        assert_eq!(after_save.download.max_concurrent, 3);
+        assert_eq!(after_save.download.user_agent, "FromFile/1.0");
+        assert_eq!(after_change.download.user_agent, "Changed/2.0");
+        assert_eq!(after_change.download.max_concurrent, 3);
+    }
+
     #[test]
     #[serial]
     fn test_load_all_folder_configs_requires_settings_toml() {
@@ -1182,6 +2186,26 @@ auto_start_downloads = false
         assert_eq!(p, ReferrerPolicy::none());
     }
 
+    #[test]
+    fn test_logging_config_defaults_and_parse() {
+        let logging: LoggingConfig = toml::from_str("").unwrap();
+        assert_eq!(logging.console_format, ConsoleFormat::Pretty);
+        assert_eq!(logging.level, "info");
+        assert_eq!(logging.rotation, LogRotation::Daily);
+        assert_eq!(logging.max_files, 0);
+
+        let logging: LoggingConfig = toml::from_str(
+            "console_format = \"json\"\nrotation = \"hourly\"\nmax_files = 14",
+        )
+        .unwrap();
+        assert_eq!(logging.console_format, ConsoleFormat::Json);
+        assert_eq!(logging.rotation, LogRotation::Hourly);
+        assert_eq!(logging.max_files, 14);
+
+        assert_eq!("PRETTY".parse::<ConsoleFormat>().unwrap(), ConsoleFormat::Pretty);
+        assert!("xml".parse::<ConsoleFormat>().is_err());
+    }
+
     #[test]
     fn test_referrer_policy_backward_compat() {
         // Existing config without referrer_policy should deserialize fine
@@ -1197,4 +2221,90 @@ max_redirects = 5
         let config: DownloadConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.referrer_policy, ReferrerPolicy::default());
     }
+
+    #[test]
+    fn test_download_protocol_options() {
+        let toml_str = r#"
+default_directory = "C:\\Downloads"
+max_concurrent = 3
+retry_count = 3
+retry_delay = 5
+user_agent = "Test/1.0"
+bandwidth_limit = 0
+min_tls_version = "1.3"
+http2_prior_knowledge = true
+"#;
+        let config: DownloadConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.min_tls_version, Some(TlsVersion::Tls13));
+        assert!(config.http2_prior_knowledge);
+        assert!(!config.danger_accept_invalid_certs);
+        assert!(!config.deep_resume_probe);
+        assert!(!config.verify_resume_overlap);
+        assert_eq!(config.schedule_policy, SchedulePolicy::Fifo);
+
+        let with_policy = format!("{}schedule_policy = \"smallest_first\"\n", toml_str);
+        let config: DownloadConfig = toml::from_str(&with_policy).unwrap();
+        assert_eq!(config.schedule_policy, SchedulePolicy::SmallestFirst);
+        assert_eq!("Largest_First".parse::<SchedulePolicy>().unwrap(), SchedulePolicy::LargestFirst);
+        assert!("random".parse::<SchedulePolicy>().is_err());
+
+        assert_eq!("1.2".parse::<TlsVersion>().unwrap(), TlsVersion::Tls12);
+        assert!("1.0".parse::<TlsVersion>().is_err());
+        assert!(toml::from_str::<DownloadConfig>(&toml_str.replace("\"1.3\"", "\"1.1\"")).is_err());
+    }
+
+    #[test]
+    fn test_allowed_schemes() {
+        let mut download = Config::default().download;
+        for url in ["http://a.example/f", "HTTPS://a.example/f"] {
+            assert!(download.check_url_scheme(url).is_ok(), "{}", url);
+        }
+        // FTP is allowed by default but cannot be downloaded yet
+        for url in ["ftp://a.example/f", "FTPS://a.example/f"] {
+            let err = download.check_url_scheme(url).unwrap_err().to_string();
+            assert!(err.contains("not supported yet"), "{}", err);
+        }
+        for url in ["file:///etc/passwd", "javascript:alert(1)", "data:text/plain,hi"] {
+            assert!(download.check_url_scheme(url).is_err(), "{}", url);
+        }
+        assert!(download.check_url_scheme("not a url").unwrap_err().to_string().contains("not a valid URL"));
+
+        // With FTP turned off, only http and https remain
+        download.allowed_schemes = vec!["http".to_string(), "HTTPS".to_string()];
+        assert!(download.check_url_scheme("http://a.example/f").is_ok());
+        assert!(download.check_url_scheme("https://a.example/f").is_ok());
+        for url in ["ftp://a.example/f", "ftps://a.example/f"] {
+            let err = download.check_url_scheme(url).unwrap_err().to_string();
+            assert!(err.contains("download.allowed_schemes: http, HTTPS"), "{}", err);
+        }
+
+        let config: DownloadConfig = toml::from_str(
+            "default_directory = \"/tmp\"\nmax_concurrent = 3\nretry_count = 3\nretry_delay = 5\n\
+             user_agent = \"Test/1.0\"\nbandwidth_limit = 0\nallowed_schemes = [\"https\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.allowed_schemes, ["https"]);
+        assert!(config.check_url_scheme("http://a.example/f").is_err());
+    }
+
+    #[test]
+    fn test_circuit_breaker_scope() {
+        let toml_str = r#"
+default_directory = "C:\\Downloads"
+max_concurrent = 3
+retry_count = 3
+retry_delay = 5
+user_agent = "Test/1.0"
+bandwidth_limit = 0
+"#;
+        let config: DownloadConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.circuit_breaker.scope, CircuitScope::Domain);
+
+        let with_scope = format!("{}\n[circuit_breaker]\nscope = \"host+path-prefix\"\n", toml_str);
+        let config: DownloadConfig = toml::from_str(&with_scope).unwrap();
+        assert_eq!(config.circuit_breaker.scope, CircuitScope::HostPathPrefix);
+
+        assert_eq!("Domain".parse::<CircuitScope>().unwrap(), CircuitScope::Domain);
+        assert!("path".parse::<CircuitScope>().is_err());
+    }
 }
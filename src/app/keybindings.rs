@@ -21,6 +21,8 @@ pub enum KeyAction {
     FocusPrevPane,
     FocusLeft,
     FocusRight,
+    NextFolder,
+    PrevFolder,
 
     // Selection
     SelectItem,
@@ -33,6 +35,7 @@ pub enum KeyAction {
     DeleteDownload,
     ToggleDownload,
     RetryDownload,
+    RetryAllFailed,
     ResumeAll,
     PauseAll,
     OpenContextMenu,
@@ -44,6 +47,9 @@ pub enum KeyAction {
     OpenHelp,
     OpenSettings,
     SwitchFolder,
+    OpenStats,
+    OpenScriptErrors,
+    ToggleBandwidthGraph,
 
     // System
     Quit,
@@ -65,6 +71,8 @@ impl KeyAction {
             KeyAction::FocusPrevPane,
             KeyAction::FocusLeft,
             KeyAction::FocusRight,
+            KeyAction::NextFolder,
+            KeyAction::PrevFolder,
             KeyAction::SelectItem,
             KeyAction::ToggleSelection,
             KeyAction::SelectAll,
@@ -73,6 +81,7 @@ impl KeyAction {
             KeyAction::DeleteDownload,
             KeyAction::ToggleDownload,
             KeyAction::RetryDownload,
+            KeyAction::RetryAllFailed,
             KeyAction::ResumeAll,
             KeyAction::PauseAll,
             KeyAction::OpenContextMenu,
@@ -82,6 +91,9 @@ impl KeyAction {
             KeyAction::OpenHelp,
             KeyAction::OpenSettings,
             KeyAction::SwitchFolder,
+            KeyAction::OpenStats,
+            KeyAction::OpenScriptErrors,
+            KeyAction::ToggleBandwidthGraph,
             KeyAction::Quit,
             KeyAction::Undo,
             KeyAction::Refresh,
@@ -240,6 +252,8 @@ impl Default for KeybindingsConfig {
             KeyAction::FocusRight,
             KeyBindingSpec::Multiple(vec!["l".into(), "Right".into()]),
         );
+        bindings.insert(KeyAction::NextFolder, KeyBindingSpec::Single("]".into()));
+        bindings.insert(KeyAction::PrevFolder, KeyBindingSpec::Single("[".into()));
 
         // Selection
         bindings.insert(KeyAction::SelectItem, KeyBindingSpec::Single("Enter".into()));
@@ -255,6 +269,7 @@ impl Default for KeybindingsConfig {
         bindings.insert(KeyAction::DeleteDownload, KeyBindingSpec::Single("d".into()));
         bindings.insert(KeyAction::ToggleDownload, KeyBindingSpec::Single("Space".into()));
         bindings.insert(KeyAction::RetryDownload, KeyBindingSpec::Single("r".into()));
+        bindings.insert(KeyAction::RetryAllFailed, KeyBindingSpec::Single("Ctrl+r".into()));
         bindings.insert(KeyAction::ResumeAll, KeyBindingSpec::Single("S".into()));
         bindings.insert(KeyAction::PauseAll, KeyBindingSpec::Single("P".into()));
         bindings.insert(KeyAction::OpenContextMenu, KeyBindingSpec::Single("m".into()));
@@ -266,6 +281,9 @@ impl Default for KeybindingsConfig {
         bindings.insert(KeyAction::OpenHelp, KeyBindingSpec::Single("?".into()));
         bindings.insert(KeyAction::OpenSettings, KeyBindingSpec::Single("x".into()));
         bindings.insert(KeyAction::SwitchFolder, KeyBindingSpec::Single("F".into()));
+        bindings.insert(KeyAction::OpenStats, KeyBindingSpec::Single("T".into()));
+        bindings.insert(KeyAction::OpenScriptErrors, KeyBindingSpec::Single("E".into()));
+        bindings.insert(KeyAction::ToggleBandwidthGraph, KeyBindingSpec::Single("B".into()));
 
         // System
         bindings.insert(
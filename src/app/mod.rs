@@ -2,3 +2,4 @@ pub mod config;
 pub mod keybindings;
 pub mod settings;
 pub mod state;
+pub mod user_agent;
@@ -1,5 +1,7 @@
-use super::config::{Config, FolderConfig, ReferrerPolicy};
+use super::config::{Config, DownloadConfig, FolderConfig, ReferrerPolicy};
 use crate::download::task::DownloadTask;
+use crate::file::path_template::{expand_save_path_template, TemplateContext};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -81,15 +83,18 @@ impl ResolvedSettings {
             return task.save_path.clone();
         }
 
-        // Apply auto-date directory if enabled for this folder
-        if folder_config
-            .map(|f| f.auto_date_directory)
-            .unwrap_or(false)
-        {
-            let date_str = task.created_at.with_timezone(&chrono::Local).format("%Y%m%d").to_string();
-            base_path.join(date_str)
-        } else {
-            base_path
+        // Apply the folder's save-path template (auto_date_directory is a shorthand)
+        match folder_config.and_then(|f| f.effective_save_path_template()) {
+            Some(template) => expand_save_path_template(
+                template,
+                &base_path,
+                &TemplateContext {
+                    url: &task.url,
+                    folder_id: &task.folder_id,
+                    created_at: task.created_at,
+                },
+            ),
+            None => base_path,
         }
     }
 
@@ -121,6 +126,10 @@ pub enum ValidationError {
         parallel_folder_count: usize,
         calculated: usize,
     },
+    /// Connection limit of zero, which would never let a download start
+    NoConnections,
+    /// `chunk_size_bytes` above `MAX_CHUNK_SIZE_BYTES`
+    ChunkSizeTooLarge(usize),
     /// Folder max_concurrent exceeds application max_concurrent
     FolderMaxConcurrentExceedsApp {
         folder_id: String,
@@ -154,6 +163,17 @@ impl std::fmt::Display for ValidationError {
                     max_concurrent
                 )
             }
+            ValidationError::NoConnections => {
+                write!(f, "Max Connections must be at least 1")
+            }
+            ValidationError::ChunkSizeTooLarge(chunk_size) => {
+                write!(
+                    f,
+                    "Chunk size ({} bytes) must be at most {} bytes",
+                    chunk_size,
+                    crate::app::config::MAX_CHUNK_SIZE_BYTES
+                )
+            }
             ValidationError::FolderMaxConcurrentExceedsApp {
                 folder_id,
                 folder_max,
@@ -192,6 +212,16 @@ pub fn validate_folder_config(config: &Config) -> Result<(), Vec<ValidationError
         }
     }
 
+    // Downloads share connections request by request, so any limit above zero works
+    if config.download.max_connections == Some(0) {
+        errors.push(ValidationError::NoConnections);
+    }
+
+    // The download loop buffers one chunk per running download
+    if config.download.chunk_size_bytes > crate::app::config::MAX_CHUNK_SIZE_BYTES {
+        errors.push(ValidationError::ChunkSizeTooLarge(config.download.chunk_size_bytes));
+    }
+
     // Validate each folder
     for (folder_id, folder_config) in &config.folders {
         // Check script validation rule
@@ -220,10 +250,70 @@ pub fn validate_folder_config(config: &Config) -> Result<(), Vec<ValidationError
     }
 }
 
+/// Concurrency limit being edited, which a suggested adjustment keeps as entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyField {
+    MaxConcurrent,
+    MaxConcurrentPerFolder,
+    ParallelFolderCount,
+}
+
+/// Proposed values that satisfy `(per_folder * active_folders) <= max_concurrent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyAdjustment {
+    pub max_concurrent: usize,
+    /// Values as entered
+    pub requested_per_folder: usize,
+    pub requested_parallel_folders: usize,
+    /// Suggested values
+    pub max_concurrent_per_folder: usize,
+    pub parallel_folder_count: usize,
+}
+
+/// Suggest per-folder and active-folder limits that fit `max_concurrent`
+///
+/// Returns None if the limits already fit (or either one is unset). Like
+/// `DownloadManager::with_config`, the active folder count is kept and the
+/// per-folder limit is lowered, except when the per-folder limit is the
+/// field being edited: then the active folder count gives way instead.
+pub fn suggest_concurrency_adjustment(
+    download: &DownloadConfig,
+    edited: ConcurrencyField,
+) -> Option<ConcurrencyAdjustment> {
+    let per_folder = download.max_concurrent_per_folder?;
+    let parallel = download.parallel_folder_count?;
+    let max_concurrent = download.max_concurrent;
+    if per_folder * parallel <= max_concurrent {
+        return None;
+    }
+
+    let total = max_concurrent.max(1);
+    let (suggested_per_folder, suggested_parallel) = match edited {
+        ConcurrencyField::MaxConcurrentPerFolder => {
+            let per_folder = per_folder.clamp(1, total);
+            (per_folder, total / per_folder)
+        }
+        ConcurrencyField::MaxConcurrent | ConcurrencyField::ParallelFolderCount => {
+            let parallel = parallel.clamp(1, total);
+            (total / parallel, parallel)
+        }
+    };
+
+    Some(ConcurrencyAdjustment {
+        max_concurrent,
+        requested_per_folder: per_folder,
+        requested_parallel_folders: parallel,
+        max_concurrent_per_folder: suggested_per_folder,
+        parallel_folder_count: suggested_parallel,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::config::{Config, DownloadConfig, FolderConfig, GeneralConfig, NetworkConfig, ScriptConfig};
+    use crate::app::config::{
+        CircuitBreakerSettings, Config, DownloadConfig, FolderConfig, GeneralConfig, NetworkConfig, ScriptConfig,
+    };
     use chrono::Utc;
     use std::collections::HashMap;
     use std::path::PathBuf;
@@ -238,6 +328,11 @@ mod tests {
                 start_minimized: false,
                 skip_download_preview: true,
                 auto_launch_dnd: false,
+                pause_on_battery: false,
+                pause_on_metered: false,
+                completion_sound: None,
+                show_short_ids: false,
+                show_bandwidth_graph: false,
             },
             download: DownloadConfig {
                 default_directory: PathBuf::from("C:\\Downloads"),
@@ -245,11 +340,32 @@ mod tests {
                 retry_count: 5,
                 retry_delay: 3,
                 user_agent: "TestAgent/1.0".to_string(),
+                user_agent_rotate: Vec::new(),
                 bandwidth_limit: 0,
+                bandwidth_by_priority: false,
                 max_concurrent_per_folder: Some(2),
                 parallel_folder_count: Some(2),
                 max_redirects: 10,
+                allow_insecure_redirect: false,
                 referrer_policy: ReferrerPolicy::default(),
+                infer_extension: false,
+                chunk_size_bytes: crate::app::config::DEFAULT_CHUNK_SIZE_BYTES,
+                pool_idle_timeout_secs: 90,
+                pool_max_idle_per_host: 10,
+                http2_prior_knowledge: false,
+                min_tls_version: None,
+                danger_accept_invalid_certs: false,
+                deep_resume_probe: false,
+                verify_resume_overlap: false,
+                schedule_policy: Default::default(),
+                max_connections: None,
+                retry_on: Vec::new(),
+                max_retry_after_secs: 300,
+                allowed_schemes: crate::app::config::DEFAULT_ALLOWED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+                temp_dir: None,
+                on_duplicate_content: None,
+                confirm_over_bytes: None,
+                circuit_breaker: CircuitBreakerSettings::default(),
             },
             network: NetworkConfig {
                 proxy_enabled: false,
@@ -264,10 +380,17 @@ mod tests {
                 enabled: true,
                 directory: PathBuf::from("./scripts"),
                 timeout: 30,
+                auto_reload: false,
+                body_peek_bytes: 0,
                 script_files: HashMap::new(),
             },
             keybindings: crate::app::keybindings::KeybindingsConfig::default(),
+            history: crate::app::config::HistoryConfig::default(),
+            routing: crate::app::config::RoutingConfig::default(),
+            logging: crate::app::config::LoggingConfig::default(),
+            schedule: crate::app::config::ScheduleConfig::default(),
             folders: HashMap::new(),
+            env_overridden: None,
         }
     }
 
@@ -293,6 +416,14 @@ mod tests {
             error_message: None,
             logs: Vec::new(),
             last_status_code: None,
+            resolved_url: None,
+            routed_from: None,
+            resume_probed: false,
+            held_reason: None,
+            awaiting_confirmation: false,
+            confirmed_size: None,
+            speed_stats: Default::default(),
+            script_runs: Vec::new(),
             retry_count: 0,
         }
     }
@@ -309,6 +440,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\TestFolder"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -316,6 +448,8 @@ mod tests {
                 user_agent: Some("FolderAgent/1.0".to_string()),
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -344,6 +478,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\TestFolder"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -351,6 +486,8 @@ mod tests {
                 user_agent: Some("FolderAgent/1.0".to_string()),
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -396,6 +533,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\TestFolder"),
                 auto_date_directory: true,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -403,6 +541,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -420,6 +560,33 @@ mod tests {
         assert_eq!(resolved.save_path, expected_path);
     }
 
+    #[test]
+    fn test_save_path_template() {
+        // Test: template evaluated at task creation and kept on resolve
+        let mut config = create_test_config();
+        config.folders.insert(
+            "test_folder".to_string(),
+            FolderConfig {
+                save_path: PathBuf::from("C:\\TestFolder"),
+                auto_date_directory: true,
+                save_path_template: Some("{base}/{yyyy}/{host}".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let task = DownloadTask::new_with_folder(
+            "https://cdn.example.com/file.zip".to_string(),
+            "test_folder".to_string(),
+            &config,
+        );
+        let year = task.created_at.with_timezone(&chrono::Local).format("%Y").to_string();
+        let expected_path = PathBuf::from("C:\\TestFolder").join(year).join("cdn.example.com");
+        assert_eq!(task.save_path, expected_path);
+
+        let resolved = ResolvedSettings::resolve(&config, "test_folder", &task);
+        assert_eq!(resolved.save_path, expected_path);
+    }
+
     #[test]
     fn test_validation_scripts_disabled() {
         // Test: folder cannot enable scripts when app disables
@@ -435,6 +602,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\BadFolder"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: Some(true), // Try to enable at folder level
                 script_files: None,
@@ -442,6 +610,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -469,6 +639,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\Folder1"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: Some(true),
                 script_files: None,
@@ -476,6 +647,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -485,6 +658,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\Folder2"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: Some(false),
                 script_files: None,
@@ -492,6 +666,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -514,6 +690,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\TestFolder"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -521,6 +698,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: folder_headers,
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -555,6 +734,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\Folder1"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -562,6 +742,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -572,6 +754,7 @@ mod tests {
                 name: String::new(),
                 save_path: PathBuf::from("C:\\Folder2"),
                 auto_date_directory: false,
+                save_path_template: None,
                 auto_start_downloads: false,
                 scripts_enabled: None,
                 script_files: None,
@@ -579,6 +762,8 @@ mod tests {
                 user_agent: None,
                 referrer_policy: None,
                 default_headers: HashMap::new(),
+                max_queued: None,
+                max_inflight_bytes: None,
             },
         );
 
@@ -602,4 +787,64 @@ mod tests {
         // Should use app-level per-folder
         assert_eq!(resolved2.max_concurrent, 5);
     }
+
+    #[test]
+    fn test_suggest_concurrency_adjustment() {
+        let mut config = create_test_config();
+        config.download.max_concurrent = 8;
+        config.download.max_concurrent_per_folder = Some(3);
+        config.download.parallel_folder_count = Some(4);
+
+        // Editing the active folder count (or the global limit) lowers the per-folder limit
+        let adjustment =
+            suggest_concurrency_adjustment(&config.download, ConcurrencyField::ParallelFolderCount).unwrap();
+        assert_eq!((adjustment.max_concurrent_per_folder, adjustment.parallel_folder_count), (2, 4));
+        assert_eq!((adjustment.requested_per_folder, adjustment.requested_parallel_folders), (3, 4));
+
+        // Editing the per-folder limit keeps it and lowers the active folder count
+        let adjustment =
+            suggest_concurrency_adjustment(&config.download, ConcurrencyField::MaxConcurrentPerFolder).unwrap();
+        assert_eq!((adjustment.max_concurrent_per_folder, adjustment.parallel_folder_count), (3, 2));
+
+        // Values larger than the global limit are clamped to it
+        config.download.parallel_folder_count = Some(20);
+        let adjustment = suggest_concurrency_adjustment(&config.download, ConcurrencyField::MaxConcurrent).unwrap();
+        assert_eq!((adjustment.max_concurrent_per_folder, adjustment.parallel_folder_count), (1, 8));
+
+        // Limits that fit, or an unset limit, need no adjustment
+        config.download.parallel_folder_count = Some(2);
+        assert!(suggest_concurrency_adjustment(&config.download, ConcurrencyField::MaxConcurrent).is_none());
+        config.download.max_concurrent_per_folder = None;
+        config.download.parallel_folder_count = Some(20);
+        assert!(suggest_concurrency_adjustment(&config.download, ConcurrencyField::MaxConcurrent).is_none());
+    }
+
+    #[test]
+    fn test_validation_connection_limit() {
+        let mut config = create_test_config();
+        config.download.parallel_folder_count = Some(1);
+        assert!(validate_folder_config(&config).is_ok());
+
+        config.download.max_connections = Some(6);
+        assert!(validate_folder_config(&config).is_ok());
+
+        // Fewer connections than downloads is allowed
+        config.download.max_connections = Some(2);
+        assert!(validate_folder_config(&config).is_ok());
+
+        config.download.max_connections = Some(0);
+        let errors = validate_folder_config(&config).unwrap_err();
+        assert!(matches!(errors[..], [ValidationError::NoConnections]));
+    }
+
+    #[test]
+    fn test_validation_chunk_size_limit() {
+        let mut config = create_test_config();
+        config.download.chunk_size_bytes = crate::app::config::MAX_CHUNK_SIZE_BYTES;
+        assert!(validate_folder_config(&config).is_ok());
+
+        config.download.chunk_size_bytes += 1;
+        let errors = validate_folder_config(&config).unwrap_err();
+        assert!(matches!(errors[..], [ValidationError::ChunkSizeTooLarge(_)]));
+    }
 }
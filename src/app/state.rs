@@ -1,6 +1,7 @@
 use super::config::Config;
-use crate::script::{executor, message::ScriptRequest};
+use crate::script::{error_log::ScriptErrorLog, executor, message::ScriptRequest};
 use crate::util::i18n::LocalizationManager;
+use crate::util::schedule::ScheduleState;
 use anyhow::Result;
 use std::sync::{mpsc, Arc};
 use tokio::sync::RwLock;
@@ -15,6 +16,10 @@ pub struct AppState {
     /// When Some, scripts are enabled and requests are sent to the executor thread.
     /// The executor thread runs in a separate OS thread with its own ScriptManager.
     pub script_sender: Option<mpsc::Sender<ScriptRequest>>,
+    /// Recent script errors, fed by the executor thread
+    pub script_errors: ScriptErrorLog,
+    /// Current quiet-window state, set by `util::schedule::spawn_monitor`
+    pub schedule: ScheduleState,
 }
 
 impl AppState {
@@ -34,15 +39,19 @@ impl AppState {
             config: Arc::new(RwLock::new(config)),
             i18n: Self::create_i18n(language),
             script_sender: None,
+            script_errors: ScriptErrorLog::default(),
+            schedule: ScheduleState::default(),
         }
     }
 
     pub async fn new_with_scripts(config: Config, language: &str) -> Result<Self> {
         // Spawn script executor thread if scripts enabled
+        let script_errors = ScriptErrorLog::default();
         let script_sender = if config.scripts.enabled {
             let (tx, rx) = std::sync::mpsc::channel();
 
             let script_config = config.scripts.clone();
+            let error_log = script_errors.clone();
 
             // Spawn in a dedicated OS thread since ScriptManager (!Send) cannot cross thread boundaries
             std::thread::spawn(move || {
@@ -59,6 +68,8 @@ impl AppState {
                     }
                 };
 
+                script_manager.set_error_log(error_log);
+
                 // Load all scripts
                 if let Err(e) = script_manager.load_all_scripts() {
                     tracing::error!("Failed to load scripts: {}", e);
@@ -80,6 +91,8 @@ impl AppState {
             config: Arc::new(RwLock::new(config)),
             i18n: Self::create_i18n(language),
             script_sender,
+            script_errors,
+            schedule: ScheduleState::default(),
         })
     }
 
@@ -0,0 +1,144 @@
+//! Named user-agent presets
+//!
+//! `download.user_agent`, the entries of `download.user_agent_rotate` and a
+//! folder's `user_agent` accept `@name` (e.g. `@firefox`) instead of a full
+//! string. The preset is expanded when settings.toml is read, so the rest of
+//! ggg only ever sees real user-agent strings, and a value equal to a preset
+//! is written back as `@name`, so a preset updated here reaches every config
+//! that uses it.
+
+/// Default `download.user_agent` (the `@chrome` preset)
+pub const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Preset names and the user-agent strings they expand to
+pub const PRESETS: &[(&str, &str)] = &[
+    ("chrome", DEFAULT_USER_AGENT),
+    ("firefox", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0"),
+    (
+        "edge",
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0",
+    ),
+    (
+        "safari",
+        "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_2) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15",
+    ),
+    ("curl", "curl/8.5.0"),
+    ("wget", "Wget/1.21.4"),
+];
+
+/// Shown under `ggg config --help`
+pub const PRESET_HELP: &str = "User-agent presets (download.user_agent, folder user_agent): \
+@chrome, @firefox, @edge, @safari, @curl, @wget";
+
+/// The string a preset expands to (name without `@`, case-insensitive)
+pub fn preset(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+        .map(|(_, user_agent)| *user_agent)
+}
+
+/// Expand `@name` to its preset; other values are returned unchanged
+pub fn expand(value: &str) -> anyhow::Result<String> {
+    match value.strip_prefix('@') {
+        Some(name) => preset(name).map(str::to_string).ok_or_else(|| {
+            let names: Vec<String> = PRESETS.iter().map(|(name, _)| format!("@{}", name)).collect();
+            anyhow::anyhow!("unknown user agent preset '{}' (available: {})", value, names.join(", "))
+        }),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// `@name` for a string equal to a preset, otherwise the string itself
+fn collapse(value: &str) -> String {
+    PRESETS
+        .iter()
+        .find(|(_, user_agent)| *user_agent == value)
+        .map(|(name, _)| format!("@{}", name))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Serde adapter for `String` fields (`#[serde(with = "crate::app::user_agent")]`)
+pub fn serialize<S: serde::Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&collapse(value))
+}
+
+pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+    expand(&value).map_err(serde::de::Error::custom)
+}
+
+/// Serde adapter for `Option<String>` fields
+pub mod option {
+    pub fn serialize<S: serde::Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&super::collapse(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+        let value = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+        value.map(|value| super::expand(&value)).transpose().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serde adapter for `Vec<String>` fields
+pub mod list {
+    pub fn serialize<S: serde::Serializer>(value: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value.iter().map(|value| super::collapse(value)))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+        let values = <Vec<String> as serde::Deserialize>::deserialize(deserializer)?;
+        values.iter().map(|value| super::expand(value)).collect::<anyhow::Result<_>>().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_presets() {
+        assert_eq!(expand("@curl").unwrap(), "curl/8.5.0");
+        assert_eq!(expand("@FireFox").unwrap(), preset("firefox").unwrap());
+        assert_eq!(expand("MyAgent/1.0").unwrap(), "MyAgent/1.0");
+
+        let err = expand("@netscape").unwrap_err().to_string();
+        assert!(err.contains("@chrome, @firefox"), "{}", err);
+
+        // Every preset is listed in the help text
+        for (name, _) in PRESETS {
+            assert!(PRESET_HELP.contains(&format!("@{}", name)), "{} missing from help", name);
+        }
+    }
+
+    #[test]
+    fn test_presets_round_trip_through_toml() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Agents {
+            #[serde(with = "super")]
+            app: String,
+            #[serde(default, with = "super::option")]
+            folder: Option<String>,
+            #[serde(default, with = "super::list")]
+            rotate: Vec<String>,
+        }
+
+        let agents: Agents =
+            toml::from_str("app = \"@wget\"\nfolder = \"Custom/2.0\"\nrotate = [\"@curl\", \"Other/1.0\"]").unwrap();
+        assert_eq!(agents.app, "Wget/1.21.4");
+        assert_eq!(agents.folder.as_deref(), Some("Custom/2.0"));
+        assert_eq!(agents.rotate, ["curl/8.5.0", "Other/1.0"]);
+
+        // Preset strings are saved by name, others verbatim
+        let saved = toml::to_string(&agents).unwrap();
+        assert!(saved.contains("app = \"@wget\""), "{}", saved);
+        assert!(saved.contains("folder = \"Custom/2.0\""), "{}", saved);
+        assert!(saved.contains("rotate = [\"@curl\", \"Other/1.0\"]"), "{}", saved);
+
+        assert!(toml::from_str::<Agents>("app = \"@nope\"").is_err());
+    }
+}
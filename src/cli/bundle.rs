@@ -0,0 +1,512 @@
+//! Whole-state bundles for `ggg export all` / `ggg import all`
+//!
+//! A bundle is one tar archive holding a manifest, the configuration
+//! (settings.toml and every folder, as `ggg export config` writes it), the
+//! download queue, the scripts directory and, with `--history`, the
+//! completion log, so moving to another machine takes a single file. Secrets
+//! (the proxy password, passwords in URLs and credential headers) are left out
+//! unless `--include-secrets` is given.
+//!
+//! Import merges by default: local settings win, and only folders, downloads,
+//! scripts and history entries that do not exist yet are added. `--replace`
+//! swaps the configuration and queue for the bundle's and overwrites scripts
+//! with the same name; history is only ever appended. Both go through the
+//! checks and backups of `cli::import`.
+
+use super::error;
+use super::import;
+use crate::app::config::Config;
+use crate::app::state::AppState;
+use crate::download::completion_log::{self, CompletedEntry};
+use crate::download::manager::DownloadManager;
+use crate::download::task::DownloadTask;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// `format` of every bundle
+pub const BUNDLE_FORMAT: &str = "ggg-bundle";
+
+/// Bundle layout version written by this build; newer bundles are refused
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Headers dropped from downloads and folder defaults without `--include-secrets`
+const SECRET_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie"];
+
+/// Archive members
+const MANIFEST_ENTRY: &str = "manifest.json";
+const CONFIG_ENTRY: &str = "settings.toml";
+const QUEUE_ENTRY: &str = "queue.json";
+const HISTORY_ENTRY: &str = "history.json";
+const SCRIPTS_PREFIX: &str = "scripts/";
+
+/// `manifest.json`: what wrote the bundle, checked before anything else is read
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    version: u32,
+    ggg_version: String,
+    created_at: DateTime<Utc>,
+    includes_secrets: bool,
+}
+
+/// Everything `ggg export all` writes
+#[derive(Debug)]
+pub struct Bundle {
+    pub version: u32,
+    /// ggg version that wrote the bundle
+    pub ggg_version: String,
+    pub created_at: DateTime<Utc>,
+    /// False if secrets were stripped on export
+    pub includes_secrets: bool,
+    /// Settings and folder configs as TOML
+    pub config: String,
+    pub tasks: Vec<DownloadTask>,
+    /// Script file name -> source
+    pub scripts: BTreeMap<String, String>,
+    pub history: Option<Vec<CompletedEntry>>,
+}
+
+impl Bundle {
+    /// Build a bundle, stripping secrets unless `include_secrets` is set
+    pub fn new(
+        mut config: Config,
+        mut tasks: Vec<DownloadTask>,
+        scripts: BTreeMap<String, String>,
+        mut history: Option<Vec<CompletedEntry>>,
+        include_secrets: bool,
+    ) -> Result<Self> {
+        if !include_secrets {
+            strip_secrets(&mut config, &mut tasks, history.as_deref_mut());
+        }
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            ggg_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            includes_secrets: include_secrets,
+            config: toml::to_string_pretty(&config)?,
+            tasks,
+            scripts,
+            history,
+        })
+    }
+
+    /// Write the bundle as a tar archive
+    pub fn write_to(&self, writer: impl Write) -> Result<()> {
+        let manifest = Manifest {
+            format: BUNDLE_FORMAT.to_string(),
+            version: self.version,
+            ggg_version: self.ggg_version.clone(),
+            created_at: self.created_at,
+            includes_secrets: self.includes_secrets,
+        };
+        let mtime = self.created_at.timestamp().max(0) as u64;
+        let mut archive = tar::Builder::new(writer);
+        append_entry(&mut archive, MANIFEST_ENTRY, serde_json::to_string_pretty(&manifest)?.as_bytes(), mtime)?;
+        append_entry(&mut archive, CONFIG_ENTRY, self.config.as_bytes(), mtime)?;
+        append_entry(&mut archive, QUEUE_ENTRY, serde_json::to_string_pretty(&self.tasks)?.as_bytes(), mtime)?;
+        for (name, source) in &self.scripts {
+            append_entry(&mut archive, &format!("{}{}", SCRIPTS_PREFIX, name), source.as_bytes(), mtime)?;
+        }
+        if let Some(ref history) = self.history {
+            append_entry(&mut archive, HISTORY_ENTRY, serde_json::to_string_pretty(history)?.as_bytes(), mtime)?;
+        }
+        archive.into_inner()?.flush()?;
+        Ok(())
+    }
+
+    /// Read a bundle archive, refusing other files and newer layouts
+    pub fn read_from(reader: impl Read) -> Result<Self> {
+        let not_a_bundle = || error::usage("Not a ggg bundle (use `ggg export all` to create one)");
+
+        let mut entries = BTreeMap::new();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries().map_err(|_| not_a_bundle())? {
+            let mut entry = entry.map_err(|_| not_a_bundle())?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut content = String::new();
+            entry
+                .read_to_string(&mut content)
+                .with_context(|| format!("invalid {} in bundle", path))?;
+            entries.insert(path, content);
+        }
+
+        let manifest: serde_json::Value = entries
+            .remove(MANIFEST_ENTRY)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .ok_or_else(not_a_bundle)?;
+        if manifest.get("format").and_then(|f| f.as_str()) != Some(BUNDLE_FORMAT) {
+            return Err(not_a_bundle());
+        }
+        let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > BUNDLE_VERSION as u64 {
+            return Err(error::usage(format!(
+                "Bundle version {} is not supported by this ggg (supports up to {})",
+                version, BUNDLE_VERSION
+            )));
+        }
+        let manifest: Manifest = serde_json::from_value(manifest).context("invalid manifest in bundle")?;
+
+        let config = entries.remove(CONFIG_ENTRY).context("bundle has no settings.toml")?;
+        let tasks = match entries.remove(QUEUE_ENTRY) {
+            Some(queue) => serde_json::from_str(&queue).context("invalid queue in bundle")?,
+            None => Vec::new(),
+        };
+        let history = entries
+            .remove(HISTORY_ENTRY)
+            .map(|history| serde_json::from_str(&history).context("invalid history in bundle"))
+            .transpose()?;
+        let scripts = entries
+            .into_iter()
+            .filter_map(|(path, source)| path.strip_prefix(SCRIPTS_PREFIX).map(|name| (name.to_string(), source)))
+            .collect();
+
+        Ok(Self {
+            version: manifest.version,
+            ggg_version: manifest.ggg_version,
+            created_at: manifest.created_at,
+            includes_secrets: manifest.includes_secrets,
+            config,
+            tasks,
+            scripts,
+            history,
+        })
+    }
+
+    /// The bundled configuration
+    pub fn config(&self) -> Result<Config> {
+        toml::from_str(&self.config).context("invalid configuration in bundle")
+    }
+}
+
+/// Add a regular file to the archive
+fn append_entry<W: Write>(archive: &mut tar::Builder<W>, path: &str, data: &[u8], mtime: u64) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    archive.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+fn strip_headers(headers: &mut HashMap<String, String>) {
+    headers.retain(|name, _| !SECRET_HEADERS.contains(&name.to_ascii_lowercase().as_str()));
+}
+
+fn strip_url(url: &mut String) {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return;
+    };
+    if parsed.password().is_some() && parsed.set_password(None).is_ok() {
+        *url = parsed.to_string();
+    }
+}
+
+/// Remove the proxy password, URL passwords and credential headers
+fn strip_secrets(config: &mut Config, tasks: &mut [DownloadTask], history: Option<&mut [CompletedEntry]>) {
+    config.network.proxy_pass.clear();
+    for folder in config.folders.values_mut() {
+        strip_headers(&mut folder.default_headers);
+    }
+    for task in tasks {
+        strip_headers(&mut task.headers);
+        strip_url(&mut task.url);
+        if let Some(ref mut resolved_url) = task.resolved_url {
+            strip_url(resolved_url);
+        }
+    }
+    for entry in history.into_iter().flatten() {
+        strip_url(&mut entry.url);
+    }
+}
+
+/// Script files (`*.js`) in `dir`; empty if the directory does not exist
+pub fn read_scripts(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut scripts = BTreeMap::new();
+    if !dir.is_dir() {
+        return Ok(scripts);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "js") {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            scripts.insert(name.to_string(), std::fs::read_to_string(&path)?);
+        }
+    }
+    Ok(scripts)
+}
+
+/// Write bundled scripts to `dir`, keeping existing files unless `overwrite`
+///
+/// Names that are not plain `*.js` file names are skipped. Returns the number
+/// of files written.
+pub fn write_scripts(dir: &Path, scripts: &BTreeMap<String, String>, overwrite: bool) -> Result<usize> {
+    let mut written = 0;
+    for (name, source) in scripts {
+        let is_plain = Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name.as_str());
+        if !is_plain || !name.ends_with(".js") {
+            tracing::warn!("Skipping bundled script with invalid name: {}", name);
+            continue;
+        }
+        let path = dir.join(name);
+        if path.exists() && !overwrite {
+            continue;
+        }
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(&path, source)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// `path` relative to the config directory when it is inside it
+///
+/// A bundled scripts directory under the exporting machine's config directory
+/// then resolves against the importing machine's config directory.
+fn relative_to_config_dir(path: &Path) -> PathBuf {
+    crate::util::paths::find_config_directory()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Where this machine keeps the bundle's scripts directory, if anywhere
+///
+/// Only a directory exported relative to the config directory is used, and
+/// it resolves inside this machine's config directory. An absolute path, or
+/// one that climbs out with `..`, would let `--replace` write scripts (and
+/// point the configuration) anywhere, so the local directory is kept instead.
+fn bundled_scripts_dir(bundled: &Path) -> Option<PathBuf> {
+    let inside = !bundled.as_os_str().is_empty()
+        && bundled.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    inside.then(|| crate::util::paths::resolve_relative_to_config(bundled))
+}
+
+/// Add the bundle's folders that do not exist locally; returns how many were added
+fn merge_folders(local: &mut Config, imported: Config) -> usize {
+    let mut added = 0;
+    for (id, folder) in imported.folders {
+        if let Entry::Vacant(slot) = local.folders.entry(id) {
+            slot.insert(folder);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// `ggg export all`
+pub async fn handle_export_all(
+    state: &AppState,
+    manager: &DownloadManager,
+    output: String,
+    history: bool,
+    include_secrets: bool,
+) -> Result<i32> {
+    // Export the settings as saved, not this run's environment overrides
+    let mut config = state.config.read().await.without_env_overrides().into_owned();
+    let scripts = read_scripts(&config.scripts.directory)?;
+    config.scripts.directory = relative_to_config_dir(&config.scripts.directory);
+    let tasks = manager.get_all_downloads().await;
+    let history = history.then(|| completion_log::read_entries(None)).transpose()?;
+
+    let bundle = Bundle::new(config, tasks, scripts, history, include_secrets)?;
+    let file = std::fs::File::create(PathBuf::from(&output))?;
+    bundle.write_to(std::io::BufWriter::new(file))?;
+
+    println!(
+        "Exported configuration, {} task(s) and {} script(s) to {}",
+        bundle.tasks.len(),
+        bundle.scripts.len(),
+        output
+    );
+    if let Some(ref history) = bundle.history {
+        println!("Included {} history entries", history.len());
+    }
+    if !include_secrets {
+        println!("Secrets were left out (use --include-secrets to keep them)");
+    }
+    Ok(error::SUCCESS)
+}
+
+/// `ggg import all`
+pub async fn handle_import_all(
+    state: &AppState,
+    manager: &DownloadManager,
+    input: String,
+    replace: bool,
+) -> Result<i32> {
+    let path = PathBuf::from(&input);
+    if !path.exists() {
+        return Err(error::not_found(format!("File not found: {}", input)));
+    }
+    let bundle = Bundle::read_from(std::io::BufReader::new(std::fs::File::open(&path)?))?;
+    let mut imported_config = bundle.config()?;
+
+    let mut config = state.config.write().await;
+    // Scripts land in this machine's config directory, or in the local scripts directory
+    imported_config.scripts.directory = bundled_scripts_dir(&imported_config.scripts.directory)
+        .unwrap_or_else(|| config.scripts.directory.clone());
+    let (new_config, folders_added) = if replace {
+        if !bundle.includes_secrets && imported_config.network.proxy_pass.is_empty() {
+            imported_config.network.proxy_pass = config.network.proxy_pass.clone();
+        }
+        (imported_config, 0)
+    } else {
+        let mut merged = config.clone();
+        let added = merge_folders(&mut merged, imported_config);
+        (merged, added)
+    };
+    let problems = import::config_problems(&new_config);
+    if !problems.is_empty() {
+        return Err(error::usage(format!("Cannot import {}: {}", input, problems.join("; "))));
+    }
+
+    let current = manager.get_all_downloads().await;
+    let existing: HashSet<_> = if replace { HashSet::new() } else { current.iter().map(|t| t.id).collect() };
+    let tasks = import::check_tasks(bundle.tasks, &new_config, &existing);
+
+    let mut files = import::config_files(&config)?;
+    files.extend(import::queue_files(&config)?);
+    import::print_backup(import::backup_files(&files)?);
+
+    *config = new_config;
+    config.save()?;
+    if replace {
+        println!("Replaced configuration ({} folder(s))", config.folders.len());
+    } else {
+        println!("Kept local settings; added {} folder(s)", folders_added);
+    }
+    let script_dir = config.scripts.directory.clone();
+    drop(config);
+
+    if replace {
+        for task in current {
+            manager.remove_download(task.id).await;
+        }
+    }
+    let added = tasks.accepted.len();
+    for task in tasks.accepted {
+        manager.add_download(task).await;
+    }
+    manager.save_queue_to_folders().await?;
+    println!("Imported {} task(s)", added);
+    import::print_skipped(&tasks.skipped);
+
+    let scripts = write_scripts(&script_dir, &bundle.scripts, replace)?;
+    println!("Imported {} script(s)", scripts);
+
+    if let Some(ref history) = bundle.history {
+        let written = completion_log::append_entries(history)?;
+        println!("Imported {} history entries", written);
+    }
+
+    if !bundle.includes_secrets {
+        println!("Note: The bundle has no secrets; re-enter credentials where needed");
+    }
+    println!("Note: Application restart may be required for some settings to take effect");
+    Ok(error::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_strips_secrets_and_round_trips() {
+        let mut config = Config::default();
+        config.network.proxy_pass = "hunter2".to_string();
+        let mut task = DownloadTask::new("https://user:pw@example.com/a.zip".to_string(), PathBuf::from("/tmp"));
+        task.headers.insert("Cookie".to_string(), "session=1".to_string());
+        task.headers.insert("Accept".to_string(), "*/*".to_string());
+
+        let scripts = BTreeMap::from([("a.js".to_string(), "// a".to_string())]);
+        let bundle = Bundle::new(config.clone(), vec![task.clone()], scripts.clone(), Some(Vec::new()), false).unwrap();
+        let mut archive = Vec::new();
+        bundle.write_to(&mut archive).unwrap();
+        let raw = String::from_utf8_lossy(&archive);
+        assert!(!raw.contains("hunter2") && !raw.contains("session=1") && !raw.contains(":pw@"), "{}", raw);
+
+        let parsed = Bundle::read_from(archive.as_slice()).unwrap();
+        assert!(!parsed.includes_secrets);
+        assert_eq!(parsed.scripts, scripts);
+        assert_eq!(parsed.history.as_ref().map(Vec::len), Some(0));
+        assert_eq!(parsed.tasks[0].url, "https://user@example.com/a.zip");
+        assert_eq!(parsed.tasks[0].headers.get("Accept").map(String::as_str), Some("*/*"));
+        assert_eq!(parsed.config().unwrap().folders.len(), config.folders.len());
+
+        // Kept when asked for
+        let bundle = Bundle::new(config, vec![task], BTreeMap::new(), None, true).unwrap();
+        assert_eq!(bundle.config().unwrap().network.proxy_pass, "hunter2");
+        assert!(bundle.tasks[0].headers.contains_key("Cookie"));
+    }
+
+    #[test]
+    fn test_read_refuses_other_files_and_newer_versions() {
+        let err = Bundle::read_from("[]".as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("Not a ggg bundle"), "{}", err);
+
+        let mut bundle = Bundle::new(Config::default(), Vec::new(), BTreeMap::new(), None, false).unwrap();
+        bundle.version = BUNDLE_VERSION + 1;
+        let mut archive = Vec::new();
+        bundle.write_to(&mut archive).unwrap();
+        let err = Bundle::read_from(archive.as_slice()).unwrap_err().to_string();
+        assert!(err.contains("not supported"), "{}", err);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_scripts_directory_relative_to_config_dir() {
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+        let inside = relative_to_config_dir(&config_dir.path().join("scripts"));
+        let outside = relative_to_config_dir(Path::new("/srv/ggg-scripts"));
+        crate::util::paths::set_config_dir_override(None);
+
+        assert_eq!(inside, PathBuf::from("scripts"));
+        assert_eq!(outside, PathBuf::from("/srv/ggg-scripts"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_bundled_scripts_dir_stays_in_config_dir() {
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+        let inside = bundled_scripts_dir(Path::new("scripts"));
+        let absolute = bundled_scripts_dir(Path::new("/srv/ggg-scripts"));
+        let climbing = bundled_scripts_dir(Path::new("scripts/../../elsewhere"));
+        crate::util::paths::set_config_dir_override(None);
+
+        assert_eq!(inside, Some(config_dir.path().join("scripts")));
+        assert_eq!(absolute, None);
+        assert_eq!(climbing, None);
+    }
+
+    #[test]
+    fn test_scripts_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("a.js"), "// a").unwrap();
+        std::fs::write(source.path().join("notes.txt"), "x").unwrap();
+        let scripts = read_scripts(source.path()).unwrap();
+        assert_eq!(scripts.keys().collect::<Vec<_>>(), ["a.js"]);
+
+        let target = tempfile::tempdir().unwrap();
+        std::fs::write(target.path().join("a.js"), "// local").unwrap();
+        let mut bundled = scripts.clone();
+        bundled.insert("../evil.js".to_string(), String::new());
+
+        // Merge keeps local files; replace overwrites them; unsafe names never land
+        assert_eq!(write_scripts(target.path(), &bundled, false).unwrap(), 0);
+        assert_eq!(std::fs::read_to_string(target.path().join("a.js")).unwrap(), "// local");
+        assert_eq!(write_scripts(target.path(), &bundled, true).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(target.path().join("a.js")).unwrap(), "// a");
+    }
+}
@@ -1,41 +1,76 @@
-use crate::download::manager::DownloadManager;
+use crate::app::state::AppState;
+use crate::download::manager::{DownloadManager, DEFAULT_SHUTDOWN_GRACE};
 use anyhow::Result;
 use tokio::signal;
 
+/// Wait for a termination signal and return its name
+#[cfg(unix)]
+async fn wait_for_signal() -> Result<&'static str> {
+    use signal::unix::{signal as unix_signal, SignalKind};
+
+    let mut sigterm = unix_signal(SignalKind::terminate())?;
+    let mut sigint = unix_signal(SignalKind::interrupt())?;
+
+    tokio::select! {
+        _ = sigint.recv() => Ok("SIGINT"),
+        _ = sigterm.recv() => Ok("SIGTERM"),
+    }
+}
+
+/// Wait for a termination signal and return its name
+#[cfg(windows)]
+async fn wait_for_signal() -> Result<&'static str> {
+    let mut ctrl_break = signal::windows::ctrl_break()?;
+    let mut ctrl_close = signal::windows::ctrl_close()?;
+    let mut ctrl_shutdown = signal::windows::ctrl_shutdown()?;
+
+    tokio::select! {
+        result = signal::ctrl_c() => result.map(|_| "Ctrl+C").map_err(Into::into),
+        _ = ctrl_break.recv() => Ok("Ctrl+Break"),
+        _ = ctrl_close.recv() => Ok("CTRL_CLOSE"),
+        _ = ctrl_shutdown.recv() => Ok("CTRL_SHUTDOWN"),
+    }
+}
+
 /// Run in headless daemon mode
-pub async fn run_daemon(manager: DownloadManager) -> Result<()> {
+pub async fn run_daemon(state: AppState, manager: DownloadManager) -> Result<()> {
     tracing::info!("Starting daemon mode...");
-    tracing::info!("Press Ctrl+C to stop");
-
-    // Clone manager for auto-save task
-    let manager_clone = manager.clone();
+    tracing::info!("Press Ctrl+C (or send SIGTERM) to stop");
 
-    // Spawn auto-save task
-    let auto_save_handle = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+    // Save modified folder queues in the background
+    let auto_save_handle = manager.spawn_queue_saver();
 
-        loop {
-            interval.tick().await;
+    // Pause/resume downloads based on battery and metered connection state
+    let power_monitor_handle = crate::util::power::spawn_monitor(manager.clone(), state.clone());
 
-            match manager_clone.save_queue_to_folders().await {
-                Ok(_) => tracing::debug!("Queue auto-saved to folder files"),
-                Err(e) => tracing::error!("Failed to auto-save queue: {}", e),
-            }
-        }
-    });
+    // Pause or throttle downloads during [schedule] quiet windows
+    let schedule_monitor_handle = crate::util::schedule::spawn_monitor(manager.clone(), state);
 
-    // Wait for Ctrl+C
-    match signal::ctrl_c().await {
-        Ok(()) => {
-            tracing::info!("Received Ctrl+C, shutting down...");
+    // Wait for SIGINT/SIGTERM
+    match wait_for_signal().await {
+        Ok(name) => {
+            tracing::info!("Received {}, shutting down...", name);
         }
         Err(e) => {
-            tracing::error!("Error waiting for Ctrl+C: {}", e);
+            tracing::error!("Error waiting for termination signal: {}", e);
         }
     }
 
+    // A second signal skips the graceful shutdown
+    tokio::spawn(async {
+        if let Ok(name) = wait_for_signal().await {
+            tracing::warn!("Received {} again, forcing exit", name);
+            std::process::exit(1);
+        }
+    });
+
     // Cancel auto-save task
     auto_save_handle.abort();
+    power_monitor_handle.abort();
+    schedule_monitor_handle.abort();
+
+    // Stop active downloads at a chunk boundary so progress can be resumed
+    manager.shutdown(DEFAULT_SHUTDOWN_GRACE).await;
 
     // Save queue one last time
     tracing::info!("Saving queue to folder files...");
@@ -0,0 +1,251 @@
+//! Environment diagnostics for `ggg doctor`
+//!
+//! Each check reports pass, warn or fail with a one-line detail. Warnings
+//! are things ggg copes with on its own (a save directory that is created
+//! when the first download starts, scripts enabled without a scripts
+//! directory); failures are things that stop downloads or settings from
+//! working. The command exits with `ERROR` if any check failed.
+
+use super::{error, output};
+use crate::app::config::Config;
+use crate::app::state::AppState;
+use crate::download::http_client::HttpClient;
+use crate::download::manager::DownloadManager;
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// URL requested by the network check unless `--url` is given
+pub const DEFAULT_TEST_URL: &str = "https://example.com/";
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// One diagnostic result
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status, detail: detail.into() }
+    }
+}
+
+/// Create and delete a scratch file to see whether `dir` accepts new files
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".ggg-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Config directory writable and configuration files readable
+fn check_config() -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    match crate::util::paths::find_config_directory() {
+        Ok(dir) => {
+            let detail = dir.display().to_string();
+            checks.push(match probe_writable(&dir) {
+                Ok(()) => Check::new("Config directory", CheckStatus::Pass, detail),
+                Err(e) => Check::new(
+                    "Config directory",
+                    CheckStatus::Fail,
+                    format!("{} is not writable ({}); settings changes cannot be saved", detail, e),
+                ),
+            });
+        }
+        Err(e) => checks.push(Check::new("Config directory", CheckStatus::Fail, e.to_string())),
+    }
+
+    checks.push(match Config::load() {
+        Ok(_) => Check::new("Configuration", CheckStatus::Pass, "settings.toml and folder configs load"),
+        Err(e) => Check::new("Configuration", CheckStatus::Fail, format!("{:#}; defaults are used instead", e)),
+    });
+
+    checks
+}
+
+/// A save directory exists and is writable (missing ones are created on demand)
+pub fn check_save_path(name: &str, path: &Path) -> Check {
+    let detail = path.display().to_string();
+    if !path.exists() {
+        return Check::new(name, CheckStatus::Warn, format!("{} does not exist yet; it is created when a download starts", detail));
+    }
+    if !path.is_dir() {
+        return Check::new(name, CheckStatus::Fail, format!("{} is not a directory", detail));
+    }
+    match probe_writable(path) {
+        Ok(()) => Check::new(name, CheckStatus::Pass, detail),
+        Err(e) => Check::new(name, CheckStatus::Fail, format!("{} is not writable ({})", detail, e)),
+    }
+}
+
+/// Scripts directory present when scripts are enabled
+fn check_scripts(config: &Config) -> Check {
+    let dir = &config.scripts.directory;
+    if !config.scripts.enabled {
+        Check::new("Scripts directory", CheckStatus::Pass, "scripts are disabled")
+    } else if dir.is_dir() {
+        Check::new("Scripts directory", CheckStatus::Pass, dir.display().to_string())
+    } else {
+        Check::new(
+            "Scripts directory",
+            CheckStatus::Warn,
+            format!("{} not found; no scripts will run", dir.display()),
+        )
+    }
+}
+
+/// The test URL answers (any HTTP status counts as reachable)
+pub async fn check_network(client: &HttpClient, url: &str) -> Check {
+    match client.get_info_with_probe(url, &Default::default(), false).await {
+        Ok(info) => Check::new("Network", CheckStatus::Pass, format!("{} is reachable (HTTP {})", url, info.status)),
+        Err(e) => Check::new("Network", CheckStatus::Fail, format!("{} is not reachable: {:#}", url, e)),
+    }
+}
+
+/// ggg-dnd.exe next to ggg.exe (required for `general.auto_launch_dnd`)
+#[cfg(windows)]
+fn check_dnd(config: &Config) -> Check {
+    let exe_path = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.join("ggg-dnd.exe")));
+    match exe_path {
+        Some(path) if path.exists() => Check::new("ggg-dnd", CheckStatus::Pass, path.display().to_string()),
+        _ if config.general.auto_launch_dnd => Check::new(
+            "ggg-dnd",
+            CheckStatus::Fail,
+            "ggg-dnd.exe not found next to ggg.exe, but auto_launch_dnd is enabled",
+        ),
+        _ => Check::new("ggg-dnd", CheckStatus::Warn, "ggg-dnd.exe not found next to ggg.exe; drag and drop is unavailable"),
+    }
+}
+
+/// Run every check in order
+pub async fn run_checks(state: &AppState, manager: &DownloadManager, url: Option<&str>) -> Vec<Check> {
+    let mut checks = check_config();
+
+    let config = state.config.read().await.clone();
+    checks.push(check_scripts(&config));
+    checks.push(check_save_path("Default directory", &config.download.default_directory));
+    if let Some(ref temp_dir) = config.download.temp_dir {
+        checks.push(check_save_path("Temp directory", temp_dir));
+    }
+    let mut folders: Vec<_> = config.folders.values().collect();
+    folders.sort_by(|a, b| a.name.cmp(&b.name));
+    for folder in folders {
+        checks.push(check_save_path(&format!("Folder '{}'", folder.name), &folder.save_path));
+    }
+
+    if let Some(url) = url {
+        checks.push(check_network(&manager.http_client(), url).await);
+    }
+
+    #[cfg(windows)]
+    checks.push(check_dnd(&config));
+
+    checks
+}
+
+/// Exit code for a set of results: `ERROR` if anything failed
+pub fn exit_code(checks: &[Check]) -> i32 {
+    if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+        error::ERROR
+    } else {
+        error::SUCCESS
+    }
+}
+
+/// `ggg doctor`
+pub async fn handle_doctor(
+    state: &AppState,
+    manager: &DownloadManager,
+    url: Option<String>,
+    skip_network: bool,
+    json: bool,
+) -> Result<i32> {
+    let url = (!skip_network).then(|| url.unwrap_or_else(|| DEFAULT_TEST_URL.to_string()));
+    let checks = run_checks(state, manager, url.as_deref()).await;
+
+    if json {
+        output::emit(&checks)?;
+    } else {
+        for check in &checks {
+            println!("[{}] {}: {}", check.status.label(), check.name, check.detail);
+        }
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        println!(
+            "\n{} passed, {} warning(s), {} failed",
+            count(CheckStatus::Pass),
+            count(CheckStatus::Warn),
+            count(CheckStatus::Fail)
+        );
+    }
+
+    Ok(exit_code(&checks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_path_checks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_save_path("dir", temp_dir.path()).status, CheckStatus::Pass);
+        assert_eq!(check_save_path("dir", &temp_dir.path().join("missing")).status, CheckStatus::Warn);
+
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert_eq!(check_save_path("file", &file).status, CheckStatus::Fail);
+        // The write probe leaves nothing behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_network_check() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        let client = HttpClient::new().unwrap();
+
+        // An HTTP error still means the server was reached
+        let check = check_network(&client, &mock_server.uri()).await;
+        assert_eq!(check.status, CheckStatus::Pass);
+
+        let check = check_network(&client, "http://127.0.0.1:1/").await;
+        assert_eq!(check.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_exit_code_reflects_failures() {
+        let pass = Check::new("a", CheckStatus::Pass, "");
+        let warn = Check::new("b", CheckStatus::Warn, "");
+        assert_eq!(exit_code(&[pass.clone(), warn.clone()]), error::SUCCESS);
+        assert_eq!(exit_code(&[pass, warn, Check::new("c", CheckStatus::Fail, "")]), error::ERROR);
+    }
+}
@@ -1,5 +1,68 @@
-// Exit codes for CLI automation
+//! Exit codes for CLI automation
+//!
+//! Every command exits with one of these codes so scripts can branch on the
+//! outcome. Handlers either return a code directly or fail with an
+//! `ExitError` (see `usage`, `not_found`, `download_failed`), which
+//! `handle_command` maps back to its code; any other error exits with `ERROR`.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0    | Success |
+//! | 1    | Unexpected error (I/O, invalid configuration, ...) |
+//! | 2    | Usage error: invalid arguments or input (clap uses 2 as well) |
+//! | 3    | Not found: download, folder, script or file does not exist |
+//! | 4    | A download failed or was deleted |
+//! | 130  | Interrupted with Ctrl-C |
+
 pub const SUCCESS: i32 = 0;
 pub const ERROR: i32 = 1;
-pub const NOT_FOUND: i32 = 2;
-pub const INVALID_INPUT: i32 = 3;
+pub const USAGE: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+pub const DOWNLOAD_FAILED: i32 = 4;
+pub const INTERRUPTED: i32 = 130;
+
+/// Error carrying a specific exit code
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct ExitError {
+    pub code: i32,
+    pub message: String,
+}
+
+/// Invalid arguments or input (exit code `USAGE`)
+pub fn usage(message: impl Into<String>) -> anyhow::Error {
+    ExitError { code: USAGE, message: message.into() }.into()
+}
+
+/// Missing download, folder, script or file (exit code `NOT_FOUND`)
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    ExitError { code: NOT_FOUND, message: message.into() }.into()
+}
+
+/// A download did not complete (exit code `DOWNLOAD_FAILED`)
+pub fn download_failed(message: impl Into<String>) -> anyhow::Error {
+    ExitError { code: DOWNLOAD_FAILED, message: message.into() }.into()
+}
+
+/// Exit code for an error returned by a handler
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    error.downcast_ref::<ExitError>().map_or(ERROR, |e| e.code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_mapping() {
+        assert_eq!(exit_code(&usage("Invalid UUID format")), USAGE);
+        assert_eq!(exit_code(&not_found("Download not found")), NOT_FOUND);
+        assert_eq!(exit_code(&download_failed("Download failed")), DOWNLOAD_FAILED);
+        assert_eq!(exit_code(&anyhow::anyhow!("disk full")), ERROR);
+
+        // Context added on the way up keeps the code
+        let error = not_found("Folder 'x' not found").context("Failed to remove folder");
+        assert_eq!(exit_code(&error), NOT_FOUND);
+        assert_eq!(usage("bad").to_string(), "bad");
+    }
+}
@@ -0,0 +1,177 @@
+//! Task selection for bulk commands
+//!
+//! `start-all`, `pause-all`, `remove` and `clear` share the `--status`,
+//! `--folder` and `--older-than` options (`TaskFilterArgs`), which are parsed
+//! into a `TaskFilter` and matched against the queue. Every bulk command also
+//! accepts `--dry-run` to list the matching downloads without touching them.
+
+use super::error;
+use crate::download::task::{DownloadStatus, DownloadTask};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Args;
+
+/// Filter options shared by bulk commands
+#[derive(Args, Debug, Clone, Default)]
+pub struct TaskFilterArgs {
+    /// Only downloads with these statuses (comma-separated: pending,downloading,paused,completed,error)
+    #[arg(long, value_name = "STATUS")]
+    pub status: Option<String>,
+
+    /// Only downloads in this folder (ID or name)
+    #[arg(long)]
+    pub folder: Option<String>,
+
+    /// Only downloads added longer ago than this (e.g. 12h, 7d, 2w)
+    #[arg(long, value_name = "DURATION")]
+    pub older_than: Option<String>,
+
+    /// List the downloads that would be affected without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl TaskFilterArgs {
+    /// True if no filter option was given (`--dry-run` is not a filter)
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.folder.is_none() && self.older_than.is_none()
+    }
+}
+
+/// Parsed task selection; an empty filter matches every task
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFilter {
+    /// Accepted statuses (empty = any)
+    pub statuses: Vec<DownloadStatus>,
+    /// Resolved folder ID
+    pub folder_id: Option<String>,
+    /// Only tasks created before this time
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl TaskFilter {
+    /// Parse the options; `folder_id` must already be resolved from a name
+    pub fn parse(args: &TaskFilterArgs, folder_id: Option<String>, now: DateTime<Utc>) -> Result<Self> {
+        let statuses = match args.status {
+            Some(ref list) => parse_statuses(list)?,
+            None => Vec::new(),
+        };
+        let created_before = match args.older_than {
+            Some(ref duration) => Some(
+                crate::download::stats::cutoff_before(now, duration).map_err(|e| error::usage(e.to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self { statuses, folder_id, created_before })
+    }
+
+    /// Use `statuses` when the user did not pick any (e.g. start-all defaults to pending and paused)
+    pub fn with_default_statuses(mut self, statuses: &[DownloadStatus]) -> Self {
+        if self.statuses.is_empty() {
+            self.statuses = statuses.to_vec();
+        }
+        self
+    }
+
+    /// True if `task` passes every given condition
+    pub fn matches(&self, task: &DownloadTask) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&task.status))
+            && self.folder_id.as_ref().is_none_or(|folder| &task.folder_id == folder)
+            && self.created_before.is_none_or(|before| task.created_at < before)
+    }
+
+    /// Tasks matching the filter, in queue order
+    pub fn apply(&self, tasks: Vec<DownloadTask>) -> Vec<DownloadTask> {
+        tasks.into_iter().filter(|task| self.matches(task)).collect()
+    }
+}
+
+/// Parse a comma-separated status list
+fn parse_statuses(list: &str) -> Result<Vec<DownloadStatus>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.to_lowercase().as_str() {
+            "pending" => Ok(DownloadStatus::Pending),
+            "downloading" => Ok(DownloadStatus::Downloading),
+            "paused" => Ok(DownloadStatus::Paused),
+            "completed" => Ok(DownloadStatus::Completed),
+            "error" => Ok(DownloadStatus::Error),
+            _ => Err(error::usage(format!(
+                "Unknown status '{}' (use pending, downloading, paused, completed or error)",
+                s
+            ))),
+        })
+        .collect()
+}
+
+/// Print the downloads a `--dry-run` would affect
+pub fn print_dry_run(action: &str, tasks: &[DownloadTask]) {
+    println!("Would {} {} download(s)", action, tasks.len());
+    for task in tasks {
+        println!("  {} {} [{}] {:?}", task.id, task.filename, task.folder_id, task.status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn task(status: DownloadStatus, folder: &str, age_days: i64) -> DownloadTask {
+        let mut task = DownloadTask::new("https://example.com/f.bin".to_string(), PathBuf::from("dl"));
+        task.status = status;
+        task.folder_id = folder.to_string();
+        task.created_at = Utc::now() - chrono::Duration::days(age_days);
+        task
+    }
+
+    #[test]
+    fn test_filter_matches_all_conditions() {
+        let args = TaskFilterArgs {
+            status: Some("paused, error".to_string()),
+            older_than: Some("7d".to_string()),
+            ..Default::default()
+        };
+        let filter = TaskFilter::parse(&args, Some("images".to_string()), Utc::now()).unwrap();
+
+        assert!(filter.matches(&task(DownloadStatus::Paused, "images", 10)));
+        assert!(filter.matches(&task(DownloadStatus::Error, "images", 30)));
+        assert!(!filter.matches(&task(DownloadStatus::Paused, "images", 1)));
+        assert!(!filter.matches(&task(DownloadStatus::Pending, "images", 10)));
+        assert!(!filter.matches(&task(DownloadStatus::Paused, "default", 10)));
+    }
+
+    #[test]
+    fn test_empty_filter_and_defaults() {
+        let filter = TaskFilter::parse(&TaskFilterArgs::default(), None, Utc::now()).unwrap();
+        assert!(filter.matches(&task(DownloadStatus::Completed, "default", 0)));
+
+        let filter = filter.with_default_statuses(&[DownloadStatus::Pending]);
+        let tasks = vec![task(DownloadStatus::Pending, "default", 0), task(DownloadStatus::Completed, "default", 0)];
+        assert_eq!(filter.apply(tasks).len(), 1);
+
+        // Explicit statuses win over the default
+        let args = TaskFilterArgs { status: Some("error".to_string()), ..Default::default() };
+        let filter = TaskFilter::parse(&args, None, Utc::now())
+            .unwrap()
+            .with_default_statuses(&[DownloadStatus::Pending]);
+        assert_eq!(filter.statuses, [DownloadStatus::Error]);
+    }
+
+    #[test]
+    fn test_invalid_options_are_usage_errors() {
+        let args = TaskFilterArgs { status: Some("done".to_string()), ..Default::default() };
+        let err = TaskFilter::parse(&args, None, Utc::now()).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+
+        let args = TaskFilterArgs { older_than: Some("7".to_string()), ..Default::default() };
+        let err = TaskFilter::parse(&args, None, Utc::now()).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+
+        let args = TaskFilterArgs { older_than: Some("99999999999d".to_string()), ..Default::default() };
+        let err = TaskFilter::parse(&args, None, Utc::now()).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+    }
+}
@@ -1,12 +1,20 @@
 use super::error;
+use super::filter::{self, TaskFilter, TaskFilterArgs};
+use super::logs;
 use super::output;
-use super::{Commands, ConfigAction, DebugAction, ScriptAction, FolderAction, ExportAction, ImportAction, TestAction};
+use super::{Commands, ConfigAction, DebugAction, ScriptAction, FolderAction, ExportAction, ImportAction, TestAction, HistoryAction};
 use crate::app::config::{Config, FolderConfig};
 use crate::app::state::AppState;
+use crate::download::http_client::HttpClient;
+use crate::download::folder_queue::sort_for_schedule;
 use crate::download::manager::DownloadManager;
 use crate::download::task::{DownloadTask, DownloadStatus};
 use crate::download::completion_log::CompletedEntry;
-use crate::script::events::{BeforeRequestContext, HookEvent};
+use crate::download::trash::Trash;
+use crate::script::events::{
+    BeforeRequestContext, CompletedContext, ErrorContext, EventContext, HeadersReceivedContext, HookEvent,
+    ProgressContext,
+};
 use anyhow::Result;
 use chrono::Utc;
 use std::path::PathBuf;
@@ -20,67 +28,254 @@ pub async fn handle_command(
     manager: DownloadManager,
 ) -> i32 {
     let result = match command {
-        Commands::Add { url, folder } => handle_add(url, folder, &state, &manager).await,
+        Commands::Add { url, folder, no_create_dirs, start, wait, headers, referer, dry_run, stdout } => {
+            let options = AddOptions { folder, headers, referer, no_create_dirs };
+            if stdout {
+                handle_add_stdout(url, options, &state, &manager).await
+            } else if dry_run {
+                handle_add_dry_run(url, options, &state, &manager).await
+            } else {
+                handle_add(url, options, start || wait, wait, &state, &manager).await
+            }
+        }
         Commands::List { json } => handle_list(&manager, json).await,
         Commands::Start { id, wait } => handle_start(id, &state, &manager, wait).await,
         Commands::Pause { id } => handle_pause(id, &manager).await,
-        Commands::Remove { id } => handle_remove(id, &manager).await,
-        Commands::Status { id, json } => handle_status(id, &manager, json).await,
+        Commands::Confirm { id, wait } => handle_confirm(id, &state, &manager, wait).await,
+        Commands::Remove { id: Some(id), filter } if filter.is_empty() && !filter.dry_run => {
+            handle_remove(id, &manager).await
+        }
+        Commands::Remove { id: Some(_), .. } => {
+            Err(error::usage("Give either a download ID or filter options (--status, --folder, --older-than), not both"))
+        }
+        Commands::Remove { id: None, filter } => handle_remove_filtered(&state, &manager, filter).await,
+        Commands::Restore { id, json } => handle_restore(id, &state, &manager, json).await,
+        Commands::Status { id, json, watch } => handle_status(id, &manager, json, watch).await,
         Commands::Config { action } => handle_config(action, &state).await,
-        Commands::Logs { follow, level, lines } => handle_logs(follow, level, lines).await,
-        Commands::History { today, folder, json } => handle_history(today, folder, json).await,
-        Commands::Stats { folder, json } => handle_stats(&manager, folder, json).await,
+        Commands::Logs { follow, level, lines, pretty } => handle_logs(follow, level, lines, pretty).await,
+        Commands::History { action: Some(HistoryAction::Prune { older_than }), .. } => {
+            handle_history_prune(&state, older_than).await
+        }
+        Commands::History { action: None, today, folder, json } => handle_history(today, folder, json).await,
+        Commands::Stats { lifetime: true, json, .. } => handle_lifetime_stats(json),
+        Commands::Stats { folder, since, today, json, .. } => handle_stats(&state, &manager, folder, since, today, json).await,
         Commands::Debug { action } => handle_debug(action, &state, &manager).await,
         Commands::Script { action } => handle_script(action, &state).await,
         Commands::Folder { action } => handle_folder(action, &state).await,
-        Commands::StartAll { folder } => handle_start_all(&state, &manager, folder).await,
-        Commands::PauseAll { folder } => handle_pause_all(&manager, folder).await,
-        Commands::Clear { status, folder } => handle_clear(&manager, status, folder).await,
-        Commands::BatchAdd { file, folder } => handle_batch_add(&state, &manager, file, folder).await,
+        Commands::StartAll { filter } => handle_start_all(&state, &manager, filter).await,
+        Commands::RetryAll { folder } => handle_retry_all(&state, &manager, folder).await,
+        Commands::PauseAll { filter } => handle_pause_all(&state, &manager, filter).await,
+        Commands::Clear { filter } => handle_clear(&state, &manager, filter).await,
+        Commands::BatchAdd { file, folder, no_create_dirs, headers } => {
+            handle_batch_add(&state, &manager, file, AddOptions { folder, headers, referer: None, no_create_dirs }).await
+        }
         Commands::Priority { id, set } => handle_priority(&manager, id, set).await,
-        Commands::Move { id, to_top, to_bottom, before, folder } => {
-            handle_move(&manager, id, to_top, to_bottom, before, folder).await
+        Commands::Move { id, to_top, to_bottom, before, after, folder, keep_path } => {
+            handle_move(&state, &manager, id, to_top, to_bottom, before, after, folder, keep_path).await
         }
         Commands::Export { action } => handle_export(action, &state, &manager).await,
         Commands::Import { action } => handle_import(action, &state, &manager).await,
         Commands::Test { action } => handle_test(action, &state, &manager).await,
+        Commands::Doctor { url, skip_network, json } => {
+            super::doctor::handle_doctor(&state, &manager, url, skip_network, json).await
+        }
     };
 
     match result {
         Ok(code) => code,
         Err(e) => {
             eprintln!("Error: {}", e);
-            error::ERROR
+            error::exit_code(&e)
         }
     }
 }
 
-/// Add a new download
+/// Options shared by `add` and `batch-add`
+struct AddOptions {
+    folder: Option<String>,
+    /// Raw `--header` values
+    headers: Vec<String>,
+    /// `--referer` value
+    referer: Option<String>,
+    no_create_dirs: bool,
+}
+
+impl AddOptions {
+    /// Parse the `--header` and `--referer` values, rejecting malformed entries
+    fn parse_headers(&self) -> Result<Vec<(String, String)>> {
+        let mut headers = self
+            .headers
+            .iter()
+            .map(|line| {
+                HttpClient::parse_header_line(line)
+                    .map_err(|e| error::usage(format!("Invalid --header '{}': {}", line, e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(ref referer) = self.referer {
+            let header = HttpClient::referer_header(referer)
+                .map_err(|e| error::usage(format!("Invalid --referer '{}': {}", referer, e)))?;
+            headers.push(header);
+        }
+        Ok(headers)
+    }
+
+    /// A new task for `url` with the folder's default headers and the `--header` overrides
+    ///
+    /// Fails with a usage error if the URL's scheme is not in `download.allowed_schemes`.
+    fn new_task(&self, config: &Config, url: &str, headers: &[(String, String)]) -> Result<DownloadTask> {
+        config.download.check_url_scheme(url).map_err(|e| error::usage(format!("Cannot add {}: {}", url, e)))?;
+        let mut task = DownloadTask::new(url.to_string(), config.download.default_directory.clone());
+        if let Some(ref folder_id) = self.folder {
+            task.folder_id = folder_id.clone();
+        }
+        if let Some(folder) = config.folders.get(&task.folder_id) {
+            task.headers = folder.default_headers.clone();
+        }
+        HttpClient::merge_headers(&mut task.headers, headers.iter().cloned());
+        Ok(task)
+    }
+}
+
+/// Add a new download, optionally starting it and waiting for it to finish
+///
+/// Starting goes through `start_download` like `ggg start`, so the folder's
+/// concurrency slots and the active-folder limit apply; the folder's
+/// auto-start setting is not involved.
 async fn handle_add(
     url: String,
-    folder: Option<String>,
+    options: AddOptions,
+    start: bool,
+    wait: bool,
     state: &AppState,
     manager: &DownloadManager,
 ) -> Result<i32> {
-    // Get default directory from config
+    let headers = options.parse_headers()?;
     let config = state.config.read().await;
-    let save_path = config.download.default_directory.clone();
-
-    let mut task = DownloadTask::new(url.clone(), save_path);
+    let task = options.new_task(&config, &url, &headers)?;
 
-    // Set folder if specified
-    if let Some(folder_id) = folder {
-        task.folder_id = folder_id;
+    if !options.no_create_dirs && !ensure_save_directories(&config, std::slice::from_ref(&task)) {
+        return Ok(error::USAGE);
     }
 
+    drop(config); // start_download reads the config
+
     manager.add_download(task.clone()).await;
     manager.save_queue_to_folders().await?;
 
     println!("Added download: {} (ID: {})", url, task.id);
 
+    if start {
+        manager.start_download(task.id, state.script_sender.clone(), state.config.clone()).await?;
+        manager.save_queue_to_folders().await?;
+
+        // A folder quota may have kept it queued
+        if let Some(reason) = manager.get_by_id(task.id).await.and_then(|task| task.held_reason) {
+            println!("Not started: {} ({})", task.filename, reason);
+            return Ok(error::SUCCESS);
+        }
+        println!("Started download: {}", task.filename);
+
+        if wait {
+            wait_for_download(task.id, manager).await?;
+        }
+    }
+
     Ok(error::SUCCESS)
 }
 
+/// `ggg add --dry-run`: report the request and target file without queueing anything
+async fn handle_add_dry_run(
+    url: String,
+    options: AddOptions,
+    state: &AppState,
+    manager: &DownloadManager,
+) -> Result<i32> {
+    let headers = options.parse_headers()?;
+    let task = options.new_task(&*state.config.read().await, &url, &headers)?;
+    let report = manager.dry_run(task, state.script_sender.clone(), state.config.clone()).await?;
+
+    println!("Dry run: nothing was added or downloaded");
+    println!("URL:        {}", report.url);
+    println!("User-Agent: {}", report.user_agent);
+    match report.routing_rule {
+        Some(rule) => println!("Folder:     {} (routing rule #{})", report.folder_id, rule),
+        None => println!("Folder:     {}", report.folder_id),
+    }
+    println!("Target:     {}", report.target_path.display());
+    if report.headers.is_empty() {
+        println!("Headers:    (none)");
+    } else {
+        println!("Headers:");
+        for (name, value) in &report.headers {
+            println!("  {}: {}", name, value);
+        }
+    }
+    for run in &report.script_runs {
+        println!("Script:     {}", output::format_script_run(run));
+    }
+    if let Some(ref reason) = report.cancelled {
+        println!("Cancelled by script: {}", reason);
+    }
+
+    Ok(error::SUCCESS)
+}
+
+/// `ggg add --stdout`: stream the body to stdout, bypassing the queue
+///
+/// Uses the same headers, user agent and referrer policy a queued download
+/// would get (from `--folder` if given), but runs no scripts. Only the body
+/// goes to stdout; an HTTP error exits with `DOWNLOAD_FAILED`, its status on
+/// stderr.
+async fn handle_add_stdout(
+    url: String,
+    options: AddOptions,
+    state: &AppState,
+    manager: &DownloadManager,
+) -> Result<i32> {
+    let headers = options.parse_headers()?;
+    let request_headers = {
+        let config = state.config.read().await;
+        let task = options.new_task(&config, &url, &headers)?;
+        let resolved = crate::app::settings::ResolvedSettings::resolve(&config, &task.folder_id, &task);
+        let has_referer = task.headers.keys().any(|k| k.eq_ignore_ascii_case("referer"));
+        let referer = if has_referer { None } else { resolved.referrer_policy.compute(&task.url) };
+        HttpClient::build_headers(Some(&resolved.user_agent), referer.as_deref(), &task.headers)?
+    };
+
+    let mut stdout = tokio::io::stdout();
+    match manager.http_client().download_to_writer(&url, &request_headers, &mut stdout).await {
+        Ok(bytes) => {
+            tracing::info!("Streamed {} bytes from {} to stdout", bytes, url);
+            Ok(error::SUCCESS)
+        }
+        Err(e)
+            if e.downcast_ref::<crate::download::http_client::HttpStatusError>().is_some()
+                || e.downcast_ref::<crate::download::http_client::IncompleteBody>().is_some() =>
+        {
+            Err(error::download_failed(format!("{}: {}", url, e)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Create the resolved save directory of each task, reporting the first failure
+///
+/// Returns false (after printing the error) if a directory cannot be created.
+fn ensure_save_directories(config: &Config, tasks: &[DownloadTask]) -> bool {
+    let dirs: std::collections::BTreeSet<PathBuf> = tasks
+        .iter()
+        .map(|task| crate::app::settings::ResolvedSettings::resolve(config, &task.folder_id, task).save_path)
+        .collect();
+    for dir in dirs {
+        if let Err(e) = crate::util::fs::ensure_save_directory(&dir) {
+            eprintln!("Error: {}", e);
+            eprintln!("Fix the folder's save_path, or pass --no-create-dirs to queue anyway");
+            return false;
+        }
+    }
+    true
+}
+
 /// List all downloads
 async fn handle_list(manager: &DownloadManager, json: bool) -> Result<i32> {
     let tasks = manager.get_all_downloads().await;
@@ -97,16 +292,22 @@ async fn handle_start(
     manager: &DownloadManager,
     wait: bool,
 ) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     // Check if download exists
     let task = manager.get_by_id(id).await
-        .ok_or_else(|| anyhow::anyhow!("Download not found"))?;
+        .ok_or_else(|| error::not_found("Download not found"))?;
 
     // Start download with script support
     manager.start_download(id, state.script_sender.clone(), state.config.clone()).await?;
     manager.save_queue_to_folders().await?;
 
+    // A folder quota may have kept it queued
+    if let Some(reason) = manager.get_by_id(id).await.and_then(|task| task.held_reason) {
+        println!("Not started: {} ({})", task.filename, reason);
+        return Ok(error::SUCCESS);
+    }
+
     println!("Started download: {}", task.filename);
 
     if wait {
@@ -117,6 +318,30 @@ async fn handle_start(
     Ok(error::SUCCESS)
 }
 
+/// Confirm the size of a download held by `download.confirm_over_bytes` and start it
+async fn handle_confirm(
+    id_str: String,
+    state: &AppState,
+    manager: &DownloadManager,
+    wait: bool,
+) -> Result<i32> {
+    let id = resolve_id(manager, &id_str).await?;
+
+    let task = manager.get_by_id(id).await
+        .ok_or_else(|| error::not_found("Download not found"))?;
+    if !task.awaiting_confirmation {
+        return Err(error::usage(format!("{} is not waiting for confirmation", task.filename)));
+    }
+
+    manager.confirm_download(id).await?;
+    println!(
+        "Confirmed {} ({})",
+        task.filename,
+        task.size.map(output::format_bytes).unwrap_or_else(|| "unknown size".to_string())
+    );
+    handle_start(id_str, state, manager, wait).await
+}
+
 /// Wait for download to complete and show progress
 async fn wait_for_download(id: Uuid, manager: &DownloadManager) -> Result<()> {
     use std::io::{self, Write};
@@ -127,7 +352,7 @@ async fn wait_for_download(id: Uuid, manager: &DownloadManager) -> Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
         let task = manager.get_by_id(id).await
-            .ok_or_else(|| anyhow::anyhow!("Download disappeared"))?;
+            .ok_or_else(|| error::not_found("Download disappeared"))?;
 
         // Show progress
         if let Some(total) = task.size {
@@ -147,11 +372,17 @@ async fn wait_for_download(id: Uuid, manager: &DownloadManager) -> Result<()> {
         match task.status {
             DownloadStatus::Completed => {
                 println!("\n✓ Download completed!");
+                println!("{}", output::format_completion_summary(&task));
                 break;
             }
             DownloadStatus::Error => {
                 println!("\n✗ Download failed!");
-                return Err(anyhow::anyhow!("Download failed"));
+                return Err(error::download_failed("Download failed"));
+            }
+            DownloadStatus::Paused if task.awaiting_confirmation => {
+                println!("\n⏸ Waiting for confirmation: {} is over download.confirm_over_bytes", task.filename);
+                println!("Run `ggg confirm {}` to download it", task.id);
+                break;
             }
             DownloadStatus::Paused => {
                 println!("\n⏸ Download paused");
@@ -169,11 +400,11 @@ async fn handle_pause(
     id_str: String,
     manager: &DownloadManager,
 ) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     // Check if download exists
     let task = manager.get_by_id(id).await
-        .ok_or_else(|| anyhow::anyhow!("Download not found"))?;
+        .ok_or_else(|| error::not_found("Download not found"))?;
 
     manager.pause_download(id).await?;
     manager.save_queue_to_folders().await?;
@@ -188,27 +419,123 @@ async fn handle_remove(
     id_str: String,
     manager: &DownloadManager,
 ) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     let task = manager.remove_download(id).await
-        .ok_or_else(|| anyhow::anyhow!("Download not found"))?;
+        .ok_or_else(|| error::not_found("Download not found"))?;
 
     manager.save_queue_to_folders().await?;
 
-    println!("Removed download: {}", task.filename);
+    // Keep a copy in the trash so it can be restored
+    let filename = task.filename.clone();
+    if let Err(e) = Trash::update_default(|trash| trash.push(task)) {
+        tracing::warn!("Failed to save trash: {}", e);
+    }
+
+    println!("Removed download: {}", filename);
+
+    Ok(error::SUCCESS)
+}
+
+/// Remove every download matching the filter, keeping copies in the trash
+async fn handle_remove_filtered(
+    state: &AppState,
+    manager: &DownloadManager,
+    args: TaskFilterArgs,
+) -> Result<i32> {
+    if args.is_empty() {
+        return Err(error::usage("Give a download ID or filter options (--status, --folder, --older-than)"));
+    }
+
+    let tasks = parse_filter(state, &args).await?.apply(manager.get_all_downloads().await);
+
+    if args.dry_run {
+        filter::print_dry_run("remove", &tasks);
+        return Ok(error::SUCCESS);
+    }
+
+    let mut removed = Vec::new();
+    for task in tasks {
+        if let Some(task) = manager.remove_download(task.id).await {
+            removed.push(task);
+        }
+    }
+
+    manager.save_queue_to_folders().await?;
+
+    let removed_count = removed.len();
+    if let Err(e) = Trash::update_default(|trash| removed.into_iter().for_each(|task| trash.push(task))) {
+        tracing::warn!("Failed to save trash: {}", e);
+    }
+
+    println!("Removed {} download(s)", removed_count);
+    Ok(error::SUCCESS)
+}
+
+/// Restore a deleted download from the trash, or list the trash
+async fn handle_restore(
+    id: Option<String>,
+    state: &AppState,
+    manager: &DownloadManager,
+    json: bool,
+) -> Result<i32> {
+    let retention_days = state.config.read().await.history.trash_retention_days;
+
+    let Some(id) = id else {
+        let trash = Trash::update_default(|trash| {
+            trash.expire(retention_days);
+            trash.clone()
+        })?;
+
+        if json {
+            output::emit(&trash.entries)?;
+        } else if trash.is_empty() {
+            println!("Trash is empty");
+        } else {
+            println!("Trash ({} entries)\n", trash.len());
+            for entry in trash.entries.iter().rev() {
+                println!("{} {} [{}] deleted {}",
+                    entry.task.id,
+                    entry.task.filename,
+                    entry.task.folder_id,
+                    entry.deleted_at.format("%Y-%m-%d %H:%M:%S")
+                );
+            }
+        }
+        return Ok(error::SUCCESS);
+    };
+
+    let entry = Trash::update_default(|trash| {
+        trash.expire(retention_days);
+        trash.take(&id)
+    })??;
+    let task = entry.into_task();
+
+    if json {
+        output::emit(&output::TaskJson::from(&task))?;
+    } else {
+        println!("Restored download: {} ({})", task.filename, task.id);
+    }
+
+    manager.add_download(task).await;
+    manager.save_queue_to_folders().await?;
 
     Ok(error::SUCCESS)
 }
 
 /// Show download status
-async fn handle_status(id_str: String, manager: &DownloadManager, json: bool) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+async fn handle_status(id_str: String, manager: &DownloadManager, json: bool, watch: bool) -> Result<i32> {
+    let id = resolve_id(manager, &id_str).await?;
+
+    if watch {
+        return watch_status(id, json).await;
+    }
 
     let task = manager.get_by_id(id).await
-        .ok_or_else(|| anyhow::anyhow!("Download not found"))?;
+        .ok_or_else(|| error::not_found("Download not found"))?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&task)?);
+        output::emit(&output::TaskJson::from(&task))?;
     } else {
         println!("{}", output::format_download(&task, true));
     }
@@ -216,6 +543,91 @@ async fn handle_status(id_str: String, manager: &DownloadManager, json: bool) ->
     Ok(error::SUCCESS)
 }
 
+/// How often `ggg status --watch` re-reads the queue files
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Load a task straight from the queue files
+///
+/// The download normally runs in a TUI or daemon, which saves its progress
+/// periodically; this process's own manager only holds the startup snapshot.
+async fn load_saved_task(id: Uuid) -> Result<Option<DownloadTask>> {
+    let queue = crate::download::queue::DownloadQueue::new();
+    queue.load_from_folder_files().await?;
+    Ok(queue.get_by_id(id).await)
+}
+
+/// Redraw a download's status until it completes or fails (`status --watch`)
+///
+/// Exits with SUCCESS once completed, DOWNLOAD_FAILED if it failed or was
+/// deleted, NOT_FOUND if the task disappeared and INTERRUPTED on Ctrl-C.
+async fn watch_status(id: Uuid, json: bool) -> Result<i32> {
+    use std::io::{self, Write};
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    // Progress arrives in steps (the owning process saves it every few
+    // seconds), so speed is measured between changes, not between refreshes
+    let mut last_change: Option<(std::time::Instant, u64)> = None;
+    let mut measured_speed: Option<f64> = None;
+
+    loop {
+        let Some(task) = load_saved_task(id).await? else {
+            if !json {
+                println!();
+            }
+            eprintln!("Download not found: {}", id);
+            return Ok(error::NOT_FOUND);
+        };
+
+        let now = std::time::Instant::now();
+        match last_change {
+            Some((at, downloaded)) if downloaded != task.downloaded => {
+                let secs = now.duration_since(at).as_secs_f64();
+                measured_speed = (task.downloaded > downloaded && secs > 0.0)
+                    .then(|| (task.downloaded - downloaded) as f64 / secs);
+                last_change = Some((now, task.downloaded));
+            }
+            None => last_change = Some((now, task.downloaded)),
+            _ => {}
+        }
+        let speed = if task.status == DownloadStatus::Downloading {
+            measured_speed.or_else(|| task.speed())
+        } else {
+            None
+        };
+
+        if json {
+            output::emit_line(&output::watch_json(&task, speed))?;
+        } else {
+            // \x1b[K clears what is left of a longer previous line
+            print!("\r{}\x1b[K", output::format_watch_line(&task, speed));
+            io::stdout().flush()?;
+        }
+
+        let exit_code = match task.status {
+            DownloadStatus::Completed => Some(error::SUCCESS),
+            DownloadStatus::Error | DownloadStatus::Deleted => Some(error::DOWNLOAD_FAILED),
+            _ => None,
+        };
+        if let Some(code) = exit_code {
+            if !json {
+                println!();
+            }
+            return Ok(code);
+        }
+
+        tokio::select! {
+            _ = &mut ctrl_c => {
+                if !json {
+                    println!();
+                }
+                return Ok(error::INTERRUPTED);
+            }
+            _ = tokio::time::sleep(WATCH_INTERVAL) => {}
+        }
+    }
+}
+
 /// Handle configuration commands
 async fn handle_config(action: ConfigAction, state: &AppState) -> Result<i32> {
     match action {
@@ -235,12 +647,40 @@ async fn handle_config(action: ConfigAction, state: &AppState) -> Result<i32> {
         ConfigAction::Show { json } => {
             let config = state.config.read().await;
             if json {
-                println!("{}", serde_json::to_string_pretty(&*config)?);
+                output::emit(&*config)?;
             } else {
                 println!("{}", toml::to_string_pretty(&*config)?);
             }
             Ok(error::SUCCESS)
         }
+        ConfigAction::Validate => handle_config_validate(),
+    }
+}
+
+/// Check the configuration files without falling back to defaults
+///
+/// Exits with ERROR if a file does not parse, a value is invalid or a key
+/// is not a setting (and would be ignored).
+fn handle_config_validate() -> Result<i32> {
+    let issues = Config::check_files()?;
+    let mut problems: Vec<String> = issues.errors;
+    // Files that parse can still break constraints (e.g. concurrency limits)
+    if problems.is_empty()
+        && let Err(e) = Config::load()
+    {
+        problems.push(format!("{:#}", e));
+    }
+    problems.extend(issues.unknown_keys.iter().map(|key| format!("Unknown key (ignored): {}", key)));
+
+    if problems.is_empty() {
+        println!("✓ Configuration is valid");
+        Ok(error::SUCCESS)
+    } else {
+        println!("✗ Configuration has {} problem(s):\n", problems.len());
+        for (i, problem) in problems.iter().enumerate() {
+            println!("{}. {}", i + 1, problem);
+        }
+        Ok(error::ERROR)
     }
 }
 
@@ -253,12 +693,48 @@ fn get_config_value(config: &Config, key: &str) -> Result<String> {
         ["general", "theme"] => Ok(config.general.theme.clone()),
         ["general", "minimize_to_tray"] => Ok(config.general.minimize_to_tray.to_string()),
         ["general", "start_minimized"] => Ok(config.general.start_minimized.to_string()),
+        ["general", "show_short_ids"] => Ok(config.general.show_short_ids.to_string()),
+        ["general", "show_bandwidth_graph"] => Ok(config.general.show_bandwidth_graph.to_string()),
+        ["general", "completion_sound"] => Ok(match &config.general.completion_sound {
+            None => "off".to_string(),
+            Some(path) if path.as_os_str().is_empty() => "beep".to_string(),
+            Some(path) => path.display().to_string(),
+        }),
         ["download", "default_directory"] => Ok(config.download.default_directory.display().to_string()),
         ["download", "max_concurrent"] => Ok(config.download.max_concurrent.to_string()),
         ["download", "retry_count"] => Ok(config.download.retry_count.to_string()),
         ["download", "retry_delay"] => Ok(config.download.retry_delay.to_string()),
         ["download", "user_agent"] => Ok(config.download.user_agent.clone()),
         ["download", "bandwidth_limit"] => Ok(config.download.bandwidth_limit.to_string()),
+        ["download", "max_redirects"] => Ok(config.download.max_redirects.to_string()),
+        ["download", "allow_insecure_redirect"] => Ok(config.download.allow_insecure_redirect.to_string()),
+        ["download", "infer_extension"] => Ok(config.download.infer_extension.to_string()),
+        ["download", "chunk_size_bytes"] => Ok(config.download.chunk_size_bytes.to_string()),
+        ["download", "pool_idle_timeout_secs"] => Ok(config.download.pool_idle_timeout_secs.to_string()),
+        ["download", "pool_max_idle_per_host"] => Ok(config.download.pool_max_idle_per_host.to_string()),
+        ["download", "http2_prior_knowledge"] => Ok(config.download.http2_prior_knowledge.to_string()),
+        ["download", "min_tls_version"] => Ok(config.download.min_tls_version.map_or("none", |v| v.as_str()).to_string()),
+        ["download", "danger_accept_invalid_certs"] => Ok(config.download.danger_accept_invalid_certs.to_string()),
+        ["download", "deep_resume_probe"] => Ok(config.download.deep_resume_probe.to_string()),
+        ["download", "verify_resume_overlap"] => Ok(config.download.verify_resume_overlap.to_string()),
+        ["download", "schedule_policy"] => Ok(config.download.schedule_policy.as_str().to_string()),
+        ["download", "max_connections"] => Ok(config.download.max_connections.map_or("none".to_string(), |v| v.to_string())),
+        ["download", "max_retry_after_secs"] => Ok(config.download.max_retry_after_secs.to_string()),
+        ["download", "circuit_breaker", "scope"] => Ok(config.download.circuit_breaker.scope.as_str().to_string()),
+        ["download", "retry_on"] => Ok(config
+            .download
+            .retry_on
+            .iter()
+            .map(|status| status.to_string())
+            .collect::<Vec<_>>()
+            .join(",")),
+        ["history", "max_entries"] => Ok(config.history.max_entries.to_string()),
+        ["history", "max_age_days"] => Ok(config.history.max_age_days.to_string()),
+        ["history", "trash_retention_days"] => Ok(config.history.trash_retention_days.to_string()),
+        ["logging", "level"] => Ok(config.logging.level.clone()),
+        ["logging", "rotation"] => Ok(config.logging.rotation.as_str().to_string()),
+        ["logging", "max_files"] => Ok(config.logging.max_files.to_string()),
+        ["logging", "console_format"] => Ok(config.logging.console_format.as_str().to_string()),
         ["network", "proxy_enabled"] => Ok(config.network.proxy_enabled.to_string()),
         ["network", "proxy_type"] => Ok(config.network.proxy_type.clone()),
         ["network", "proxy_host"] => Ok(config.network.proxy_host.clone()),
@@ -266,7 +742,9 @@ fn get_config_value(config: &Config, key: &str) -> Result<String> {
         ["scripts", "enabled"] => Ok(config.scripts.enabled.to_string()),
         ["scripts", "directory"] => Ok(config.scripts.directory.display().to_string()),
         ["scripts", "timeout"] => Ok(config.scripts.timeout.to_string()),
-        _ => Err(anyhow::anyhow!("Unknown configuration key: {}", key)),
+        ["scripts", "auto_reload"] => Ok(config.scripts.auto_reload.to_string()),
+        ["scripts", "body_peek_bytes"] => Ok(config.scripts.body_peek_bytes.to_string()),
+        _ => Err(error::usage(format!("Unknown configuration key: {}", key))),
     }
 }
 
@@ -275,16 +753,87 @@ fn set_config_value(config: &mut Config, key: &str, value: &str) -> Result<()> {
     let parts: Vec<&str> = key.split('.').collect();
 
     match parts.as_slice() {
-        ["general", "language"] => config.general.language = value.to_string(),
+        ["general", "language"] => {
+            let available = crate::util::i18n::LocalizationManager::available_locales();
+            if crate::util::i18n::resolve_locale(value, &available).is_none() {
+                return Err(error::usage(format!(
+                    "Unknown language '{}' (available: {})",
+                    value,
+                    available.join(", ")
+                )));
+            }
+            config.general.language = value.to_string();
+        }
         ["general", "theme"] => config.general.theme = value.to_string(),
         ["general", "minimize_to_tray"] => config.general.minimize_to_tray = value.parse()?,
         ["general", "start_minimized"] => config.general.start_minimized = value.parse()?,
+        ["general", "show_short_ids"] => config.general.show_short_ids = value.parse()?,
+        ["general", "show_bandwidth_graph"] => config.general.show_bandwidth_graph = value.parse()?,
+        ["general", "completion_sound"] => {
+            config.general.completion_sound = match value {
+                "off" => None,
+                "beep" => Some(PathBuf::new()),
+                path => Some(PathBuf::from(path)),
+            }
+        }
         ["download", "default_directory"] => config.download.default_directory = PathBuf::from(value),
         ["download", "max_concurrent"] => config.download.max_concurrent = value.parse()?,
         ["download", "retry_count"] => config.download.retry_count = value.parse()?,
         ["download", "retry_delay"] => config.download.retry_delay = value.parse()?,
-        ["download", "user_agent"] => config.download.user_agent = value.to_string(),
+        ["download", "user_agent"] => config.download.user_agent = crate::app::user_agent::expand(value)?,
         ["download", "bandwidth_limit"] => config.download.bandwidth_limit = value.parse()?,
+        ["download", "max_redirects"] => config.download.max_redirects = value.parse()?,
+        ["download", "allow_insecure_redirect"] => config.download.allow_insecure_redirect = value.parse()?,
+        ["download", "infer_extension"] => config.download.infer_extension = value.parse()?,
+        ["download", "chunk_size_bytes"] => {
+            let size: usize = value.parse()?;
+            if size < crate::app::config::MIN_CHUNK_SIZE_BYTES {
+                return Err(error::usage(format!(
+                    "download.chunk_size_bytes must be at least {}",
+                    crate::app::config::MIN_CHUNK_SIZE_BYTES
+                )));
+            }
+            config.download.chunk_size_bytes = size;
+        }
+        ["download", "pool_idle_timeout_secs"] => config.download.pool_idle_timeout_secs = value.parse()?,
+        ["download", "pool_max_idle_per_host"] => config.download.pool_max_idle_per_host = value.parse()?,
+        ["download", "http2_prior_knowledge"] => config.download.http2_prior_knowledge = value.parse()?,
+        ["download", "min_tls_version"] => {
+            config.download.min_tls_version = match value {
+                "none" | "" => None,
+                version => Some(version.parse()?),
+            }
+        }
+        ["download", "danger_accept_invalid_certs"] => config.download.danger_accept_invalid_certs = value.parse()?,
+        ["download", "deep_resume_probe"] => config.download.deep_resume_probe = value.parse()?,
+        ["download", "verify_resume_overlap"] => config.download.verify_resume_overlap = value.parse()?,
+        ["download", "schedule_policy"] => config.download.schedule_policy = value.parse()?,
+        ["download", "max_connections"] => {
+            config.download.max_connections = match value {
+                "none" | "" => None,
+                count => Some(count.parse()?),
+            }
+        }
+        ["download", "max_retry_after_secs"] => config.download.max_retry_after_secs = value.parse()?,
+        ["download", "circuit_breaker", "scope"] => config.download.circuit_breaker.scope = value.parse()?,
+        ["download", "retry_on"] => {
+            config.download.retry_on = value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::parse)
+                .collect::<std::result::Result<_, _>>()?;
+        }
+        ["history", "max_entries"] => config.history.max_entries = value.parse()?,
+        ["history", "max_age_days"] => config.history.max_age_days = value.parse()?,
+        ["history", "trash_retention_days"] => config.history.trash_retention_days = value.parse()?,
+        ["logging", "level"] => {
+            logs::parse_level(value)?;
+            config.logging.level = value.to_ascii_lowercase();
+        }
+        ["logging", "rotation"] => config.logging.rotation = value.parse()?,
+        ["logging", "max_files"] => config.logging.max_files = value.parse()?,
+        ["logging", "console_format"] => config.logging.console_format = value.parse()?,
         ["network", "proxy_enabled"] => config.network.proxy_enabled = value.parse()?,
         ["network", "proxy_type"] => config.network.proxy_type = value.to_string(),
         ["network", "proxy_host"] => config.network.proxy_host = value.to_string(),
@@ -292,7 +841,9 @@ fn set_config_value(config: &mut Config, key: &str, value: &str) -> Result<()> {
         ["scripts", "enabled"] => config.scripts.enabled = value.parse()?,
         ["scripts", "directory"] => config.scripts.directory = PathBuf::from(value),
         ["scripts", "timeout"] => config.scripts.timeout = value.parse()?,
-        _ => return Err(anyhow::anyhow!("Unknown configuration key: {}", key)),
+        ["scripts", "auto_reload"] => config.scripts.auto_reload = value.parse()?,
+        ["scripts", "body_peek_bytes"] => config.scripts.body_peek_bytes = value.parse()?,
+        _ => return Err(error::usage(format!("Unknown configuration key: {}", key))),
     }
 
     Ok(())
@@ -303,88 +854,52 @@ async fn handle_logs(
     follow: bool,
     level: Option<String>,
     lines: Option<usize>,
+    pretty: bool,
 ) -> Result<i32> {
-    let log_file = PathBuf::from("ggg.log");
-
-    if !log_file.exists() {
-        return Err(anyhow::anyhow!("Log file not found: ggg.log"));
-    }
+    use std::io::IsTerminal;
 
+    let min_level = match level.as_deref().map(logs::parse_level).transpose() {
+        Ok(level) => level,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(error::USAGE);
+        }
+    };
     let lines_to_show = lines.unwrap_or(50);
+    let color = std::io::stdout().is_terminal();
+    let logs_dir = crate::util::paths::get_logs_dir()?;
 
+    // Follow mode is always pretty-printed
     if follow {
-        // Follow mode - tail -f
-        println!("Following log file (Ctrl+C to stop)...");
-        follow_log_file(&log_file, level).await?;
-    } else {
-        // Show last N lines
-        let content = std::fs::read_to_string(&log_file)?;
-        let mut log_lines: Vec<&str> = content.lines().collect();
+        eprintln!("Following logs in {} (Ctrl+C to stop)...", logs_dir.display());
+        logs::follow(&logs_dir, lines_to_show, min_level, color).await?;
+        return Ok(error::SUCCESS);
+    }
 
-        // Filter by log level if specified
-        if let Some(ref level_filter) = level {
-            let level_upper = level_filter.to_uppercase();
-            log_lines.retain(|line| line.contains(&level_upper));
-        }
+    let Some(log_file) = logs::current_log_file(&logs_dir) else {
+        eprintln!("No log files found in {}", logs_dir.display());
+        return Ok(error::NOT_FOUND);
+    };
 
-        // Take last N lines
-        let start_idx = log_lines.len().saturating_sub(lines_to_show);
-        for line in &log_lines[start_idx..] {
+    let shown = logs::tail(&log_file, lines_to_show, min_level)?;
+    for line in &shown {
+        if pretty {
+            println!("{}", logs::render_pretty(line, color));
+        } else {
             println!("{}", line);
         }
-
-        println!("\n({} lines shown)", log_lines.len() - start_idx);
     }
+    println!("\n({} lines shown)", shown.len());
 
     Ok(error::SUCCESS)
 }
 
-/// Follow log file (tail -f mode)
-async fn follow_log_file(log_file: &PathBuf, level: Option<String>) -> Result<()> {
-    use std::io::{BufRead, BufReader, Seek, SeekFrom};
-    use std::fs::File;
-
-    let mut file = File::open(log_file)?;
-    file.seek(SeekFrom::End(0))?;
-
-    let mut reader = BufReader::new(file);
-    let mut line = String::new();
-
-    loop {
-        match reader.read_line(&mut line) {
-            Ok(bytes_read) => {
-                if bytes_read > 0 {
-                    // Filter by log level if specified
-                    if let Some(ref level_filter) = level {
-                        let level_upper = level_filter.to_uppercase();
-                        if line.contains(&level_upper) {
-                            print!("{}", line);
-                        }
-                    } else {
-                        print!("{}", line);
-                    }
-                    line.clear();
-                } else {
-                    // No new data, sleep briefly
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                }
-            }
-            Err(e) => return Err(e.into()),
-        }
-    }
-}
-
-/// Show download completion history
-async fn handle_history(
-    today: bool,
-    folder: Option<String>,
-    json: bool,
-) -> Result<i32> {
+/// Read completion history entries (empty if there is no history yet)
+fn read_history(today: bool, folder: Option<&str>) -> Result<Vec<CompletedEntry>> {
     let logs_dir = crate::util::paths::get_logs_dir()?;
 
     if !logs_dir.exists() {
-        println!("No completion history found");
-        return Ok(error::SUCCESS);
+        return Ok(Vec::new());
     }
 
     // Collect all log files
@@ -409,8 +924,7 @@ async fn handle_history(
     }
 
     if log_files.is_empty() {
-        println!("No completion history found");
-        return Ok(error::SUCCESS);
+        return Ok(Vec::new());
     }
 
     // Read and parse all entries
@@ -424,8 +938,8 @@ async fn handle_history(
             match serde_json::from_str::<CompletedEntry>(line) {
                 Ok(entry) => {
                     // Filter by folder if specified
-                    if let Some(ref folder_filter) = folder {
-                        if entry.folder_id == *folder_filter {
+                    if let Some(folder_filter) = folder {
+                        if entry.folder_id == folder_filter {
                             entries.push(entry);
                         }
                     } else {
@@ -439,14 +953,23 @@ async fn handle_history(
         }
     }
 
-    if entries.is_empty() {
-        println!("No completion history found");
-        return Ok(error::SUCCESS);
-    }
+    Ok(entries)
+}
+
+/// Show download completion history
+async fn handle_history(
+    today: bool,
+    folder: Option<String>,
+    json: bool,
+) -> Result<i32> {
+    let entries = read_history(today, folder.as_deref())?;
 
     // Output results
     if json {
-        println!("{}", serde_json::to_string_pretty(&entries)?);
+        let entries: Vec<output::HistoryJson> = entries.iter().map(output::HistoryJson::from).collect();
+        output::emit(&entries)?;
+    } else if entries.is_empty() {
+        println!("No completion history found");
     } else {
         println!("Completion History ({} entries)\n", entries.len());
         for entry in entries {
@@ -471,101 +994,126 @@ async fn handle_history(
     Ok(error::SUCCESS)
 }
 
+/// Prune completion history
+///
+/// `--older-than` overrides the configured policy; otherwise `history.max_age_days`
+/// and `history.max_entries` apply.
+async fn handle_history_prune(
+    state: &AppState,
+    older_than: Option<String>,
+) -> Result<i32> {
+    let (cutoff, max_entries) = match older_than {
+        Some(ref duration) => {
+            let cutoff = crate::download::stats::cutoff_before(Utc::now(), duration)
+                .map_err(|e| error::usage(e.to_string()))?;
+            (Some(cutoff), 0)
+        }
+        None => {
+            let config = state.config.read().await;
+            let max_age_days = config.history.max_age_days;
+            let cutoff = (max_age_days > 0)
+                .then(|| Utc::now().checked_sub_signed(chrono::Duration::days(max_age_days as i64)))
+                .flatten();
+            (cutoff, config.history.max_entries)
+        }
+    };
+
+    let removed = crate::download::completion_log::prune(cutoff, max_entries)?;
+    tracing::info!("Pruned {} history entries", removed);
+
+    println!("Removed {} history entries", removed);
+    Ok(error::SUCCESS)
+}
+
+/// `ggg stats --lifetime`
+fn handle_lifetime_stats(json: bool) -> Result<i32> {
+    let lifetime = crate::download::lifetime::LifetimeStats::load_default()?;
+
+    if json {
+        output::emit(&lifetime)?;
+        return Ok(error::SUCCESS);
+    }
+
+    println!("Lifetime Statistics\n");
+    println!("  Files: {}", lifetime.files);
+    println!("  Total Bytes: {}", output::format_bytes(lifetime.bytes));
+    println!("  Time Spent: {}", crate::download::task::format_duration(lifetime.seconds as u64));
+    if let Some(since) = lifetime.since {
+        println!("  Since: {}", since.format("%Y-%m-%d"));
+    }
+    Ok(error::SUCCESS)
+}
+
 /// Show download statistics
 async fn handle_stats(
+    state: &AppState,
     manager: &DownloadManager,
     folder: Option<String>,
+    since: Option<String>,
+    today: bool,
     json: bool,
 ) -> Result<i32> {
-    let tasks = manager.get_all_downloads().await;
-    let logs_dir = crate::util::paths::get_logs_dir()?;
+    use crate::download::stats::{self, StatsWindow};
 
-    // Calculate queue statistics
-    let mut queue_stats = std::collections::HashMap::new();
-    queue_stats.insert("total", tasks.len());
-    queue_stats.insert("pending", tasks.iter().filter(|t| matches!(t.status, DownloadStatus::Pending)).count());
-    queue_stats.insert("downloading", tasks.iter().filter(|t| matches!(t.status, DownloadStatus::Downloading)).count());
-    queue_stats.insert("paused", tasks.iter().filter(|t| matches!(t.status, DownloadStatus::Paused)).count());
-    queue_stats.insert("completed", tasks.iter().filter(|t| matches!(t.status, DownloadStatus::Completed)).count());
-    queue_stats.insert("error", tasks.iter().filter(|t| matches!(t.status, DownloadStatus::Error)).count());
-
-    // Calculate total bytes (queue only)
-    let total_bytes: u64 = tasks.iter().filter_map(|t| t.size).sum();
-    let downloaded_bytes: u64 = tasks.iter().map(|t| t.downloaded).sum();
-
-    // Read completion history for all-time stats
-    let mut completed_count = 0;
-    let mut error_count = 0;
-    let mut total_duration_secs = 0.0;
-
-    if logs_dir.exists() {
-        for entry in std::fs::read_dir(&logs_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                let content = std::fs::read_to_string(&path)?;
-                for line in content.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    if let Ok(entry) = serde_json::from_str::<CompletedEntry>(line) {
-                        // Filter by folder if specified
-                        if let Some(ref folder_filter) = folder {
-                            if entry.folder_id != *folder_filter {
-                                continue;
-                            }
-                        }
+    let window = if today {
+        StatsWindow::Today
+    } else if let Some(ref since) = since {
+        StatsWindow::Since(stats::cutoff_before(Utc::now(), since).map_err(|e| error::usage(e.to_string()))?)
+    } else {
+        StatsWindow::All
+    };
 
-                        if entry.status == "completed" {
-                            completed_count += 1;
-                            if let Some(duration) = entry.duration_secs {
-                                total_duration_secs += duration;
-                            }
-                        } else {
-                            error_count += 1;
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let config = state.config.read().await;
+    let folder_id = folder
+        .as_deref()
+        .map(|f| resolve_folder_id(&config, f).unwrap_or_else(|| f.to_string()));
+
+    let history = crate::download::completion_log::read_entries(window.start())?;
+    let tasks = manager.get_all_downloads().await;
+    let stats = stats::compute(&history, &tasks, window, folder_id.as_deref());
 
     if json {
-        let stats = serde_json::json!({
-            "queue": queue_stats,
-            "bytes": {
-                "total": total_bytes,
-                "downloaded": downloaded_bytes,
-            },
-            "history": {
-                "completed": completed_count,
-                "errors": error_count,
-                "avg_duration_secs": if completed_count > 0 {
-                    total_duration_secs / completed_count as f64
-                } else {
-                    0.0
-                },
-            },
-        });
-        println!("{}", serde_json::to_string_pretty(&stats)?);
-    } else {
-        println!("Download Statistics\n");
-        println!("Queue:");
-        println!("  Total: {}", queue_stats["total"]);
-        println!("  Pending: {}", queue_stats["pending"]);
-        println!("  Downloading: {}", queue_stats["downloading"]);
-        println!("  Paused: {}", queue_stats["paused"]);
-        println!("  Completed: {}", queue_stats["completed"]);
-        println!("  Error: {}", queue_stats["error"]);
-        println!("\nBytes:");
-        println!("  Total: {}", output::format_bytes(total_bytes));
-        println!("  Downloaded: {}", output::format_bytes(downloaded_bytes));
-        println!("\nHistory (all-time):");
-        println!("  Completed: {}", completed_count);
-        println!("  Errors: {}", error_count);
-        if completed_count > 0 {
-            let avg_duration = total_duration_secs / completed_count as f64;
-            println!("  Avg Duration: {:.1}s", avg_duration);
+        output::emit(&stats)?;
+        return Ok(error::SUCCESS);
+    }
+
+    let window_label = match (today, since.as_deref()) {
+        (true, _) => "today".to_string(),
+        (false, Some(since)) => format!("last {}", since),
+        (false, None) => "all-time".to_string(),
+    };
+
+    println!("Download Statistics ({})\n", window_label);
+    println!("Queue:");
+    println!("  Total: {}", stats.queue.total);
+    println!("  Pending: {}", stats.queue.pending);
+    println!("  Downloading: {}", stats.queue.downloading);
+    println!("  Paused: {}", stats.queue.paused);
+    println!("  Error: {}", stats.queue.error);
+    println!("\nFinished:");
+    println!("  Completed: {}", stats.completed);
+    println!("  Failed: {}", stats.failed);
+    if let Some(rate) = stats.success_rate {
+        println!("  Success Rate: {:.1}%", rate * 100.0);
+    }
+    println!("  Total Bytes: {}", output::format_bytes(stats.total_bytes));
+    if let Some(speed) = stats.avg_speed {
+        println!("  Avg Speed: {}/s", output::format_bytes(speed as u64));
+    }
+    if let Some(avg_duration) = stats.avg_duration_secs {
+        println!("  Avg Duration: {:.1}s", avg_duration);
+    }
+
+    if !stats.folders.is_empty() {
+        println!("\nBy Folder:");
+        for folder_stats in &stats.folders {
+            println!(
+                "  {:<20} {:>5} ok {:>5} failed {:>12}",
+                config.folder_name(&folder_stats.folder_id),
+                folder_stats.completed,
+                folder_stats.failed,
+                output::format_bytes(folder_stats.bytes)
+            );
         }
     }
 
@@ -579,12 +1127,92 @@ async fn handle_debug(
     manager: &DownloadManager,
 ) -> Result<i32> {
     match action {
+        DebugAction::Info { json } => handle_debug_info(state, manager, json).await,
         DebugAction::ManagerState { json } => handle_debug_manager_state(manager, json).await,
         DebugAction::FolderSlots { json } => handle_debug_folder_slots(manager, json).await,
         DebugAction::Task { id, json } => handle_debug_task(id, manager, json).await,
         DebugAction::ValidateConfig => handle_debug_validate_config(state).await,
+        DebugAction::I18n { missing, json } => handle_debug_i18n_missing(&missing, json),
         DebugAction::CheckQueue { json } => handle_debug_check_queue(manager, json).await,
+        DebugAction::CircuitBreaker { reset, reset_all, json } => {
+            if let Some(key) = reset {
+                handle_debug_circuit_breaker_reset(manager, &key, json)
+            } else if reset_all {
+                let cleared = manager.reset_all_circuits();
+                if json {
+                    output::emit(&serde_json::json!({ "reset": cleared }))?;
+                } else {
+                    println!("Reset {} circuit(s)", cleared);
+                }
+                Ok(error::SUCCESS)
+            } else {
+                handle_debug_circuit_breaker(state, manager, json).await
+            }
+        }
+    }
+}
+
+/// Version, build and environment details for bug reports (read-only)
+async fn debug_info(state: &AppState, manager: &DownloadManager) -> serde_json::Value {
+    use crate::util::paths;
+
+    let path_or_error = |path: Result<PathBuf>| match path {
+        Ok(path) => path.display().to_string(),
+        Err(e) => format!("(unavailable: {})", e),
+    };
+    let config = state.config.read().await;
+
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "target": format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+        "profile": if cfg!(debug_assertions) { "debug" } else { "release" },
+        "executable": path_or_error(std::env::current_exe().map_err(Into::into)),
+        "config_dir": path_or_error(paths::find_config_directory()),
+        "logs_dir": path_or_error(paths::get_logs_dir()),
+        "language": config.general.language,
+        "locale": state.i18n.current_locale(),
+        "scripts_enabled": config.scripts.enabled,
+        "limits": {
+            "max_concurrent": config.download.max_concurrent,
+            "max_concurrent_per_folder": config.download.max_concurrent_per_folder,
+            "parallel_folder_count": config.download.parallel_folder_count,
+            "max_connections": manager.max_connections(),
+            "bandwidth_limit": manager.bandwidth_limit(),
+            "retry_count": config.download.retry_count,
+            "max_redirects": config.download.max_redirects,
+        },
+    })
+}
+
+/// Show version, build target, config/log locations and limits
+async fn handle_debug_info(state: &AppState, manager: &DownloadManager, json: bool) -> Result<i32> {
+    let info = debug_info(state, manager).await;
+
+    if json {
+        output::emit(&info)?;
+        return Ok(error::SUCCESS);
+    }
+
+    let text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => "not set".to_string(),
+        other => other.to_string(),
+    };
+    println!("ggg {} ({}, {})", text(&info["version"]), text(&info["target"]), text(&info["profile"]));
+    println!();
+    println!("Executable: {}", text(&info["executable"]));
+    println!("Config directory: {}", text(&info["config_dir"]));
+    println!("Logs directory: {}", text(&info["logs_dir"]));
+    println!("Language: {} (locale: {})", text(&info["language"]), text(&info["locale"]));
+    println!("Scripts: {}", if info["scripts_enabled"] == true { "enabled" } else { "disabled" });
+    println!("\nLimits:");
+    if let Some(limits) = info["limits"].as_object() {
+        for (name, value) in limits {
+            println!("  {}: {}", name, text(value));
+        }
     }
+
+    Ok(error::SUCCESS)
 }
 
 /// Show download manager internal state
@@ -598,7 +1226,7 @@ async fn handle_debug_manager_state(manager: &DownloadManager, json: bool) -> Re
             "active_downloads": active_count,
             "task_ids": tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
         });
-        println!("{}", serde_json::to_string_pretty(&state)?);
+        output::emit(&state)?;
     } else {
         println!("Download Manager State\n");
         println!("Total Tasks: {}", tasks.len());
@@ -634,7 +1262,7 @@ async fn handle_debug_folder_slots(manager: &DownloadManager, json: bool) -> Res
                 "task_ids": tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
             }));
         }
-        println!("{}", serde_json::to_string_pretty(&folder_info)?);
+        output::emit(&folder_info)?;
     } else {
         println!("Folder Slot States\n");
         for (folder_id, tasks) in folder_tasks {
@@ -652,13 +1280,13 @@ async fn handle_debug_folder_slots(manager: &DownloadManager, json: bool) -> Res
 
 /// Show detailed task information
 async fn handle_debug_task(id_str: String, manager: &DownloadManager, json: bool) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     let task = manager.get_by_id(id).await
-        .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+        .ok_or_else(|| error::not_found("Task not found"))?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&task)?);
+        output::emit(&task)?;
     } else {
         println!("Task Details\n");
         println!("ID: {}", task.id);
@@ -666,7 +1294,11 @@ async fn handle_debug_task(id_str: String, manager: &DownloadManager, json: bool
         println!("Filename: {}", task.filename);
         println!("Folder: {}", task.folder_id);
         println!("Save Path: {}", task.save_path.display());
-        println!("Status: {:?}", task.status);
+        if task.awaiting_confirmation {
+            println!("Status: {:?} (awaiting size confirmation)", task.status);
+        } else {
+            println!("Status: {:?}", task.status);
+        }
         println!("Size: {}", task.size.map(|s| output::format_bytes(s)).unwrap_or_else(|| "Unknown".to_string()));
         println!("Downloaded: {}", output::format_bytes(task.downloaded));
         println!("Priority: {}", task.priority);
@@ -718,6 +1350,25 @@ async fn handle_debug_validate_config(state: &AppState) -> Result<i32> {
         issues.push("max_concurrent cannot be 0".to_string());
     }
 
+    // Check language
+    let available = crate::util::i18n::LocalizationManager::available_locales();
+    if crate::util::i18n::resolve_locale(&config.general.language, &available).is_none() {
+        issues.push(format!(
+            "language '{}' is not available (available: {}); English is used",
+            config.general.language,
+            available.join(", ")
+        ));
+    }
+
+    // Check chunk size
+    if config.download.chunk_size_bytes < crate::app::config::MIN_CHUNK_SIZE_BYTES {
+        issues.push(format!(
+            "chunk_size_bytes must be at least {} (got {})",
+            crate::app::config::MIN_CHUNK_SIZE_BYTES,
+            config.download.chunk_size_bytes
+        ));
+    }
+
     // Check scripts directory
     if config.scripts.enabled && !config.scripts.directory.exists() {
         issues.push(format!("Scripts directory does not exist: {}", config.scripts.directory.display()));
@@ -742,6 +1393,41 @@ async fn handle_debug_validate_config(state: &AppState) -> Result<i32> {
     }
 }
 
+/// List translation keys present in en-US but missing from a locale
+///
+/// Exits with ERROR when keys are missing so it can gate translation updates.
+fn handle_debug_i18n_missing(lang: &str, json: bool) -> Result<i32> {
+    use crate::util::i18n::{resolve_locale, LocalizationManager, FALLBACK_LOCALE};
+
+    let available = LocalizationManager::available_locales();
+    let Some(locale_id) = resolve_locale(lang, &available) else {
+        return Err(error::not_found(format!(
+            "Locale '{}' not found (available: {})",
+            lang,
+            available.join(", ")
+        )));
+    };
+    let missing = LocalizationManager::missing_keys(&locale_id)?;
+
+    if json {
+        let report = serde_json::json!({
+            "locale": locale_id,
+            "reference": FALLBACK_LOCALE,
+            "missing": missing,
+        });
+        output::emit(&report)?;
+    } else if missing.is_empty() {
+        println!("✓ {} defines every key in {}", locale_id, FALLBACK_LOCALE);
+    } else {
+        println!("✗ {} key(s) in {} are missing from {}:\n", missing.len(), FALLBACK_LOCALE, locale_id);
+        for key in &missing {
+            println!("  {}", key);
+        }
+    }
+
+    Ok(if missing.is_empty() { error::SUCCESS } else { error::ERROR })
+}
+
 /// Check queue integrity
 async fn handle_debug_check_queue(manager: &DownloadManager, json: bool) -> Result<i32> {
     let tasks = manager.get_all_downloads().await;
@@ -779,7 +1465,7 @@ async fn handle_debug_check_queue(manager: &DownloadManager, json: bool) -> Resu
             "issues": issues,
             "is_valid": issues.is_empty(),
         });
-        println!("{}", serde_json::to_string_pretty(&result)?);
+        output::emit(&result)?;
     } else {
         println!("Queue Integrity Check\n");
         println!("Total Tasks: {}", tasks.len());
@@ -801,14 +1487,85 @@ async fn handle_debug_check_queue(manager: &DownloadManager, json: bool) -> Resu
     }
 }
 
+/// Show circuit breaker keys for queued downloads and every tracked circuit
+async fn handle_debug_circuit_breaker(state: &AppState, manager: &DownloadManager, json: bool) -> Result<i32> {
+    use crate::download::circuit_breaker::{circuit_key, CircuitState};
+
+    let scope = state.config.read().await.download.circuit_breaker.scope;
+    let breaker = manager.circuit_breaker();
+
+    // key -> (state, failures, unfinished downloads using it)
+    let mut circuits: std::collections::BTreeMap<String, (CircuitState, u32, usize)> = breaker
+        .snapshot()
+        .into_iter()
+        .map(|(key, state, failures)| (key, (state, failures, 0)))
+        .collect();
+    for task in manager.get_all_downloads().await {
+        if task.status == DownloadStatus::Completed {
+            continue;
+        }
+        if let Some(key) = circuit_key(&task.url, scope) {
+            let entry = circuits.entry(key).or_insert((CircuitState::Closed, 0, 0));
+            entry.2 += 1;
+        }
+    }
+
+    if json {
+        let result = serde_json::json!({
+            "scope": scope.as_str(),
+            "circuits": circuits
+                .iter()
+                .map(|(key, (state, failures, downloads))| serde_json::json!({
+                    "key": key,
+                    "state": state.as_str(),
+                    "failures": failures,
+                    "downloads": downloads,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        output::emit(&result)?;
+    } else {
+        println!("Circuit Breaker (scope: {})\n", scope.as_str());
+        if circuits.is_empty() {
+            println!("No circuits tracked");
+        } else {
+            let width = circuits.keys().map(|key| key.len()).max().unwrap_or(0).max(3);
+            println!("{:<width$}  {:<9}  {:>8}  {:>9}", "KEY", "STATE", "FAILURES", "DOWNLOADS");
+            for (key, (state, failures, downloads)) in &circuits {
+                println!("{:<width$}  {:<9}  {:>8}  {:>9}", key, state.as_str(), failures, downloads);
+            }
+        }
+    }
+
+    Ok(error::SUCCESS)
+}
+
+/// Clear the failure state of one circuit breaker key
+fn handle_debug_circuit_breaker_reset(manager: &DownloadManager, key: &str, json: bool) -> Result<i32> {
+    if !manager.reset_circuit(key) {
+        return Err(error::not_found(format!("No circuit tracked for '{}'", key)));
+    }
+    if json {
+        output::emit(&serde_json::json!({ "reset": 1, "key": key }))?;
+    } else {
+        println!("Circuit for '{}' reset", key);
+    }
+    Ok(error::SUCCESS)
+}
+
 /// Handle script management commands
 async fn handle_script(action: ScriptAction, state: &AppState) -> Result<i32> {
     match action {
         ScriptAction::List { enabled_only, json } => handle_script_list(state, enabled_only, json).await,
         ScriptAction::Enable { name } => handle_script_enable(state, name).await,
         ScriptAction::Disable { name } => handle_script_disable(state, name).await,
-        ScriptAction::Test { name, event, url } => handle_script_test(state, name, event, url).await,
+        ScriptAction::Test { name, event, url, filename, size, status, downloaded, total } => {
+            let values = ScriptTestValues { filename, size, status, downloaded, total };
+            handle_script_test(state, name, event, url, values).await
+        }
         ScriptAction::Reload => handle_script_reload(state).await,
+        ScriptAction::Validate { name, all } => handle_script_validate(state, name, all).await,
+        ScriptAction::New { name, event, force } => handle_script_new(state, name, event, force).await,
     }
 }
 
@@ -824,7 +1581,7 @@ async fn handle_script_list(state: &AppState, enabled_only: bool, json: bool) ->
     let scripts_dir = &config.scripts.directory;
 
     if !scripts_dir.exists() {
-        return Err(anyhow::anyhow!("Scripts directory does not exist: {}", scripts_dir.display()));
+        return Err(error::not_found(format!("Scripts directory does not exist: {}", scripts_dir.display())));
     }
 
     // List all .js files in scripts directory
@@ -865,7 +1622,7 @@ async fn handle_script_list(state: &AppState, enabled_only: bool, json: bool) ->
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&script_list)?);
+        output::emit(&script_list)?;
     } else {
         println!("Scripts ({} total)\n", scripts.len());
         for (name, enabled) in scripts {
@@ -884,7 +1641,7 @@ async fn handle_script_enable(state: &AppState, name: String) -> Result<i32> {
     // Verify script exists
     let script_path = config.scripts.directory.join(&name);
     if !script_path.exists() {
-        return Err(anyhow::anyhow!("Script not found: {}", name));
+        return Err(error::not_found(format!("Script not found: {}", name)));
     }
 
     // Set enabled status
@@ -904,7 +1661,7 @@ async fn handle_script_disable(state: &AppState, name: String) -> Result<i32> {
     // Verify script exists
     let script_path = config.scripts.directory.join(&name);
     if !script_path.exists() {
-        return Err(anyhow::anyhow!("Script not found: {}", name));
+        return Err(error::not_found(format!("Script not found: {}", name)));
     }
 
     // Set disabled status
@@ -917,29 +1674,194 @@ async fn handle_script_disable(state: &AppState, name: String) -> Result<i32> {
     Ok(error::SUCCESS)
 }
 
+/// Validate scripts (syntax check only)
+async fn handle_script_validate(state: &AppState, name: Option<String>, all: bool) -> Result<i32> {
+    let config = state.config.read().await;
+
+    let scripts = if all {
+        crate::script::loader::ScriptLoader::new(&config.scripts.directory).list_scripts()?
+    } else {
+        let name = name.unwrap_or_default();
+        let path = config.scripts.directory.join(&name);
+        if !path.exists() {
+            return Err(error::not_found(format!("Script not found: {}", name)));
+        }
+        vec![path]
+    };
+
+    if scripts.is_empty() {
+        println!("No scripts found in {}", config.scripts.directory.display());
+        return Ok(error::SUCCESS);
+    }
+
+    let timeout = std::time::Duration::from_secs(config.scripts.timeout);
+    let mut engine = crate::script::engine::ScriptEngine::new(timeout)?;
+
+    let mut failed = 0;
+    for path in &scripts {
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        match engine.validate_script(path) {
+            Ok(()) => println!("OK    {}", filename),
+            Err(e) => {
+                failed += 1;
+                println!("FAIL  {}", filename);
+                println!("      {}", e);
+            }
+        }
+    }
+
+    println!("\n{} script(s) checked, {} failed", scripts.len(), failed);
+
+    Ok(if failed > 0 { error::ERROR } else { error::SUCCESS })
+}
+
+/// Create a new script from a template
+async fn handle_script_new(state: &AppState, name: String, event: String, force: bool) -> Result<i32> {
+    let hook_event = HookEvent::from_str(&event).ok_or_else(|| {
+        error::usage(format!(
+            "Invalid event: {}. Valid events: beforeRequest, headersReceived, authRequired, completed, error, progress",
+            event
+        ))
+    })?;
+
+    let stem = name.strip_suffix(".js").unwrap_or(&name);
+    if stem.is_empty() || stem != crate::file::naming::sanitize_filename(stem) {
+        return Err(error::usage(format!("Invalid script name: {}", name)));
+    }
+    let filename = format!("{}.js", stem);
+
+    let script_dir = state.config.read().await.scripts.directory.clone();
+    let script_path = script_dir.join(&filename);
+    if script_path.exists() && !force {
+        return Err(anyhow::anyhow!(
+            "Script already exists: {} (use --force to overwrite)",
+            script_path.display()
+        ));
+    }
+
+    std::fs::create_dir_all(&script_dir)?;
+    std::fs::write(&script_path, crate::script::template::render(stem, hook_event))?;
+
+    println!("Created script: {}", script_path.display());
+    println!("Hook: {}", hook_event.name());
+
+    Ok(error::SUCCESS)
+}
+
+/// Context values given to `ggg script test` on the command line
+#[derive(Debug, Default)]
+struct ScriptTestValues {
+    filename: Option<String>,
+    size: Option<u64>,
+    status: Option<u16>,
+    downloaded: Option<u64>,
+    total: Option<u64>,
+}
+
+impl ScriptTestValues {
+    /// `--filename`, or the last URL segment
+    fn filename(&self, url: &str) -> String {
+        self.filename
+            .clone()
+            .or_else(|| crate::file::naming::filename_from_url(url))
+            .unwrap_or_else(|| "download".to_string())
+    }
+
+    fn headers_received(&self, url: &str) -> HeadersReceivedContext {
+        HeadersReceivedContext {
+            url: url.to_string(),
+            status: self.status.unwrap_or(200),
+            headers: HashMap::new(),
+            content_length: self.size,
+            etag: None,
+            last_modified: None,
+            content_type: None,
+            body_peek: None,
+        }
+    }
+
+    fn completed(&self, url: &str, save_path: &std::path::Path) -> CompletedContext {
+        CompletedContext {
+            url: url.to_string(),
+            filename: self.filename(url),
+            save_path: save_path.to_string_lossy().to_string(),
+            new_filename: None,
+            move_to_path: None,
+            size: self.size.unwrap_or(0),
+            status_code: Some(self.status.unwrap_or(200)),
+            duration: None,
+            body_peek: None,
+        }
+    }
+
+    fn error(&self, url: &str) -> ErrorContext {
+        ErrorContext {
+            url: url.to_string(),
+            filename: Some(self.filename(url)),
+            error: match self.status {
+                Some(status) => format!("HTTP error: {}", status),
+                None => "Test error".to_string(),
+            },
+            retry_count: 0,
+            status_code: self.status,
+        }
+    }
+
+    fn progress(&self, url: &str) -> ProgressContext {
+        let downloaded = self.downloaded.unwrap_or(0);
+        ProgressContext {
+            url: url.to_string(),
+            filename: self.filename(url),
+            downloaded,
+            total: self.total,
+            speed: None,
+            percentage: self
+                .total
+                .filter(|&total| total > 0)
+                .map(|total| (downloaded as f64 / total as f64 * 100.0) as f32),
+        }
+    }
+}
+
+/// Run the loaded handlers for one event and print the context they leave behind
+fn run_test_event<C: EventContext>(
+    engine: &mut crate::script::engine::ScriptEngine,
+    event: HookEvent,
+    mut ctx: C,
+) -> Result<C> {
+    let result = engine.execute_handlers(event, &mut ctx, &HashMap::new())?;
+
+    println!("Execution result: {}", if result { "Continue" } else { "Stop" });
+    println!("\nContext after handlers:");
+    println!("{}", serde_json::to_string_pretty(&ctx.to_json()?)?);
+    Ok(ctx)
+}
+
 /// Test a script (dry run)
 async fn handle_script_test(
     state: &AppState,
     name: String,
     event: String,
     url: String,
+    values: ScriptTestValues,
 ) -> Result<i32> {
     let config = state.config.read().await;
 
     // Verify script exists
     let script_path = config.scripts.directory.join(&name);
     if !script_path.exists() {
-        return Err(anyhow::anyhow!("Script not found: {}", name));
+        return Err(error::not_found(format!("Script not found: {}", name)));
     }
 
     // Parse event
     let hook_event = match event.as_str() {
         "beforeRequest" | "before_request" => HookEvent::BeforeRequest,
         "headersReceived" | "headers_received" => HookEvent::HeadersReceived,
+        "authRequired" | "auth_required" => HookEvent::AuthRequired,
         "completed" => HookEvent::Completed,
         "errorOccurred" | "error_occurred" | "error" => HookEvent::ErrorOccurred,
         "progress" => HookEvent::Progress,
-        _ => return Err(anyhow::anyhow!("Invalid event: {}. Valid events: beforeRequest, headersReceived, completed, errorOccurred, progress", event)),
+        _ => return Err(error::usage(format!("Invalid event: {}. Valid events: beforeRequest, headersReceived, authRequired, completed, errorOccurred, progress", event))),
     };
 
     println!("Testing script: {}", name);
@@ -953,34 +1875,45 @@ async fn handle_script_test(
     // Load the script
     engine.load_script(&script_path)?;
 
-    // Create test context (for now only support beforeRequest)
     match hook_event {
         HookEvent::BeforeRequest => {
-            let mut ctx = BeforeRequestContext {
+            let ctx = BeforeRequestContext {
                 url: url.clone(),
                 headers: HashMap::new(),
                 user_agent: None,
                 download_id: None,
+                cancel: false,
+                cancel_reason: None,
             };
-
-            let effective_scripts = HashMap::new();
-            let result = engine.execute_handlers(hook_event, &mut ctx, &effective_scripts)?;
-
-            println!("Execution result: {}", if result { "Continue" } else { "Stop" });
-            println!("\nModified context:");
-            println!("  URL: {}", ctx.url);
-            if let Some(ref ua) = ctx.user_agent {
-                println!("  User-Agent: {}", ua);
-            }
-            if !ctx.headers.is_empty() {
-                println!("  Headers:");
-                for (key, value) in &ctx.headers {
-                    println!("    {}: {}", key, value);
-                }
+            let ctx = run_test_event(&mut engine, hook_event, ctx)?;
+            if ctx.cancel {
+                println!(
+                    "\nDownload cancelled: {}",
+                    ctx.cancel_reason.as_deref().unwrap_or("no reason given")
+                );
             }
         }
-        _ => {
-            println!("Note: Only 'beforeRequest' event is currently supported for testing");
+        HookEvent::HeadersReceived => {
+            run_test_event(&mut engine, hook_event, values.headers_received(&url))?;
+        }
+        HookEvent::Completed => {
+            let ctx = values.completed(&url, &config.download.default_directory);
+            run_test_event(&mut engine, hook_event, ctx)?;
+        }
+        HookEvent::ErrorOccurred => {
+            run_test_event(&mut engine, hook_event, values.error(&url))?;
+        }
+        HookEvent::Progress => {
+            run_test_event(&mut engine, hook_event, values.progress(&url))?;
+        }
+        HookEvent::AuthRequired => {
+            let ctx = crate::script::events::AuthRequiredContext {
+                url: url.clone(),
+                realm: None,
+                username: None,
+                password: None,
+            };
+            run_test_event(&mut engine, hook_event, ctx)?;
         }
     }
 
@@ -1006,6 +1939,8 @@ async fn handle_folder(action: FolderAction, state: &AppState) -> Result<i32> {
         FolderAction::Show { id, json } => handle_folder_show(state, id, json).await,
         FolderAction::Config { id, set } => handle_folder_config(state, id, set).await,
         FolderAction::Delete { id } => handle_folder_delete(state, id).await,
+        FolderAction::Rename { from, to } => handle_folder_rename(state, from, to).await,
+        FolderAction::Clone { from, to, path } => handle_folder_clone(state, from, to, path).await,
     }
 }
 
@@ -1027,13 +1962,14 @@ async fn handle_folder_list(state: &AppState, json: bool) -> Result<i32> {
                     "name": folder.name,
                     "save_path": folder.save_path.display().to_string(),
                     "auto_date_directory": folder.auto_date_directory,
+                    "save_path_template": folder.save_path_template,
                     "auto_start_downloads": folder.auto_start_downloads,
                     "scripts_enabled": folder.scripts_enabled,
                     "max_concurrent": folder.max_concurrent,
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&folders)?);
+        output::emit(&folders)?;
     } else {
         println!("Folders ({} total)\n", config.folders.len());
         for (id, folder) in &config.folders {
@@ -1041,6 +1977,9 @@ async fn handle_folder_list(state: &AppState, json: bool) -> Result<i32> {
             println!("  ID: {}", id);
             println!("  Path: {}", folder.save_path.display());
             println!("  Auto-Date Directory: {}", folder.auto_date_directory);
+            if let Some(ref template) = folder.save_path_template {
+                println!("  Save Path Template: {}", template);
+            }
             println!("  Auto-Start: {}", folder.auto_start_downloads);
             if let Some(enabled) = folder.scripts_enabled {
                 println!("  Scripts: {}", if enabled { "enabled" } else { "disabled" });
@@ -1075,6 +2014,7 @@ async fn handle_folder_create(
         name: id.clone(),
         save_path: PathBuf::from(&path),
         auto_date_directory: false,
+        save_path_template: None,
         auto_start_downloads: auto_start,
         scripts_enabled: None,
         script_files: None,
@@ -1082,6 +2022,8 @@ async fn handle_folder_create(
         user_agent: None,
         referrer_policy: None,
         default_headers: HashMap::new(),
+        max_queued: None,
+        max_inflight_bytes: None,
     };
 
     // Create directory if it doesn't exist
@@ -1097,6 +2039,37 @@ async fn handle_folder_create(
     Ok(error::SUCCESS)
 }
 
+/// Resolve a download ID given as a full UUID or an unambiguous prefix
+///
+/// A full UUID is returned as-is (commands report it as not found later if
+/// needed); a prefix is matched against the downloads in the queue.
+async fn resolve_id(manager: &DownloadManager, id: &str) -> Result<Uuid> {
+    if let Ok(uuid) = Uuid::parse_str(id) {
+        return Ok(uuid);
+    }
+    let ids: Vec<Uuid> = manager.get_all_downloads().await.iter().map(|task| task.id).collect();
+    match_id_prefix(id, &ids)
+}
+
+/// Find the single ID in `ids` starting with `prefix` (case-insensitive)
+fn match_id_prefix(prefix: &str, ids: &[Uuid]) -> Result<Uuid> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(error::usage(format!("Invalid download ID '{}' (expected a UUID or UUID prefix)", prefix)));
+    }
+
+    let matches: Vec<Uuid> = ids.iter().copied().filter(|id| id.to_string().starts_with(&prefix)).collect();
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(error::not_found(format!("No download matches '{}'", prefix))),
+        _ => Err(error::usage(format!(
+            "ID prefix '{}' is ambiguous ({} matches); type more characters",
+            prefix,
+            matches.len()
+        ))),
+    }
+}
+
 /// Resolve folder identifier: accepts either UUID key or display name
 fn resolve_folder_id(config: &Config, id: &str) -> Option<String> {
     // Try direct key match first (UUID)
@@ -1112,7 +2085,7 @@ async fn handle_folder_show(state: &AppState, id: String, json: bool) -> Result<
     let config = state.config.read().await;
 
     let folder_id = resolve_folder_id(&config, &id)
-        .ok_or_else(|| anyhow::anyhow!("Folder '{}' not found", id))?;
+        .ok_or_else(|| error::not_found(format!("Folder '{}' not found", id)))?;
     let folder = config.folders.get(&folder_id).unwrap();
 
     if json {
@@ -1120,18 +2093,24 @@ async fn handle_folder_show(state: &AppState, id: String, json: bool) -> Result<
             "id": id,
             "save_path": folder.save_path.display().to_string(),
             "auto_date_directory": folder.auto_date_directory,
+            "save_path_template": folder.save_path_template,
             "auto_start_downloads": folder.auto_start_downloads,
             "scripts_enabled": folder.scripts_enabled,
             "max_concurrent": folder.max_concurrent,
+            "max_queued": folder.max_queued,
+            "max_inflight_bytes": folder.max_inflight_bytes,
             "user_agent": folder.user_agent,
             "default_headers": folder.default_headers,
             "script_files": folder.script_files,
         });
-        println!("{}", serde_json::to_string_pretty(&folder_info)?);
+        output::emit(&folder_info)?;
     } else {
         println!("Folder: {}\n", id);
         println!("Save Path: {}", folder.save_path.display());
         println!("Auto-Date Directory: {}", folder.auto_date_directory);
+        if let Some(ref template) = folder.save_path_template {
+            println!("Save Path Template: {}", template);
+        }
         println!("Auto-Start Downloads: {}", folder.auto_start_downloads);
 
         if let Some(enabled) = folder.scripts_enabled {
@@ -1146,6 +2125,13 @@ async fn handle_folder_show(state: &AppState, id: String, json: bool) -> Result<
             println!("Max Concurrent: (inherit from application)");
         }
 
+        if let Some(max_queued) = folder.max_queued {
+            println!("Max Queued: {}", max_queued);
+        }
+        if let Some(max_inflight_bytes) = folder.max_inflight_bytes {
+            println!("Max In-Flight: {}", output::format_bytes(max_inflight_bytes));
+        }
+
         if let Some(ref ua) = folder.user_agent {
             println!("User-Agent: {}", ua);
         }
@@ -1176,13 +2162,13 @@ async fn handle_folder_config(state: &AppState, id: String, set: String) -> Resu
     let mut config = state.config.write().await;
 
     let folder_id = resolve_folder_id(&config, &id)
-        .ok_or_else(|| anyhow::anyhow!("Folder '{}' not found", id))?;
+        .ok_or_else(|| error::not_found(format!("Folder '{}' not found", id)))?;
     let folder = config.folders.get_mut(&folder_id).unwrap();
 
     // Parse key=value
     let parts: Vec<&str> = set.split('=').collect();
     if parts.len() != 2 {
-        return Err(anyhow::anyhow!("Invalid format. Expected: key=value"));
+        return Err(error::usage("Invalid format. Expected: key=value"));
     }
 
     let key = parts[0].trim();
@@ -1194,6 +2180,10 @@ async fn handle_folder_config(state: &AppState, id: String, set: String) -> Resu
             folder.auto_date_directory = value.parse()?;
             println!("Updated auto_date_directory to {}", value);
         }
+        "save_path_template" => {
+            folder.save_path_template = (!value.is_empty()).then(|| value.to_string());
+            println!("Updated save_path_template to {}", value);
+        }
         "auto_start_downloads" => {
             folder.auto_start_downloads = value.parse()?;
             println!("Updated auto_start_downloads to {}", value);
@@ -1207,10 +2197,19 @@ async fn handle_folder_config(state: &AppState, id: String, set: String) -> Resu
             println!("Updated scripts_enabled to {}", value);
         }
         "user_agent" => {
-            folder.user_agent = Some(value.to_string());
+            folder.user_agent = Some(crate::app::user_agent::expand(value)?);
             println!("Updated user_agent to {}", value);
         }
-        _ => return Err(anyhow::anyhow!("Unknown configuration key: {}. Valid keys: auto_date_directory, auto_start_downloads, max_concurrent, scripts_enabled, user_agent", key)),
+        // Quotas: an empty value removes the cap
+        "max_queued" => {
+            folder.max_queued = if value.is_empty() { None } else { Some(value.parse()?) };
+            println!("Updated max_queued to {}", value);
+        }
+        "max_inflight_bytes" => {
+            folder.max_inflight_bytes = if value.is_empty() { None } else { Some(value.parse()?) };
+            println!("Updated max_inflight_bytes to {}", value);
+        }
+        _ => return Err(error::usage(format!("Unknown configuration key: {}. Valid keys: auto_date_directory, save_path_template, auto_start_downloads, max_concurrent, scripts_enabled, user_agent, max_queued, max_inflight_bytes", key))),
     }
 
     config.save()?;
@@ -1223,7 +2222,7 @@ async fn handle_folder_delete(state: &AppState, id: String) -> Result<i32> {
     let mut config = state.config.write().await;
 
     let folder_id = resolve_folder_id(&config, &id)
-        .ok_or_else(|| anyhow::anyhow!("Folder '{}' not found", id))?;
+        .ok_or_else(|| error::not_found(format!("Folder '{}' not found", id)))?;
     let display_name = config.folder_name(&folder_id);
 
     config.folders.remove(&folder_id);
@@ -1235,33 +2234,115 @@ async fn handle_folder_delete(state: &AppState, id: String) -> Result<i32> {
     Ok(error::SUCCESS)
 }
 
+/// Rename a folder
+///
+/// Only the display name changes: the folder's UUID key, its config directory
+/// and the `folder_id` of its downloads stay the same.
+async fn handle_folder_rename(state: &AppState, from: String, to: String) -> Result<i32> {
+    let mut config = state.config.write().await;
+
+    let folder_id = resolve_folder_id(&config, &from)
+        .ok_or_else(|| error::not_found(format!("Folder '{}' not found", from)))?;
+    let old_name = config.folder_name(&folder_id);
+
+    let to = to.trim().to_string();
+    if to.is_empty() {
+        return Err(error::usage("Folder name cannot be empty"));
+    }
+    if old_name == "default" {
+        return Err(error::usage("The default folder cannot be renamed"));
+    }
+    if resolve_folder_id(&config, &to).is_some() {
+        return Err(anyhow::anyhow!("Folder '{}' already exists", to));
+    }
+
+    if let Some(folder_config) = config.folders.get_mut(&folder_id) {
+        folder_config.name = to.clone();
+    }
+    config.save()?;
+
+    println!("Renamed folder: {} -> {}", old_name, to);
+
+    Ok(error::SUCCESS)
+}
+
+/// Create a folder with the settings of an existing one
+async fn handle_folder_clone(state: &AppState, from: String, to: String, path: String) -> Result<i32> {
+    let mut config = state.config.write().await;
+
+    let source_id = resolve_folder_id(&config, &from)
+        .ok_or_else(|| error::not_found(format!("Folder '{}' not found", from)))?;
+
+    let to = to.trim().to_string();
+    if to.is_empty() {
+        return Err(error::usage("Folder name cannot be empty"));
+    }
+    if resolve_folder_id(&config, &to).is_some() {
+        return Err(anyhow::anyhow!("Folder '{}' already exists", to));
+    }
+
+    // Headers, scripts, user agent, concurrency etc. carry over; only name and path change
+    let folder_config = FolderConfig {
+        name: to.clone(),
+        save_path: PathBuf::from(&path),
+        ..config.folders[&source_id].clone()
+    };
+
+    std::fs::create_dir_all(&folder_config.save_path)?;
+
+    config.folders.insert(Config::generate_folder_id(), folder_config);
+    config.save()?;
+
+    println!("Cloned folder: {} -> {}", config.folder_name(&source_id), to);
+    println!("  Path: {}", path);
+
+    Ok(error::SUCCESS)
+}
+
 // ========================================
 // Batch Operations
 // ========================================
 
-/// Start all downloads
+/// Parse bulk-command filter options, resolving `--folder` by ID or name
+async fn parse_filter(state: &AppState, args: &TaskFilterArgs) -> Result<TaskFilter> {
+    let folder_id = match args.folder {
+        Some(ref id) => {
+            let config = state.config.read().await;
+            Some(resolve_folder_id(&config, id).ok_or_else(|| error::not_found(format!("Folder '{}' not found", id)))?)
+        }
+        None => None,
+    };
+    TaskFilter::parse(args, folder_id, Utc::now())
+}
+
+/// Start all downloads matching the filter
 async fn handle_start_all(
     state: &AppState,
     manager: &DownloadManager,
-    folder: Option<String>,
+    args: TaskFilterArgs,
 ) -> Result<i32> {
-    let tasks = manager.get_all_downloads().await;
+    let filter = parse_filter(state, &args).await?
+        .with_default_statuses(&[DownloadStatus::Pending, DownloadStatus::Paused]);
+    // Running and finished downloads cannot be started again
+    let mut tasks: Vec<DownloadTask> = filter
+        .apply(manager.get_all_downloads().await)
+        .into_iter()
+        .filter(|task| !matches!(task.status, DownloadStatus::Downloading | DownloadStatus::Completed))
+        // Held for size confirmation: only `ggg confirm` starts these
+        .filter(|task| !task.awaiting_confirmation)
+        .collect();
+    sort_for_schedule(&mut tasks, state.config.read().await.download.schedule_policy);
+
+    if args.dry_run {
+        filter::print_dry_run("start", &tasks);
+        return Ok(error::SUCCESS);
+    }
 
     let mut started_count = 0;
     for task in tasks {
-        // Filter by folder if specified
-        if let Some(ref folder_filter) = folder {
-            if task.folder_id != *folder_filter {
-                continue;
-            }
-        }
-
-        // Only start pending or paused tasks
-        if matches!(task.status, DownloadStatus::Pending | DownloadStatus::Paused) {
-            match manager.start_download(task.id, state.script_sender.clone(), state.config.clone()).await {
-                Ok(_) => started_count += 1,
-                Err(e) => tracing::warn!("Failed to start {}: {}", task.filename, e),
-            }
+        match manager.start_download(task.id, state.script_sender.clone(), state.config.clone()).await {
+            Ok(_) => started_count += 1,
+            Err(e) => tracing::warn!("Failed to start {}: {}", task.filename, e),
         }
     }
 
@@ -1271,28 +2352,55 @@ async fn handle_start_all(
     Ok(error::SUCCESS)
 }
 
-/// Pause all downloads
-async fn handle_pause_all(
+/// Retry all failed downloads
+async fn handle_retry_all(
+    state: &AppState,
     manager: &DownloadManager,
     folder: Option<String>,
 ) -> Result<i32> {
-    let tasks = manager.get_all_downloads().await;
+    let folder_id = match folder {
+        Some(ref id) => {
+            let config = state.config.read().await;
+            Some(resolve_folder_id(&config, id).ok_or_else(|| error::not_found(format!("Folder '{}' not found", id)))?)
+        }
+        None => None,
+    };
+
+    let retried = manager
+        .retry_failed_tasks(folder_id.as_deref(), state.script_sender.clone(), state.config.clone())
+        .await;
+
+    manager.save_queue_to_folders().await?;
+
+    println!("Retried {} failed download(s)", retried);
+    Ok(error::SUCCESS)
+}
+
+/// Pause all downloads matching the filter
+async fn handle_pause_all(
+    state: &AppState,
+    manager: &DownloadManager,
+    args: TaskFilterArgs,
+) -> Result<i32> {
+    let filter = parse_filter(state, &args).await?
+        .with_default_statuses(&[DownloadStatus::Downloading]);
+    // Only downloading tasks can be paused
+    let tasks: Vec<DownloadTask> = filter
+        .apply(manager.get_all_downloads().await)
+        .into_iter()
+        .filter(|task| task.status == DownloadStatus::Downloading)
+        .collect();
+
+    if args.dry_run {
+        filter::print_dry_run("pause", &tasks);
+        return Ok(error::SUCCESS);
+    }
 
     let mut paused_count = 0;
     for task in tasks {
-        // Filter by folder if specified
-        if let Some(ref folder_filter) = folder {
-            if task.folder_id != *folder_filter {
-                continue;
-            }
-        }
-
-        // Only pause downloading tasks
-        if matches!(task.status, DownloadStatus::Downloading) {
-            match manager.pause_download(task.id).await {
-                Ok(_) => paused_count += 1,
-                Err(e) => tracing::warn!("Failed to pause {}: {}", task.filename, e),
-            }
+        match manager.pause_download(task.id).await {
+            Ok(_) => paused_count += 1,
+            Err(e) => tracing::warn!("Failed to pause {}: {}", task.filename, e),
         }
     }
 
@@ -1304,39 +2412,26 @@ async fn handle_pause_all(
 
 /// Clear downloads by status
 async fn handle_clear(
+    state: &AppState,
     manager: &DownloadManager,
-    status_str: String,
-    folder: Option<String>,
+    args: TaskFilterArgs,
 ) -> Result<i32> {
-    // Parse status list (comma-separated)
-    let statuses: Vec<&str> = status_str.split(',').map(|s| s.trim()).collect();
-
-    let tasks = manager.get_all_downloads().await;
-    let mut removed_count = 0;
+    if args.status.is_none() {
+        return Err(error::usage("clear requires --status (e.g. --status completed,error)"));
+    }
 
-    for task in tasks {
-        // Filter by folder if specified
-        if let Some(ref folder_filter) = folder {
-            if task.folder_id != *folder_filter {
-                continue;
-            }
-        }
+    let filter = parse_filter(state, &args).await?;
+    let tasks = filter.apply(manager.get_all_downloads().await);
 
-        // Check if task status matches any of the specified statuses
-        let should_remove = statuses.iter().any(|status| {
-            match *status {
-                "completed" => matches!(task.status, DownloadStatus::Completed),
-                "error" => matches!(task.status, DownloadStatus::Error),
-                "paused" => matches!(task.status, DownloadStatus::Paused),
-                "pending" => matches!(task.status, DownloadStatus::Pending),
-                _ => false,
-            }
-        });
+    if args.dry_run {
+        filter::print_dry_run("clear", &tasks);
+        return Ok(error::SUCCESS);
+    }
 
-        if should_remove {
-            if manager.remove_download(task.id).await.is_some() {
-                removed_count += 1;
-            }
+    let mut removed_count = 0;
+    for task in tasks {
+        if manager.remove_download(task.id).await.is_some() {
+            removed_count += 1;
         }
     }
 
@@ -1351,12 +2446,13 @@ async fn handle_batch_add(
     state: &AppState,
     manager: &DownloadManager,
     file: String,
-    folder: Option<String>,
+    options: AddOptions,
 ) -> Result<i32> {
+    let headers = options.parse_headers()?;
     let file_path = PathBuf::from(&file);
 
     if !file_path.exists() {
-        return Err(anyhow::anyhow!("File not found: {}", file));
+        return Err(error::not_found(format!("File not found: {}", file)));
     }
 
     let content = std::fs::read_to_string(&file_path)?;
@@ -1371,17 +2467,19 @@ async fn handle_batch_add(
     }
 
     let config = state.config.read().await;
-    let save_path = config.download.default_directory.clone();
+    let tasks = urls
+        .iter()
+        .map(|url| options.new_task(&config, url, &headers))
+        .collect::<Result<Vec<DownloadTask>>>()?;
+
+    // Validate every destination before queueing anything
+    if !options.no_create_dirs && !ensure_save_directories(&config, &tasks) {
+        return Ok(error::USAGE);
+    }
     drop(config);
 
     let mut added_count = 0;
-    for url in urls {
-        let mut task = DownloadTask::new(url.to_string(), save_path.clone());
-
-        if let Some(ref folder_id) = folder {
-            task.folder_id = folder_id.clone();
-        }
-
+    for task in tasks {
         manager.add_download(task).await;
         added_count += 1;
     }
@@ -1402,7 +2500,7 @@ async fn handle_priority(
     id_str: String,
     priority: u8,
 ) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     manager.set_priority(id, priority).await?;
     manager.save_queue_to_folders().await?;
@@ -1412,27 +2510,31 @@ async fn handle_priority(
 }
 
 /// Move download in queue or to another folder
+#[allow(clippy::too_many_arguments)]
 async fn handle_move(
+    state: &AppState,
     manager: &DownloadManager,
     id_str: String,
     to_top: bool,
     to_bottom: bool,
     before: Option<String>,
+    after: Option<String>,
     folder: Option<String>,
+    keep_path: bool,
 ) -> Result<i32> {
-    let id = Uuid::parse_str(&id_str).map_err(|_| anyhow::anyhow!("Invalid UUID format"))?;
+    let id = resolve_id(manager, &id_str).await?;
 
     // Check that only one operation is specified
-    let ops_count = [to_top, to_bottom, before.is_some(), folder.is_some()]
+    let ops_count = [to_top, to_bottom, before.is_some(), after.is_some(), folder.is_some()]
         .iter()
         .filter(|&&x| x)
         .count();
 
     if ops_count == 0 {
-        return Err(anyhow::anyhow!("Must specify one of: --to-top, --to-bottom, --before, --folder"));
+        return Err(error::usage("Must specify one of: --to-top, --to-bottom, --before, --after, --folder"));
     }
     if ops_count > 1 {
-        return Err(anyhow::anyhow!("Can only specify one operation at a time"));
+        return Err(error::usage("Can only specify one operation at a time"));
     }
 
     if to_top {
@@ -1442,13 +2544,23 @@ async fn handle_move(
         manager.move_to_bottom(id).await?;
         println!("Moved download {} to bottom of queue", id);
     } else if let Some(before_id_str) = before {
-        let before_id = Uuid::parse_str(&before_id_str)
-            .map_err(|_| anyhow::anyhow!("Invalid before UUID format"))?;
+        let before_id = resolve_id(manager, &before_id_str).await?;
         manager.move_before(id, before_id).await?;
         println!("Moved download {} before {}", id, before_id);
-    } else if let Some(folder_id) = folder {
-        manager.change_folder(id, folder_id.clone()).await?;
-        println!("Moved download {} to folder '{}'", id, folder_id);
+    } else if let Some(after_id_str) = after {
+        let after_id = resolve_id(manager, &after_id_str).await?;
+        manager.move_after(id, after_id).await?;
+        println!("Moved download {} after {}", id, after_id);
+    } else if let Some(folder) = folder {
+        let config = state.config.read().await;
+        let folder_id = resolve_folder_id(&config, &folder)
+            .ok_or_else(|| error::not_found(format!("Folder '{}' not found", folder)))?;
+        // Queue files are written by move_task_to_folder
+        let task = manager
+            .move_task_to_folder(id, &folder_id, (!keep_path).then_some(&*config))
+            .await?;
+        println!("Moved download {} to folder '{}' ({})", id, folder, task.save_path.display());
+        return Ok(error::SUCCESS);
     }
 
     manager.save_queue_to_folders().await?;
@@ -1468,6 +2580,9 @@ async fn handle_export(
     match action {
         ExportAction::Queue { output } => handle_export_queue(manager, output).await,
         ExportAction::Config { output } => handle_export_config(_state, output).await,
+        ExportAction::All { output, history, include_secrets } => {
+            super::bundle::handle_export_all(_state, manager, output, history, include_secrets).await
+        }
     }
 }
 
@@ -1508,56 +2623,10 @@ async fn handle_import(
     manager: &DownloadManager,
 ) -> Result<i32> {
     match action {
-        ImportAction::Queue { input } => handle_import_queue(manager, input).await,
-        ImportAction::Config { input } => handle_import_config(state, input).await,
-    }
-}
-
-/// Import queue from file
-async fn handle_import_queue(
-    manager: &DownloadManager,
-    input: String,
-) -> Result<i32> {
-    let input_path = PathBuf::from(&input);
-
-    if !input_path.exists() {
-        return Err(anyhow::anyhow!("File not found: {}", input));
-    }
-
-    let content = std::fs::read_to_string(&input_path)?;
-    let tasks: Vec<DownloadTask> = serde_json::from_str(&content)?;
-
-    for task in &tasks {
-        manager.add_download(task.clone()).await;
-    }
-
-    manager.save_queue_to_folders().await?;
-
-    println!("Imported {} task(s) from {}", tasks.len(), input);
-    Ok(error::SUCCESS)
-}
-
-/// Import configuration from file
-async fn handle_import_config(
-    state: &AppState,
-    input: String,
-) -> Result<i32> {
-    let input_path = PathBuf::from(&input);
-
-    if !input_path.exists() {
-        return Err(anyhow::anyhow!("File not found: {}", input));
+        ImportAction::Queue { input, merge } => super::import::handle_import_queue(state, manager, input, merge).await,
+        ImportAction::Config { input } => super::import::handle_import_config(state, input).await,
+        ImportAction::All { input, replace } => super::bundle::handle_import_all(state, manager, input, replace).await,
     }
-
-    let content = std::fs::read_to_string(&input_path)?;
-    let new_config: Config = toml::from_str(&content)?;
-
-    let mut config = state.config.write().await;
-    *config = new_config;
-    config.save()?;
-
-    println!("Imported configuration from {}", input);
-    println!("Note: Application restart may be required for some settings to take effect");
-    Ok(error::SUCCESS)
 }
 
 // ========================================
@@ -1635,3 +2704,89 @@ async fn handle_test_reset_config(state: &AppState) -> Result<i32> {
     println!("Note: Application restart may be required");
     Ok(error::SUCCESS)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_id_prefix() {
+        let a = Uuid::parse_str("5f0c1a2b-0000-4000-8000-000000000001").unwrap();
+        let b = Uuid::parse_str("5f0d9e8f-0000-4000-8000-000000000002").unwrap();
+        let ids = [a, b];
+
+        assert_eq!(match_id_prefix("5f0c", &ids).unwrap(), a);
+        assert_eq!(match_id_prefix("5F0D9E8F-0000", &ids).unwrap(), b);
+
+        let err = match_id_prefix("5f0", &ids).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+        assert!(err.to_string().contains("ambiguous (2 matches)"));
+
+        let err = match_id_prefix("abc", &ids).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::NOT_FOUND);
+
+        let err = match_id_prefix("file.zip", &ids).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+    }
+
+    #[test]
+    fn test_add_rejects_disallowed_scheme() {
+        let mut config = Config::default();
+        config.download.allowed_schemes = vec!["https".to_string()];
+        let options = AddOptions { folder: None, headers: Vec::new(), referer: None, no_create_dirs: true };
+
+        assert!(options.new_task(&config, "https://example.com/a.zip", &[]).is_ok());
+        let err = options.new_task(&config, "ftp://example.com/a.zip", &[]).unwrap_err();
+        assert_eq!(error::exit_code(&err), error::USAGE);
+        assert!(err.to_string().contains("URL scheme 'ftp' is not allowed"), "{}", err);
+    }
+
+    #[test]
+    fn test_script_test_contexts() {
+        let url = "https://example.com/media/photo.jpg?size=large";
+        let values = ScriptTestValues {
+            size: Some(2048),
+            status: Some(206),
+            downloaded: Some(512),
+            total: Some(2048),
+            ..Default::default()
+        };
+
+        let completed = values.completed(url, std::path::Path::new("dl"));
+        assert_eq!(completed.filename, "photo.jpg");
+        assert_eq!(completed.size, 2048);
+        assert_eq!(completed.status_code, Some(206));
+
+        let progress = values.progress(url);
+        assert_eq!(progress.downloaded, 512);
+        assert_eq!(progress.percentage, Some(25.0));
+
+        // Without the options the contexts still carry sensible defaults
+        let values = ScriptTestValues { filename: Some("named.bin".to_string()), ..Default::default() };
+        assert_eq!(values.completed(url, std::path::Path::new("dl")).filename, "named.bin");
+        assert_eq!(values.progress(url).percentage, None);
+        assert_eq!(values.headers_received(url).status, 200);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_debug_info_reports_paths_and_limits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        let mut config = Config::default();
+        config.download.max_concurrent = 4;
+        let state = AppState::new(config, "en");
+        let manager = DownloadManager::with_max_concurrent(4);
+
+        let info = debug_info(&state, &manager).await;
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(info["config_dir"], temp_dir.path().display().to_string());
+        assert_eq!(info["logs_dir"], temp_dir.path().join(".logs").display().to_string());
+        assert_eq!(info["locale"], state.i18n.current_locale());
+        assert_eq!(info["limits"]["max_concurrent"], 4);
+        assert_eq!(info["limits"]["max_connections"], 4);
+        // Gathering the info creates nothing
+        assert!(!temp_dir.path().join(".logs").exists());
+        crate::util::paths::set_config_dir_override(None);
+    }
+}
@@ -0,0 +1,307 @@
+//! Checks for `ggg import queue`, `ggg import config` and `ggg import all`
+//!
+//! Imported files are parsed into the typed structs and checked before
+//! anything live is touched. A configuration with problems is refused as a
+//! whole; a queue is imported task by task, skipping tasks whose folder does
+//! not exist, whose URL scheme is not allowed or whose ID is already queued,
+//! and reporting each skip. The files an import rewrites are copied to
+//! `{config_dir}/backups/<timestamp>/` first.
+
+use super::error;
+use crate::app::config::{Config, ConfigIssues, MIN_CHUNK_SIZE_BYTES};
+use crate::app::state::AppState;
+use crate::download::manager::DownloadManager;
+use crate::download::task::{DownloadStatus, DownloadTask};
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Tasks accepted for import and the reasons others were left out
+#[derive(Debug, Default)]
+pub struct QueueImport {
+    pub accepted: Vec<DownloadTask>,
+    /// `url: reason` for each skipped task
+    pub skipped: Vec<String>,
+}
+
+/// Problems that make a configuration unfit to replace the live one
+pub fn config_problems(config: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if let Err(errors) = crate::app::settings::validate_folder_config(config) {
+        problems.extend(errors.iter().map(|e| e.to_string()));
+    }
+    if config.download.max_concurrent == 0 {
+        problems.push("download.max_concurrent must be at least 1".to_string());
+    }
+    if config.download.chunk_size_bytes < MIN_CHUNK_SIZE_BYTES {
+        problems.push(format!(
+            "download.chunk_size_bytes must be at least {} (got {})",
+            MIN_CHUNK_SIZE_BYTES, config.download.chunk_size_bytes
+        ));
+    }
+    if config.download.allowed_schemes.is_empty() {
+        problems.push("download.allowed_schemes is empty, so no URL could be added".to_string());
+    }
+
+    let mut folders: Vec<_> = config.folders.iter().collect();
+    folders.sort_by(|a, b| a.0.cmp(b.0));
+    for (id, folder) in folders {
+        if folder.name.trim().is_empty() {
+            problems.push(format!("folder '{}' has no name", id));
+        }
+        if folder.save_path.as_os_str().is_empty() {
+            problems.push(format!("folder '{}' has no save_path", folder.name));
+        }
+    }
+
+    problems
+}
+
+/// Split imported tasks into those that can be queued under `config` and skips
+///
+/// `existing` holds the IDs already queued. A `folder_id` naming a folder
+/// (`default`) is resolved to its key. Downloads that were running when
+/// exported come back paused.
+pub fn check_tasks(tasks: Vec<DownloadTask>, config: &Config, existing: &HashSet<Uuid>) -> QueueImport {
+    let mut seen = existing.clone();
+    let mut result = QueueImport::default();
+
+    for mut task in tasks {
+        if !config.folders.contains_key(&task.folder_id)
+            && let Some(id) = config.find_folder_id_by_name(&task.folder_id)
+        {
+            task.folder_id = id;
+        }
+
+        let problem = if !config.folders.contains_key(&task.folder_id) {
+            Some(format!("folder '{}' does not exist", task.folder_id))
+        } else if let Err(e) = config.download.check_url_scheme(&task.url) {
+            Some(e.to_string())
+        } else if task.filename.trim().is_empty() {
+            Some("no filename".to_string())
+        } else if task.status == DownloadStatus::Deleted {
+            Some("deleted".to_string())
+        } else if task.size.is_some_and(|size| task.downloaded > size) {
+            Some(format!("{} bytes downloaded of a {}-byte file", task.downloaded, task.size.unwrap_or(0)))
+        } else if !seen.insert(task.id) {
+            Some("already queued".to_string())
+        } else {
+            None
+        };
+
+        match problem {
+            Some(reason) => result.skipped.push(format!("{}: {}", task.url, reason)),
+            None => {
+                if task.status == DownloadStatus::Downloading {
+                    task.status = DownloadStatus::Paused;
+                }
+                result.accepted.push(task);
+            }
+        }
+    }
+
+    result
+}
+
+/// Settings files of the current configuration (settings.toml and each folder's)
+pub fn config_files(config: &Config) -> Result<Vec<PathBuf>> {
+    let mut files = vec![crate::util::paths::get_app_config_path()?];
+    for id in config.folders.keys() {
+        files.push(crate::util::paths::get_folder_config_path(id)?);
+    }
+    Ok(files)
+}
+
+/// Queue files of every folder in `config`
+pub fn queue_files(config: &Config) -> Result<Vec<PathBuf>> {
+    config.folders.keys().map(|id| crate::util::paths::get_folder_queue_path(id)).collect()
+}
+
+/// Copy `files` that exist into `backup_dir`, keeping their place relative to `config_dir`
+///
+/// Returns the number of files copied.
+fn backup_into(config_dir: &Path, backup_dir: &Path, files: &[PathBuf]) -> Result<usize> {
+    let mut copied = 0;
+    for file in files.iter().filter(|file| file.is_file()) {
+        let relative = file
+            .strip_prefix(config_dir)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| PathBuf::from(file.file_name().unwrap_or_default()));
+        let target = backup_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(file, &target)?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Copy the files an import is about to rewrite to `{config_dir}/backups/<timestamp>/`
+///
+/// Returns the backup directory, or None if none of the files exist yet.
+pub fn backup_files(files: &[PathBuf]) -> Result<Option<PathBuf>> {
+    let config_dir = crate::util::paths::find_config_directory()?;
+    let backup_dir = crate::util::paths::get_backups_dir()?.join(Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string());
+    let copied = backup_into(&config_dir, &backup_dir, files)?;
+    Ok((copied > 0).then_some(backup_dir))
+}
+
+/// Read an import file, mapping a missing file to `NOT_FOUND`
+pub fn read_input(input: &str) -> Result<String> {
+    let path = PathBuf::from(input);
+    if !path.exists() {
+        return Err(error::not_found(format!("File not found: {}", input)));
+    }
+    Ok(std::fs::read_to_string(&path)?)
+}
+
+/// Print where the replaced files were copied, if anywhere
+pub fn print_backup(backup: Option<PathBuf>) {
+    if let Some(dir) = backup {
+        println!("Backed up the current files to {}", dir.display());
+    }
+}
+
+/// Print the skipped tasks, if any
+pub fn print_skipped(skipped: &[String]) {
+    if skipped.is_empty() {
+        return;
+    }
+    println!("Skipped {} task(s):", skipped.len());
+    for reason in skipped {
+        println!("  {}", reason);
+    }
+}
+
+/// `ggg import queue`
+pub async fn handle_import_queue(
+    state: &AppState,
+    manager: &DownloadManager,
+    input: String,
+    merge: bool,
+) -> Result<i32> {
+    let content = read_input(&input)?;
+    let tasks: Vec<DownloadTask> =
+        serde_json::from_str(&content).map_err(|e| error::usage(format!("Cannot import {}: {}", input, e)))?;
+
+    let config = state.config.read().await.clone();
+    let current = manager.get_all_downloads().await;
+    let existing: HashSet<Uuid> = if merge { current.iter().map(|t| t.id).collect() } else { HashSet::new() };
+    let import = check_tasks(tasks, &config, &existing);
+
+    print_backup(backup_files(&queue_files(&config)?)?);
+    if !merge {
+        for task in current {
+            manager.remove_download(task.id).await;
+        }
+    }
+    let imported = import.accepted.len();
+    for task in import.accepted {
+        manager.add_download(task).await;
+    }
+    manager.save_queue_to_folders().await?;
+
+    let verb = if merge { "Added" } else { "Imported" };
+    println!("{} {} task(s) from {}", verb, imported, input);
+    print_skipped(&import.skipped);
+    Ok(error::SUCCESS)
+}
+
+/// `ggg import config`
+pub async fn handle_import_config(state: &AppState, input: String) -> Result<i32> {
+    let content = read_input(&input)?;
+
+    let mut issues = ConfigIssues::default();
+    issues.check_content::<Config>(&content, &input);
+    if !issues.errors.is_empty() {
+        return Err(error::usage(format!("Cannot import {}", issues.errors.join("; "))));
+    }
+    let mut imported: Config = toml::from_str(&content)?;
+    imported.scripts.directory = crate::util::paths::resolve_relative_to_config(&imported.scripts.directory);
+
+    let problems = config_problems(&imported);
+    if !problems.is_empty() {
+        return Err(error::usage(format!("Cannot import {}: {}", input, problems.join("; "))));
+    }
+
+    let mut config = state.config.write().await;
+    print_backup(backup_files(&config_files(&config)?)?);
+    *config = imported;
+    config.save()?;
+
+    println!("Imported configuration from {} ({} folder(s))", input, config.folders.len());
+    for key in &issues.unknown_keys {
+        println!("Skipped unknown setting {}", key);
+    }
+    println!("Note: Application restart may be required for some settings to take effect");
+    Ok(error::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(url: &str) -> DownloadTask {
+        DownloadTask::new(url.to_string(), PathBuf::from("/tmp"))
+    }
+
+    #[test]
+    fn test_check_tasks_skips_invalid_and_duplicates() {
+        let mut config = Config::default();
+        config.folders.insert("f-1".to_string(), crate::app::config::FolderConfig::new_with_name("default"));
+
+        let queued = task("https://example.com/queued.zip");
+        let mut running = task("https://example.com/running.zip");
+        running.status = DownloadStatus::Downloading;
+        let mut orphan = task("https://example.com/orphan.zip");
+        orphan.folder_id = "missing".to_string();
+        let mut overrun = task("https://example.com/overrun.zip");
+        overrun.size = Some(10);
+        overrun.downloaded = 20;
+        let bad_scheme = task("file:///etc/passwd");
+
+        let existing = HashSet::from([queued.id]);
+        let import = check_tasks(
+            vec![queued, running.clone(), running, orphan, overrun, bad_scheme],
+            &config,
+            &existing,
+        );
+
+        assert_eq!(import.accepted.len(), 1);
+        assert_eq!(import.accepted[0].status, DownloadStatus::Paused);
+        // Folder names resolve to folder keys
+        assert_eq!(import.accepted[0].folder_id, "f-1");
+        assert_eq!(import.skipped.len(), 5, "{:?}", import.skipped);
+        assert!(import.skipped[0].ends_with("already queued"));
+        assert!(import.skipped[2].contains("folder 'missing'"));
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let mut config = Config::default();
+        assert!(config_problems(&config).is_empty(), "{:?}", config_problems(&config));
+
+        config.download.max_concurrent = 0;
+        config.download.chunk_size_bytes = 1;
+        let problems = config_problems(&config);
+        assert!(problems.iter().any(|p| p.contains("max_concurrent")), "{:?}", problems);
+        assert!(problems.iter().any(|p| p.contains("chunk_size_bytes")), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_backup_keeps_relative_paths() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let folder_file = config_dir.path().join("abc").join("queue.toml");
+        std::fs::create_dir_all(folder_file.parent().unwrap()).unwrap();
+        std::fs::write(&folder_file, "tasks = []").unwrap();
+        let missing = config_dir.path().join("def").join("queue.toml");
+
+        let backup_dir = config_dir.path().join("backups").join("now");
+        assert_eq!(backup_into(config_dir.path(), &backup_dir, &[folder_file, missing]).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(backup_dir.join("abc").join("queue.toml")).unwrap(), "tasks = []");
+    }
+}
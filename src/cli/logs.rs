@@ -0,0 +1,324 @@
+//! Application log viewer for `ggg logs`
+//!
+//! Application logs are written by `tracing-appender` as JSON lines to
+//! `{config_dir}/.logs/app.jsonl.YYYY-MM-DD` (rotation per `[logging]`:
+//! `-HH` is appended for hourly files, and a single `app.jsonl` is used when
+//! rotation is off). This module finds the current file, filters records by
+//! level and renders them as compact colored lines, and implements
+//! `--follow` by tailing the file and switching to the next one when the
+//! log rotates. Old files are pruned at startup according to `max_files`.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::Level;
+
+/// File name prefix passed to the rolling appender in `main.rs`
+pub const LOG_FILE_PREFIX: &str = "app.jsonl";
+
+/// How often the followed file is polled for new lines
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Application log files in `logs_dir`, oldest first
+///
+/// Rotated names (`app.jsonl.YYYY-MM-DD[-HH]`) sort chronologically. An
+/// unrotated `app.jsonl` is only written with rotation off, so it is always
+/// the live file and sorts last, after any files left from earlier rotation.
+/// Completion logs sharing the directory (`YYYYMMDD.jsonl`) are not included.
+pub fn list_log_files(logs_dir: &Path) -> Vec<PathBuf> {
+    let rotated_prefix = format!("{}.", LOG_FILE_PREFIX);
+    let Ok(entries) = std::fs::read_dir(logs_dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == LOG_FILE_PREFIX || name.starts_with(&rotated_prefix))
+        })
+        .collect();
+    files.sort_by_key(|path| (path.file_name().is_some_and(|name| name == LOG_FILE_PREFIX), path.clone()));
+    files
+}
+
+/// The application log currently being written (the newest file)
+pub fn current_log_file(logs_dir: &Path) -> Option<PathBuf> {
+    list_log_files(logs_dir).pop()
+}
+
+/// Delete the oldest log files so that at most `max_files` remain (0 = keep all)
+///
+/// Returns the number of deleted files.
+pub fn prune_log_files(logs_dir: &Path, max_files: usize) -> Result<usize> {
+    if max_files == 0 {
+        return Ok(0);
+    }
+    let files = list_log_files(logs_dir);
+    let excess = files.len().saturating_sub(max_files);
+    for path in &files[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(excess)
+}
+
+/// A parsed JSON log line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Extra structured fields (e.g. `script`), in file order
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    /// Parse one line of `tracing_subscriber`'s JSON output
+    ///
+    /// Returns None for lines that are not JSON log records.
+    pub fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line.trim()).ok()?;
+        let level = value.get("level")?.as_str()?.parse().ok()?;
+        let timestamp = value.get("timestamp").and_then(Value::as_str).unwrap_or_default().to_string();
+        let target = value.get("target").and_then(Value::as_str).unwrap_or_default().to_string();
+
+        let mut message = String::new();
+        let mut fields = Vec::new();
+        if let Some(Value::Object(map)) = value.get("fields") {
+            for (key, field) in map {
+                let text = match field {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                if key == "message" {
+                    message = text;
+                } else {
+                    fields.push((key.clone(), text));
+                }
+            }
+        }
+
+        Some(Self { timestamp, level, target, message, fields })
+    }
+
+    /// True if the record is at least as severe as `min_level`
+    pub fn passes(&self, min_level: Option<Level>) -> bool {
+        // tracing orders levels by verbosity: ERROR < WARN < ... < TRACE
+        min_level.is_none_or(|min| self.level <= min)
+    }
+
+    /// Render as `YYYY-MM-DD HH:MM:SS.mmm LEVEL target: message key=value` (local time)
+    pub fn format(&self, color: bool) -> String {
+        let time = chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone());
+        let level = format!("{:<5}", self.level.as_str());
+        let fields: String = self.fields.iter().map(|(k, v)| format!(" {}={}", k, v)).collect();
+
+        if color {
+            format!(
+                "\x1b[2m{}\x1b[0m {}{}\x1b[0m \x1b[2m{}:\x1b[0m {}\x1b[2m{}\x1b[0m",
+                time,
+                level_color(self.level),
+                level,
+                self.target,
+                self.message,
+                fields
+            )
+        } else {
+            format!("{} {} {}: {}{}", time, level, self.target, self.message, fields)
+        }
+    }
+}
+
+fn level_color(level: Level) -> &'static str {
+    match level {
+        Level::ERROR => "\x1b[31m",
+        Level::WARN => "\x1b[33m",
+        Level::INFO => "\x1b[32m",
+        Level::DEBUG => "\x1b[34m",
+        Level::TRACE => "\x1b[35m",
+    }
+}
+
+/// Parse a `--level` argument (error, warn, info, debug, trace)
+pub fn parse_level(level: &str) -> Result<Level> {
+    level
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid log level '{}' (expected error, warn, info, debug or trace)", level))
+}
+
+/// True if a raw line passes the level filter
+///
+/// Lines that are not JSON records are kept unless a level filter is set.
+fn line_passes(line: &str, min_level: Option<Level>) -> bool {
+    if line.trim().is_empty() {
+        return false;
+    }
+    match LogRecord::parse(line) {
+        Some(record) => record.passes(min_level),
+        None => min_level.is_none(),
+    }
+}
+
+/// Render a raw line in the compact format (non-JSON lines are kept as-is)
+pub fn render_pretty(line: &str, color: bool) -> String {
+    match LogRecord::parse(line) {
+        Some(record) => record.format(color),
+        None => line.trim_end().to_string(),
+    }
+}
+
+/// Last `count` raw lines of `path` that pass the level filter
+pub fn tail(path: &Path, count: usize, min_level: Option<Level>) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().filter(|line| line_passes(line, min_level)).collect();
+    let start = lines.len().saturating_sub(count);
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Follow the current log file until Ctrl-C, like `tail -f`
+///
+/// Lines are rendered with `render_pretty`; the last `initial` matching
+/// lines are printed first. When the log rotates, the new file is read from
+/// the start as soon as the appender creates it.
+pub async fn follow(logs_dir: &Path, initial: usize, min_level: Option<Level>, color: bool) -> Result<()> {
+    let mut path = current_log_file(logs_dir);
+
+    let mut reader = match path.as_ref().map(|p| (p, File::open(p))) {
+        Some((p, Ok(mut file))) => {
+            for line in tail(p, initial, min_level)? {
+                println!("{}", render_pretty(&line, color));
+            }
+            file.seek(SeekFrom::End(0))?;
+            Some(BufReader::new(file))
+        }
+        _ => None,
+    };
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    // Holds a line until its newline arrives (the appender may be mid-write)
+    let mut line = String::new();
+
+    loop {
+        if let Some(ref mut reader) = reader {
+            while reader.read_line(&mut line)? > 0 {
+                if !line.ends_with('\n') {
+                    break;
+                }
+                if line_passes(&line, min_level) {
+                    println!("{}", render_pretty(&line, color));
+                }
+                line.clear();
+            }
+        }
+
+        // Rotation: move on to the newest file, reading it from the start
+        let latest = current_log_file(logs_dir);
+        if latest.is_some() && latest != path {
+            path = latest;
+            reader = None;
+            line.clear();
+        }
+        if reader.is_none() {
+            reader = path.as_ref().and_then(|p| File::open(p).ok()).map(BufReader::new);
+        }
+
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            _ = tokio::time::sleep(FOLLOW_POLL_INTERVAL) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r#"{"timestamp":"2026-01-02T03:04:05.678901Z","level":"WARN","fields":{"message":"Suppressed 3 log line(s)","script":"chatty.js"},"target":"script"}"#;
+
+    #[test]
+    fn test_parse_json_record() {
+        let record = LogRecord::parse(LINE).unwrap();
+        assert_eq!(record.level, Level::WARN);
+        assert_eq!(record.target, "script");
+        assert_eq!(record.message, "Suppressed 3 log line(s)");
+        assert_eq!(record.fields, vec![("script".to_string(), "chatty.js".to_string())]);
+
+        let plain = record.format(false);
+        assert!(plain.contains("WARN  script: Suppressed 3 log line(s) script=chatty.js"));
+
+        assert!(LogRecord::parse("not json").is_none());
+    }
+
+    #[test]
+    fn test_level_filter_is_minimum_severity() {
+        let record = LogRecord::parse(LINE).unwrap();
+        assert!(record.passes(None));
+        assert!(record.passes(Some(Level::INFO)));
+        assert!(record.passes(Some(Level::WARN)));
+        assert!(!record.passes(Some(Level::ERROR)));
+
+        assert!(parse_level("Debug").is_ok());
+        assert!(parse_level("verbose").is_err());
+    }
+
+    #[test]
+    fn test_list_and_prune_log_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in [
+            "app.jsonl.2026-01-03",
+            "app.jsonl",
+            "app.jsonl.2026-01-02-23",
+            "app.jsonl.2026-01-02",
+            "20260102.jsonl",
+        ] {
+            std::fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+
+        let names: Vec<String> = list_log_files(temp_dir.path())
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        // The unrotated file is the live one (rotation = "never"), whatever the dated files say
+        assert_eq!(
+            names,
+            ["app.jsonl.2026-01-02", "app.jsonl.2026-01-02-23", "app.jsonl.2026-01-03", "app.jsonl"]
+        );
+        assert!(current_log_file(temp_dir.path()).unwrap().ends_with("app.jsonl"));
+
+        assert_eq!(prune_log_files(temp_dir.path(), 0).unwrap(), 0);
+        assert_eq!(prune_log_files(temp_dir.path(), 2).unwrap(), 2);
+        assert_eq!(list_log_files(temp_dir.path()).len(), 2);
+        assert!(temp_dir.path().join("app.jsonl").exists());
+        assert!(temp_dir.path().join("app.jsonl.2026-01-03").exists());
+        // Completion logs are left alone
+        assert!(temp_dir.path().join("20260102.jsonl").exists());
+    }
+
+    #[test]
+    fn test_tail_filters_and_limits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("app.jsonl.2026-01-02");
+
+        let info = LINE.replace("WARN", "INFO");
+        let error = LINE.replace("WARN", "ERROR");
+        std::fs::write(&path, format!("{}\n{}\n{}\n{}\n", error, info, LINE, info)).unwrap();
+
+        let lines = tail(&path, 10, Some(Level::WARN)).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("ERROR"));
+
+        let lines = tail(&path, 1, None).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert!(render_pretty(&lines[0], false).contains("INFO"));
+    }
+}
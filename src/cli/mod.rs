@@ -1,9 +1,14 @@
 use clap::{Parser, Subcommand};
 
 pub mod error;
+pub mod filter;
+pub mod logs;
 pub mod output;
 pub mod handler;
 pub mod daemon;
+pub mod doctor;
+pub mod bundle;
+pub mod import;
 
 /// Great Grimoire Grabber - A classic-style download manager
 #[derive(Parser, Debug)]
@@ -22,6 +27,10 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Only print errors to stderr (the log file keeps the configured level)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -38,6 +47,34 @@ pub enum Commands {
         /// Folder ID to assign (default, images, videos, audio, archives)
         #[arg(long)]
         folder: Option<String>,
+
+        /// Don't create a missing save directory now (it is created when the download starts)
+        #[arg(long)]
+        no_create_dirs: bool,
+
+        /// Start the download right away (folder concurrency limits still apply)
+        #[arg(long)]
+        start: bool,
+
+        /// Start the download and wait for it to finish, showing progress (implies --start)
+        #[arg(long)]
+        wait: bool,
+
+        /// Extra request header, e.g. "Authorization: Bearer TOKEN" (repeatable; overrides the folder's default headers)
+        #[arg(long = "header", value_name = "NAME: VALUE")]
+        headers: Vec<String>,
+
+        /// Referer to send (shortcut for --header "Referer: URL"; wins over a Referer given with --header)
+        #[arg(long, value_name = "URL")]
+        referer: Option<String>,
+
+        /// Run beforeRequest scripts and routing, then show the request and target file without adding or downloading anything
+        #[arg(long, conflicts_with_all = ["start", "wait"])]
+        dry_run: bool,
+
+        /// Write the body to stdout instead of queueing a download (no scripts, routing or progress)
+        #[arg(long, conflicts_with_all = ["start", "wait", "dry_run", "no_create_dirs"])]
+        stdout: bool,
     },
 
     /// List all downloads
@@ -49,7 +86,7 @@ pub enum Commands {
 
     /// Start a download
     Start {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
         id: String,
 
         /// Wait for download to complete and show progress
@@ -59,27 +96,55 @@ pub enum Commands {
 
     /// Pause a download
     Pause {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
+        id: String,
+    },
+
+    /// Confirm a download held by download.confirm_over_bytes and start it
+    Confirm {
+        /// Download ID (UUID or unique prefix)
         id: String,
+
+        /// Wait for download to complete and show progress
+        #[arg(long)]
+        wait: bool,
     },
 
     /// Remove a download
     Remove {
-        /// Download ID (UUID)
-        id: String,
+        /// Download ID (UUID); omit to remove every download matching the filter
+        id: Option<String>,
+
+        #[command(flatten)]
+        filter: filter::TaskFilterArgs,
+    },
+
+    /// Restore a deleted download from the trash (lists the trash when no ID is given)
+    Restore {
+        /// Download ID (UUID or unique prefix)
+        id: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show download status
     Status {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
         id: String,
 
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Refresh until the download completes or fails (one JSON line per refresh with --json)
+        #[arg(long)]
+        watch: bool,
     },
 
     /// Manage configuration
+    #[command(after_help = crate::app::user_agent::PRESET_HELP)]
     Config {
         /// Configuration action
         #[command(subcommand)]
@@ -88,21 +153,29 @@ pub enum Commands {
 
     /// Display application logs
     Logs {
-        /// Follow log output (tail -f mode)
+        /// Follow the current log file (tail -f mode, pretty-printed; Ctrl+C to stop)
         #[arg(long, short)]
         follow: bool,
 
-        /// Filter by log level (error, warn, info, debug, trace)
+        /// Show only this level and more severe (error, warn, info, debug, trace)
         #[arg(long)]
         level: Option<String>,
 
-        /// Number of lines to show (default: 50)
+        /// Number of lines to show (default: 50; initial lines in follow mode)
         #[arg(long, short = 'n')]
         lines: Option<usize>,
+
+        /// Render timestamp, level, target and message instead of raw JSON
+        #[arg(long)]
+        pretty: bool,
     },
 
     /// Show download completion history
     History {
+        /// History action (omit to list entries)
+        #[command(subcommand)]
+        action: Option<HistoryAction>,
+
         /// Show only today's completions
         #[arg(long)]
         today: bool,
@@ -122,6 +195,18 @@ pub enum Commands {
         #[arg(long)]
         folder: Option<String>,
 
+        /// Only include activity within this window (e.g. 24h, 7d, 2w)
+        #[arg(long, conflicts_with = "today")]
+        since: Option<String>,
+
+        /// Only include today's activity
+        #[arg(long)]
+        today: bool,
+
+        /// Show cumulative totals since ggg was first used
+        #[arg(long, conflicts_with_all = ["folder", "since", "today"])]
+        lifetime: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -150,6 +235,13 @@ pub enum Commands {
 
     /// Start all downloads
     StartAll {
+        /// Which downloads to start (default status: pending,paused)
+        #[command(flatten)]
+        filter: filter::TaskFilterArgs,
+    },
+
+    /// Retry all failed downloads
+    RetryAll {
         /// Filter by folder ID
         #[arg(long)]
         folder: Option<String>,
@@ -157,20 +249,16 @@ pub enum Commands {
 
     /// Pause all downloads
     PauseAll {
-        /// Filter by folder ID
-        #[arg(long)]
-        folder: Option<String>,
+        /// Which downloads to pause (default status: downloading)
+        #[command(flatten)]
+        filter: filter::TaskFilterArgs,
     },
 
-    /// Clear downloads by status
+    /// Clear downloads by status (without keeping them in the trash)
     Clear {
-        /// Comma-separated status list (completed,error,paused)
-        #[arg(long)]
-        status: String,
-
-        /// Filter by folder ID
-        #[arg(long)]
-        folder: Option<String>,
+        /// Which downloads to clear (--status is required)
+        #[command(flatten)]
+        filter: filter::TaskFilterArgs,
     },
 
     /// Batch add downloads from file
@@ -181,11 +269,19 @@ pub enum Commands {
         /// Folder ID to assign
         #[arg(long)]
         folder: Option<String>,
+
+        /// Don't create missing save directories now (they are created when downloads start)
+        #[arg(long)]
+        no_create_dirs: bool,
+
+        /// Extra request header for every URL (repeatable; overrides the folder's default headers)
+        #[arg(long = "header", value_name = "NAME: VALUE")]
+        headers: Vec<String>,
     },
 
     /// Set download priority
     Priority {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
         id: String,
 
         /// Priority value (0-255, higher = more priority)
@@ -195,7 +291,7 @@ pub enum Commands {
 
     /// Move download in queue or to another folder
     Move {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
         id: String,
 
         /// Move to top of queue
@@ -206,13 +302,21 @@ pub enum Commands {
         #[arg(long)]
         to_bottom: bool,
 
-        /// Move before another download
+        /// Move before another download (UUID or unique prefix)
         #[arg(long)]
         before: Option<String>,
 
-        /// Move to different folder
+        /// Move after another download (UUID or unique prefix)
+        #[arg(long)]
+        after: Option<String>,
+
+        /// Move to different folder (ID or name)
         #[arg(long)]
         folder: Option<String>,
+
+        /// With --folder, keep the current save path instead of the new folder's
+        #[arg(long, requires = "folder")]
+        keep_path: bool,
     },
 
     /// Export data
@@ -235,6 +339,21 @@ pub enum Commands {
         #[command(subcommand)]
         action: TestAction,
     },
+
+    /// Check the configuration, save directories and network for common problems
+    Doctor {
+        /// URL requested by the network check
+        #[arg(long, value_name = "URL", conflicts_with = "skip_network")]
+        url: Option<String>,
+
+        /// Skip the network check
+        #[arg(long)]
+        skip_network: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Configuration actions
@@ -261,11 +380,21 @@ pub enum ConfigAction {
         #[arg(long)]
         json: bool,
     },
+
+    /// Check the configuration files for parse errors, invalid values and unknown keys
+    Validate,
 }
 
 /// Debug and diagnostic actions
 #[derive(Subcommand, Debug)]
 pub enum DebugAction {
+    /// Show version, build target, config/log locations and limits (for bug reports)
+    Info {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Show download manager internal state
     ManagerState {
         /// Output as JSON
@@ -282,7 +411,7 @@ pub enum DebugAction {
 
     /// Show detailed task information
     Task {
-        /// Download ID (UUID)
+        /// Download ID (UUID or unique prefix)
         id: String,
 
         /// Output as JSON
@@ -293,12 +422,38 @@ pub enum DebugAction {
     /// Validate configuration
     ValidateConfig,
 
+    /// Check translations
+    I18n {
+        /// List keys present in en-US but missing from this locale (e.g. ja, ja-JP)
+        #[arg(long, value_name = "LANG")]
+        missing: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Check queue integrity
     CheckQueue {
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
+
+    /// Show circuit breaker keys and states, or reset them
+    CircuitBreaker {
+        /// Clear the failure state of one key (as listed by this command)
+        #[arg(long, value_name = "KEY", conflicts_with = "reset_all")]
+        reset: Option<String>,
+
+        /// Clear the failure state of every key
+        #[arg(long)]
+        reset_all: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Script management actions
@@ -332,17 +487,62 @@ pub enum ScriptAction {
         /// Script filename to test
         name: String,
 
-        /// Event to trigger (beforeRequest, headersReceived, completed, error, progress)
+        /// Event to trigger (beforeRequest, headersReceived, authRequired, completed, error, progress)
         #[arg(long)]
         event: String,
 
         /// URL for test context
         #[arg(long)]
         url: String,
+
+        /// File name for completed, error and progress (default: taken from the URL)
+        #[arg(long)]
+        filename: Option<String>,
+
+        /// File size in bytes for completed
+        #[arg(long)]
+        size: Option<u64>,
+
+        /// HTTP status for headersReceived, completed and error
+        #[arg(long)]
+        status: Option<u16>,
+
+        /// Bytes downloaded so far for progress
+        #[arg(long)]
+        downloaded: Option<u64>,
+
+        /// Total size in bytes for progress
+        #[arg(long)]
+        total: Option<u64>,
     },
 
     /// Reload all scripts (for daemon mode)
     Reload,
+
+    /// Check scripts for syntax errors without running them
+    Validate {
+        /// Script filename to validate
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+
+        /// Validate every script in the scripts directory
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Create a new script from a template
+    New {
+        /// Script name (".js" is appended if missing)
+        name: String,
+
+        /// Hook to wire up (beforeRequest, headersReceived, authRequired, completed, error, progress)
+        #[arg(long, default_value = "beforeRequest")]
+        event: String,
+
+        /// Overwrite an existing script
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 /// Folder management actions
@@ -394,6 +594,39 @@ pub enum FolderAction {
         /// Folder ID
         id: String,
     },
+
+    /// Rename a folder
+    Rename {
+        /// Current folder name (or ID)
+        from: String,
+
+        /// New folder name
+        to: String,
+    },
+
+    /// Create a folder with the same settings as an existing one (downloads are not copied)
+    Clone {
+        /// Folder to copy (name or ID)
+        from: String,
+
+        /// Name of the new folder
+        to: String,
+
+        /// Save path for the new folder's downloads
+        #[arg(long)]
+        path: String,
+    },
+}
+
+/// History actions
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    /// Remove old history entries (uses the [history] config policy by default)
+    Prune {
+        /// Remove entries older than this duration (e.g., 30d, 12h, 2w)
+        #[arg(long)]
+        older_than: Option<String>,
+    },
 }
 
 /// Export actions
@@ -412,16 +645,35 @@ pub enum ExportAction {
         #[arg(long)]
         output: String,
     },
+
+    /// Export configuration, queue and scripts to a single tar archive
+    All {
+        /// Output file path
+        #[arg(long)]
+        output: String,
+
+        /// Include the completion history
+        #[arg(long)]
+        history: bool,
+
+        /// Keep the proxy password, URL passwords and credential headers
+        #[arg(long)]
+        include_secrets: bool,
+    },
 }
 
 /// Import actions
 #[derive(Subcommand, Debug)]
 pub enum ImportAction {
-    /// Import queue from file
+    /// Import queue from file (replaces the queue unless --merge)
     Queue {
         /// Input file path
         #[arg(long)]
         input: String,
+
+        /// Add the imported tasks to the existing queues instead of replacing them
+        #[arg(long)]
+        merge: bool,
     },
 
     /// Import configuration from file
@@ -430,6 +682,17 @@ pub enum ImportAction {
         #[arg(long)]
         input: String,
     },
+
+    /// Import a bundle written by `export all` (merges unless --replace)
+    All {
+        /// Input file path
+        #[arg(long)]
+        input: String,
+
+        /// Replace the configuration and queue instead of merging
+        #[arg(long)]
+        replace: bool,
+    },
 }
 
 /// Test utility actions
@@ -1,6 +1,109 @@
-use crate::download::task::DownloadTask;
+use crate::download::completion_log::CompletedEntry;
+use crate::download::task::{short_id, short_id_len, DownloadTask};
+use crate::script::events::ScriptRun;
+use serde::Serialize;
 use serde_json;
 
+/// Version of the `--json` output format
+///
+/// Bumped whenever a payload changes in a way that could break consumers
+/// (removed or renamed fields, changed types). Adding fields is not breaking.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Versioned wrapper around every `--json` payload: `{ "schema": 1, "data": ... }`
+#[derive(Debug, Serialize)]
+pub struct Envelope<T> {
+    pub schema: u32,
+    pub data: T,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(data: T) -> Self {
+        Self { schema: JSON_SCHEMA_VERSION, data }
+    }
+}
+
+/// Render `data` in the versioned envelope (pretty-printed)
+pub fn to_json<T: Serialize>(data: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&Envelope::new(data))?)
+}
+
+/// Render `data` in the versioned envelope on a single line (for NDJSON streams)
+pub fn to_json_line<T: Serialize>(data: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(&Envelope::new(data))?)
+}
+
+/// Print a `--json` payload to stdout
+///
+/// Handlers must not print anything else to stdout in JSON mode; messages
+/// go to stderr (or are logged) so the output always parses.
+pub fn emit<T: Serialize>(data: &T) -> anyhow::Result<()> {
+    println!("{}", to_json(data)?);
+    Ok(())
+}
+
+/// Print one record of a newline-delimited `--json` stream to stdout
+pub fn emit_line<T: Serialize>(data: &T) -> anyhow::Result<()> {
+    println!("{}", to_json_line(data)?);
+    Ok(())
+}
+
+/// JSON view of a task for `list`/`status`: the stored task plus derived progress
+///
+/// Numbers are raw (bytes, bytes per second, seconds); `downloaded` and
+/// `size` come from the task itself.
+#[derive(Debug, Serialize)]
+pub struct TaskJson<'a> {
+    #[serde(flatten)]
+    pub task: &'a DownloadTask,
+    /// Seconds since the download started, or its total time once completed
+    pub elapsed_secs: Option<f64>,
+    /// Average speed since the download started (null when not measurable)
+    pub speed_bytes_per_sec: Option<f64>,
+    /// Estimated seconds remaining (only while downloading with a known size)
+    pub eta_secs: Option<u64>,
+}
+
+impl<'a> From<&'a DownloadTask> for TaskJson<'a> {
+    fn from(task: &'a DownloadTask) -> Self {
+        Self {
+            task,
+            elapsed_secs: task.elapsed_secs(chrono::Utc::now()),
+            speed_bytes_per_sec: task.speed(),
+            eta_secs: task.eta_seconds(),
+        }
+    }
+}
+
+/// JSON view of a completion history entry, with the same progress fields as `TaskJson`
+#[derive(Debug, Serialize)]
+pub struct HistoryJson<'a> {
+    #[serde(flatten)]
+    pub entry: &'a CompletedEntry,
+    /// Bytes written (the file size for completed entries)
+    pub downloaded: Option<u64>,
+    /// Average speed over the whole download
+    pub speed_bytes_per_sec: Option<f64>,
+    /// Always null; finished downloads have nothing left
+    pub eta_secs: Option<u64>,
+}
+
+impl<'a> From<&'a CompletedEntry> for HistoryJson<'a> {
+    fn from(entry: &'a CompletedEntry) -> Self {
+        let downloaded = entry.size.filter(|_| entry.status == "completed");
+        let speed_bytes_per_sec = match (downloaded, entry.duration_secs) {
+            (Some(bytes), Some(secs)) if secs > 0.0 => Some(bytes as f64 / secs),
+            _ => None,
+        };
+        Self {
+            entry,
+            downloaded,
+            speed_bytes_per_sec,
+            eta_secs: None,
+        }
+    }
+}
+
 /// Format bytes into human-readable string (KB, MB, GB)
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -25,9 +128,16 @@ pub fn format_download(task: &DownloadTask, detailed: bool) -> String {
     if detailed {
         output.push_str(&format!("ID: {}\n", task.id));
         output.push_str(&format!("URL: {}\n", task.url));
+        if let Some(ref resolved_url) = task.resolved_url {
+            output.push_str(&format!("Resolved URL: {}\n", resolved_url));
+        }
         output.push_str(&format!("Filename: {}\n", task.filename));
         output.push_str(&format!("Folder: {}\n", task.folder_id));
-        output.push_str(&format!("Status: {:?}\n", task.status));
+        if task.awaiting_confirmation {
+            output.push_str(&format!("Status: {:?} (awaiting confirmation)\n", task.status));
+        } else {
+            output.push_str(&format!("Status: {:?}\n", task.status));
+        }
 
         if let Some(total) = task.size {
             output.push_str(&format!("Size: {}\n", format_bytes(total)));
@@ -55,47 +165,294 @@ pub fn format_download(task: &DownloadTask, detailed: bool) -> String {
         if let Some(completed) = task.completed_at {
             output.push_str(&format!("Completed: {}\n", completed.format("%Y-%m-%d %H:%M:%S")));
         }
-    } else {
-        // Compact format for lists
-        let status_icon = match task.status {
-            crate::download::task::DownloadStatus::Pending => "⏸",
-            crate::download::task::DownloadStatus::Downloading => "⬇",
-            crate::download::task::DownloadStatus::Completed => "✓",
-            crate::download::task::DownloadStatus::Error => "✗",
-            crate::download::task::DownloadStatus::Paused => "⏸",
-            crate::download::task::DownloadStatus::Deleted => "🗑",
-        };
-
-        let progress_str = if let Some(total) = task.size {
-            let progress = (task.downloaded as f64 / total as f64 * 100.0) as u8;
-            format!("{}%", progress)
-        } else {
-            format_bytes(task.downloaded)
-        };
 
-        output.push_str(&format!("{} {} [{}] {}",
-            status_icon,
-            task.id,
-            progress_str,
-            task.filename
-        ));
+        if let Some(elapsed) = task.elapsed_secs(chrono::Utc::now()) {
+            let label = if task.completed_at.is_some() { "Total time" } else { "Elapsed" };
+            output.push_str(&format!("{}: {}\n", label, crate::download::task::format_duration(elapsed as u64)));
+        }
+        if let Some(speed) = task.average_speed() {
+            output.push_str(&format!("Average speed: {}/s\n", format_bytes(speed as u64)));
+        }
+        if let Some(peak) = task.speed_stats.peak() {
+            output.push_str(&format!("Peak speed: {}/s\n", format_bytes(peak as u64)));
+        }
+        if !task.script_runs.is_empty() {
+            output.push_str("Scripts:\n");
+            for run in &task.script_runs {
+                output.push_str(&format!(
+                    "  {} {}\n",
+                    run.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                    format_script_run(run)
+                ));
+            }
+        }
+    } else {
+        output.push_str(&format_list_line(task, 36));
     }
 
     output
 }
 
+/// `hook script.js: modified` (or `unchanged`, or `failed (error)`)
+pub fn format_script_run(run: &ScriptRun) -> String {
+    let effect = match run.error {
+        Some(ref error) => format!("failed ({})", error),
+        None if run.modified => "modified".to_string(),
+        None => "unchanged".to_string(),
+    };
+    format!("{} {}: {}", run.hook, run.script, effect)
+}
+
+/// One compact list line, showing the first `id_len` characters of the ID
+fn format_list_line(task: &DownloadTask, id_len: usize) -> String {
+    let status_icon = match task.status {
+        _ if task.awaiting_confirmation => "?",
+        crate::download::task::DownloadStatus::Pending => "⏸",
+        crate::download::task::DownloadStatus::Downloading => "⬇",
+        crate::download::task::DownloadStatus::Completed => "✓",
+        crate::download::task::DownloadStatus::Error => "✗",
+        crate::download::task::DownloadStatus::Paused => "⏸",
+        crate::download::task::DownloadStatus::Deleted => "🗑",
+    };
+
+    let progress_str = if let Some(total) = task.size {
+        let progress = (task.downloaded as f64 / total as f64 * 100.0) as u8;
+        format!("{}%", progress)
+    } else {
+        format_bytes(task.downloaded)
+    };
+
+    format!("{} {} [{}] {}",
+        status_icon,
+        short_id(task.id, id_len),
+        progress_str,
+        task.filename
+    )
+}
+
 /// Format multiple downloads for display (human or JSON)
 pub fn format_downloads(tasks: &[DownloadTask], json: bool) -> String {
     if json {
-        serde_json::to_string_pretty(tasks).unwrap_or_else(|_| "[]".to_string())
+        let tasks: Vec<TaskJson> = tasks.iter().map(TaskJson::from).collect();
+        to_json(&tasks).unwrap_or_else(|_| format!("{{\"schema\":{},\"data\":[]}}", JSON_SCHEMA_VERSION))
     } else {
         if tasks.is_empty() {
             return "No downloads in queue.".to_string();
         }
 
+        // Short IDs, lengthened where needed to stay unique among the listed downloads
+        let id_len = short_id_len(tasks.iter().map(|task| task.id));
         tasks.iter()
-            .map(|task| format_download(task, false))
+            .map(|task| format_list_line(task, id_len))
             .collect::<Vec<_>>()
             .join("\n")
     }
 }
+
+/// Seconds left at `speed` bytes/s, for tasks that are downloading
+fn watch_eta(task: &DownloadTask, speed: Option<f64>) -> Option<u64> {
+    if task.status != crate::download::task::DownloadStatus::Downloading {
+        return None;
+    }
+    let remaining = task.size?.saturating_sub(task.downloaded);
+    let speed = speed.filter(|s| *s > 0.0)?;
+    Some((remaining as f64 / speed).ceil() as u64)
+}
+
+/// One-line summary of a completed download: path, size, time and average speed
+pub fn format_completion_summary(task: &DownloadTask) -> String {
+    let path = task.save_path.join(&task.filename);
+    let size = format_bytes(task.size.unwrap_or(task.downloaded));
+    let elapsed = task
+        .started_at
+        .zip(task.completed_at)
+        .map(|(started, completed)| (completed - started).num_milliseconds().max(0) as f64 / 1000.0);
+
+    match elapsed {
+        Some(secs) if secs > 0.0 => format!(
+            "  {} ({} in {:.1}s, {}/s)",
+            path.display(),
+            size,
+            secs,
+            format_bytes((task.downloaded as f64 / secs) as u64)
+        ),
+        _ => format!("  {} ({})", path.display(), size),
+    }
+}
+
+/// Format the single status line redrawn by `ggg status --watch`
+///
+/// `speed` is the measured transfer rate in bytes per second, if known.
+pub fn format_watch_line(task: &DownloadTask, speed: Option<f64>) -> String {
+    let mut line = match task.size {
+        Some(total) if total > 0 => {
+            let progress = (task.downloaded as f64 / total as f64 * 100.0) as u8;
+            format!("[{:3}%] {} / {}", progress, format_bytes(task.downloaded), format_bytes(total))
+        }
+        _ => format_bytes(task.downloaded),
+    };
+    line.push_str(&format!("  {:?}", task.status));
+    if task.awaiting_confirmation {
+        line.push_str(" (awaiting confirmation)");
+    }
+
+    if task.status == crate::download::task::DownloadStatus::Downloading {
+        if let Some(speed) = speed {
+            line.push_str(&format!("  {}/s", format_bytes(speed as u64)));
+        }
+        if let Some(eta) = watch_eta(task, speed) {
+            line.push_str(&format!("  ETA {}", crate::download::task::format_duration(eta)));
+        }
+    }
+    if let Some(entry) = task.logs.last() {
+        line.push_str(&format!("  | {}", entry.message));
+    }
+    line
+}
+
+/// One `ggg status --watch --json` record (printed as a single line)
+pub fn watch_json(task: &DownloadTask, speed: Option<f64>) -> serde_json::Value {
+    let percent = task
+        .size
+        .filter(|total| *total > 0)
+        .map(|total| task.downloaded as f64 / total as f64 * 100.0);
+    serde_json::json!({
+        "id": task.id,
+        "status": task.status,
+        "downloaded": task.downloaded,
+        "size": task.size,
+        "percent": percent,
+        "speed_bytes_per_sec": speed,
+        "eta_secs": watch_eta(task, speed),
+        "last_log": task.logs.last().map(|entry| entry.message.as_str()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::task::DownloadStatus;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_completion_summary() {
+        let mut task = DownloadTask::new("https://example.com/a.bin".to_string(), PathBuf::from("dl"));
+        task.size = Some(4 * 1024 * 1024);
+        task.downloaded = 4 * 1024 * 1024;
+        let summary = format_completion_summary(&task);
+        assert!(summary.ends_with("(4.00 MB)"), "{}", summary);
+
+        let started = chrono::Utc::now();
+        task.started_at = Some(started);
+        task.completed_at = Some(started + chrono::Duration::seconds(2));
+        let summary = format_completion_summary(&task);
+        assert!(summary.contains(&PathBuf::from("dl").join("a.bin").display().to_string()));
+        assert!(summary.ends_with("(4.00 MB in 2.0s, 2.00 MB/s)"), "{}", summary);
+    }
+
+    #[test]
+    fn test_list_uses_unique_short_ids() {
+        let task = |id: &str| {
+            let mut task = DownloadTask::new("https://example.com/a.bin".to_string(), PathBuf::from("dl"));
+            task.id = uuid::Uuid::parse_str(id).unwrap();
+            task
+        };
+        let a = task("5f0c1a2b-1111-4000-8000-000000000001");
+        let b = task("5f0c1a2b-2222-4000-8000-000000000002");
+        let c = task("9a1e0000-0000-4000-8000-000000000003");
+
+        assert_eq!(short_id_len([c.id]), 8);
+        assert_eq!(short_id_len([a.id, c.id]), 8);
+        // Sharing the first 8 characters skips the hyphen and adds one more
+        assert_eq!(short_id_len([a.id, b.id, c.id]), 10);
+
+        let list = format_downloads(&[a.clone(), c.clone()], false);
+        assert!(list.contains(" 5f0c1a2b [") && list.contains(" 9a1e0000 ["), "{}", list);
+        let list = format_downloads(&[a.clone(), b], false);
+        assert!(list.contains(" 5f0c1a2b-1 [") && list.contains(" 5f0c1a2b-2 ["), "{}", list);
+
+        // JSON keeps full UUIDs
+        let json = format_downloads(&[a], true);
+        assert!(json.contains("5f0c1a2b-1111-4000-8000-000000000001"));
+    }
+
+    #[test]
+    fn test_json_envelope() {
+        let value: serde_json::Value = serde_json::from_str(&to_json(&vec![1, 2]).unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({ "schema": JSON_SCHEMA_VERSION, "data": [1, 2] }));
+
+        let line = to_json_line(&serde_json::json!({ "a": 1 })).unwrap();
+        assert!(!line.contains('\n'));
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&line).unwrap()["data"]["a"], 1);
+    }
+
+    #[test]
+    fn test_json_list_is_pure_json() {
+        // An empty queue is an empty array, not the "No downloads" message
+        let output = format_downloads(&[], true);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["schema"], JSON_SCHEMA_VERSION);
+        assert_eq!(value["data"], serde_json::json!([]));
+
+        let task = DownloadTask::new("https://example.com/file.zip".to_string(), std::path::PathBuf::from("/tmp"));
+        let output = format_downloads(std::slice::from_ref(&task), true);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["data"][0]["id"], task.id.to_string());
+        assert_eq!(format_downloads(&[], false), "No downloads in queue.");
+    }
+
+    #[test]
+    fn test_watch_line_and_json() {
+        let mut task = DownloadTask::new("https://example.com/file.zip".to_string(), std::path::PathBuf::from("/tmp"));
+        task.status = DownloadStatus::Downloading;
+        task.size = Some(4 * 1024 * 1024);
+        task.downloaded = 1024 * 1024;
+        task.log_info("Connected".to_string());
+
+        let line = format_watch_line(&task, Some(1024.0 * 1024.0));
+        assert!(line.starts_with("[ 25%] 1.00 MB / 4.00 MB  Downloading  1.00 MB/s"));
+        assert!(line.ends_with("| Connected"));
+
+        let json = watch_json(&task, Some(1024.0 * 1024.0));
+        assert_eq!(json["eta_secs"], 3);
+        assert_eq!(json["percent"], 25.0);
+        assert_eq!(json["last_log"], "Connected");
+        assert!(!serde_json::to_string(&json).unwrap().contains('\n'));
+
+        // No ETA once the download stopped
+        task.status = DownloadStatus::Paused;
+        assert!(watch_json(&task, None)["eta_secs"].is_null());
+        assert!(!format_watch_line(&task, Some(1.0)).contains("ETA"));
+    }
+
+    #[test]
+    fn test_task_json_includes_progress_fields() {
+        let mut task = DownloadTask::new("https://example.com/file.zip".to_string(), std::path::PathBuf::from("/tmp"));
+        task.size = Some(2048);
+        task.downloaded = 1024;
+
+        let value = serde_json::to_value(TaskJson::from(&task)).unwrap();
+        assert_eq!(value["downloaded"], 1024);
+        assert_eq!(value["size"], 2048);
+        assert_eq!(value["url"], "https://example.com/file.zip");
+        // Not started yet: nothing to measure
+        assert!(value["speed_bytes_per_sec"].is_null());
+        assert!(value["eta_secs"].is_null());
+        assert!(value["elapsed_secs"].is_null());
+
+        task.status = DownloadStatus::Completed;
+        task.started_at = Some(chrono::Utc::now() - chrono::Duration::seconds(4));
+        task.completed_at = Some(task.started_at.unwrap() + chrono::Duration::seconds(2));
+        task.downloaded = 2048;
+        // Completed: timed up to completion rather than up to now
+        let value = serde_json::to_value(TaskJson::from(&task)).unwrap();
+        assert_eq!(value["elapsed_secs"], 2.0);
+        assert_eq!(value["speed_bytes_per_sec"], 1024.0);
+
+        let entry = CompletedEntry::from(&task);
+        let value = serde_json::to_value(HistoryJson::from(&entry)).unwrap();
+        assert_eq!(value["downloaded"], 2048);
+        assert_eq!(value["speed_bytes_per_sec"], 1024.0);
+        assert!(value["eta_secs"].is_null());
+    }
+}
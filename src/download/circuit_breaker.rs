@@ -1,14 +1,26 @@
 //! Circuit breaker pattern for HTTP requests
 //!
 //! Prevents repeated failed requests to unavailable servers by tracking
-//! failures per domain and temporarily blocking requests.
-
-use std::collections::HashMap;
-use std::sync::RwLock;
-use std::time::{Duration, Instant};
+//! failures per key and temporarily blocking requests. The key is the host,
+//! or the host plus the URL's directory when `download.circuit_breaker.scope`
+//! is `host+path-prefix` (see `circuit_key`).
+//!
+//! With a store (`with_store`), the state is kept in `circuit_breaker.json`
+//! in the config directory, so it survives restarts and a reset from
+//! `ggg debug circuit-breaker --reset` reaches a running TUI or daemon: the
+//! file is reloaded whenever another process has changed it.
+
+use crate::app::config::CircuitScope;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Circuit breaker state
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CircuitState {
     /// Normal operation, requests allowed
     Closed,
@@ -18,6 +30,23 @@ pub enum CircuitState {
     HalfOpen,
 }
 
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half-open",
+        }
+    }
+}
+
+/// Returned by `DownloadManager::start_download` when the circuit is open
+#[derive(Debug, thiserror::Error)]
+#[error("Circuit breaker open for '{key}'. Too many consecutive failures.")]
+pub struct CircuitOpenError {
+    pub key: String,
+}
+
 /// Per-domain circuit state
 #[derive(Debug)]
 struct DomainCircuit {
@@ -41,6 +70,17 @@ impl Default for DomainCircuit {
     }
 }
 
+/// A circuit as stored on disk (wall-clock times, so they survive restarts)
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredCircuit {
+    state: CircuitState,
+    failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opened_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_success: Option<DateTime<Utc>>,
+}
+
 /// Configuration for circuit breaker behavior
 #[derive(Debug, Clone)]
 pub struct CircuitBreakerConfig {
@@ -67,6 +107,10 @@ impl Default for CircuitBreakerConfig {
 pub struct CircuitBreaker {
     config: CircuitBreakerConfig,
     circuits: RwLock<HashMap<String, DomainCircuit>>,
+    /// State file (None = in memory only)
+    store: Option<PathBuf>,
+    /// Modification time of the state file when it was last read or written
+    store_modified: Mutex<Option<SystemTime>>,
 }
 
 impl CircuitBreaker {
@@ -80,6 +124,86 @@ impl CircuitBreaker {
         Self {
             config,
             circuits: RwLock::new(HashMap::new()),
+            store: None,
+            store_modified: Mutex::new(None),
+        }
+    }
+
+    /// Keep the state in `path`, loading what is already there
+    pub fn with_store(mut self, path: PathBuf) -> Self {
+        self.store = Some(path);
+        self.load();
+        self
+    }
+
+    /// Reload the state file if another process changed it
+    pub fn refresh(&self) {
+        let Some(ref path) = self.store else {
+            return;
+        };
+        let modified = file_modified(path);
+        let known = *self.store_modified.lock().unwrap();
+        if modified != known {
+            self.load();
+        }
+    }
+
+    fn load(&self) {
+        let Some(ref path) = self.store else {
+            return;
+        };
+        let modified = file_modified(path);
+        let stored: HashMap<String, StoredCircuit> = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid circuit breaker state {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                tracing::warn!("Failed to read circuit breaker state {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        let (now, wall) = (Instant::now(), Utc::now());
+        let circuits = stored
+            .into_iter()
+            .map(|(key, c)| {
+                let circuit = DomainCircuit {
+                    state: c.state,
+                    failures: c.failures,
+                    opened_at: c.opened_at.map(|time| to_instant(time, now, wall)),
+                    last_success: c.last_success.map(|time| to_instant(time, now, wall)),
+                };
+                (key, circuit)
+            })
+            .collect();
+        *self.circuits.write().unwrap() = circuits;
+        *self.store_modified.lock().unwrap() = modified;
+    }
+
+    /// Write the state file (called with the circuits still locked)
+    fn save(&self, circuits: &HashMap<String, DomainCircuit>) {
+        let Some(ref path) = self.store else {
+            return;
+        };
+        let (now, wall) = (Instant::now(), Utc::now());
+        let stored: BTreeMap<&String, StoredCircuit> = circuits
+            .iter()
+            .map(|(key, c)| {
+                let circuit = StoredCircuit {
+                    state: c.state,
+                    failures: c.failures,
+                    opened_at: c.opened_at.map(|instant| to_wall(instant, now, wall)),
+                    last_success: c.last_success.map(|instant| to_wall(instant, now, wall)),
+                };
+                (key, circuit)
+            })
+            .collect();
+
+        match write_store(path, &stored) {
+            Ok(()) => *self.store_modified.lock().unwrap() = file_modified(path),
+            Err(e) => tracing::warn!("Failed to save circuit breaker state {}: {}", path.display(), e),
         }
     }
 
@@ -87,6 +211,7 @@ impl CircuitBreaker {
     ///
     /// Returns the current circuit state for the domain
     pub fn can_request(&self, domain: &str) -> CircuitState {
+        self.refresh();
         let mut circuits = self.circuits.write().unwrap();
         let circuit = circuits.entry(domain.to_string()).or_default();
 
@@ -116,8 +241,11 @@ impl CircuitBreaker {
 
     /// Record a successful request to a domain
     pub fn record_success(&self, domain: &str) {
+        self.refresh();
         let mut circuits = self.circuits.write().unwrap();
         let circuit = circuits.entry(domain.to_string()).or_default();
+        // Only worth a write when something other than the timestamp changes
+        let changed = circuit.failures > 0 || circuit.state != CircuitState::Closed;
 
         circuit.failures = 0;
         circuit.last_success = Some(Instant::now());
@@ -128,12 +256,17 @@ impl CircuitBreaker {
 
         circuit.state = CircuitState::Closed;
         circuit.opened_at = None;
+
+        if changed {
+            self.save(&circuits);
+        }
     }
 
     /// Record a failed request to a domain
     ///
     /// Returns true if the circuit was just opened
     pub fn record_failure(&self, domain: &str) -> bool {
+        self.refresh();
         let mut circuits = self.circuits.write().unwrap();
         let circuit = circuits.entry(domain.to_string()).or_default();
 
@@ -154,6 +287,7 @@ impl CircuitBreaker {
                 "Circuit for {} re-opened (recovery test failed)",
                 domain
             );
+            self.save(&circuits);
             return true;
         }
 
@@ -168,9 +302,11 @@ impl CircuitBreaker {
                 domain,
                 circuit.failures
             );
+            self.save(&circuits);
             return true;
         }
 
+        self.save(&circuits);
         false
     }
 
@@ -189,17 +325,47 @@ impl CircuitBreaker {
     }
 
     /// Reset circuit for a domain
-    pub fn reset(&self, domain: &str) {
+    ///
+    /// Returns false if the domain was not tracked
+    pub fn reset(&self, domain: &str) -> bool {
+        self.refresh();
         let mut circuits = self.circuits.write().unwrap();
-        circuits.remove(domain);
-        tracing::debug!("Circuit for {} reset", domain);
+        let Some(circuit) = circuits.remove(domain) else {
+            return false;
+        };
+        tracing::info!(
+            "Circuit for {} reset (was {}, {} failures)",
+            domain,
+            circuit.state.as_str(),
+            circuit.failures
+        );
+        self.save(&circuits);
+        true
     }
 
     /// Clear all circuits
-    pub fn clear_all(&self) {
+    ///
+    /// Returns the number of circuits that were tracked
+    pub fn clear_all(&self) -> usize {
+        self.refresh();
         let mut circuits = self.circuits.write().unwrap();
+        let count = circuits.len();
         circuits.clear();
-        tracing::debug!("All circuits cleared");
+        tracing::info!("All circuits cleared ({} tracked)", count);
+        self.save(&circuits);
+        count
+    }
+
+    /// State and failure count of every tracked key, sorted by key
+    pub fn snapshot(&self) -> Vec<(String, CircuitState, u32)> {
+        self.refresh();
+        let circuits = self.circuits.read().unwrap();
+        let mut entries: Vec<_> = circuits
+            .iter()
+            .map(|(key, c)| (key.clone(), c.state, c.failures))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
     }
 
     /// Get list of domains with open circuits
@@ -219,11 +385,54 @@ impl Default for CircuitBreaker {
     }
 }
 
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Atomic write: temp file + rename
+fn write_store(path: &Path, stored: &BTreeMap<&String, StoredCircuit>) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(stored)?)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Wall-clock time of an `Instant` in the past
+fn to_wall(instant: Instant, now: Instant, wall: DateTime<Utc>) -> DateTime<Utc> {
+    wall - chrono::Duration::from_std(now.saturating_duration_since(instant)).unwrap_or_default()
+}
+
+/// `Instant` of a stored wall-clock time (future times count as now)
+fn to_instant(time: DateTime<Utc>, now: Instant, wall: DateTime<Utc>) -> Instant {
+    let ago = (wall - time).to_std().unwrap_or_default();
+    now.checked_sub(ago).unwrap_or(now)
+}
+
 /// Extract domain from URL for circuit breaker tracking
 pub fn extract_domain(url: &str) -> Option<String> {
     url::Url::parse(url).ok().and_then(|u| u.host_str().map(String::from))
 }
 
+/// Circuit key for a URL under `scope`
+///
+/// `host+path-prefix` keys are the host plus the directory part of the path
+/// (`cdn.example.com/videos/2026` for `.../videos/2026/a.mp4`); files at the
+/// root share the plain host key.
+pub fn circuit_key(url: &str, scope: CircuitScope) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    match scope {
+        CircuitScope::Domain => Some(host.to_string()),
+        CircuitScope::HostPathPrefix => {
+            let dir = parsed.path().rsplit_once('/').map_or("", |(dir, _)| dir);
+            Some(format!("{}{}", host, dir))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +493,48 @@ mod tests {
         assert_eq!(extract_domain("not-a-url"), None);
     }
 
+    #[test]
+    fn test_circuit_key_scopes() {
+        let url = "https://cdn.example.com:8443/videos/2026/a.mp4?sig=1";
+        assert_eq!(circuit_key(url, CircuitScope::Domain), Some("cdn.example.com".to_string()));
+        assert_eq!(
+            circuit_key(url, CircuitScope::HostPathPrefix),
+            Some("cdn.example.com/videos/2026".to_string())
+        );
+        assert_eq!(
+            circuit_key("https://example.com/file.zip", CircuitScope::HostPathPrefix),
+            Some("example.com".to_string())
+        );
+        assert_eq!(circuit_key("not-a-url", CircuitScope::HostPathPrefix), None);
+    }
+
+    #[test]
+    fn test_store_shared_between_breakers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("circuit_breaker.json");
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            ..Default::default()
+        };
+
+        let running = CircuitBreaker::with_config(config.clone()).with_store(path.clone());
+        running.record_failure("example.com");
+        running.record_failure("example.com");
+        running.record_failure("other.com");
+
+        // A second process sees the open circuit and resets it
+        let cli = CircuitBreaker::with_config(config).with_store(path.clone());
+        assert_eq!(cli.get_status("example.com"), (CircuitState::Open, 2));
+        assert!(cli.reset("example.com"));
+        assert!(!cli.reset("unknown.com"));
+
+        assert_eq!(running.can_request("example.com"), CircuitState::Closed);
+        assert_eq!(running.get_status("other.com"), (CircuitState::Closed, 1));
+
+        assert_eq!(cli.clear_all(), 1);
+        assert!(running.snapshot().is_empty());
+    }
+
     #[test]
     fn test_different_domains_independent() {
         let config = CircuitBreakerConfig {
@@ -8,6 +8,7 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Entry in completion log (subset of DownloadTask fields)
@@ -109,11 +110,189 @@ pub async fn append_completion(task: &DownloadTask) -> Result<()> {
     Ok(())
 }
 
+/// Reads completion log entries from the application-wide logs directory
+///
+/// When `since` is set, log files dated before that day are skipped entirely and
+/// entries completed before `since` are filtered out. Malformed lines are logged and skipped.
+pub fn read_entries(since: Option<DateTime<Utc>>) -> Result<Vec<CompletedEntry>> {
+    read_entries_in(&crate::util::paths::get_logs_dir()?, since)
+}
+
+fn read_entries_in(logs_dir: &Path, since: Option<DateTime<Utc>>) -> Result<Vec<CompletedEntry>> {
+    if !logs_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let since_day = since.map(|s| s.format("%Y%m%d").to_string());
+    let log_files = list_log_files(logs_dir)?
+        .into_iter()
+        .filter(|(day, _)| since_day.as_deref().is_none_or(|since_day| day.as_str() >= since_day))
+        .map(|(_, path)| path);
+
+    let mut entries = Vec::new();
+    for log_file in log_files {
+        let content = std::fs::read_to_string(&log_file)?;
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<CompletedEntry>(line) {
+                Ok(entry) => {
+                    let in_window = match (since, entry.completed_at) {
+                        (Some(since), Some(completed_at)) => completed_at >= since,
+                        _ => true,
+                    };
+                    if in_window {
+                        entries.push(entry);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to parse completion entry: {}", e);
+                }
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Appends entries to the log files of the days they completed, skipping IDs already logged
+///
+/// Used to bring history from another machine (`ggg import all`). Returns the
+/// number of entries written.
+pub fn append_entries(entries: &[CompletedEntry]) -> Result<usize> {
+    append_entries_in(&crate::util::paths::get_logs_dir()?, entries)
+}
+
+fn append_entries_in(logs_dir: &Path, entries: &[CompletedEntry]) -> Result<usize> {
+    let mut known: std::collections::HashSet<Uuid> =
+        read_entries_in(logs_dir, None)?.into_iter().map(|entry| entry.id).collect();
+    std::fs::create_dir_all(logs_dir)?;
+
+    let mut written = 0;
+    for entry in entries {
+        if !known.insert(entry.id) {
+            continue;
+        }
+        let day = entry.completed_at.or(entry.started_at).unwrap_or_else(Utc::now).format("%Y%m%d");
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(logs_dir.join(format!("{}.jsonl", day)))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Lists date-named completion log files (YYYYMMDD.jsonl), oldest first
+fn list_log_files(logs_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut log_files = Vec::new();
+    for entry in std::fs::read_dir(logs_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+            continue;
+        }
+        // Only date-named files (YYYYMMDD.jsonl) are completion logs
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if stem.len() != 8 || !stem.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        log_files.push((stem.to_string(), path));
+    }
+    log_files.sort();
+    Ok(log_files)
+}
+
+/// Removes completion log entries exceeding the retention policy, oldest first
+///
+/// Entries completed before `older_than` are removed, then the oldest entries beyond
+/// `max_entries` (0 = unlimited). Files left empty are deleted; malformed lines are kept.
+/// Returns the number of entries removed.
+pub fn prune(older_than: Option<DateTime<Utc>>, max_entries: usize) -> Result<usize> {
+    let logs_dir = crate::util::paths::get_logs_dir()?;
+    if !logs_dir.exists() {
+        return Ok(0);
+    }
+    prune_in(&logs_dir, older_than, max_entries)
+}
+
+/// Raw lines of one log file with their parsed entries (`None` if malformed)
+type LogLines = Vec<(String, Option<CompletedEntry>)>;
+
+fn prune_in(logs_dir: &Path, older_than: Option<DateTime<Utc>>, max_entries: usize) -> Result<usize> {
+    let log_files = list_log_files(logs_dir)?;
+
+    // Load every file; `None` marks lines that failed to parse
+    let mut files: Vec<(PathBuf, LogLines)> = Vec::new();
+    let mut total = 0usize;
+    for (_, path) in log_files {
+        let content = std::fs::read_to_string(&path)?;
+        let lines: Vec<_> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| (line.to_string(), serde_json::from_str::<CompletedEntry>(line).ok()))
+            .collect();
+        total += lines.iter().filter(|(_, entry)| entry.is_some()).count();
+        files.push((path, lines));
+    }
+
+    // Files are dated oldest first, so the first entries seen are the oldest
+    let mut excess = if max_entries > 0 {
+        total.saturating_sub(max_entries)
+    } else {
+        0
+    };
+    let mut removed = 0usize;
+
+    for (path, lines) in files {
+        let before = lines.len();
+        let kept: Vec<String> = lines
+            .into_iter()
+            .filter(|(_, entry)| {
+                let Some(entry) = entry else {
+                    return true;
+                };
+                let expired = older_than.is_some_and(|cutoff| {
+                    entry.completed_at.or(entry.started_at).is_some_and(|at| at < cutoff)
+                });
+                if expired {
+                    excess = excess.saturating_sub(1);
+                    return false;
+                }
+                if excess > 0 {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            })
+            .map(|(line, _)| line)
+            .collect();
+
+        if kept.len() == before {
+            continue;
+        }
+        removed += before - kept.len();
+
+        if kept.is_empty() {
+            std::fs::remove_file(&path)?;
+        } else {
+            // Atomic rewrite using temp file + rename
+            let temp_path = path.with_extension("jsonl.tmp");
+            std::fs::write(&temp_path, kept.join("\n") + "\n")?;
+            std::fs::rename(&temp_path, &path)?;
+        }
+    }
+
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::download::task::DownloadStatus;
-    use std::path::PathBuf;
 
     #[test]
     fn test_completed_entry_from_task() {
@@ -139,6 +318,14 @@ mod tests {
             logs: Vec::new(),
             retry_count: 0,
             last_status_code: Some(200),
+            resolved_url: None,
+            routed_from: None,
+            resume_probed: false,
+            held_reason: None,
+            awaiting_confirmation: false,
+            confirmed_size: None,
+            speed_stats: Default::default(),
+            script_runs: Vec::new(),
         };
 
         let entry = CompletedEntry::from(&task);
@@ -203,9 +390,87 @@ mod tests {
             logs: Vec::new(),
             retry_count: 0,
             last_status_code: Some(200),
+            resolved_url: None,
+            routed_from: None,
+            resume_probed: false,
+            held_reason: None,
+            awaiting_confirmation: false,
+            confirmed_size: None,
+            speed_stats: Default::default(),
+            script_runs: Vec::new(),
         };
 
         // Should not panic (may fail if permissions issue)
         let _ = append_completion(&task).await;
     }
+
+    fn write_log(dir: &Path, day: &str, ages_days: &[i64]) {
+        let lines: Vec<String> = ages_days
+            .iter()
+            .map(|days| {
+                let at = Utc::now() - chrono::Duration::days(*days);
+                serde_json::to_string(&CompletedEntry {
+                    id: Uuid::new_v4(),
+                    url: "https://example.com/file.zip".to_string(),
+                    filename: "file.zip".to_string(),
+                    folder_id: "default".to_string(),
+                    size: Some(1),
+                    started_at: Some(at),
+                    completed_at: Some(at),
+                    duration_secs: Some(0.0),
+                    status: "completed".to_string(),
+                    error_message: None,
+                })
+                .unwrap()
+            })
+            .collect();
+        std::fs::write(dir.join(format!("{}.jsonl", day)), lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_prune_by_age_removes_empty_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_log(temp_dir.path(), "20240101", &[40, 40]);
+        write_log(temp_dir.path(), "20240301", &[1]);
+        std::fs::write(temp_dir.path().join("app.jsonl.2024-01-01"), "keep").unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::days(30);
+        let removed = prune_in(temp_dir.path(), Some(cutoff), 0).unwrap();
+
+        assert_eq!(removed, 2);
+        assert!(!temp_dir.path().join("20240101.jsonl").exists());
+        assert!(temp_dir.path().join("20240301.jsonl").exists());
+        assert!(temp_dir.path().join("app.jsonl.2024-01-01").exists());
+    }
+
+    #[test]
+    fn test_prune_max_entries_keeps_newest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_log(temp_dir.path(), "20240101", &[3, 3]);
+        write_log(temp_dir.path(), "20240102", &[2, 1]);
+
+        let removed = prune_in(temp_dir.path(), None, 3).unwrap();
+
+        assert_eq!(removed, 1);
+        let first = std::fs::read_to_string(temp_dir.path().join("20240101.jsonl")).unwrap();
+        assert_eq!(first.lines().count(), 1);
+        let second = std::fs::read_to_string(temp_dir.path().join("20240102.jsonl")).unwrap();
+        assert_eq!(second.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_append_entries_skips_known_ids() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_log(temp_dir.path(), "20240101", &[1]);
+        let existing = read_entries_in(temp_dir.path(), None).unwrap();
+
+        let at = "2024-03-05T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let imported = CompletedEntry { id: Uuid::new_v4(), completed_at: Some(at), ..existing[0].clone() };
+        let written = append_entries_in(temp_dir.path(), &[existing[0].clone(), imported.clone(), imported]).unwrap();
+
+        assert_eq!(written, 1);
+        assert_eq!(read_entries_in(temp_dir.path(), None).unwrap().len(), 2);
+        // Filed under the day it completed
+        assert!(temp_dir.path().join("20240305.jsonl").exists());
+    }
 }
@@ -0,0 +1,128 @@
+//! Resizable concurrency limits
+//!
+//! `tokio::sync::Semaphore` can grow but not shrink while permits are held,
+//! so lowering a limit forgets the permits that are free right away and
+//! records the rest as debt; permits released later pay the debt off instead
+//! of going back to the semaphore. Raising the limit cancels outstanding debt
+//! before adding new permits, so the number of running downloads converges
+//! on the latest limit without interrupting any of them.
+
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Debug)]
+struct State {
+    /// Current limit
+    limit: usize,
+    /// Permits still to be removed as running holders release them
+    debt: usize,
+}
+
+/// Concurrency limit that can be changed while permits are held (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    state: Arc<Mutex<State>>,
+}
+
+/// Slot held by a running download; released (or retired) on drop
+#[derive(Debug)]
+pub struct LimitPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    state: Arc<Mutex<State>>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            state: Arc::new(Mutex::new(State { limit, debt: 0 })),
+        }
+    }
+
+    /// Current limit
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Free slots
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Change the limit; running holders keep their slots
+    pub fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        if limit > state.limit {
+            let grow = limit - state.limit;
+            let cancelled = grow.min(state.debt);
+            state.debt -= cancelled;
+            self.semaphore.add_permits(grow - cancelled);
+        } else if limit < state.limit {
+            let shrink = state.limit - limit;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            state.debt += shrink - forgotten;
+        }
+        state.limit = limit;
+    }
+
+    /// Wait for a free slot
+    pub async fn acquire(&self) -> LimitPermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("concurrency semaphore is never closed");
+        LimitPermit { permit: Some(permit), state: self.state.clone() }
+    }
+}
+
+impl Drop for LimitPermit {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.debt > 0 {
+            state.debt -= 1;
+            permit.forget();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shrink_waits_for_running_holders() {
+        let limit = ConcurrencyLimit::new(3);
+        let first = limit.acquire().await;
+        let second = limit.acquire().await;
+
+        // One slot is free and goes immediately; the other is retired on release
+        limit.set_limit(1);
+        assert_eq!(limit.available(), 0);
+        drop(first);
+        assert_eq!(limit.available(), 0);
+        drop(second);
+        assert_eq!(limit.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_grow_cancels_debt_first() {
+        let limit = ConcurrencyLimit::new(2);
+        let first = limit.acquire().await;
+        let _second = limit.acquire().await;
+
+        limit.set_limit(1);
+        limit.set_limit(3);
+        assert_eq!(limit.limit(), 3);
+        assert_eq!(limit.available(), 1);
+
+        // The released slot is no longer owed and goes back to the pool
+        drop(first);
+        assert_eq!(limit.available(), 2);
+    }
+}
@@ -0,0 +1,254 @@
+//! Duplicate content detection for completed downloads
+//!
+//! With `download.on_duplicate_content` set, every completed file is hashed
+//! and recorded in its folder's `content_index.json` (next to `queue.toml`).
+//! A new file whose size and hash match an indexed file that still exists is
+//! compared byte for byte, and only a confirmed match is kept, hard-linked to
+//! the earlier file or deleted. Only files ggg completed are indexed; files
+//! put in the save directory by other means are never matched.
+//!
+//! The hash is 64-bit FNV-1a: cheap, stable across builds, and only used to
+//! find candidates, since the byte comparison makes the decision.
+
+use crate::app::config::DuplicateContentAction;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Read buffer for hashing and comparing
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Serializes read-modify-write cycles of the index files within this process
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// An indexed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedFile {
+    size: u64,
+    /// FNV-1a 64 of the content, hex
+    hash: String,
+}
+
+/// Completed files of one folder by path
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ContentIndex {
+    files: BTreeMap<PathBuf, IndexedFile>,
+}
+
+impl ContentIndex {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Atomic write: temp file + rename
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Drop entries whose file is gone or has changed size
+    fn prune(&mut self) {
+        self.files.retain(|path, file| std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() == file.size));
+    }
+}
+
+/// What happened to a completed file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplicateOutcome {
+    /// No earlier file has the same content
+    Unique,
+    /// Same content as the given file; both kept
+    Kept(PathBuf),
+    /// Replaced by a hard link to the given file
+    Hardlinked(PathBuf),
+    /// Deleted; the given file has the same content
+    Deleted(PathBuf),
+}
+
+/// FNV-1a 64 of a file's content, as hex
+pub fn content_hash(path: &Path) -> std::io::Result<String> {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut hash = OFFSET;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buffer[..read] {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// True if both files have exactly the same bytes
+fn same_content(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let (mut a, mut b) = (std::fs::File::open(a)?, std::fs::File::open(b)?);
+    let (mut buf_a, mut buf_b) = (vec![0u8; BUFFER_SIZE], vec![0u8; BUFFER_SIZE]);
+    loop {
+        let read = a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(b.read(&mut buf_b[..1])? == 0);
+        }
+        if b.read_exact(&mut buf_b[..read]).is_err() || buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Replace `path` with a hard link to `original` (via a temp name, so `path` is never missing)
+fn replace_with_link(original: &Path, path: &Path) -> std::io::Result<()> {
+    let mut link_name = path.as_os_str().to_os_string();
+    link_name.push(".ggg-link");
+    let link = PathBuf::from(link_name);
+    std::fs::hard_link(original, &link)?;
+    std::fs::rename(&link, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&link);
+    })
+}
+
+/// Check a completed file against the folder index at `index_path` and apply `action`
+///
+/// The file is added to the index unless it was deleted. A hard link that
+/// cannot be made (e.g. across filesystems) leaves both files in place.
+pub fn check_completed(index_path: &Path, path: &Path, action: DuplicateContentAction) -> Result<DuplicateOutcome> {
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = ContentIndex::load(index_path)?;
+    index.prune();
+
+    let size = std::fs::metadata(path)?.len();
+    let hash = content_hash(path)?;
+    let original = index
+        .files
+        .iter()
+        .filter(|(other, file)| other.as_path() != path && file.size == size && file.hash == hash)
+        .map(|(other, _)| other.clone())
+        .find(|other| same_content(other, path).unwrap_or(false));
+
+    let outcome = match original {
+        None => DuplicateOutcome::Unique,
+        Some(original) => match action {
+            DuplicateContentAction::Keep => DuplicateOutcome::Kept(original),
+            DuplicateContentAction::Hardlink => match replace_with_link(&original, path) {
+                Ok(()) => DuplicateOutcome::Hardlinked(original),
+                Err(e) => {
+                    tracing::warn!("Could not hard-link {} to {}: {}", path.display(), original.display(), e);
+                    DuplicateOutcome::Kept(original)
+                }
+            },
+            DuplicateContentAction::Delete => {
+                std::fs::remove_file(path)?;
+                DuplicateOutcome::Deleted(original)
+            }
+        },
+    };
+
+    if !matches!(outcome, DuplicateOutcome::Deleted(_)) {
+        index.files.insert(path.to_path_buf(), IndexedFile { size, hash });
+    }
+    index.save(index_path)?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("a.txt");
+        std::fs::write(&path, "a").unwrap();
+        // Published FNV-1a 64 test vector
+        assert_eq!(content_hash(&path).unwrap(), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_duplicates_are_kept_linked_or_deleted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let index_path = temp_dir.path().join("content_index.json");
+        let file = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+
+        let first = file("first.bin", "same bytes");
+        assert_eq!(check_completed(&index_path, &first, DuplicateContentAction::Delete).unwrap(), DuplicateOutcome::Unique);
+        // Same size, different content
+        let other = file("other.bin", "diff bytes");
+        assert_eq!(check_completed(&index_path, &other, DuplicateContentAction::Delete).unwrap(), DuplicateOutcome::Unique);
+
+        let kept = file("kept.bin", "same bytes");
+        assert_eq!(
+            check_completed(&index_path, &kept, DuplicateContentAction::Keep).unwrap(),
+            DuplicateOutcome::Kept(first.clone())
+        );
+        assert!(kept.exists());
+
+        let deleted = file("deleted.bin", "same bytes");
+        assert!(matches!(
+            check_completed(&index_path, &deleted, DuplicateContentAction::Delete).unwrap(),
+            DuplicateOutcome::Deleted(_)
+        ));
+        assert!(!deleted.exists());
+
+        let linked = file("linked.bin", "same bytes");
+        assert!(matches!(
+            check_completed(&index_path, &linked, DuplicateContentAction::Hardlink).unwrap(),
+            DuplicateOutcome::Hardlinked(_)
+        ));
+        assert_eq!(std::fs::read_to_string(&linked).unwrap(), "same bytes");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(std::fs::metadata(&linked).unwrap().nlink(), 2);
+        }
+
+        // A removed original is dropped from the index
+        for path in [&first, &kept, &linked] {
+            std::fs::remove_file(path).unwrap();
+        }
+        let again = file("again.bin", "same bytes");
+        assert_eq!(check_completed(&index_path, &again, DuplicateContentAction::Delete).unwrap(), DuplicateOutcome::Unique);
+    }
+
+    #[test]
+    fn test_concurrent_checks_keep_every_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let index_path = temp_dir.path().join("content_index.json");
+        let paths: Vec<PathBuf> = (0..8)
+            .map(|i| {
+                let path = temp_dir.path().join(format!("file{}.bin", i));
+                std::fs::write(&path, format!("content {}", i)).unwrap();
+                path
+            })
+            .collect();
+
+        // Downloads completing together must not drop each other's entries
+        std::thread::scope(|scope| {
+            for path in &paths {
+                let index_path = &index_path;
+                scope.spawn(move || check_completed(index_path, path, DuplicateContentAction::Keep).unwrap());
+            }
+        });
+
+        assert_eq!(ContentIndex::load(&index_path).unwrap().files.len(), paths.len());
+    }
+}
@@ -2,17 +2,22 @@
 //!
 //! Each folder maintains its own queue of download tasks with:
 //! - Independent task list (VecDeque for efficient operations)
-//! - Per-folder concurrency semaphore
+//! - Per-folder concurrency limit (resizable at runtime)
 //! - Task count tracking (pending/downloading)
+//! - Dirty tracking, so only modified folders are written back to disk
 //!
 //! This enables fair round-robin scheduling across folders while
 //! respecting both per-folder and global concurrent download limits.
 
+use crate::app::config::SchedulePolicy;
+use crate::download::concurrency::ConcurrencyLimit;
 use crate::download::task::{DownloadStatus, DownloadTask};
+use crate::util::file_lock::{content_stamp, file_stamp, queue_lock_path, FileLock, QUEUE_LOCK_TIMEOUT};
 use std::collections::VecDeque;
 use std::path::Path;
-use std::sync::Arc;
-use tokio::sync::{RwLock, Semaphore};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
 /// Task counts for a folder queue
@@ -49,10 +54,18 @@ pub struct FolderQueue {
     folder_id: String,
     /// Tasks in this folder's queue
     tasks: Arc<RwLock<VecDeque<DownloadTask>>>,
-    /// Semaphore for per-folder concurrent download limit
-    semaphore: Arc<Semaphore>,
+    /// Per-folder concurrent download limit
+    limit: ConcurrencyLimit,
     /// Task counts (pending/downloading) for efficient status checks
     counts: Arc<RwLock<FolderTaskCounts>>,
+    /// Set when tasks changed since the last save
+    dirty: Arc<AtomicBool>,
+    /// Set when only download progress changed since the last save
+    progress_dirty: Arc<AtomicBool>,
+    /// `content_stamp` of the queue file as last read or written here (`None` = no file)
+    file_stamp: Arc<Mutex<Option<u64>>>,
+    /// Set once a save was refused because another instance changed the file
+    conflict: Arc<AtomicBool>,
 }
 
 impl FolderQueue {
@@ -65,23 +78,69 @@ impl FolderQueue {
         Self {
             folder_id: folder_id.into(),
             tasks: Arc::new(RwLock::new(VecDeque::new())),
-            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            limit: ConcurrencyLimit::new(max_concurrent),
             counts: Arc::new(RwLock::new(FolderTaskCounts::default())),
+            dirty: Arc::new(AtomicBool::new(false)),
+            progress_dirty: Arc::new(AtomicBool::new(false)),
+            file_stamp: Arc::new(Mutex::new(None)),
+            conflict: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Returns true if tasks changed since the last save or load
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::Acquire)
+    }
+
+    fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    /// Returns true if download progress changed since the last save or load
+    pub fn has_unsaved_progress(&self) -> bool {
+        self.progress_dirty.load(Ordering::Acquire)
+    }
+
+    fn clear_dirty(&self) {
+        self.dirty.store(false, Ordering::Release);
+        self.progress_dirty.store(false, Ordering::Release);
+    }
+
+    /// Remember the queue file's stamp from a load done elsewhere
+    /// (see `DownloadQueue::file_stamps`)
+    pub fn set_file_stamp(&self, stamp: Option<u64>) {
+        *self.file_stamp.lock().unwrap() = stamp;
+    }
+
     /// Get the folder ID
     pub fn folder_id(&self) -> &str {
         &self.folder_id
     }
 
-    /// Get the semaphore for this folder's concurrent downloads
-    pub fn semaphore(&self) -> Arc<Semaphore> {
-        Arc::clone(&self.semaphore)
+    /// Get the limit for this folder's concurrent downloads
+    pub fn concurrency_limit(&self) -> ConcurrencyLimit {
+        self.limit.clone()
+    }
+
+    /// Change this folder's concurrent download limit; running downloads keep their slots
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.limit.set_limit(max_concurrent);
     }
 
     /// Add a task to the queue
     pub async fn add(&self, task: DownloadTask) {
+        self.push(task).await;
+        self.mark_dirty();
+    }
+
+    /// Add a task just read from this folder's queue file
+    ///
+    /// Unlike `add`, the queue is not marked dirty: the file already holds it.
+    pub async fn add_saved(&self, task: DownloadTask) {
+        self.push(task).await;
+    }
+
+    async fn push(&self, task: DownloadTask) {
         let is_pending = task.status == DownloadStatus::Pending;
         let is_downloading = task.status == DownloadStatus::Downloading;
 
@@ -122,6 +181,7 @@ impl FolderQueue {
         let mut tasks = self.tasks.write().await;
         if let Some(pos) = tasks.iter().position(|t| t.id == id) {
             let task = tasks.remove(pos)?;
+            self.mark_dirty();
 
             // Update counts
             let mut counts = self.counts.write().await;
@@ -155,6 +215,7 @@ impl FolderQueue {
             let new_status = task.status;
 
             tasks[pos] = task;
+            self.mark_dirty();
 
             // Update counts if status changed
             if old_status != new_status {
@@ -183,6 +244,22 @@ impl FolderQueue {
         }
     }
 
+    /// Record download progress without marking the queue dirty
+    ///
+    /// Progress ticks arrive several times a second per download; rewriting
+    /// the queue file for each would defeat incremental saves. The offsets
+    /// are only flagged as unsaved progress, which the queue saver persists
+    /// at a slower pace (see `save_if_dirty`).
+    pub async fn update_progress(&self, id: Uuid, downloaded: u64, size: Option<u64>) {
+        let mut tasks = self.tasks.write().await;
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            task.downloaded = downloaded;
+            task.size = size.or(task.size);
+            task.speed_stats.record(chrono::Utc::now(), downloaded);
+            self.progress_dirty.store(true, Ordering::Release);
+        }
+    }
+
     /// Get current task counts
     pub async fn get_counts(&self) -> FolderTaskCounts {
         let counts = self.counts.read().await;
@@ -247,21 +324,46 @@ impl FolderQueue {
             .collect()
     }
 
+    /// Pending tasks in the order `policy` starts them
+    pub async fn pending_in_order(&self, policy: SchedulePolicy) -> Vec<DownloadTask> {
+        let mut pending = self.get_pending_tasks().await;
+        sort_for_schedule(&mut pending, policy);
+        pending
+    }
+
     /// Get next pending task (for scheduling)
     /// Returns the highest priority pending task
     pub async fn next_pending(&self) -> Option<DownloadTask> {
-        let tasks = self.tasks.read().await;
-        tasks
-            .iter()
-            .filter(|t| t.status == DownloadStatus::Pending)
-            .max_by_key(|t| t.priority)
-            .cloned()
+        self.pending_in_order(SchedulePolicy::Priority).await.into_iter().next()
     }
 
     /// Save queue to TOML file
     ///
     /// Uses the folder-specific queue path: {config_dir}/{folder_id}/queue.toml
     pub async fn save(&self) -> anyhow::Result<()> {
+        // Cleared up front so changes made while writing mark the queue again
+        self.clear_dirty();
+        let result = self.write_file().await;
+        if result.is_err() {
+            self.mark_dirty();
+        }
+        result
+    }
+
+    /// Save only if tasks changed since the last save
+    ///
+    /// With `include_progress`, unsaved download progress also counts as a
+    /// change. Returns true if the file was written.
+    pub async fn save_if_dirty(&self, include_progress: bool) -> anyhow::Result<bool> {
+        if !(self.is_dirty() || include_progress && self.has_unsaved_progress()) {
+            return Ok(false);
+        }
+        self.save().await?;
+        Ok(true)
+    }
+
+    /// Write all tasks to the queue file under the folder's file lock
+    async fn write_file(&self) -> anyhow::Result<()> {
         let queue_path = crate::util::paths::get_folder_queue_path(&self.folder_id)?;
 
         // Create parent directory if needed
@@ -269,6 +371,32 @@ impl FolderQueue {
             tokio::fs::create_dir_all(parent).await?;
         }
 
+        // Another instance writing the same file: skip rather than clobber it
+        let Some(_lock) = FileLock::exclusive(&queue_lock_path(&queue_path), QUEUE_LOCK_TIMEOUT).await? else {
+            tracing::warn!(
+                "Queue file {} is locked by another ggg instance; folder '{}' not saved",
+                queue_path.display(),
+                self.folder_id
+            );
+            // Try again on the next save
+            self.mark_dirty();
+            return Ok(());
+        };
+
+        // Another instance replaced the file since this one read or wrote it:
+        // its version stays, and this folder's changes are kept in memory only
+        let expected = *self.file_stamp.lock().unwrap();
+        if file_stamp(&queue_path).await? != expected {
+            if !self.conflict.swap(true, Ordering::AcqRel) {
+                tracing::warn!(
+                    "Queue file {} was changed by another ggg instance; folder '{}' is no longer saved",
+                    queue_path.display(),
+                    self.folder_id
+                );
+            }
+            return Ok(());
+        }
+
         let tasks = self.tasks.read().await;
         let queue_file = QueueFile {
             tasks: tasks.iter().cloned().collect(),
@@ -279,6 +407,7 @@ impl FolderQueue {
         let temp_path = queue_path.with_extension("toml.tmp");
         tokio::fs::write(&temp_path, &toml).await?;
         tokio::fs::rename(&temp_path, &queue_path).await?;
+        self.set_file_stamp(Some(content_stamp(toml.as_bytes())));
 
         tracing::debug!(
             "Saved {} tasks to folder queue: {}",
@@ -301,11 +430,23 @@ impl FolderQueue {
                 self.folder_id,
                 queue_path.display()
             );
+            self.set_file_stamp(None);
             return Ok(());
         }
 
+        // Writes are atomic renames, so reading without the lock is still safe
+        let lock = FileLock::shared(&queue_lock_path(&queue_path), QUEUE_LOCK_TIMEOUT).await?;
+        if lock.is_none() {
+            tracing::warn!(
+                "Queue file {} is locked by another ggg instance; loading the last saved version",
+                queue_path.display()
+            );
+        }
+
         let content = tokio::fs::read_to_string(&queue_path).await?;
         let queue_file: QueueFile = toml::from_str(&content)?;
+        self.set_file_stamp(Some(content_stamp(content.as_bytes())));
+        self.conflict.store(false, Ordering::Release);
 
         {
             let mut tasks = self.tasks.write().await;
@@ -321,6 +462,7 @@ impl FolderQueue {
 
         // Rebuild counts after loading
         self.rebuild_counts().await;
+        self.clear_dirty();
 
         Ok(())
     }
@@ -341,6 +483,7 @@ impl FolderQueue {
         }
 
         self.rebuild_counts().await;
+        self.clear_dirty();
         Ok(())
     }
 
@@ -352,6 +495,7 @@ impl FolderQueue {
             tokio::fs::remove_file(&queue_path).await?;
             tracing::debug!("Deleted queue file: {}", queue_path.display());
         }
+        self.set_file_stamp(None);
 
         Ok(())
     }
@@ -361,6 +505,7 @@ impl FolderQueue {
         let mut tasks = self.tasks.write().await;
         if let Some(pos) = tasks.iter().position(|t| t.id == id) {
             tasks[pos].priority = priority;
+            self.mark_dirty();
             true
         } else {
             false
@@ -374,6 +519,7 @@ impl FolderQueue {
             if pos > 0 {
                 let task = tasks.remove(pos).unwrap();
                 tasks.push_front(task);
+                self.mark_dirty();
             }
             true
         } else {
@@ -388,6 +534,7 @@ impl FolderQueue {
             if pos < tasks.len() - 1 {
                 let task = tasks.remove(pos).unwrap();
                 tasks.push_back(task);
+                self.mark_dirty();
             }
             true
         } else {
@@ -407,12 +554,32 @@ impl FolderQueue {
                 let task = tasks.remove(from).unwrap();
                 let new_to = if from < to { to - 1 } else { to };
                 tasks.insert(new_to, task);
+                self.mark_dirty();
                 return true;
             }
         }
         false
     }
 
+    /// Move task after another task
+    pub async fn move_after(&self, id: Uuid, after_id: Uuid) -> bool {
+        let mut tasks = self.tasks.write().await;
+
+        let from_pos = tasks.iter().position(|t| t.id == id);
+        let to_pos = tasks.iter().position(|t| t.id == after_id);
+
+        if let (Some(from), Some(to)) = (from_pos, to_pos)
+            && from != to
+        {
+            let task = tasks.remove(from).unwrap();
+            let new_to = if from < to { to } else { to + 1 };
+            tasks.insert(new_to, task);
+            self.mark_dirty();
+            return true;
+        }
+        false
+    }
+
     /// Count of downloading tasks
     pub async fn downloading_count(&self) -> usize {
         let counts = self.counts.read().await;
@@ -424,6 +591,75 @@ impl FolderQueue {
         let counts = self.counts.read().await;
         counts.pending
     }
+
+    /// Why `task` would exceed the folder's `max_queued`, if it would
+    ///
+    /// Queued tasks are the downloading ones plus pending ones not held back
+    /// (held tasks count once they start). `task` itself is not counted.
+    pub async fn max_queued_reason(&self, task: &DownloadTask, max_queued: Option<usize>) -> Option<String> {
+        let max = max_queued?;
+        let tasks = self.tasks.read().await;
+        let queued = tasks
+            .iter()
+            .filter(|t| t.id != task.id)
+            .filter(|t| {
+                t.status == DownloadStatus::Downloading
+                    || (t.status == DownloadStatus::Pending && t.held_reason.is_none())
+            })
+            .count();
+        (queued >= max).then(|| format!("folder has {} queued downloads (max_queued = {})", queued, max))
+    }
+
+    /// Why starting `task` would exceed the folder's quotas, if it would
+    ///
+    /// `max_queued` caps the queued tasks (see `max_queued_reason`) and
+    /// `max_inflight_bytes` the bytes the downloading ones still have to
+    /// transfer. Tasks of unknown size count as 0 bytes, and a task always
+    /// fits when nothing else is downloading, so an oversized download is
+    /// not held forever.
+    pub async fn quota_hold_reason(
+        &self,
+        task: &DownloadTask,
+        max_queued: Option<usize>,
+        max_inflight_bytes: Option<u64>,
+    ) -> Option<String> {
+        if let Some(reason) = self.max_queued_reason(task, max_queued).await {
+            return Some(reason);
+        }
+        let max = max_inflight_bytes?;
+
+        let tasks = self.tasks.read().await;
+        let started: Vec<&DownloadTask> = tasks
+            .iter()
+            .filter(|t| t.status == DownloadStatus::Downloading && t.id != task.id)
+            .collect();
+        if started.is_empty() {
+            return None;
+        }
+
+        let remaining = |t: &DownloadTask| t.size.map_or(0, |size| size.saturating_sub(t.downloaded));
+        let inflight: u64 = started.iter().map(|t| remaining(t)).sum();
+        (inflight + remaining(task) > max).then(|| {
+            format!(
+                "{} bytes in flight plus {} would exceed max_inflight_bytes = {}",
+                inflight,
+                remaining(task),
+                max
+            )
+        })
+    }
+}
+
+/// Sort tasks into the order `policy` starts them
+///
+/// The sort is stable, so ties (and every task under `fifo`) keep queue order.
+pub fn sort_for_schedule(tasks: &mut [DownloadTask], policy: SchedulePolicy) {
+    match policy {
+        SchedulePolicy::Fifo => {}
+        SchedulePolicy::Priority => tasks.sort_by_key(|t| std::cmp::Reverse(t.priority)),
+        SchedulePolicy::SmallestFirst => tasks.sort_by_key(|t| (t.size.is_none(), t.size)),
+        SchedulePolicy::LargestFirst => tasks.sort_by_key(|t| (t.size.is_none(), std::cmp::Reverse(t.size))),
+    }
 }
 
 #[cfg(test)]
@@ -484,6 +720,31 @@ mod tests {
         assert!(counts.has_active_tasks());
     }
 
+    #[tokio::test]
+    async fn test_folder_queue_dirty_tracking() {
+        let queue = FolderQueue::new("test-folder", 3);
+        assert!(!queue.is_dirty());
+
+        let task = create_test_task(DownloadStatus::Downloading);
+        let task_id = task.id;
+        queue.add(task).await;
+        assert!(queue.is_dirty());
+
+        // Progress ticks do not require a rewrite
+        queue.dirty.store(false, Ordering::Release);
+        queue.update_progress(task_id, 1024, Some(4096)).await;
+        assert!(!queue.is_dirty());
+        assert!(queue.has_unsaved_progress());
+        let task = queue.get_by_id(task_id).await.unwrap();
+        assert_eq!(task.downloaded, 1024);
+        assert_eq!(task.size, Some(4096));
+
+        assert!(!queue.save_if_dirty(false).await.unwrap());
+
+        queue.set_priority(task_id, 5).await;
+        assert!(queue.is_dirty());
+    }
+
     #[tokio::test]
     async fn test_folder_queue_remove() {
         let queue = FolderQueue::new("test-folder", 3);
@@ -547,6 +808,36 @@ mod tests {
         assert_eq!(next.unwrap().priority, 5);
     }
 
+    #[tokio::test]
+    async fn test_pending_order_follows_schedule_policy() {
+        let queue = FolderQueue::new("test-folder", 3);
+        // (priority, size) in queue order
+        for (priority, size) in [(0, Some(500)), (2, None), (5, Some(100)), (2, Some(900))] {
+            let mut task = create_test_task(DownloadStatus::Pending);
+            task.priority = priority;
+            task.size = size;
+            queue.add(task).await;
+        }
+        let mut done = create_test_task(DownloadStatus::Completed);
+        done.size = Some(1);
+        queue.add(done).await;
+
+        let order = async |policy| {
+            queue
+                .pending_in_order(policy)
+                .await
+                .iter()
+                .map(|t| (t.priority, t.size))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(SchedulePolicy::Fifo).await, [(0, Some(500)), (2, None), (5, Some(100)), (2, Some(900))]);
+        // Equal priorities keep queue order
+        assert_eq!(order(SchedulePolicy::Priority).await, [(5, Some(100)), (2, None), (2, Some(900)), (0, Some(500))]);
+        // Unknown sizes go last either way
+        assert_eq!(order(SchedulePolicy::SmallestFirst).await, [(5, Some(100)), (0, Some(500)), (2, Some(900)), (2, None)]);
+        assert_eq!(order(SchedulePolicy::LargestFirst).await, [(2, Some(900)), (0, Some(500)), (5, Some(100)), (2, None)]);
+    }
+
     #[tokio::test]
     async fn test_folder_queue_move_operations() {
         let queue = FolderQueue::new("test-folder", 3);
@@ -578,6 +869,53 @@ mod tests {
         let all = queue.get_all().await;
         assert_eq!(all[0].id, id3);
         assert_eq!(all[1].id, id1);
+
+        // Move task3 after task1, then task1 after the last task
+        assert!(queue.move_after(id3, id1).await);
+        let all = queue.get_all().await;
+        assert_eq!(all[0].id, id1);
+        assert_eq!(all[1].id, id3);
+        assert!(queue.move_after(id1, _id2).await);
+        let all = queue.get_all().await;
+        assert_eq!(all[2].id, id1);
+    }
+
+    #[tokio::test]
+    async fn test_quota_hold_reason() {
+        let queue = FolderQueue::new("test-folder", 3);
+
+        let mut running = create_test_task(DownloadStatus::Downloading);
+        running.size = Some(1000);
+        running.downloaded = 400;
+        queue.add(running).await;
+
+        let mut next = create_test_task(DownloadStatus::Pending);
+        next.size = Some(500);
+        queue.add(next.clone()).await;
+
+        // No quotas, or within both
+        assert_eq!(queue.quota_hold_reason(&next, None, None).await, None);
+        assert_eq!(queue.quota_hold_reason(&next, Some(2), Some(1100)).await, None);
+
+        let reason = queue.quota_hold_reason(&next, Some(1), None).await.unwrap();
+        assert!(reason.contains("max_queued = 1"), "{}", reason);
+        // 600 remaining in flight + 500 > 1000
+        let reason = queue.quota_hold_reason(&next, None, Some(1000)).await.unwrap();
+        assert!(reason.contains("max_inflight_bytes = 1000"), "{}", reason);
+
+        // Pending tasks count towards max_queued, held ones do not
+        let waiting = create_test_task(DownloadStatus::Pending);
+        queue.add(waiting.clone()).await;
+        let reason = queue.max_queued_reason(&next, Some(2)).await.unwrap();
+        assert!(reason.contains("2 queued"), "{}", reason);
+        let mut held = waiting;
+        held.held_reason = Some("folder has 2 queued downloads (max_queued = 2)".to_string());
+        queue.update(held).await;
+        assert_eq!(queue.max_queued_reason(&next, Some(2)).await, None);
+
+        // The first started task always fits max_inflight_bytes
+        let empty = FolderQueue::new("empty", 3);
+        assert_eq!(empty.quota_hold_reason(&next, None, Some(1)).await, None);
     }
 
     #[tokio::test]
@@ -9,17 +9,27 @@ use uuid::Uuid;
 
 use super::task::DownloadTask;
 
+/// Retention limits for history items (0 = unlimited)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryRetention {
+    pub max_entries: usize,
+    pub max_age_days: u32,
+}
+
 /// Download history storage
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DownloadHistory {
     /// List of historical download items (completed, failed, deleted)
     pub items: Vec<DownloadTask>,
+    /// Retention limits enforced on add (runtime only)
+    #[serde(skip)]
+    retention: HistoryRetention,
 }
 
 impl DownloadHistory {
     /// Creates a new empty history
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self::default()
     }
 
     /// Adds a task to history
@@ -27,7 +37,50 @@ impl DownloadHistory {
         // Avoid duplicates by ID
         if !self.items.iter().any(|t| t.id == task.id) {
             self.items.push(task);
+            self.prune();
+        }
+    }
+
+    /// Sets retention limits and immediately applies them
+    ///
+    /// Returns the number of items removed.
+    pub fn set_retention(&mut self, retention: HistoryRetention) -> usize {
+        self.retention = retention;
+        self.prune()
+    }
+
+    /// Returns the current retention limits
+    pub fn retention(&self) -> HistoryRetention {
+        self.retention
+    }
+
+    /// Removes items exceeding the retention limits, oldest first
+    ///
+    /// Returns the number of items removed.
+    pub fn prune(&mut self) -> usize {
+        let before = self.items.len();
+
+        // A cutoff before the earliest representable time keeps everything
+        let max_age = chrono::Duration::days(self.retention.max_age_days as i64);
+        if self.retention.max_age_days > 0
+            && let Some(cutoff) = chrono::Utc::now().checked_sub_signed(max_age)
+        {
+            self.items.retain(|t| Self::finished_at(t) >= cutoff);
         }
+
+        if self.retention.max_entries > 0 && self.items.len() > self.retention.max_entries {
+            // Stable sort keeps insertion order for items with equal timestamps
+            self.items.sort_by_key(Self::finished_at);
+            let excess = self.items.len() - self.retention.max_entries;
+            self.items.drain(..excess);
+        }
+
+        before - self.items.len()
+    }
+
+    /// Best-known time the item left the queue
+    fn finished_at(task: &DownloadTask) -> chrono::DateTime<chrono::Utc> {
+        task.completed_at.or(task.started_at).unwrap_or(task.created_at)
     }
 
     /// Removes a task from history by ID
@@ -125,6 +178,59 @@ mod tests {
         assert!(history.get(id).is_some());
     }
 
+    #[test]
+    fn test_history_prune_max_entries_oldest_first() {
+        let mut history = DownloadHistory::new();
+        let mut ids = Vec::new();
+        for days_ago in [1, 3, 2] {
+            let mut task = create_test_task(DownloadStatus::Completed);
+            task.completed_at = Some(chrono::Utc::now() - chrono::Duration::days(days_ago));
+            ids.push(task.id);
+            history.add(task);
+        }
+
+        let removed = history.set_retention(HistoryRetention { max_entries: 2, max_age_days: 0 });
+
+        assert_eq!(removed, 1);
+        assert!(history.get(ids[1]).is_none()); // 3 days ago is the oldest
+        assert!(history.get(ids[0]).is_some());
+        assert!(history.get(ids[2]).is_some());
+
+        // Limit is enforced on add
+        history.add(create_test_task(DownloadStatus::Completed));
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn test_history_prune_max_age() {
+        let mut history = DownloadHistory::new();
+        let mut old = create_test_task(DownloadStatus::Completed);
+        old.completed_at = Some(chrono::Utc::now() - chrono::Duration::days(40));
+        let recent = create_test_task(DownloadStatus::Error);
+        let recent_id = recent.id;
+        history.add(old);
+        history.add(recent);
+
+        let removed = history.set_retention(HistoryRetention { max_entries: 0, max_age_days: 30 });
+
+        assert_eq!(removed, 1);
+        assert_eq!(history.len(), 1);
+        assert!(history.get(recent_id).is_some());
+    }
+
+    #[test]
+    fn test_history_prune_huge_max_age_keeps_all() {
+        let mut history = DownloadHistory::new();
+        let mut old = create_test_task(DownloadStatus::Completed);
+        old.completed_at = Some(chrono::Utc::now() - chrono::Duration::days(40));
+        history.add(old);
+
+        let removed = history.set_retention(HistoryRetention { max_entries: 0, max_age_days: u32::MAX });
+
+        assert_eq!(removed, 0);
+        assert_eq!(history.len(), 1);
+    }
+
     #[test]
     fn test_history_no_duplicates() {
         let mut history = DownloadHistory::new();
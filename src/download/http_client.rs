@@ -1,11 +1,17 @@
 use anyhow::{anyhow, Result};
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, ETAG, LAST_MODIFIED, RANGE, REFERER, USER_AGENT};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, ETAG, LAST_MODIFIED, RANGE, REFERER, RETRY_AFTER, USER_AGENT};
 use std::path::Path;
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::{AsyncWriteExt, BufWriter};
 use futures_util::StreamExt;
+use tokio_util::sync::CancellationToken;
 
+use super::concurrency::{ConcurrencyLimit, LimitPermit};
 use super::http_errors::HttpErrorInfo;
+use super::rate_limit::RateLimiter;
+use crate::app::config::{TlsVersion, DEFAULT_CHUNK_SIZE_BYTES, MAX_CHUNK_SIZE_BYTES, MIN_CHUNK_SIZE_BYTES};
 
 /// Progress callback for download operations
 pub type ProgressCallback = Box<dyn Fn(u64, Option<u64>) + Send + Sync>;
@@ -25,6 +31,10 @@ pub struct DownloadInfo {
     pub auth_realm: Option<String>,
     /// The final URL after following redirects (if any)
     pub final_url: Option<String>,
+    /// `resume_supported` comes from a Range probe rather than `Accept-Ranges`
+    pub range_probed: bool,
+    /// The partial file failed the resume overlap check and was downloaded again from scratch
+    pub resume_restarted: bool,
 }
 
 /// Parsed HTTP response headers
@@ -40,6 +50,14 @@ struct ParsedHeaders {
 }
 
 /// Parse common HTTP response headers
+/// Fail unless exactly the `expected` number of bytes arrived (None = length unknown)
+fn check_complete(received: u64, expected: Option<u64>) -> Result<()> {
+    match expected {
+        Some(expected) if received != expected => Err(IncompleteBody { received, expected }.into()),
+        _ => Ok(()),
+    }
+}
+
 fn parse_response_headers(headers: &HeaderMap) -> ParsedHeaders {
     let size = headers
         .get(CONTENT_LENGTH)
@@ -62,15 +80,11 @@ fn parse_response_headers(headers: &HeaderMap) -> ParsedHeaders {
         .and_then(|v| v.to_str().ok())
         .map(str::to_string);
 
+    // Servers occasionally send raw UTF-8 in Content-Disposition, which to_str() rejects
     let filename = headers
         .get("content-disposition")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| {
-            // Parse filename from Content-Disposition header
-            v.split("filename=")
-                .nth(1)
-                .map(|s| s.trim_matches('"').to_string())
-        });
+        .and_then(|v| std::str::from_utf8(v.as_bytes()).ok())
+        .and_then(crate::file::naming::parse_content_disposition_filename);
 
     let content_type = headers
         .get("content-type")
@@ -126,35 +140,293 @@ fn parse_response_headers(headers: &HeaderMap) -> ParsedHeaders {
 
 pub struct HttpClient {
     client: reqwest::Client,
+    /// Bandwidth cap shared by all downloads through this client
+    rate_limiter: RateLimiter,
+    /// Largest slice written, throttled and checked for stop at once
+    chunk_size: usize,
+    /// TLS certificate verification is disabled
+    accepts_invalid_certs: bool,
+    /// `get_info` confirms resume support with a Range probe
+    deep_resume_probe: bool,
+    /// Resumed downloads re-fetch the tail of the partial file and compare it before appending
+    verify_resume_overlap: bool,
+    /// Simultaneous requests through this client (None = unlimited)
+    connection_limit: Option<ConcurrencyLimit>,
+}
+
+/// Returned when a download stops early because a stop was requested
+///
+/// Everything received so far has been flushed to disk.
+#[derive(Debug, thiserror::Error)]
+#[error("download stopped at {downloaded} bytes")]
+pub struct DownloadStopped {
+    pub downloaded: u64,
+}
+
+/// Returned when the GET response reports a larger file than the caller allowed
+///
+/// Nothing has been written for the response; `size` is the full file size.
+#[derive(Debug, thiserror::Error)]
+#[error("file is {size} bytes, over the allowed {max_size}")]
+pub struct SizeOverLimit {
+    pub size: u64,
+    pub max_size: u64,
+}
+
+/// Returned when the body does not match its Content-Length
+#[derive(Debug, thiserror::Error)]
+#[error("incomplete: got {received} of {expected} bytes")]
+pub struct IncompleteBody {
+    pub received: u64,
+    pub expected: u64,
+}
+
+/// Returned when the server answers with an error status
+///
+/// Lets the retry loop tell HTTP failures (which may not be worth retrying)
+/// apart from network and I/O errors.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct HttpStatusError {
+    pub status: u16,
+    /// Wait requested by `Retry-After` (429 and 503 only)
+    pub retry_after: Option<Duration>,
+    message: String,
+}
+
+impl HttpStatusError {
+    pub fn new(status: u16) -> Self {
+        Self { status, retry_after: None, message: HttpErrorInfo::from_status(status).format() }
+    }
+
+    /// Error for a failed response, picking up `Retry-After` on 429 and 503
+    fn from_response(response: &reqwest::Response) -> Self {
+        let mut error = Self::new(response.status().as_u16());
+        if matches!(error.status, 429 | 503) {
+            error.retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, Utc::now()));
+        }
+        error
+    }
+
+    /// Whether a failed attempt with this status should be retried
+    ///
+    /// Statuses in `retry_on` are always retried; otherwise only those
+    /// `HttpErrorInfo` marks as retryable (429 and 5xx).
+    pub fn is_retryable(&self, retry_on: &[u16]) -> bool {
+        retry_on.contains(&self.status) || HttpErrorInfo::from_status(self.status).is_retryable
+    }
+}
+
+/// Delay requested by a `Retry-After` value: delay-seconds or an HTTP-date
+///
+/// Dates in the past mean "retry now"; unparsable values give None.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Default user agent used when none is configured
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36";
+
+/// Default maximum number of redirects to follow
+const DEFAULT_MAX_REDIRECTS: u32 = 5;
+
+/// URL schemes the client can download (reqwest has no FTP support)
+pub const SUPPORTED_SCHEMES: &[&str] = &["http", "https"];
+
+/// Bytes before the resume offset fetched again and compared with the partial
+/// file when `verify_resume_overlap` is enabled
+pub const RESUME_OVERLAP_BYTES: u64 = 1024;
+
+/// Decide whether a redirect hop should be followed
+///
+/// `previous` contains every URL visited so far (including the original request).
+/// Returns an error message when the hop exceeds `max_redirects` or downgrades
+/// from HTTPS to HTTP without `allow_insecure` being set.
+fn check_redirect(
+    previous: &[url::Url],
+    next: &url::Url,
+    max_redirects: u32,
+    allow_insecure: bool,
+) -> std::result::Result<(), String> {
+    if previous.len() > max_redirects as usize {
+        return Err(format!("Too many redirects (max {})", max_redirects));
+    }
+
+    if !allow_insecure {
+        let from_https = previous.last().is_some_and(|u| u.scheme() == "https");
+        if from_https && next.scheme() == "http" {
+            return Err(format!(
+                "Refusing insecure redirect from HTTPS to HTTP: {}",
+                next
+            ));
+        }
+    }
+
+    Ok(())
 }
 
 impl HttpClient {
     /// Create a new HTTP client with default settings
     pub fn new() -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-            .timeout(std::time::Duration::from_secs(300))        // 5 min total timeout
-            .connect_timeout(std::time::Duration::from_secs(30)) // 30s connect timeout
-            .pool_max_idle_per_host(10)                          // Allow more idle connections
-            .build()?;
-
-        Ok(Self { client })
+        Self::with_options(DEFAULT_USER_AGENT, DEFAULT_MAX_REDIRECTS, false)
     }
 
     /// Create a new HTTP client with custom user agent
     pub fn with_user_agent(user_agent: &str) -> Result<Self> {
-        let client = reqwest::Client::builder()
+        Self::with_options(user_agent, DEFAULT_MAX_REDIRECTS, false)
+    }
+
+    /// Create a new HTTP client from download configuration
+    pub fn from_config(config: &crate::app::config::DownloadConfig) -> Result<Self> {
+        let pool_idle_timeout = (config.pool_idle_timeout_secs > 0)
+            .then(|| std::time::Duration::from_secs(config.pool_idle_timeout_secs));
+        let mut builder = Self::builder(&config.user_agent, config.max_redirects, config.allow_insecure_redirect)
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(pool_idle_timeout);
+        if config.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(version) = config.min_tls_version {
+            builder = builder.tls_version_min(match version {
+                TlsVersion::Tls12 => reqwest::tls::Version::TLS_1_2,
+                TlsVersion::Tls13 => reqwest::tls::Version::TLS_1_3,
+            });
+        }
+        if config.danger_accept_invalid_certs {
+            tracing::warn!(
+                "download.danger_accept_invalid_certs is enabled: TLS certificates are NOT verified \
+                 and HTTPS downloads can be intercepted or tampered with"
+            );
+            builder = builder.tls_danger_accept_invalid_certs(true);
+        }
+
+        let mut client = Self::from_reqwest(builder.build()?);
+        client.accepts_invalid_certs = config.danger_accept_invalid_certs;
+        client.deep_resume_probe = config.deep_resume_probe;
+        client.verify_resume_overlap = config.verify_resume_overlap;
+        client.rate_limiter.set_limit(config.bandwidth_limit);
+
+        if config.chunk_size_bytes < MIN_CHUNK_SIZE_BYTES {
+            tracing::warn!(
+                "download.chunk_size_bytes = {} is below the minimum; using {}",
+                config.chunk_size_bytes,
+                MIN_CHUNK_SIZE_BYTES
+            );
+        } else if config.chunk_size_bytes > MAX_CHUNK_SIZE_BYTES {
+            tracing::warn!(
+                "download.chunk_size_bytes = {} is above the maximum; using {}",
+                config.chunk_size_bytes,
+                MAX_CHUNK_SIZE_BYTES
+            );
+        }
+        client.chunk_size = config.chunk_size_bytes.clamp(MIN_CHUNK_SIZE_BYTES, MAX_CHUNK_SIZE_BYTES);
+        Ok(client)
+    }
+
+    /// Chunk size used by the download loop
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// True if TLS certificate verification is disabled (`danger_accept_invalid_certs`)
+    pub fn accepts_invalid_certs(&self) -> bool {
+        self.accepts_invalid_certs
+    }
+
+    /// True if `get_info` probes resume support with a Range request (`deep_resume_probe`)
+    pub fn deep_resume_probe(&self) -> bool {
+        self.deep_resume_probe
+    }
+
+    /// True if resumed downloads are checked against the partial file (`verify_resume_overlap`)
+    pub fn verify_resume_overlap(&self) -> bool {
+        self.verify_resume_overlap
+    }
+
+    /// Bandwidth limiter applied to file downloads
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Create a new HTTP client with custom user agent and redirect policy
+    pub fn with_options(user_agent: &str, max_redirects: u32, allow_insecure_redirect: bool) -> Result<Self> {
+        let client = Self::builder(user_agent, max_redirects, allow_insecure_redirect)
+            .pool_max_idle_per_host(10) // Allow more idle connections
+            .build()?;
+        Ok(Self::from_reqwest(client))
+    }
+
+    /// reqwest builder with the settings every client shares
+    ///
+    /// Connections are pooled per host by reqwest, so one `HttpClient` (shared
+    /// through `DownloadManager`) reuses keep-alive connections and TLS
+    /// sessions across all downloads from the same server.
+    fn builder(user_agent: &str, max_redirects: u32, allow_insecure_redirect: bool) -> reqwest::ClientBuilder {
+        let redirect_policy = reqwest::redirect::Policy::custom(move |attempt| {
+            match check_redirect(attempt.previous(), attempt.url(), max_redirects, allow_insecure_redirect) {
+                Ok(()) => attempt.follow(),
+                Err(msg) => attempt.error(msg),
+            }
+        });
+
+        reqwest::Client::builder()
             .user_agent(user_agent)
+            .redirect(redirect_policy)
             .timeout(std::time::Duration::from_secs(300))        // 5 min total timeout
             .connect_timeout(std::time::Duration::from_secs(30)) // 30s connect timeout
-            .pool_max_idle_per_host(10)                          // Allow more idle connections
-            .build()?;
+    }
+
+    fn from_reqwest(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            rate_limiter: RateLimiter::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE_BYTES,
+            accepts_invalid_certs: false,
+            deep_resume_probe: false,
+            verify_resume_overlap: false,
+            connection_limit: None,
+        }
+    }
+
+    /// Hold a slot of `limit` for every request sent, until its response is dropped
+    pub(crate) fn with_connection_limit(mut self, limit: ConcurrencyLimit) -> Self {
+        self.connection_limit = Some(limit);
+        self
+    }
 
-        Ok(Self { client })
+    /// Wait for a connection slot (None when connections are not limited)
+    async fn connection(&self) -> Option<LimitPermit> {
+        match &self.connection_limit {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        }
     }
 
     /// Get download information without downloading the file
+    ///
+    /// Resume support is probed with a Range request when the client was
+    /// configured with `deep_resume_probe` (see `get_info_with_probe`).
     pub async fn get_info(&self, url: &str, headers: &HeaderMap) -> Result<DownloadInfo> {
+        self.get_info_with_probe(url, headers, self.deep_resume_probe).await
+    }
+
+    /// Get download information, optionally probing resume support
+    ///
+    /// Some servers advertise `Accept-Ranges` and still ignore Range. With
+    /// `probe` set and a successful HEAD, a 1-byte `Range: bytes=0-0` GET
+    /// decides `resume_supported` instead: only 206 Partial Content counts.
+    /// If the probe itself fails, the `Accept-Ranges` answer is kept.
+    pub async fn get_info_with_probe(&self, url: &str, headers: &HeaderMap, probe: bool) -> Result<DownloadInfo> {
+        let connection = self.connection().await;
         let response = self.client
             .head(url)
             .headers(headers.clone())
@@ -166,10 +438,20 @@ impl HttpClient {
         let status = response.status().as_u16();
         let (auth_required, auth_realm) = Self::check_auth_required(status, response.headers());
         let final_url = Some(response.url().to_string());
+        let head_succeeded = response.status().is_success();
+        // The probe takes its own slot; keeping this one would deadlock a limit of 1
+        drop(response);
+        drop(connection);
+
+        let probed = if probe && head_succeeded {
+            self.probe_range(url, headers).await
+        } else {
+            None
+        };
 
         Ok(DownloadInfo {
             size: parsed.size,
-            resume_supported: parsed.resume_supported,
+            resume_supported: probed.unwrap_or(parsed.resume_supported),
             etag: parsed.etag,
             last_modified: parsed.last_modified,
             filename: parsed.filename,
@@ -179,9 +461,98 @@ impl HttpClient {
             auth_required,
             auth_realm,
             final_url,
+            range_probed: probed.is_some(),
+            resume_restarted: false,
         })
     }
 
+    /// Whether the server answers `Range: bytes=0-0` with 206 (None if the request failed)
+    async fn probe_range(&self, url: &str, headers: &HeaderMap) -> Option<bool> {
+        let _connection = self.connection().await;
+        match self.client
+            .get(url)
+            .headers(headers.clone())
+            .header(RANGE, "bytes=0-0")
+            .send()
+            .await
+        {
+            // Dropping a 200 response closes the connection without reading the body
+            Ok(response) => Some(response.status() == reqwest::StatusCode::PARTIAL_CONTENT),
+            Err(e) => {
+                tracing::debug!("Range probe failed for {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    /// Fetch at most the first `max_bytes` bytes of the response body
+    ///
+    /// Sends a ranged GET so supporting servers stop early; for servers that
+    /// ignore the range, reading stops after `max_bytes` and the connection
+    /// is dropped.
+    pub async fn peek_body(&self, url: &str, headers: &HeaderMap, max_bytes: usize) -> Result<Vec<u8>> {
+        if max_bytes == 0 {
+            return Ok(Vec::new());
+        }
+
+        let _connection = self.connection().await;
+        let response = self.client
+            .get(url)
+            .headers(headers.clone())
+            .header(RANGE, format!("bytes=0-{}", max_bytes - 1))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(HttpStatusError::from_response(&response).into());
+        }
+
+        // Grows as chunks arrive; max_bytes comes from the config and can be far larger
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let take = chunk.len().min(max_bytes - body.len());
+            body.extend_from_slice(&chunk[..take]);
+            if body.len() >= max_bytes {
+                break;
+            }
+        }
+        Ok(body)
+    }
+
+    /// Stream the response body into `writer` (e.g. stdout) and return its length
+    ///
+    /// Nothing touches the disk and there is no resume; `bandwidth_limit`
+    /// still applies. An error status fails before anything is written, and
+    /// a body shorter than its Content-Length fails after it was written.
+    pub async fn download_to_writer<W>(&self, url: &str, headers: &HeaderMap, writer: &mut W) -> Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+    {
+        let _connection = self.connection().await;
+        let response = self.client.get(url).headers(headers.clone()).send().await?;
+        if !response.status().is_success() {
+            return Err(HttpStatusError::from_response(&response).into());
+        }
+
+        let expected = parse_response_headers(response.headers()).size;
+        let flow = self.rate_limiter.flow(1);
+        let mut written = 0;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            for piece in chunk.chunks(self.chunk_size) {
+                flow.acquire(piece.len()).await;
+                writer.write_all(piece).await?;
+                written += piece.len() as u64;
+            }
+        }
+        writer.flush().await?;
+        check_complete(written, expected)?;
+        Ok(written)
+    }
+
     /// Download a file with streaming and progress callback
     pub async fn download_to_file<F>(
         &self,
@@ -191,6 +562,31 @@ impl HttpClient {
         resume_from: Option<u64>,
         progress_callback: Option<F>,
     ) -> Result<DownloadInfo>
+    where
+        F: Fn(u64, Option<u64>) + Send + Sync,
+    {
+        self.download_to_file_until(url, path, headers, resume_from, progress_callback, None, 1, None).await
+    }
+
+    /// Download a file, stopping at the next chunk boundary once `stop` is cancelled
+    ///
+    /// On stop, buffered data is flushed and `DownloadStopped` is returned so the
+    /// partial file can be resumed later. `weight` is this download's share of
+    /// `bandwidth_limit` relative to the other running downloads. A response
+    /// whose Content-Length makes the file larger than `max_size` fails with
+    /// `SizeOverLimit` before anything is written.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn download_to_file_until<F>(
+        &self,
+        url: &str,
+        path: &Path,
+        headers: &HeaderMap,
+        resume_from: Option<u64>,
+        progress_callback: Option<F>,
+        stop: Option<&CancellationToken>,
+        weight: u32,
+        max_size: Option<u64>,
+    ) -> Result<DownloadInfo>
     where
         F: Fn(u64, Option<u64>) + Send + Sync,
     {
@@ -198,13 +594,30 @@ impl HttpClient {
 
         let mut request = self.client.get(url).headers(headers.clone());
 
-        // Add Range header for resume support
+        // Add Range header for resume support, starting early enough to
+        // re-fetch the overlap checked against the partial file
         let mut actual_resume_from = resume_from;
+        let overlap = match resume_from {
+            Some(offset) if self.verify_resume_overlap => offset.min(RESUME_OVERLAP_BYTES),
+            _ => 0,
+        };
         if let Some(offset) = resume_from {
-            tracing::trace!("Adding Range header for resume: bytes={}-", offset);
-            request = request.header(RANGE, format!("bytes={}-", offset));
+            tracing::trace!("Adding Range header for resume: bytes={}-", offset - overlap);
+            request = request.header(RANGE, format!("bytes={}-", offset - overlap));
         }
 
+        // One slot covers this transfer; the 416 and overlap re-requests drop
+        // the previous response first, so they never hold a second connection
+        let _connection = match (self.connection_limit.as_ref(), stop) {
+            (Some(limit), Some(stop)) => tokio::select! {
+                permit = limit.acquire() => Some(permit),
+                _ = stop.cancelled() => {
+                    return Err(DownloadStopped { downloaded: resume_from.unwrap_or(0) }.into());
+                }
+            },
+            _ => self.connection().await,
+        };
+
         tracing::trace!("Sending HTTP request to {}", url);
         let mut response = request.send().await?;
         tracing::trace!("Received response with status: {}", response.status());
@@ -215,27 +628,52 @@ impl HttpClient {
             tracing::warn!("Got 416 Range Not Satisfiable, retrying without Range header");
             actual_resume_from = None;
             let retry_request = self.client.get(url).headers(headers.clone());
+            drop(response);
             response = retry_request.send().await?;
             tracing::trace!("Retry response status: {}", response.status());
         }
 
+        // Compare the overlap with the end of the partial file; appending to a
+        // file that does not match would only produce a corrupt download
+        let mut verified_overlap = 0;
+        let mut pending_chunk = None;
+        let mut resume_restarted = false;
+        if let Some(offset) = actual_resume_from.filter(|_| overlap > 0 && response.status().as_u16() == 206) {
+            match Self::check_resume_overlap(&mut response, path, offset, overlap).await? {
+                Some(rest) => {
+                    verified_overlap = overlap;
+                    pending_chunk = Some(rest).filter(|rest| !rest.is_empty());
+                }
+                None => {
+                    tracing::warn!("Partial file {:?} does not match the server; downloading from scratch", path);
+                    actual_resume_from = None;
+                    resume_restarted = true;
+                    drop(response);
+                    response = self.client.get(url).headers(headers.clone()).send().await?;
+                }
+            }
+        }
+
         // Check for auth requirement BEFORE generic error check
         let status = response.status().as_u16();
         let (auth_required, auth_realm) = Self::check_auth_required(status, response.headers());
 
         if auth_required {
             let error_info = HttpErrorInfo::from_status(status);
-            return Err(anyhow!(
-                "{}: realm={}",
-                error_info.format(),
-                auth_realm.unwrap_or_else(|| "unknown".to_string())
-            ));
+            return Err(HttpStatusError {
+                status,
+                retry_after: None,
+                message: format!(
+                    "{}: realm={}",
+                    error_info.format(),
+                    auth_realm.unwrap_or_else(|| "unknown".to_string())
+                ),
+            }
+            .into());
         }
 
         if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let error_info = HttpErrorInfo::from_status(status);
-            return Err(anyhow!("{}", error_info.format()));
+            return Err(HttpStatusError::from_response(&response).into());
         }
 
         // Get download info from response headers
@@ -244,8 +682,8 @@ impl HttpClient {
 
         tracing::trace!("Download info: size={:?}, resume_supported={}", parsed.size, parsed.resume_supported);
 
-        // Extract values for use in download logic
-        let size = parsed.size;
+        // Extract values for use in download logic (the overlap is not part of the remaining size)
+        let size = parsed.size.map(|len| len.saturating_sub(verified_overlap));
         let resume_supported = parsed.resume_supported;
         let etag = parsed.etag;
         let last_modified = parsed.last_modified;
@@ -255,6 +693,14 @@ impl HttpClient {
         let response_headers = parsed.all_headers;
         let final_url = Some(response.url().to_string());
 
+        // The HEAD may have reported a smaller size (or none); check again before writing
+        if let (Some(size), Some(max_size)) = (size, max_size) {
+            let total = actual_resume_from.unwrap_or(0) + size;
+            if total > max_size {
+                return Err(SizeOverLimit { size: total, max_size }.into());
+            }
+        }
+
         // Open file for writing (append if resuming, fresh if fallback occurred)
         let file = if actual_resume_from.is_some() {
             tokio::fs::OpenOptions::new()
@@ -266,9 +712,10 @@ impl HttpClient {
             File::create(path).await?
         };
 
-        // Wrap file in BufWriter for better I/O performance (64KB buffer)
+        // Wrap file in BufWriter sized to the chunk size (64KB by default)
         // Larger buffer reduces syscall overhead for high-speed downloads
-        let mut file = BufWriter::with_capacity(64 * 1024, file);
+        let mut file = BufWriter::with_capacity(self.chunk_size, file);
+        let flow = self.rate_limiter.flow(weight);
 
         // Stream the response body to file
         let mut stream = response.bytes_stream();
@@ -280,25 +727,60 @@ impl HttpClient {
         const MIN_PROGRESS_BYTES: u64 = 1024 * 1024; // 1 MB
         const MIN_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
 
-        while let Some(chunk) = stream.next().await {
+        let mut stopped = false;
+        'read: loop {
+            // A stop also interrupts a read that is waiting for the server
+            let next = match stop {
+                _ if pending_chunk.is_some() => pending_chunk.take().map(|rest: Vec<u8>| Ok(rest.into())),
+                Some(stop) => tokio::select! {
+                    biased;
+                    chunk = stream.next() => chunk,
+                    _ = stop.cancelled() => {
+                        stopped = true;
+                        break 'read;
+                    }
+                },
+                None => stream.next().await,
+            };
+            let Some(chunk) = next else {
+                break;
+            };
             let chunk = chunk?;
-            file.write_all(&chunk).await?;
-            downloaded += chunk.len() as u64;
+            // Network reads can be large; slicing them keeps throttling and
+            // stop requests at chunk-size granularity
+            for piece in chunk.chunks(self.chunk_size) {
+                flow.acquire(piece.len()).await;
+                file.write_all(piece).await?;
+                downloaded += piece.len() as u64;
+
+                // Call progress callback (throttled by both time and data size to reduce overhead)
+                if let Some(ref callback) = progress_callback {
+                    let now = std::time::Instant::now();
+                    let bytes_since_update = downloaded - last_progress_bytes;
+                    let time_since_update = now.duration_since(last_progress_update);
+
+                    if bytes_since_update >= MIN_PROGRESS_BYTES || time_since_update >= MIN_PROGRESS_INTERVAL {
+                        callback(downloaded, size);
+                        last_progress_bytes = downloaded;
+                        last_progress_update = now;
+                    }
+                }
 
-            // Call progress callback (throttled by both time and data size to reduce overhead)
-            if let Some(ref callback) = progress_callback {
-                let now = std::time::Instant::now();
-                let bytes_since_update = downloaded - last_progress_bytes;
-                let time_since_update = now.duration_since(last_progress_update);
-
-                if bytes_since_update >= MIN_PROGRESS_BYTES || time_since_update >= MIN_PROGRESS_INTERVAL {
-                    callback(downloaded, size);
-                    last_progress_bytes = downloaded;
-                    last_progress_update = now;
+                if stop.is_some_and(|s| s.is_cancelled()) {
+                    stopped = true;
+                    break 'read;
                 }
             }
         }
 
+        if stopped {
+            file.flush().await?;
+            if let Some(ref callback) = progress_callback {
+                callback(downloaded, size);
+            }
+            return Err(DownloadStopped { downloaded }.into());
+        }
+
         // Final progress update to ensure 100% is reported
         if let Some(ref callback) = progress_callback {
             callback(downloaded, size);
@@ -306,6 +788,10 @@ impl HttpClient {
 
         file.flush().await?;
 
+        // Verify the body was fully received. Content-Length covers only the
+        // requested range, so add the resume offset back in before comparing.
+        check_complete(downloaded, size.map(|content_length| actual_resume_from.unwrap_or(0) + content_length))?;
+
         Ok(DownloadInfo {
             size,
             resume_supported,
@@ -318,9 +804,47 @@ impl HttpClient {
             auth_required: false,  // Already checked above, would have returned early if true
             auth_realm: None,
             final_url,
+            range_probed: false,
+            resume_restarted,
         })
     }
 
+    /// Read the first `overlap` bytes of a resumed response and compare them
+    /// with the partial file's bytes just before `offset`
+    ///
+    /// Returns the rest of the last chunk read (to be written first) if they
+    /// match, or None if they differ, the file is shorter than `offset` or
+    /// the body ends early.
+    async fn check_resume_overlap(
+        response: &mut reqwest::Response,
+        path: &Path,
+        offset: u64,
+        overlap: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let overlap = overlap as usize;
+        let mut expected = vec![0u8; overlap];
+        let mut file = File::open(path).await?;
+        if file.metadata().await?.len() < offset {
+            return Ok(None);
+        }
+        file.seek(std::io::SeekFrom::Start(offset - overlap as u64)).await?;
+        file.read_exact(&mut expected).await?;
+
+        let mut received = Vec::with_capacity(overlap);
+        loop {
+            let Some(chunk) = response.chunk().await? else {
+                return Ok(None);
+            };
+            let take = chunk.len().min(overlap - received.len());
+            received.extend_from_slice(&chunk[..take]);
+            if received.len() == overlap {
+                return Ok((received == expected).then(|| chunk[take..].to_vec()));
+            }
+        }
+    }
+
     /// Build custom headers from user-specified values
     pub fn build_headers(
         user_agent: Option<&str>,
@@ -345,6 +869,50 @@ impl HttpClient {
         Ok(headers)
     }
 
+    /// Parse a `Name: Value` header line (as given to `ggg add --header`)
+    pub fn parse_header_line(line: &str) -> Result<(String, String)> {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected 'Name: Value'"))?;
+        let (name, value) = (name.trim(), value.trim());
+        HeaderName::from_bytes(name.as_bytes()).map_err(|_| anyhow!("invalid header name '{}'", name))?;
+        HeaderValue::from_str(value).map_err(|_| anyhow!("invalid value for header '{}'", name))?;
+        Ok((name.to_string(), value.to_string()))
+    }
+
+    /// Referer header for an http(s) page URL (as given to `ggg add --referer`)
+    pub fn referer_header(url: &str) -> Result<(String, String)> {
+        let url = url.trim();
+        match url::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(("Referer".to_string(), url.to_string())),
+            _ => Err(anyhow!("expected an http(s) URL")),
+        }
+    }
+
+    /// Refuse URLs with a scheme the client cannot fetch (e.g. `ftp://`)
+    ///
+    /// Unparseable URLs pass; the request itself reports those.
+    pub fn check_supported_scheme(url: &str) -> Result<()> {
+        match url::Url::parse(url) {
+            Ok(parsed) if !SUPPORTED_SCHEMES.contains(&parsed.scheme()) => Err(anyhow!(
+                "{}:// downloads are not supported yet; only http and https URLs can be downloaded",
+                parsed.scheme()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set `overrides` on `headers`, replacing entries whose names differ only in case
+    pub fn merge_headers(
+        headers: &mut std::collections::HashMap<String, String>,
+        overrides: impl IntoIterator<Item = (String, String)>,
+    ) {
+        for (name, value) in overrides {
+            headers.retain(|existing, _| !existing.eq_ignore_ascii_case(&name));
+            headers.insert(name, value);
+        }
+    }
+
     /// Check if response requires authentication
     /// Returns (requires_auth, realm)
     fn check_auth_required(
@@ -401,6 +969,93 @@ mod tests {
     use wiremock::{Mock, MockServer, ResponseTemplate};
     use std::sync::{Arc, Mutex};
 
+    #[test]
+    fn test_parse_and_merge_header_lines() {
+        assert_eq!(
+            HttpClient::parse_header_line(" Referer :  https://example.com/page ").unwrap(),
+            ("Referer".to_string(), "https://example.com/page".to_string())
+        );
+        // Values may contain colons
+        assert_eq!(HttpClient::parse_header_line("X-Time: 12:30").unwrap().1, "12:30");
+        assert!(HttpClient::parse_header_line("Referer").is_err());
+        assert!(HttpClient::parse_header_line(": value").is_err());
+        assert!(HttpClient::parse_header_line("Bad Name: value").is_err());
+        assert!(HttpClient::parse_header_line("X-Token: a\nb").is_err());
+
+        let mut headers = std::collections::HashMap::from([
+            ("referer".to_string(), "https://folder.example/".to_string()),
+            ("Accept".to_string(), "*/*".to_string()),
+        ]);
+        HttpClient::merge_headers(&mut headers, [("Referer".to_string(), "https://cli.example/".to_string())]);
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["Referer"], "https://cli.example/");
+
+        assert_eq!(
+            HttpClient::referer_header(" https://example.com/gallery ").unwrap(),
+            ("Referer".to_string(), "https://example.com/gallery".to_string())
+        );
+        assert!(HttpClient::referer_header("example.com/gallery").is_err());
+        assert!(HttpClient::referer_header("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_check_supported_scheme() {
+        assert!(HttpClient::check_supported_scheme("http://example.com/a.zip").is_ok());
+        assert!(HttpClient::check_supported_scheme("HTTPS://example.com/a.zip").is_ok());
+        for url in ["ftp://example.com/a.zip", "ftps://example.com/a.zip"] {
+            let err = HttpClient::check_supported_scheme(url).unwrap_err().to_string();
+            assert!(err.contains("not supported"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after_formats() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+
+        // delay-seconds
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 ", now), Some(Duration::ZERO));
+
+        // HTTP-date, relative to now; past dates mean no wait
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-5", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_only_on_429_and_503() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/busy"))
+            .respond_with(ResponseTemplate::new(503).append_header("Retry-After", "30"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing"))
+            .respond_with(ResponseTemplate::new(404).append_header("Retry-After", "30"))
+            .mount(&mock_server)
+            .await;
+
+        let client = HttpClient::new().unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fetch = async |name: &str| {
+            let url = format!("{}/{}", mock_server.uri(), name);
+            let err = client
+                .download_to_file(&url, &temp_dir.path().join(name), &HeaderMap::new(), None, None::<fn(u64, Option<u64>)>)
+                .await
+                .unwrap_err();
+            err.downcast::<HttpStatusError>().unwrap()
+        };
+
+        let busy = fetch("busy").await;
+        assert_eq!(busy.status, 503);
+        assert_eq!(busy.retry_after, Some(Duration::from_secs(30)));
+        assert_eq!(fetch("missing").await.retry_after, None);
+    }
+
     #[tokio::test]
     async fn test_get_info_parses_content_length() {
         let mock_server = MockServer::start().await;
@@ -420,6 +1075,63 @@ mod tests {
         assert_eq!(info.size, Some(1024));
     }
 
+    #[tokio::test]
+    async fn test_peek_body_truncates_to_limit() {
+        let mock_server = MockServer::start().await;
+
+        // Server ignores the Range header and sends the whole body
+        Mock::given(method("GET"))
+            .and(path("/page"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>not the file you wanted</html>"))
+            .mount(&mock_server)
+            .await;
+
+        let client = HttpClient::new().unwrap();
+        let url = format!("{}/page", mock_server.uri());
+        let peek = client.peek_body(&url, &Default::default(), 6).await.unwrap();
+
+        assert_eq!(peek, b"<html>");
+        let request = &mock_server.received_requests().await.unwrap()[0];
+        assert_eq!(request.headers.get("range").unwrap(), "bytes=0-5");
+
+        // A limit far beyond the body allocates only what arrives
+        let peek = client.peek_body(&url, &Default::default(), usize::MAX).await.unwrap();
+        assert_eq!(peek, b"<html>not the file you wanted</html>");
+    }
+
+    #[tokio::test]
+    async fn test_download_to_writer() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/data.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("streamed body"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found page"))
+            .mount(&mock_server)
+            .await;
+
+        let client = HttpClient::new().unwrap();
+        let mut out = Vec::new();
+        let written = client
+            .download_to_writer(&format!("{}/data.txt", mock_server.uri()), &Default::default(), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(written, 13);
+        assert_eq!(out, b"streamed body");
+
+        // An error page is never written out
+        let mut out = Vec::new();
+        let err = client
+            .download_to_writer(&format!("{}/missing", mock_server.uri()), &Default::default(), &mut out)
+            .await
+            .unwrap_err();
+        assert_eq!(err.downcast_ref::<HttpStatusError>().unwrap().status, 404);
+        assert!(out.is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_info_detects_resume_support() {
         let mock_server = MockServer::start().await;
@@ -439,6 +1151,82 @@ mod tests {
         assert!(info.resume_supported);
     }
 
+    #[tokio::test]
+    async fn test_connection_limit_of_one_allows_range_probe() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).append_header("Accept-Ranges", "bytes"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(vec![0u8]))
+            .mount(&mock_server)
+            .await;
+
+        // The HEAD gives its connection back before the probe asks for one
+        let limit = ConcurrencyLimit::new(1);
+        let client = HttpClient::new().unwrap().with_connection_limit(limit.clone());
+        let url = format!("{}/file.zip", mock_server.uri());
+        let info = tokio::time::timeout(Duration::from_secs(5), client.get_info_with_probe(&url, &Default::default(), true))
+            .await
+            .expect("probe should not wait for the HEAD's connection")
+            .unwrap();
+
+        assert!(info.range_probed);
+        assert_eq!(limit.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_info_range_probe() {
+        use wiremock::matchers::header;
+
+        let mock_server = MockServer::start().await;
+        for name in ["/honest.zip", "/liar.zip"] {
+            Mock::given(method("HEAD"))
+                .and(path(name))
+                .respond_with(ResponseTemplate::new(200)
+                    .append_header("Content-Length", "2048")
+                    .append_header("Accept-Ranges", "bytes"))
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/honest.zip"))
+            .and(header("range", "bytes=0-0"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(vec![0u8]))
+            .mount(&mock_server)
+            .await;
+        // Advertises ranges but always sends the whole file
+        Mock::given(method("GET"))
+            .and(path("/liar.zip"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 2048]))
+            .mount(&mock_server)
+            .await;
+
+        let client = HttpClient::new().unwrap();
+        let liar = format!("{}/liar.zip", mock_server.uri());
+
+        // Without probing, Accept-Ranges is trusted
+        let info = client.get_info(&liar, &Default::default()).await.unwrap();
+        assert!(info.resume_supported);
+        assert!(!info.range_probed);
+
+        let info = client.get_info_with_probe(&liar, &Default::default(), true).await.unwrap();
+        assert!(!info.resume_supported);
+        assert!(info.range_probed);
+
+        let honest = format!("{}/honest.zip", mock_server.uri());
+        let info = client.get_info_with_probe(&honest, &Default::default(), true).await.unwrap();
+        assert!(info.resume_supported);
+        assert!(info.range_probed);
+
+        // The config flag turns probing on for plain get_info
+        let mut config = crate::app::config::Config::default().download;
+        config.deep_resume_probe = true;
+        let probing = HttpClient::from_config(&config).unwrap();
+        assert!(!probing.get_info(&liar, &Default::default()).await.unwrap().resume_supported);
+    }
+
     #[tokio::test]
     async fn test_get_info_extracts_etag() {
         let mock_server = MockServer::start().await;
@@ -505,6 +1293,74 @@ mod tests {
         assert_eq!(content, test_data);
     }
 
+    #[tokio::test]
+    async fn test_download_stops_and_flushes_when_requested() {
+        let mock_server = MockServer::start().await;
+
+        let test_data = vec![b'x'; 4096];
+        Mock::given(method("GET"))
+            .and(path("/file.bin"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_data))
+            .mount(&mock_server)
+            .await;
+
+        let client = HttpClient::new().unwrap();
+        let url = format!("{}/file.bin", mock_server.uri());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("partial.bin");
+
+        let stop = CancellationToken::new();
+        stop.cancel();
+        let err = client
+            .download_to_file_until(&url, &file_path, &Default::default(), None, None::<fn(u64, Option<u64>)>, Some(&stop), 1, None)
+            .await
+            .unwrap_err();
+
+        let stopped = err.downcast_ref::<DownloadStopped>().expect("should stop, not fail");
+        assert!(stopped.downloaded > 0);
+        assert_eq!(std::fs::metadata(&file_path).unwrap().len(), stopped.downloaded);
+    }
+
+    #[tokio::test]
+    async fn test_download_stops_within_one_chunk() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/file.bin"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 64 * 1024]))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = crate::app::config::Config::default().download;
+        config.chunk_size_bytes = 1024;
+        let client = HttpClient::from_config(&config).unwrap();
+        // Below the minimum: raised rather than used as-is
+        assert_eq!(client.chunk_size(), MIN_CHUNK_SIZE_BYTES);
+
+        // Above the maximum: lowered, so the write buffer stays bounded
+        config.chunk_size_bytes = usize::MAX;
+        assert_eq!(HttpClient::from_config(&config).unwrap().chunk_size(), MAX_CHUNK_SIZE_BYTES);
+
+        config.chunk_size_bytes = 8192;
+        let client = HttpClient::from_config(&config).unwrap();
+        assert_eq!(client.chunk_size(), 8192);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("partial.bin");
+        let url = format!("{}/file.bin", mock_server.uri());
+        let stop = CancellationToken::new();
+        stop.cancel();
+        let err = client
+            .download_to_file_until(&url, &file_path, &Default::default(), None, None::<fn(u64, Option<u64>)>, Some(&stop), 1, None)
+            .await
+            .unwrap_err();
+
+        // The stop request is honored after the first slice, not the whole read
+        let stopped = err.downcast_ref::<DownloadStopped>().unwrap();
+        assert!(stopped.downloaded > 0 && stopped.downloaded <= 8192);
+        assert_eq!(std::fs::metadata(&file_path).unwrap().len(), stopped.downloaded);
+    }
+
     #[tokio::test]
     async fn test_download_progress_callback() {
         let mock_server = MockServer::start().await;
@@ -578,6 +1434,55 @@ mod tests {
         assert_eq!(content, full_data);
     }
 
+    #[tokio::test]
+    async fn test_resume_overlap_mismatch_restarts_download() {
+        use wiremock::matchers::header;
+
+        let mock_server = MockServer::start().await;
+        let full_data = b"Complete file content";
+        let resume_offset = 12u64;
+
+        // Range request (overlap included): 206 from the start of the overlap
+        Mock::given(method("GET"))
+            .and(path("/file.txt"))
+            .and(header("Range", "bytes=0-"))
+            .respond_with(ResponseTemplate::new(206)
+                .set_body_bytes(full_data.to_vec())
+                .append_header("Accept-Ranges", "bytes"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/file.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(full_data.to_vec()))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = crate::app::config::Config::default().download;
+        config.verify_resume_overlap = true;
+        let client = HttpClient::from_config(&config).unwrap();
+        let url = format!("{}/file.txt", mock_server.uri());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("resume.txt");
+
+        // Matching partial file: resumed in place
+        std::fs::write(&file_path, &full_data[..resume_offset as usize]).unwrap();
+        let info = client
+            .download_to_file(&url, &file_path, &Default::default(), Some(resume_offset), None::<fn(u64, Option<u64>)>)
+            .await
+            .unwrap();
+        assert!(!info.resume_restarted);
+        assert_eq!(std::fs::read(&file_path).unwrap(), full_data);
+
+        // Corrupt partial file: the overlap does not match, so it starts over
+        std::fs::write(&file_path, b"Complete fXXX").unwrap();
+        let info = client
+            .download_to_file(&url, &file_path, &Default::default(), Some(resume_offset), None::<fn(u64, Option<u64>)>)
+            .await
+            .unwrap();
+        assert!(info.resume_restarted);
+        assert_eq!(std::fs::read(&file_path).unwrap(), full_data);
+    }
+
     #[tokio::test]
     async fn test_download_handles_http_error() {
         let mock_server = MockServer::start().await;
@@ -600,6 +1505,227 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_client_reuses_pooled_connection() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::AsyncReadExt;
+
+        // Keep-alive server that counts accepted connections
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = Arc::clone(&connections);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n";
+                        if socket.write_all(response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let client = HttpClient::from_config(&crate::app::config::Config::default().download).unwrap();
+        for name in ["a.bin", "b.bin", "c.bin"] {
+            let info = client
+                .get_info(&format!("http://{}/{}", addr, name), &Default::default())
+                .await
+                .unwrap();
+            assert_eq!(info.size, Some(2));
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_from_config_with_protocol_options() {
+        let base = crate::app::config::Config::default().download;
+        assert!(!HttpClient::from_config(&base).unwrap().accepts_invalid_certs());
+
+        let mut config = base.clone();
+        config.http2_prior_knowledge = true;
+        HttpClient::from_config(&config).unwrap();
+
+        for version in [TlsVersion::Tls12, TlsVersion::Tls13] {
+            let mut config = base.clone();
+            config.min_tls_version = Some(version);
+            HttpClient::from_config(&config).unwrap();
+        }
+
+        let mut config = base;
+        config.danger_accept_invalid_certs = true;
+        assert!(HttpClient::from_config(&config).unwrap().accepts_invalid_certs());
+    }
+
+    /// Serve one response that advertises 100 bytes, sends 10 and closes the connection
+    ///
+    /// wiremock always sends the full body, so this uses a raw listener. A
+    /// non-chunked Transfer-Encoding makes hyper read until close instead of
+    /// failing on the short body itself, so the Content-Length check runs.
+    async fn serve_truncated_body() -> std::net::SocketAddr {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: identity\r\nContent-Length: 100\r\nConnection: close\r\n\r\n0123456789";
+            socket.write_all(response).await.unwrap();
+            socket.shutdown().await.unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_download_fails_when_body_is_truncated() {
+        let addr = serve_truncated_body().await;
+
+        let client = HttpClient::new().unwrap();
+        let url = format!("http://{}/short.bin", addr);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("short.bin");
+
+        let err = client.download_to_file(&url, &file_path, &Default::default(), None, None::<fn(u64, Option<u64>)>)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "incomplete: got 10 of 100 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_download_to_writer_fails_when_body_is_truncated() {
+        let addr = serve_truncated_body().await;
+
+        let mut out = Vec::new();
+        let err = HttpClient::new()
+            .unwrap()
+            .download_to_writer(&format!("http://{}/short.bin", addr), &Default::default(), &mut out)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "incomplete: got 10 of 100 bytes");
+        assert_eq!(out, b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_get_info_stops_after_max_redirects() {
+        let mock_server = MockServer::start().await;
+
+        for i in 0..3 {
+            Mock::given(method("HEAD"))
+                .and(path(format!("/hop{}", i)))
+                .respond_with(ResponseTemplate::new(302)
+                    .append_header("Location", format!("{}/hop{}", mock_server.uri(), i + 1)))
+                .mount(&mock_server)
+                .await;
+        }
+
+        Mock::given(method("HEAD"))
+            .and(path("/hop3"))
+            .respond_with(ResponseTemplate::new(200).append_header("Content-Length", "10"))
+            .mount(&mock_server)
+            .await;
+
+        let url = format!("{}/hop0", mock_server.uri());
+
+        let limited = HttpClient::with_options("test", 2, false).unwrap();
+        assert!(limited.get_info(&url, &Default::default()).await.is_err());
+
+        let relaxed = HttpClient::with_options("test", 3, false).unwrap();
+        let info = relaxed.get_info(&url, &Default::default()).await.unwrap();
+        assert_eq!(info.final_url, Some(format!("{}/hop3", mock_server.uri())));
+    }
+
+    #[test]
+    fn test_check_redirect_blocks_https_to_http_downgrade() {
+        let chain = vec![
+            url::Url::parse("http://example.com/start").unwrap(),
+            url::Url::parse("https://cdn.example.com/step").unwrap(),
+        ];
+        let downgrade = url::Url::parse("http://mirror.example.com/file.zip").unwrap();
+        let upgrade = url::Url::parse("https://mirror.example.com/file.zip").unwrap();
+
+        assert!(check_redirect(&chain, &downgrade, 5, false).is_err());
+        assert!(check_redirect(&chain, &downgrade, 5, true).is_ok());
+        assert!(check_redirect(&chain[..1], &upgrade, 5, false).is_ok());
+        assert!(check_redirect(&chain, &upgrade, 1, false).is_err());
+    }
+
+    /// Serve a redirect to `location` over HTTPS (self-signed) for every request
+    async fn https_redirect_server(location: String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::rustls;
+
+        let cert = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string()]).unwrap();
+        let key = rustls::pki_types::PrivateKeyDer::Pkcs8(cert.key_pair.serialize_der().into());
+        let tls = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.cert.der().clone()], key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                let location = location.clone();
+                tokio::spawn(async move {
+                    let Ok(mut stream) = acceptor.accept(tcp).await else {
+                        return;
+                    };
+                    let mut request = [0u8; 4096];
+                    let _ = stream.read(&mut request).await;
+                    let response = format!(
+                        "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        location
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        format!("https://127.0.0.1:{}/file.bin", port)
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy_refuses_https_to_http_unless_allowed() {
+        let mock_server = MockServer::start().await;
+        Mock::given(path("/file.bin"))
+            .respond_with(ResponseTemplate::new(200).insert_header("content-length", "5"))
+            .mount(&mock_server)
+            .await;
+        let target = format!("{}/file.bin", mock_server.uri());
+        let url = https_redirect_server(target.clone()).await;
+
+        // The same builder (and so the same redirect policy) as `with_options`,
+        // accepting the test server's self-signed certificate
+        let client = |allow_insecure_redirect| {
+            let builder = HttpClient::builder("test", DEFAULT_MAX_REDIRECTS, allow_insecure_redirect)
+                .tls_danger_accept_invalid_certs(true);
+            HttpClient::from_reqwest(builder.build().unwrap())
+        };
+
+        let refused = client(false).get_info(&url, &Default::default()).await;
+        assert!(refused.is_err());
+        assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+        let info = client(true).get_info(&url, &Default::default()).await.unwrap();
+        assert_eq!(info.final_url, Some(target));
+    }
+
     #[test]
     fn test_parse_response_headers_all_fields() {
         let mut headers = HeaderMap::new();
@@ -0,0 +1,112 @@
+//! Lifetime download totals
+//!
+//! A small odometer in `{config_dir}/lifetime_stats.json`: files downloaded,
+//! bytes transferred and time spent downloading, added to on every
+//! completion. Unlike the completion log it is never pruned by the `[history]`
+//! retention settings. Updates rewrite the file through a temp file and
+//! a rename, so a crash leaves either the old or the new totals.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::task::DownloadTask;
+
+/// Serializes read-modify-write cycles within this process
+static UPDATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Totals across every completed download
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    pub files: u64,
+    pub bytes: u64,
+    /// Seconds spent transferring (latest start to completion of each download)
+    pub seconds: f64,
+    /// When the first completion was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl LifetimeStats {
+    /// Loads the totals from a JSON file (zero if the file does not exist)
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Saves the totals to a JSON file (atomic write)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Loads the totals from `{config_dir}/lifetime_stats.json`
+    pub fn load_default() -> Result<Self> {
+        Self::load(&crate::util::paths::get_lifetime_stats_path()?)
+    }
+
+    /// Adds a completed download to the totals
+    pub fn record(&mut self, task: &DownloadTask) {
+        let now = Utc::now();
+        self.files += 1;
+        self.bytes += task.size.unwrap_or(task.downloaded);
+        self.seconds += task.elapsed_secs(now).unwrap_or(0.0);
+        self.since.get_or_insert(task.completed_at.unwrap_or(now));
+    }
+}
+
+/// Adds a completed download to the totals in `path`
+pub fn record_completion_at(path: &Path, task: &DownloadTask) -> Result<()> {
+    let _guard = UPDATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut stats = LifetimeStats::load(path)?;
+    stats.record(task);
+    stats.save(path)
+}
+
+/// Adds a completed download to `{config_dir}/lifetime_stats.json`
+pub fn record_completion(task: &DownloadTask) -> Result<()> {
+    record_completion_at(&crate::util::paths::get_lifetime_stats_path()?, task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_record_accumulates_and_persists() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("lifetime_stats.json");
+        assert_eq!(LifetimeStats::load(&path).unwrap(), LifetimeStats::default());
+
+        let mut task = DownloadTask::new("https://example.com/a.bin".to_string(), PathBuf::from("/tmp"));
+        let completed_at = Utc::now();
+        task.started_at = Some(completed_at - Duration::seconds(4));
+        task.completed_at = Some(completed_at);
+        task.size = Some(1000);
+
+        record_completion_at(&path, &task).unwrap();
+        task.size = Some(500);
+        record_completion_at(&path, &task).unwrap();
+
+        let stats = LifetimeStats::load(&path).unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.bytes, 1500);
+        assert!((stats.seconds - 8.0).abs() < 0.01, "{}", stats.seconds);
+        assert_eq!(stats.since, Some(completed_at));
+        // No temp file is left behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,6 +1,6 @@
 use super::folder_queue::FolderQueue;
 use super::history::DownloadHistory;
-use super::http_client::HttpClient;
+use super::http_client::{DownloadStopped, HttpClient, HttpStatusError, SizeOverLimit};
 use super::queue::DownloadQueue;
 use super::task::{DownloadStatus, DownloadTask};
 use crate::file::metadata::apply_last_modified;
@@ -9,13 +9,24 @@ use crate::script::events::BeforeRequestContext;
 use crate::script::message::ScriptRequest;
 use crate::script::sender;
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{mpsc, Arc};
-use tokio::sync::{RwLock, Semaphore};
+use super::concurrency::ConcurrencyLimit;
+use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// How long `shutdown` waits for active downloads to stop cleanly
+pub const DEFAULT_SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How often `spawn_queue_saver` writes modified folder queues
+pub const QUEUE_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Queue saver passes between writes that persist download progress alone
+const PROGRESS_SAVE_EVERY: u32 = 5;
+
 /// Progress update sent to UI
 #[derive(Debug, Clone)]
 pub struct ProgressUpdate {
@@ -25,26 +36,99 @@ pub struct ProgressUpdate {
     pub speed: f64, // bytes per second
 }
 
+/// Picks an index below the given length from `download.user_agent_rotate`
+pub type UserAgentChooser = Arc<dyn Fn(usize) -> usize + Send + Sync>;
+
+/// What starting a download would do (see `DownloadManager::dry_run`)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DryRunReport {
+    /// URL after beforeRequest scripts
+    pub url: String,
+    /// User-Agent the request would carry
+    pub user_agent: String,
+    /// Request headers (name, value), sorted by name
+    pub headers: Vec<(String, String)>,
+    /// Folder after routing rules
+    pub folder_id: String,
+    /// Routing rule that picked the folder (1-based), if one matched
+    pub routing_rule: Option<usize>,
+    /// File name after conflict resolution
+    pub filename: String,
+    /// File the download would be written to
+    pub target_path: std::path::PathBuf,
+    /// Reason given by a beforeRequest script that cancelled the download
+    pub cancelled: Option<String>,
+    /// beforeRequest handlers that ran
+    pub script_runs: Vec<crate::script::events::ScriptRun>,
+}
+
+/// How long pause and remove wait for a download to flush and exit before aborting it
+const STOP_GRACE: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// A running download and the token that stops it
+struct ActiveDownload {
+    handle: JoinHandle<()>,
+    /// Cancelled on pause or remove; a child of the manager's shutdown token
+    cancel: CancellationToken,
+}
+
+impl ActiveDownload {
+    /// False once the spawned task has exited (completed, failed, held or cancelled)
+    fn is_running(&self) -> bool {
+        !self.handle.is_finished()
+    }
+
+    /// Cancel the download and wait up to `grace` for it to exit, then abort it
+    async fn stop(mut self, grace: std::time::Duration) {
+        self.cancel.cancel();
+        if tokio::time::timeout(grace, &mut self.handle).await.is_err() {
+            tracing::warn!("Download did not stop within {:?}; aborting", grace);
+            self.handle.abort();
+        }
+    }
+}
+
 /// Per-folder task counts for O(1) folder status checks
 /// Re-exported from folder_queue for backward compatibility
 pub use super::folder_queue::FolderTaskCounts;
 
+/// Downloads refused because every folder slot was taken
+///
+/// Kept per folder, in the order the folders were refused; when a slot frees
+/// the folder at the front goes next, so folders take turns instead of the
+/// same busy folder winning every free slot. Downloads held by a folder quota
+/// wait the same way, per folder, until a download in the folder exits.
+struct WaitingFolder {
+    folder_id: String,
+    tasks: Vec<Uuid>,
+    script_sender: Option<mpsc::Sender<ScriptRequest>>,
+    config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+}
+
 #[derive(Clone)]
 pub struct DownloadManager {
     /// Per-folder download queues
     folder_queues: Arc<RwLock<HashMap<String, FolderQueue>>>,
 
     http_client: Arc<HttpClient>,
-    active_downloads: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    active_downloads: Arc<RwLock<HashMap<Uuid, ActiveDownload>>>,
 
     // Application-wide concurrent download limit
     max_concurrent: Arc<RwLock<usize>>,
-    global_semaphore: Arc<Semaphore>,
+    global_limit: ConcurrencyLimit,
+
+    // Simultaneous HTTP connections across all downloads
+    connection_limit: ConcurrencyLimit,
 
     // Per-folder concurrent download limits
-    max_concurrent_per_folder: usize, // Maximum downloads per folder
+    max_concurrent_per_folder: Arc<AtomicUsize>, // Maximum downloads per folder
     parallel_folder_count: usize,     // Maximum folders active simultaneously
     active_folders: Arc<RwLock<HashSet<String>>>,
+    waiting_folders: Arc<std::sync::Mutex<VecDeque<WaitingFolder>>>,
+    // Downloads held by a folder quota, by folder
+    quota_waiting: Arc<std::sync::Mutex<HashMap<String, WaitingFolder>>>,
+    // Each folder's `max_queued`, from the config last seen
+    max_queued: Arc<std::sync::RwLock<HashMap<String, usize>>>,
 
     // Retry settings
     max_retries: u32,
@@ -56,6 +140,15 @@ pub struct DownloadManager {
     // Circuit breaker for failing domains
     circuit_breaker: Arc<super::circuit_breaker::CircuitBreaker>,
 
+    // Cancelled by `shutdown` to stop active downloads at the next chunk boundary
+    shutdown: CancellationToken,
+
+    // Picks from `download.user_agent_rotate` (random unless replaced for tests)
+    user_agent_chooser: UserAgentChooser,
+
+    // Why new downloads are held back (power monitor, quiet-window schedule)
+    hold_reasons: Arc<std::sync::Mutex<Vec<String>>>,
+
 }
 
 impl DownloadManager {
@@ -108,22 +201,56 @@ impl DownloadManager {
                 (max_concurrent_per_folder, parallel_folder_count)
             };
 
+        let connection_limit = ConcurrencyLimit::new(max_concurrent);
         Self {
             folder_queues: Arc::new(RwLock::new(HashMap::new())),
-            http_client: Arc::new(HttpClient::new().unwrap()),
+            http_client: Arc::new(HttpClient::new().unwrap().with_connection_limit(connection_limit.clone())),
             active_downloads: Arc::new(RwLock::new(HashMap::new())),
             max_concurrent: Arc::new(RwLock::new(max_concurrent)),
-            global_semaphore: Arc::new(Semaphore::new(max_concurrent)),
-            max_concurrent_per_folder: adjusted_folder_limit,
+            global_limit: ConcurrencyLimit::new(max_concurrent),
+            connection_limit,
+            max_concurrent_per_folder: Arc::new(AtomicUsize::new(adjusted_folder_limit)),
             parallel_folder_count: adjusted_active_limit,
             active_folders: Arc::new(RwLock::new(HashSet::new())),
+            waiting_folders: Arc::new(std::sync::Mutex::new(VecDeque::new())),
+            quota_waiting: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            max_queued: Arc::new(std::sync::RwLock::new(HashMap::new())),
             max_retries,
             retry_delay_secs,
             history: Arc::new(RwLock::new(DownloadHistory::new())),
             circuit_breaker: Arc::new(super::circuit_breaker::CircuitBreaker::new()),
+            shutdown: CancellationToken::new(),
+            user_agent_chooser: Arc::new(|len| (Uuid::new_v4().as_u128() % len as u128) as usize),
+            hold_reasons: Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
 
+    /// Replace the HTTP client (e.g. one built from the download configuration)
+    pub fn with_http_client(mut self, http_client: HttpClient) -> Self {
+        // Every request of the new client counts against max_connections
+        self.http_client = Arc::new(http_client.with_connection_limit(self.connection_limit.clone()));
+        self
+    }
+
+    /// Keep circuit breaker state in `path` (shared with other ggg processes)
+    pub fn with_circuit_store(mut self, path: std::path::PathBuf) -> Self {
+        self.circuit_breaker = Arc::new(super::circuit_breaker::CircuitBreaker::new().with_store(path));
+        self
+    }
+
+    /// Replace how entries of `download.user_agent_rotate` are picked
+    pub fn with_user_agent_chooser(mut self, chooser: UserAgentChooser) -> Self {
+        self.user_agent_chooser = chooser;
+        self
+    }
+
+    /// The HTTP client shared by every download (and clone of this manager)
+    ///
+    /// Reuse it for one-off requests so they share pooled connections.
+    pub fn http_client(&self) -> Arc<HttpClient> {
+        Arc::clone(&self.http_client)
+    }
+
     pub fn with_max_concurrent(max_concurrent: usize) -> Self {
         Self::with_config(max_concurrent, max_concurrent, 1, 3, 5)
     }
@@ -139,7 +266,7 @@ impl DownloadManager {
         let mut queues = self.folder_queues.write().await;
         queues
             .entry(folder_id.to_string())
-            .or_insert_with(|| FolderQueue::new(folder_id, self.max_concurrent_per_folder))
+            .or_insert_with(|| FolderQueue::new(folder_id, self.max_concurrent_per_folder.load(Ordering::SeqCst)))
             .clone()
     }
 
@@ -149,6 +276,20 @@ impl DownloadManager {
         queues.get(folder_id).cloned()
     }
 
+    /// Folder queue that currently holds a task
+    ///
+    /// This is the queue for `task.folder_id`, except for a routed task whose
+    /// transfer was interrupted before it could be moved (see `download::routing`).
+    async fn queue_containing(&self, id: Uuid) -> Option<FolderQueue> {
+        let queues = self.folder_queues.read().await;
+        for queue in queues.values() {
+            if queue.get_by_id(id).await.is_some() {
+                return Some(queue.clone());
+            }
+        }
+        None
+    }
+
     /// Check if a folder has active tasks (O(1) operation)
     async fn folder_has_active_tasks(&self, folder_id: &str) -> bool {
         if let Some(queue) = self.get_folder_queue(folder_id).await {
@@ -158,13 +299,6 @@ impl DownloadManager {
         }
     }
 
-    /// Decrement downloading count for a folder (for cleanup after download completes)
-    async fn decrement_downloading(&self, folder_id: &str) {
-        if let Some(queue) = self.get_folder_queue(folder_id).await {
-            queue.decrement_downloading().await;
-        }
-    }
-
     // ========== Download Operations ==========
 
     pub async fn add_download(&self, mut task: DownloadTask) {
@@ -172,9 +306,29 @@ impl DownloadManager {
         task.filename = sanitize_filename(&task.filename);
         let folder_id = task.folder_id.clone();
         let queue = self.get_or_create_folder_queue(&folder_id).await;
+
+        // A full folder still takes the task, held until it has room
+        let max_queued = self.max_queued.read().unwrap().get(&folder_id).copied();
+        if task.status == DownloadStatus::Pending
+            && task.held_reason.is_none()
+            && let Some(reason) = queue.max_queued_reason(&task, max_queued).await
+        {
+            tracing::info!("Holding download {}: {}", task.filename, reason);
+            task.log_warn(format!("Not started: {}", reason));
+            task.held_reason = Some(reason);
+        }
         queue.add(task).await;
     }
 
+    /// Remember each folder's `max_queued` for `add_download`
+    pub fn set_folder_quotas(&self, config: &crate::app::config::Config) {
+        *self.max_queued.write().unwrap() = config
+            .folders
+            .iter()
+            .filter_map(|(id, folder)| Some((id.clone(), folder.max_queued?)))
+            .collect();
+    }
+
     /// Get all downloads from all folder queues
     pub async fn get_all_downloads(&self) -> Vec<DownloadTask> {
         let queues = self.folder_queues.read().await;
@@ -196,14 +350,18 @@ impl DownloadManager {
 
     pub async fn remove_download(&self, id: Uuid) -> Option<DownloadTask> {
         // Cancel active download if running
-        if let Some(handle) = self.active_downloads.write().await.remove(&id) {
-            handle.abort();
+        let active = self.active_downloads.write().await.remove(&id);
+        if let Some(active) = active {
+            active.stop(STOP_GRACE).await;
         }
         
         // Find and remove from the appropriate folder queue
-        let queues = self.folder_queues.read().await;
-        for queue in queues.values() {
+        let queues: Vec<FolderQueue> = self.folder_queues.read().await.values().cloned().collect();
+        for queue in queues {
             if let Some(task) = queue.remove(id).await {
+                self.start_quota_waiting(&task.folder_id);
+                // Hand the folder slot on if nothing else in the folder is left to run
+                self.deactivate_folder_if_empty(queue.folder_id()).await;
                 return Some(task);
             }
         }
@@ -223,80 +381,145 @@ impl DownloadManager {
             return Ok(()); // Already downloading
         }
 
-        // Check circuit breaker for the domain
-        if let Some(domain) = super::circuit_breaker::extract_domain(&task.url) {
+        // FTP URLs queued before they were refused at add time fail here, not in reqwest
+        if let Err(e) = HttpClient::check_supported_scheme(&task.url) {
+            task.status = DownloadStatus::Error;
+            task.error_message = Some(e.to_string());
+            task.log_error(e.to_string());
+            if let Some(queue) = self.get_folder_queue(&task.folder_id).await {
+                queue.update(task).await;
+            }
+            return Err(e);
+        }
+
+        // Finish moving a routed task whose previous transfer was interrupted
+        if self.queue_containing(id).await.is_some_and(|q| q.folder_id() != task.folder_id) {
+            self.change_folder(id, task.folder_id.clone()).await?;
+        }
+
+        // Held back (e.g. on battery): leave the task queued, with the reason
+        let hold_reason = self.hold_reasons.lock().unwrap().first().cloned();
+        if let Some(reason) = hold_reason {
+            if task.held_reason.as_deref() != Some(reason.as_str()) {
+                task.log_warn(format!("Not started: {}", reason));
+            }
+            task.held_reason = Some(reason);
+            if let Some(queue) = self.get_folder_queue(&task.folder_id).await {
+                queue.update(task).await;
+            }
+            return Ok(());
+        }
+
+        // Check circuit breaker for the domain (or host and directory)
+        let circuit_scope = config.read().await.download.circuit_breaker.scope;
+        let circuit_key = super::circuit_breaker::circuit_key(&task.url, circuit_scope);
+        if let Some(ref key) = circuit_key {
             use super::circuit_breaker::CircuitState;
-            match self.circuit_breaker.can_request(&domain) {
+            match self.circuit_breaker.can_request(key) {
                 CircuitState::Open => {
-                    return Err(anyhow::anyhow!(
-                        "Circuit breaker open for domain '{}'. Too many consecutive failures.",
-                        domain
-                    ));
+                    return Err(super::circuit_breaker::CircuitOpenError { key: key.clone() }.into());
                 }
                 CircuitState::HalfOpen => {
-                    tracing::info!("Testing recovery for domain '{}'", domain);
+                    tracing::info!("Testing recovery for '{}'", key);
                 }
                 CircuitState::Closed => {}
             }
         }
 
-        // Try to activate folder (check active folder limit)
+        // Folder quotas: leave the task queued, with the reason, instead of
+        // starting it; it is tried again when a download in the folder exits
+        let (max_queued, max_inflight_bytes) = {
+            let config = config.read().await;
+            self.set_folder_quotas(&config);
+            config
+                .folders
+                .get(&task.folder_id)
+                .map(|f| (f.max_queued, f.max_inflight_bytes))
+                .unwrap_or_default()
+        };
+        if max_queued.is_some() || max_inflight_bytes.is_some() {
+            let queue = self.get_or_create_folder_queue(&task.folder_id).await;
+            if let Some(reason) = queue.quota_hold_reason(&task, max_queued, max_inflight_bytes).await {
+                tracing::info!("Holding download {}: {}", task.filename, reason);
+                if task.held_reason.as_deref() != Some(reason.as_str()) {
+                    task.log_warn(format!("Not started: {}", reason));
+                }
+                task.held_reason = Some(reason);
+                let folder_id = task.folder_id.clone();
+                queue.update(task).await;
+                self.wait_for_quota(&folder_id, id, script_sender, config);
+                return Ok(());
+            }
+        }
+
+        // Try to activate folder (check active folder limit); a refused
+        // download waits for its folder's turn at the next free slot
         let folder_id = task.folder_id.clone();
         if !self.try_activate_folder(&folder_id).await {
+            let active = self.active_folders.read().await.len();
+            if let Some(queue) = self.get_folder_queue(&folder_id).await {
+                task.held_reason = Some(format!(
+                    "waiting for a folder slot ({} of {} folders active)",
+                    active, self.parallel_folder_count
+                ));
+                queue.update(task).await;
+            }
+            self.wait_for_folder_slot(&folder_id, id, script_sender, config);
+            // A slot freed while this download was being queued would otherwise go unused
+            if self.active_folders.read().await.len() < self.parallel_folder_count {
+                self.start_next_waiting_folder();
+            }
             return Err(anyhow::anyhow!(
                 "Cannot start download: folder '{}' cannot be activated ({} folders already active, max active folders: {})",
                 folder_id,
-                self.active_folders.read().await.len(),
+                active,
                 self.parallel_folder_count
             ));
         }
 
-        // Get folder queue and its semaphore
+        // Get folder queue and its concurrency limit
         let folder_queue = self.get_or_create_folder_queue(&folder_id).await;
-        let folder_semaphore = folder_queue.semaphore();
+        let folder_limit = folder_queue.concurrency_limit();
 
         // Hook Point 1: beforeRequest - Modify URL, headers, user-agent before HTTP request
         // Execute via message passing BEFORE spawning download task
-        if let Some(ref sender) = script_sender {
-            // Compute effective script_files (Application + Folder override)
-            let effective_script_files = Self::compute_effective_script_files(&config, &task.folder_id).await;
-
-            let ctx = BeforeRequestContext {
-                url: task.url.clone(),
-                headers: task.headers.clone(),
-                user_agent: task.user_agent.clone(),
-                download_id: Some(task.id.to_string()),
-            };
-
-            // Send request and await response
-            match sender::send_script_request_with_context(sender, move |response_tx| {
-                ScriptRequest::BeforeRequest {
-                    ctx,
-                    effective_script_files,
-                    response: response_tx,
-                }
-            }).await {
-                Ok((modified_ctx, Ok(()))) => {
-                    // Apply modifications from script
-                    task.url = modified_ctx.url;
-                    task.headers = modified_ctx.headers;
-                    task.user_agent = modified_ctx.user_agent;
-                    task.log_info("beforeRequest hook executed".to_string());
-                }
-                Ok((_, Err(e))) => {
-                    tracing::error!("beforeRequest hook error: {}", e);
-                }
-                Err(e) => {
-                    tracing::error!("beforeRequest error: {}", e);
-                }
+        if let Some(ref sender) = script_sender
+            && let Some(modified_ctx) = Self::run_before_request(&mut task, sender, &config).await
+        {
+            // A script cancelled the download: park it as Paused, not Error
+            if modified_ctx.cancel {
+                let reason = modified_ctx
+                    .cancel_reason
+                    .unwrap_or_else(|| "no reason given".to_string());
+                tracing::info!("Download {} cancelled by beforeRequest script: {}", task.id, reason);
+                task.status = DownloadStatus::Paused;
+                task.log_info(format!("Cancelled by script: {}", reason));
+                folder_queue.update(task).await;
+                self.deactivate_folder_if_empty(&folder_id).await;
+                return Ok(());
             }
+
+            // Apply modifications from script
+            task.url = modified_ctx.url;
+            task.headers = modified_ctx.headers;
+            task.user_agent = modified_ctx.user_agent;
+            task.log_info("beforeRequest hook executed".to_string());
         }
 
         // Update folder task counts based on previous status
         let previous_status = task.status;
+
+        // An explicit start of a failed task gets the full retry budget again.
+        // Automatic retries run inside the spawned loop and never come through here.
+        if previous_status == DownloadStatus::Error {
+            task.retry_count = 0;
+        }
+
         task.status = DownloadStatus::Downloading;
         task.started_at = Some(chrono::Utc::now());
+        task.speed_stats.begin_run(chrono::Utc::now(), task.downloaded);
         task.error_message = None; // Clear any previous error
+        task.held_reason = None;
         task.log_info(format!("Starting download: {}", task.url));
         folder_queue.update(task.clone()).await;
 
@@ -315,18 +538,33 @@ impl DownloadManager {
         // Clone folder queue for the spawned task
         let queue = folder_queue.clone();
         let http_client = self.http_client.clone();
-        let global_semaphore = self.global_semaphore.clone();
+        let global_limit = self.global_limit.clone();
         let script_sender_for_error = script_sender.clone();
         let max_retries = self.max_retries;
         let retry_delay_secs = self.retry_delay_secs;
         let manager_for_cleanup = self.clone();
         let circuit_breaker = self.circuit_breaker.clone();
-        let task_url = task.url.clone();
+        let user_agent_chooser = self.user_agent_chooser.clone();
+        let shutdown = self.shutdown.clone();
+        let cancel = self.shutdown.child_token();
+        let stop = cancel.clone();
+
+        // Take a place in the slot queues now, so downloads get their slots
+        // in the order they were started (the schedule policy's order), not
+        // in whatever order the spawned tasks happen to be polled. The
+        // semaphores are fair, and a waiter keeps its place once polled.
+        let mut permits = Box::pin(async move { (global_limit.acquire().await, folder_limit.acquire().await) });
+        let ready = futures_util::poll!(&mut permits);
 
         let handle = tokio::spawn(async move {
-            // Acquire both global and folder semaphore permits
-            let _global_permit = global_semaphore.acquire().await.unwrap();
-            let _folder_permit = folder_semaphore.acquire().await.unwrap();
+            // Acquire both global and folder slots (unless paused while waiting)
+            let (_global_permit, _folder_permit) = match ready {
+                std::task::Poll::Ready(permits) => permits,
+                std::task::Poll::Pending => tokio::select! {
+                    permits = permits => permits,
+                    _ = stop.cancelled() => return,
+                },
+            };
 
             tracing::debug!(
                 "Acquired slots for '{}' (folder: {})",
@@ -339,24 +577,85 @@ impl DownloadManager {
             // Retry loop
             loop {
                 // Clone Arc-wrapped types (cheap) and task for retry attempt
-                match Self::download_task(current_task.clone(), http_client.clone(), queue.clone(), script_sender.clone(), config.clone(), is_resuming).await {
+                // Retries continue from whatever the failed attempt left on disk
+                let resume = is_resuming || current_task.retry_count > 0;
+                // Every attempt picks its own rotated user agent
+                let rotated_user_agent = Self::rotated_user_agent(&current_task, &config, &user_agent_chooser).await;
+                let result = Self::download_task(current_task.clone(), http_client.clone(), queue.clone(), script_sender.clone(), config.clone(), rotated_user_agent, resume, stop.clone()).await;
+                match result {
                     Ok(_) => {
                         // Download succeeded - record success for circuit breaker
-                        if let Some(domain) = super::circuit_breaker::extract_domain(&task_url) {
-                            circuit_breaker.record_success(&domain);
+                        if let Some(ref key) = circuit_key {
+                            circuit_breaker.record_success(key);
                         }
                         break;
                     }
                     Err(e) => {
+                        // Pick up the filename the attempt actually wrote to so a retry
+                        // resumes the partial file instead of starting a new one
+                        if let Some(latest) = queue.get_by_id(current_task.id).await {
+                            current_task.filename = latest.filename;
+                            current_task.folder_id = latest.folder_id;
+                            current_task.save_path = latest.save_path;
+                            current_task.routed_from = latest.routed_from;
+                            current_task.resume_supported = latest.resume_supported;
+                            current_task.resume_probed = latest.resume_probed;
+                            current_task.downloaded = latest.downloaded;
+                            current_task.size = latest.size;
+                            current_task.speed_stats = latest.speed_stats;
+                            current_task.script_runs = latest.script_runs;
+                        }
+
+                        // Shutting down: keep the partial file and resume next time
+                        if shutdown.is_cancelled() {
+                            if let Some(stopped) = e.downcast_ref::<DownloadStopped>() {
+                                current_task.downloaded = stopped.downloaded;
+                            }
+                            current_task.status = DownloadStatus::Paused;
+                            current_task.log_info(format!("Stopped for shutdown at {} bytes", current_task.downloaded));
+                            queue.update(current_task.clone()).await;
+                            break;
+                        }
+
+                        // Paused or removed: the caller updates the task
+                        if stop.is_cancelled() {
+                            return;
+                        }
+
                         tracing::error!("Download failed for {}: {}", current_task.filename, e);
                         current_task.error_message = Some(e.to_string());
-                        current_task.retry_count += 1;
-                        current_task.log_error(format!("Download failed (attempt {}): {}", current_task.retry_count, e));
+
+                        // Client errors such as 404 fail at once unless listed in retry_on
+                        let (retry_on, max_retry_after_secs) = {
+                            let cfg = config.read().await;
+                            (cfg.download.retry_on.clone(), cfg.download.max_retry_after_secs)
+                        };
+                        let status_error = e.downcast_ref::<HttpStatusError>();
+                        let retryable = status_error.is_none_or(|status| status.is_retryable(&retry_on));
+                        if retryable {
+                            current_task.retry_count += 1;
+                            current_task.log_error(format!("Download failed (attempt {}): {}", current_task.retry_count, e));
+                        } else {
+                            current_task.log_error(format!("Download failed: {} (not retried)", e));
+                        }
 
                         // Check if we should retry
-                        if current_task.retry_count < max_retries {
-                            // Calculate exponential backoff delay: base_delay * 2^(retry_count - 1)
-                            let backoff_delay = retry_delay_secs * 2_u64.pow(current_task.retry_count.saturating_sub(1));
+                        if retryable && current_task.retry_count < max_retries {
+                            // Calculate exponential backoff delay: base_delay * 2^(retry_count - 1),
+                            // unless a 429/503 said how long to wait
+                            let mut backoff_delay = retry_delay_secs * 2_u64.pow(current_task.retry_count.saturating_sub(1));
+                            if let Some(retry_after) = status_error.and_then(|status| status.retry_after) {
+                                let requested = retry_after.as_secs();
+                                backoff_delay = requested.min(max_retry_after_secs);
+                                if backoff_delay < requested {
+                                    current_task.log_info(format!(
+                                        "Server asked to wait {} seconds (Retry-After), capped at {}",
+                                        requested, backoff_delay
+                                    ));
+                                } else {
+                                    current_task.log_info(format!("Server asked to wait {} seconds (Retry-After)", requested));
+                                }
+                            }
                             tracing::info!(
                                 "Retrying download {} in {} seconds (attempt {}/{})",
                                 current_task.filename,
@@ -369,21 +668,26 @@ impl DownloadManager {
                             queue.update(current_task.clone()).await;
 
                             // Wait before retry with exponential backoff
-                            tokio::time::sleep(tokio::time::Duration::from_secs(backoff_delay)).await;
+                            tokio::select! {
+                                _ = tokio::time::sleep(tokio::time::Duration::from_secs(backoff_delay)) => {}
+                                _ = stop.cancelled() => return,
+                            }
 
                             // Prepare for retry
                             current_task.status = DownloadStatus::Downloading;
                             current_task.error_message = None;
                             queue.update(current_task.clone()).await;
                         } else {
-                            // Max retries exceeded, mark as error
+                            // Max retries exceeded (or not retryable), mark as error
                             current_task.status = DownloadStatus::Error;
-                            current_task.log_error(format!("Max retries ({}) exceeded", max_retries));
+                            if retryable {
+                                current_task.log_error(format!("Max retries ({}) exceeded", max_retries));
+                            }
                             queue.update(current_task.clone()).await;
 
                             // Record failure for circuit breaker
-                            if let Some(domain) = super::circuit_breaker::extract_domain(&task_url) {
-                                circuit_breaker.record_failure(&domain);
+                            if let Some(ref key) = circuit_key {
+                                circuit_breaker.record_failure(key);
                             }
 
                             // Hook Point 4: error - Error handling (fire-and-forget)
@@ -416,12 +720,25 @@ impl DownloadManager {
                 }
             }
 
-            // Cleanup: Decrement downloading count and deactivate folder if empty
-            manager_for_cleanup.decrement_downloading(&folder_id).await;
+            // Cleanup: let quota-held downloads have the room and deactivate
+            // folder if empty (counts follow the status updates above)
+            manager_for_cleanup.start_quota_waiting(&folder_id);
             manager_for_cleanup.deactivate_folder_if_empty(&folder_id).await;
+
+            // A routing rule picked another folder: move the task there now that
+            // its slots in the original folder are released
+            if let Some(latest) = queue.get_by_id(id).await
+                && latest.folder_id != folder_id
+                && let Err(e) = manager_for_cleanup.change_folder(id, latest.folder_id).await
+            {
+                tracing::warn!("Failed to move routed download {}: {}", id, e);
+            }
         });
 
-        self.active_downloads.write().await.insert(id, handle);
+        // Entries are not removed when their task exits on its own; drop those here
+        let mut active_downloads = self.active_downloads.write().await;
+        active_downloads.retain(|_, active| active.is_running());
+        active_downloads.insert(id, ActiveDownload { handle, cancel });
 
         Ok(())
     }
@@ -433,6 +750,161 @@ impl DownloadManager {
         format!("Basic {}", STANDARD.encode(credentials.as_bytes()))
     }
 
+    /// Run the beforeRequest hook for `task`, recording the handlers that ran on it
+    ///
+    /// Returns the context as modified by scripts; hook errors are logged and
+    /// give `None`, so the download goes ahead unchanged.
+    async fn run_before_request(
+        task: &mut DownloadTask,
+        sender: &mpsc::Sender<ScriptRequest>,
+        config: &tokio::sync::RwLock<crate::app::config::Config>,
+    ) -> Option<BeforeRequestContext> {
+        // Compute effective script_files (Application + Folder override)
+        let effective_script_files = Self::compute_effective_script_files(config, &task.folder_id).await;
+
+        let ctx = BeforeRequestContext {
+            url: task.url.clone(),
+            headers: task.headers.clone(),
+            user_agent: task.user_agent.clone(),
+            download_id: Some(task.id.to_string()),
+            cancel: false,
+            cancel_reason: None,
+        };
+
+        // Send request and await response
+        match sender::send_script_request_with_context(sender, move |response_tx| {
+            ScriptRequest::BeforeRequest {
+                ctx,
+                effective_script_files,
+                response: response_tx,
+            }
+        }).await {
+            Ok((modified_ctx, Ok(()), runs)) => {
+                task.record_script_runs(runs);
+                Some(modified_ctx)
+            }
+            Ok((_, Err(e), runs)) => {
+                task.record_script_runs(runs);
+                tracing::error!("beforeRequest hook error: {}", e);
+                None
+            }
+            Err(e) => {
+                tracing::error!("beforeRequest error: {}", e);
+                None
+            }
+        }
+    }
+
+    /// User agent from `download.user_agent_rotate` for one request
+    ///
+    /// `None` when the task, its folder or a beforeRequest script already set
+    /// one (explicit settings win), or when the list is empty.
+    async fn rotated_user_agent(
+        task: &DownloadTask,
+        config: &tokio::sync::RwLock<crate::app::config::Config>,
+        chooser: &UserAgentChooser,
+    ) -> Option<String> {
+        if task.user_agent.is_some() {
+            return None;
+        }
+        let cfg = config.read().await;
+        if cfg.folders.get(&task.folder_id).is_some_and(|f| f.user_agent.is_some()) {
+            return None;
+        }
+        let agents = &cfg.download.user_agent_rotate;
+        if agents.is_empty() {
+            return None;
+        }
+        Some(agents[chooser(agents.len()) % agents.len()].clone())
+    }
+
+    /// User agent sent for `task`: its own, else the rotated one, else the
+    /// folder's, else the app-wide one
+    fn resolved_user_agent(
+        task: &DownloadTask,
+        rotated_user_agent: Option<String>,
+        cfg: &crate::app::config::Config,
+    ) -> String {
+        task.user_agent
+            .clone()
+            .or(rotated_user_agent)
+            .or_else(|| cfg.folders.get(&task.folder_id).and_then(|f| f.user_agent.clone()))
+            .unwrap_or_else(|| cfg.download.user_agent.clone())
+    }
+
+    /// Work out what starting `task` would do, without sending any request
+    ///
+    /// Runs the beforeRequest hook, the referrer policy and the routing rules,
+    /// then reports the request and the file it would create. Routing only
+    /// sees the URL and file name here, since Content-Type needs a response.
+    /// Nothing is queued, written or downloaded.
+    pub async fn dry_run(
+        &self,
+        mut task: DownloadTask,
+        script_sender: Option<mpsc::Sender<ScriptRequest>>,
+        config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+    ) -> Result<DryRunReport> {
+        let mut cancelled = None;
+        if let Some(ref sender) = script_sender
+            && let Some(modified_ctx) = Self::run_before_request(&mut task, sender, &config).await
+        {
+            if modified_ctx.cancel {
+                cancelled = Some(modified_ctx.cancel_reason.unwrap_or_else(|| "no reason given".to_string()));
+            }
+            task.url = modified_ctx.url;
+            task.headers = modified_ctx.headers;
+            task.user_agent = modified_ctx.user_agent;
+        }
+
+        let rotated_user_agent = Self::rotated_user_agent(&task, &config, &self.user_agent_chooser).await;
+
+        let cfg = config.read().await;
+        let user_agent = Self::resolved_user_agent(&task, rotated_user_agent, &cfg);
+
+        // Same referrer rule as download_task: an explicit Referer header wins
+        let has_task_referer = task.headers.keys().any(|k| k.eq_ignore_ascii_case("referer"));
+        let policy_referer = if has_task_referer {
+            None
+        } else {
+            cfg.folders
+                .get(&task.folder_id)
+                .and_then(|f| f.referrer_policy.clone())
+                .unwrap_or_else(|| cfg.download.referrer_policy.clone())
+                .compute(&task.url)
+        };
+        let header_map = HttpClient::build_headers(Some(user_agent.as_str()), policy_referer.as_deref(), &task.headers)?;
+        let mut headers: Vec<(String, String)> = header_map
+            .iter()
+            .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+            .collect();
+        headers.sort();
+
+        let mut routing_rule = None;
+        if let Some(route) = super::routing::route(&cfg.routing.rules, &task.url, &task.filename, None)
+            .filter(|r| r.folder_id != task.folder_id)
+            && let Some(folder) = cfg.folders.get(route.folder_id)
+        {
+            routing_rule = Some(route.rule_number);
+            task.folder_id = route.folder_id.to_string();
+            task.save_path = folder.save_path.clone();
+        }
+
+        let save_path = crate::app::settings::ResolvedSettings::resolve(&cfg, &task.folder_id, &task).save_path;
+        let filename = crate::file::naming::ensure_unique_filename(&save_path, &task.filename);
+
+        Ok(DryRunReport {
+            url: task.url,
+            user_agent,
+            headers,
+            folder_id: task.folder_id,
+            routing_rule,
+            target_path: save_path.join(&filename),
+            filename,
+            cancelled,
+            script_runs: task.script_runs,
+        })
+    }
+
     /// Compute effective script files by merging application-level and folder-level settings
     ///
     /// Folder-level settings override application-level settings for the same script file.
@@ -456,17 +928,77 @@ impl DownloadManager {
         script_files
     }
 
+    /// First `max_bytes` of a downloaded file as lossy UTF-8 (for `bodyPeek`)
+    fn peek_file(path: &std::path::Path, max_bytes: usize) -> Option<String> {
+        use std::io::Read;
+
+        // Grows with the file; max_bytes comes from the config and can be far larger
+        let mut bytes = Vec::new();
+        match std::fs::File::open(path).and_then(|f| f.take(max_bytes as u64).read_to_end(&mut bytes)) {
+            Ok(_) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => {
+                tracing::warn!("Failed to read {} for bodyPeek: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Apply `download.on_duplicate_content` to a completed file, noting the outcome in the task log
+    ///
+    /// A deleted copy leaves the task pointing at the earlier file, so the
+    /// completion log and history name a file that exists. Failures are
+    /// logged and leave the file as it is; they never fail the download.
+    async fn check_duplicate_content(
+        task: &mut DownloadTask,
+        path: std::path::PathBuf,
+        action: crate::app::config::DuplicateContentAction,
+    ) {
+        use crate::download::dedup::{self, DuplicateOutcome};
+
+        let index_path = match crate::util::paths::get_folder_content_index_path(&task.folder_id) {
+            Ok(index_path) => index_path,
+            Err(e) => {
+                tracing::warn!("Duplicate content check skipped: {}", e);
+                return;
+            }
+        };
+        let outcome = tokio::task::spawn_blocking(move || dedup::check_completed(&index_path, &path, action)).await;
+        match outcome {
+            Ok(Ok(DuplicateOutcome::Unique)) => {}
+            Ok(Ok(DuplicateOutcome::Kept(original))) => {
+                task.log_info(format!("Same content as {}", original.display()));
+            }
+            Ok(Ok(DuplicateOutcome::Hardlinked(original))) => {
+                task.log_info(format!("Same content as {}; replaced with a hard link", original.display()));
+            }
+            Ok(Ok(DuplicateOutcome::Deleted(original))) => {
+                task.log_info(format!("Same content as {}; deleted this copy ({})", original.display(), task.filename));
+                if let (Some(dir), Some(name)) = (original.parent(), original.file_name()) {
+                    task.save_path = dir.to_path_buf();
+                    task.filename = name.to_string_lossy().into_owned();
+                }
+            }
+            Ok(Err(e)) => tracing::warn!("Duplicate content check failed for {}: {}", task.filename, e),
+            Err(e) => tracing::warn!("Duplicate content check failed for {}: {}", task.filename, e),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn download_task(
         mut task: DownloadTask,
         http_client: Arc<HttpClient>,
         queue: FolderQueue,
         script_sender: Option<mpsc::Sender<ScriptRequest>>,
         config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+        rotated_user_agent: Option<String>,
         is_resuming: bool,
+        stop: CancellationToken,
     ) -> Result<()> {
         // Compute effective script_files (Application + Folder override)
         let effective_script_files = Self::compute_effective_script_files(&config, &task.folder_id).await;
 
+        let user_agent = Self::resolved_user_agent(&task, rotated_user_agent, &*config.read().await);
+
         // Resolve referrer from policy (folder > app), unless task.headers already has one
         let has_task_referer = task.headers.keys().any(|k| k.eq_ignore_ascii_case("referer"));
         let policy_referer = if has_task_referer {
@@ -481,47 +1013,60 @@ impl DownloadManager {
 
         // Build headers
         let headers = HttpClient::build_headers(
-            task.user_agent.as_deref(),
+            Some(user_agent.as_str()),
             policy_referer.as_deref(),
             &task.headers,
         )?;
 
-        // Get download info
-        let mut info = http_client.get_info(&task.url, &headers).await?;
+        // Get download info; a Range probe already made (e.g. by the preview) is reused
+        let probe = http_client.deep_resume_probe() && !task.resume_probed;
+        let mut info = http_client.get_info_with_probe(&task.url, &headers, probe).await?;
 
         // Update task with server info
         task.size = info.size;
-        task.resume_supported = info.resume_supported;
+        if task.resume_probed {
+            info.resume_supported = task.resume_supported;
+        } else {
+            task.resume_supported = info.resume_supported;
+            task.resume_probed = info.range_probed;
+        }
         task.etag = info.etag.clone();
         task.last_modified = info.last_modified.clone();
         task.last_status_code = Some(info.status);
 
+        // Record where redirects ended up
+        task.resolved_url = info.final_url.clone().filter(|final_url| final_url != &task.url);
+        if let Some(ref resolved_url) = task.resolved_url {
+            task.log_info(format!("Redirected to: {}", resolved_url));
+        }
+
         // Log server info
         let size_str = info.size.map(|s| format!("{} bytes", s)).unwrap_or("unknown".to_string());
         task.log_info(format!("Server info: size={}, resume={}", size_str, info.resume_supported));
 
         // Use filename from Content-Disposition if available (highest priority)
-        if let Some(server_filename) = info.filename {
-            task.filename = sanitize_filename(&server_filename);
+        if let Some(ref server_filename) = info.filename {
+            task.filename = sanitize_filename(server_filename);
             task.log_info(format!("Filename from server: {}", task.filename));
         } else if let Some(ref final_url) = info.final_url {
             // Fallback: extract filename from redirect destination URL
-            if final_url != &task.url {
-                let redirect_filename = final_url
-                    .split('/')
-                    .last()
-                    .unwrap_or("")
-                    .split('?')
-                    .next()
-                    .unwrap_or("");
-                if !redirect_filename.is_empty() {
-                    let sanitized = sanitize_filename(redirect_filename);
-                    task.log_info(format!("Filename from redirect: {} -> {}", task.filename, sanitized));
-                    task.filename = sanitized;
-                }
+            if final_url != &task.url
+                && let Some(redirect_filename) = crate::file::naming::filename_from_url(final_url)
+            {
+                let sanitized = sanitize_filename(&redirect_filename);
+                task.log_info(format!("Filename from redirect: {} -> {}", task.filename, sanitized));
+                task.filename = sanitized;
             }
         }
 
+        // Extensionless names (e.g. `file` served as image/png) get one from Content-Type
+        if config.read().await.download.infer_extension
+            && let Some(name) = crate::file::naming::infer_extension(&task.filename, info.content_type.as_deref())
+        {
+            task.log_info(format!("Extension from Content-Type: {} -> {}", task.filename, name));
+            task.filename = name;
+        }
+
         queue.update(task.clone()).await;
 
         // Hook Point: authRequired - Handle authentication if needed
@@ -548,7 +1093,8 @@ impl DownloadManager {
                         response: response_tx,
                     }
                 }).await {
-                    Ok((modified_ctx, Ok(()))) => {
+                    Ok((modified_ctx, Ok(()), runs)) => {
+                        task.record_script_runs(runs);
                         // Check if script provided credentials
                         if let (Some(username), Some(password)) =
                             (modified_ctx.username, modified_ctx.password)
@@ -560,12 +1106,12 @@ impl DownloadManager {
 
                             // Retry get_info with auth
                             let headers = HttpClient::build_headers(
-                                task.user_agent.as_deref(),
+                                Some(user_agent.as_str()),
                                 policy_referer.as_deref(),
                                 &task.headers,
                             )?;
 
-                            let retry_info = http_client.get_info(&task.url, &headers).await?;
+                            let retry_info = http_client.get_info_with_probe(&task.url, &headers, probe).await?;
 
                             if retry_info.auth_required {
                                 task.log_error("Authentication failed (credentials rejected)".to_string());
@@ -574,7 +1120,10 @@ impl DownloadManager {
 
                             // Success! Update task with new info
                             task.size = retry_info.size;
-                            task.resume_supported = retry_info.resume_supported;
+                            if !task.resume_probed {
+                                task.resume_supported = retry_info.resume_supported;
+                                task.resume_probed = retry_info.range_probed;
+                            }
                             task.etag = retry_info.etag.clone();
                             task.last_modified = retry_info.last_modified.clone();
                             task.last_status_code = Some(retry_info.status);
@@ -590,7 +1139,8 @@ impl DownloadManager {
                             ));
                         }
                     }
-                    Ok((_, Err(e))) => {
+                    Ok((_, Err(e), runs)) => {
+                        task.record_script_runs(runs);
                         tracing::error!("authRequired hook error: {}", e);
                         return Err(anyhow::anyhow!("authRequired hook failed: {}", e));
                     }
@@ -611,8 +1161,28 @@ impl DownloadManager {
             queue.update(task.clone()).await;
         }
 
+        let body_peek_bytes = config.read().await.scripts.body_peek_bytes;
+
         // Hook Point 2: headersReceived - Inspect server response
         if let Some(ref sender) = script_sender {
+            // Optional body peek, fetched before committing to the full download
+            let body_peek = if body_peek_bytes > 0 {
+                let headers = HttpClient::build_headers(
+                    Some(user_agent.as_str()),
+                    policy_referer.as_deref(),
+                    &task.headers,
+                )?;
+                match http_client.peek_body(&task.url, &headers, body_peek_bytes).await {
+                    Ok(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                    Err(e) => {
+                        task.log_warn(format!("Failed to peek response body: {}", e));
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
             let ctx = crate::script::events::HeadersReceivedContext {
                 url: task.url.clone(),
                 status: info.status,
@@ -621,6 +1191,7 @@ impl DownloadManager {
                 etag: info.etag.clone(),
                 last_modified: info.last_modified.clone(),
                 content_type: info.content_type.clone(),
+                body_peek,
             };
 
             let effective_files = effective_script_files.clone();
@@ -633,31 +1204,98 @@ impl DownloadManager {
                     response: response_tx,
                 }
             }).await {
-                Ok(Ok(())) => {
+                Ok((Ok(()), runs)) => {
+                    task.record_script_runs(runs);
                     task.log_info("headersReceived hook executed".to_string());
                 }
-                Ok(Err(e)) => {
+                Ok((Err(e), runs)) => {
+                    task.record_script_runs(runs);
                     tracing::error!("headersReceived hook error: {}", e);
                 }
                 Err(e) => {
                     tracing::error!("headersReceived error: {}", e);
                 }
             }
+            queue.update(task.clone()).await;
+        }
+
+        // Auto-folder routing: pick the target folder before the file is created.
+        // Resumed transfers keep their folder so the partial file is found again.
+        if !is_resuming && task.routed_from.is_none() {
+            let cfg = config.read().await;
+            let route = super::routing::route(
+                &cfg.routing.rules,
+                &task.url,
+                &task.filename,
+                info.content_type.as_deref(),
+            );
+            if let Some(route) = route.filter(|r| r.folder_id != task.folder_id) {
+                match cfg.folders.get(route.folder_id) {
+                    Some(folder) => {
+                        tracing::info!(
+                            "Routed {} from folder '{}' to '{}' (rule #{})",
+                            task.filename,
+                            task.folder_id,
+                            route.folder_id,
+                            route.rule_number
+                        );
+                        task.log_info(format!(
+                            "Routed to folder '{}' by routing rule #{}",
+                            route.folder_id, route.rule_number
+                        ));
+                        task.routed_from = Some(std::mem::replace(&mut task.folder_id, route.folder_id.to_string()));
+                        task.save_path = folder.save_path.clone();
+                        queue.update(task.clone()).await;
+                    }
+                    None => tracing::warn!(
+                        "Routing rule #{} targets unknown folder '{}'; ignored",
+                        route.rule_number,
+                        route.folder_id
+                    ),
+                }
+            }
+        }
+
+        // Large downloads wait for the user to confirm the size before anything is written
+        let confirm_over_bytes = config.read().await.download.confirm_over_bytes;
+        if task.needs_size_confirmation(confirm_over_bytes) {
+            tracing::info!("Holding {} for size confirmation", task.filename);
+            task.status = DownloadStatus::Paused;
+            task.awaiting_confirmation = true;
+            task.log_warn(format!(
+                "Waiting for confirmation: {} bytes is over download.confirm_over_bytes ({} bytes)",
+                task.size.unwrap_or(0),
+                confirm_over_bytes.unwrap_or(0)
+            ));
+            queue.update(task).await;
+            return Ok(());
         }
 
         // Resolve settings (applies auto-date directory, etc.)
-        let resolved_save_path = {
+        let (resolved_save_path, temp_dir, bandwidth_weight) = {
             let cfg = config.read().await;
-            crate::app::settings::ResolvedSettings::resolve(&cfg, &task.folder_id, &task)
-                .save_path
+            let resolved = crate::app::settings::ResolvedSettings::resolve(&cfg, &task.folder_id, &task);
+            let weight = if cfg.download.bandwidth_by_priority { 1 + task.priority.clamp(0, 255) as u32 } else { 1 };
+            (resolved.save_path, cfg.download.temp_dir.clone(), weight)
         };
         // Ensure directory exists (handles auto-date subdirectories)
         tokio::fs::create_dir_all(&resolved_save_path).await?;
 
+        // With download.temp_dir, the transfer goes to a partial file there
+        // and is moved into the save directory once it is complete
+        let partial_path = match temp_dir {
+            Some(ref dir) => {
+                tokio::fs::create_dir_all(dir).await?;
+                Some(task.partial_path(dir))
+            }
+            None => None,
+        };
+
         // Resume: only for interrupted tasks (Paused/Error) with existing partial file
         let mut file_path = resolved_save_path.join(&task.filename);
-        let resume_from = if is_resuming && file_path.exists() && task.resume_supported {
-            Some(std::fs::metadata(&file_path)?.len())
+        let transfer_path = partial_path.clone().unwrap_or_else(|| file_path.clone());
+        let resume_from = if is_resuming && transfer_path.exists() && task.resume_supported {
+            Some(std::fs::metadata(&transfer_path)?.len())
         } else {
             None
         };
@@ -717,9 +1355,9 @@ impl DownloadManager {
             let effective_script_files = effective_script_files_for_progress.clone();
 
             tokio::spawn(async move {
-                if let Some(mut task) = queue.get_by_id(task_id).await {
-                    task.downloaded = downloaded;
-                    task.size = total.or(task.size);
+                queue.update_progress(task_id, downloaded, total).await;
+
+                if let Some(task) = queue.get_by_id(task_id).await {
 
                     // Hook Point 5: progress - Progress updates (fire-and-forget)
                     if let Some(ref sender) = script_sender {
@@ -751,29 +1389,85 @@ impl DownloadManager {
                             }
                         });
                     }
-
-                    queue.update(task).await;
                 }
             });
         };
 
         // Rebuild headers to include any auth header from authRequired hook
         let headers = HttpClient::build_headers(
-            task.user_agent.as_deref(),
+            Some(user_agent.as_str()),
             policy_referer.as_deref(),
             &task.headers,
         )?;
 
-        // Perform download
-        let download_info = http_client
-            .download_to_file(
+        // Perform download; the size limit is checked again against the GET response
+        let max_size = confirm_over_bytes.map(|threshold| threshold.max(task.confirmed_size.unwrap_or(0)));
+        let result = http_client
+            .download_to_file_until(
                 &task.url,
-                &file_path,
+                &transfer_path,
                 &headers,
                 resume_from,
                 Some(progress_callback),
+                Some(&stop),
+                bandwidth_weight,
+                max_size,
             )
-            .await?;
+            .await;
+        let download_info = match result {
+            Ok(download_info) => download_info,
+            Err(e) => {
+                let Some(over) = e.downcast_ref::<SizeOverLimit>() else {
+                    return Err(e);
+                };
+                tracing::info!("Holding {} for size confirmation", task.filename);
+                task.size = Some(over.size);
+                task.status = DownloadStatus::Paused;
+                task.awaiting_confirmation = true;
+                task.log_warn(format!(
+                    "Waiting for confirmation: the server now reports {} bytes, over download.confirm_over_bytes ({} bytes)",
+                    over.size,
+                    confirm_over_bytes.unwrap_or(0)
+                ));
+                queue.update(task).await;
+                return Ok(());
+            }
+        };
+
+        if download_info.resume_restarted {
+            task.log_warn("Partial file did not match the server; downloaded again from scratch".to_string());
+        }
+
+        if let Some(partial_path) = partial_path {
+            // Another download may have taken the name while this one ran
+            if file_path.exists() {
+                task.filename = crate::file::naming::ensure_unique_filename(&resolved_save_path, &task.filename);
+                file_path = resolved_save_path.join(&task.filename);
+            }
+            let target = file_path.clone();
+            tokio::task::spawn_blocking(move || crate::util::fs::move_file(&partial_path, &target)).await??;
+            task.log_info(format!("Moved from temp directory to {}", file_path.display()));
+        }
+
+        // Some servers only send Content-Disposition on GET, not on HEAD
+        if info.filename.is_none()
+            && resume_from.is_none()
+            && let Some(ref server_filename) = download_info.filename
+        {
+            let sanitized = sanitize_filename(server_filename);
+            if sanitized != task.filename {
+                let file_dir = file_path.parent().unwrap_or(&resolved_save_path).to_path_buf();
+                let final_name = crate::file::naming::ensure_unique_filename(&file_dir, &sanitized);
+                let new_path = file_dir.join(&final_name);
+                if let Err(e) = std::fs::rename(&file_path, &new_path) {
+                    tracing::error!("Failed to rename file to server filename: {}", e);
+                } else {
+                    task.log_info(format!("Filename from server: {} -> {}", task.filename, final_name));
+                    task.filename = final_name;
+                    file_path = new_path;
+                }
+            }
+        }
 
         // Apply last modified time if available
         if let Some(ref last_modified) = download_info.last_modified {
@@ -788,6 +1482,12 @@ impl DownloadManager {
                 (end - start).num_milliseconds() as f64 / 1000.0
             });
 
+            let body_peek = if body_peek_bytes > 0 {
+                Self::peek_file(&file_path, body_peek_bytes)
+            } else {
+                None
+            };
+
             let ctx = crate::script::events::CompletedContext {
                 url: task.url.clone(),
                 filename: task.filename.clone(),
@@ -795,7 +1495,9 @@ impl DownloadManager {
                 new_filename: None,
                 move_to_path: None,
                 size: task.size.unwrap_or(0),
+                status_code: task.last_status_code,
                 duration,
+                body_peek,
             };
 
             let effective_files = effective_script_files.clone();
@@ -809,7 +1511,8 @@ impl DownloadManager {
                     response: response_tx,
                 }
             }).await {
-                Ok((modified_ctx, Ok(()))) => {
+                Ok((modified_ctx, Ok(()), runs)) => {
+                    task.record_script_runs(runs);
                     let file_dir = file_path_for_ops.parent()
                         .unwrap_or(&task.save_path)
                         .to_path_buf();
@@ -834,6 +1537,7 @@ impl DownloadManager {
                             );
                         } else {
                             task.filename = final_name;
+                            file_path = new_path;
                             task.log_info("File renamed by script".to_string());
                         }
                     }
@@ -847,12 +1551,14 @@ impl DownloadManager {
                             tracing::error!("Failed to move file: {}", e);
                         } else {
                             task.save_path = new_dir;
+                            file_path = new_path;
                             task.log_info("File moved by script".to_string());
                         }
                     }
                     task.log_info("completed hook executed".to_string());
                 }
-                Ok((_, Err(e))) => {
+                Ok((_, Err(e), runs)) => {
+                    task.record_script_runs(runs);
                     tracing::error!("completed hook error: {}", e);
                 }
                 Err(e) => {
@@ -861,6 +1567,17 @@ impl DownloadManager {
             }
         }
 
+        // Same content as an earlier download in the folder (download.on_duplicate_content)
+        let duplicate_action = config.read().await.download.on_duplicate_content;
+        if let Some(action) = duplicate_action {
+            Self::check_duplicate_content(&mut task, file_path.clone(), action).await;
+        }
+
+        // Keep the speed stats gathered by progress updates since this task was cloned
+        if let Some(latest) = queue.get_by_id(task.id).await {
+            task.speed_stats = latest.speed_stats;
+        }
+
         // Mark as completed
         task.status = DownloadStatus::Completed;
         task.completed_at = Some(chrono::Utc::now());
@@ -872,35 +1589,92 @@ impl DownloadManager {
             tracing::error!("Failed to append completion log: {}", e);
             // Continue anyway - don't fail download on log error
         }
+        if let Err(e) = crate::download::lifetime::record_completion(&task) {
+            tracing::error!("Failed to update lifetime stats: {}", e);
+        }
 
         // Remove from queue (completed tasks are logged to completion log)
         queue.remove(task.id).await;
         tracing::info!("Download completed and logged: {}", task.filename);
 
+        let completion_sound = config.read().await.general.completion_sound.clone();
+        crate::util::notify::play_completion_sound(completion_sound.as_deref());
+
         Ok(())
     }
 
     pub async fn pause_download(&self, id: Uuid) -> Result<()> {
-        // Abort the download task
-        if let Some(handle) = self.active_downloads.write().await.remove(&id) {
-            handle.abort();
+        // Stop the transfer at the next chunk boundary, or at once if it is waiting for data
+        let active = self.active_downloads.write().await.remove(&id);
+        if let Some(active) = active {
+            active.stop(STOP_GRACE).await;
         }
 
-        // Update status and counts
-        if let Some(mut task) = self.get_by_id(id).await {
-            let folder_id = task.folder_id.clone();
-            if task.status == DownloadStatus::Downloading {
-                self.decrement_downloading(&folder_id).await;
-            }
-            task.status = DownloadStatus::Paused;
-            if let Some(queue) = self.get_folder_queue(&folder_id).await {
+        // Update status; `update` adjusts the counts (a routed task may still
+        // sit in its original queue)
+        if let Some(queue) = self.queue_containing(id).await {
+            if let Some(mut task) = queue.get_by_id(id).await {
+                task.status = DownloadStatus::Paused;
+                // A paused download no longer waits for a slot or quota
+                task.held_reason = None;
                 queue.update(task).await;
             }
+            self.start_quota_waiting(queue.folder_id());
+            // The stopped transfer skips its own cleanup, so release the folder slot here
+            self.deactivate_folder_if_empty(queue.folder_id()).await;
         }
 
         Ok(())
     }
 
+    /// Stop all downloads gracefully
+    ///
+    /// Active transfers stop at the next chunk boundary, flush their data and are
+    /// marked `Paused` so they resume from the partial file. Downloads still running
+    /// after `grace` are aborted and paused.
+    pub async fn shutdown(&self, grace: std::time::Duration) {
+        self.shutdown.cancel();
+
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            let running = self
+                .active_downloads
+                .read()
+                .await
+                .values()
+                .filter(|active| !active.handle.is_finished())
+                .count();
+            if running == 0 || tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let handles: Vec<_> = self.active_downloads.write().await.drain().collect();
+        let mut aborted = 0;
+        for (id, active) in handles {
+            if !active.handle.is_finished() {
+                active.handle.abort();
+                aborted += 1;
+            }
+
+            // Tasks that never reached the transfer (waiting for a slot, backing off)
+            if let Some(mut task) = self.get_by_id(id).await
+                && task.status == DownloadStatus::Downloading
+            {
+                task.status = DownloadStatus::Paused;
+                if let Some(queue) = self.get_folder_queue(&task.folder_id).await {
+                    queue.update(task).await;
+                }
+            }
+        }
+
+        if aborted > 0 {
+            tracing::warn!("Aborted {} download(s) that did not stop within {:?}", aborted, grace);
+        }
+        tracing::info!("Download manager shut down");
+    }
+
     pub async fn change_folder(&self, id: Uuid, new_folder_id: String) -> Result<()> {
         // Find and remove from old folder queue
         let task = {
@@ -928,8 +1702,49 @@ impl DownloadManager {
         }
     }
 
-    /// Rename a folder: update folder_id on all tasks in the old folder queue,
-    /// then move the queue entry to the new key.
+    /// Move a download to another folder and persist both folder queues
+    ///
+    /// With `config`, a download that has not transferred any data yet also
+    /// takes the new folder's save path (template expanded); one with a
+    /// partial or finished file keeps its path. Active downloads must be
+    /// paused first. Returns the task as stored in the new folder.
+    pub async fn move_task_to_folder(
+        &self,
+        id: Uuid,
+        folder_id: &str,
+        config: Option<&crate::app::config::Config>,
+    ) -> Result<DownloadTask> {
+        let task = self.get_by_id(id).await.ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+        if task.folder_id == folder_id {
+            return Ok(task);
+        }
+        let running = self.active_downloads.read().await.get(&id).is_some_and(ActiveDownload::is_running);
+        if task.status == DownloadStatus::Downloading || running {
+            return Err(anyhow::anyhow!("Cannot move an active download; pause it first"));
+        }
+
+        let old_queue = self.queue_containing(id).await;
+        self.change_folder(id, folder_id.to_string()).await?;
+        let new_queue = self.get_or_create_folder_queue(folder_id).await;
+
+        let mut task = new_queue.get_by_id(id).await.ok_or_else(|| anyhow::anyhow!("Task not found"))?;
+        if let Some(config) = config
+            && task.downloaded == 0
+            && task.status != DownloadStatus::Completed
+        {
+            task.save_path = super::task::folder_save_path(config, folder_id, &task.url, task.created_at);
+            new_queue.update(task.clone()).await;
+        }
+
+        if let Some(old_queue) = old_queue {
+            old_queue.save_if_dirty(false).await?;
+        }
+        new_queue.save_if_dirty(false).await?;
+        Ok(task)
+    }
+
+    /// Rename a folder: update folder_id on all tasks in the old folder queue,
+    /// then move the queue entry to the new key.
     pub async fn rename_folder(&self, old_id: &str, new_id: &str) -> Result<()> {
         let mut queues = self.folder_queues.write().await;
         if let Some(queue) = queues.remove(old_id) {
@@ -962,19 +1777,22 @@ impl DownloadManager {
         }
     }
 
-    /// Resume all paused and error downloads
+    /// Resume all paused and error downloads (except those waiting for size confirmation)
     /// Returns the number of downloads resumed
     pub async fn resume_all(
         &self,
         script_sender: Option<mpsc::Sender<ScriptRequest>>,
         config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
     ) -> usize {
-        let downloads = self.get_all_downloads().await;
-        let resumable: Vec<Uuid> = downloads
-            .iter()
+        let mut resumable: Vec<DownloadTask> = self
+            .get_all_downloads()
+            .await
+            .into_iter()
             .filter(|t| matches!(t.status, DownloadStatus::Paused | DownloadStatus::Error))
-            .map(|t| t.id)
+            .filter(|t| !t.awaiting_confirmation)
             .collect();
+        super::folder_queue::sort_for_schedule(&mut resumable, config.read().await.download.schedule_policy);
+        let resumable: Vec<Uuid> = resumable.iter().map(|t| t.id).collect();
         
         let mut resumed = 0;
         for id in resumable {
@@ -1004,6 +1822,59 @@ impl DownloadManager {
         paused
     }
 
+    /// Keep downloads from starting until `release_held_downloads(reason)`
+    ///
+    /// `start_download` leaves them queued with `reason` as their held reason,
+    /// like a folder quota does. Running downloads are not affected. Holds
+    /// with different reasons are independent.
+    pub fn hold_new_downloads(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        let mut reasons = self.hold_reasons.lock().unwrap();
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+
+    /// End the `hold_new_downloads` hold for `reason` and start the downloads it kept back
+    ///
+    /// Downloads resumed during the hold keep their status (`Paused` or
+    /// `Error`) and are started too. Downloads still held by another hold stay
+    /// queued under that one. Returns the number of downloads started.
+    pub async fn release_held_downloads(
+        &self,
+        reason: &str,
+        script_sender: Option<mpsc::Sender<ScriptRequest>>,
+        config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+    ) -> usize {
+        {
+            let mut reasons = self.hold_reasons.lock().unwrap();
+            let Some(index) = reasons.iter().position(|r| r == reason) else {
+                return 0;
+            };
+            reasons.remove(index);
+        }
+
+        let mut held: Vec<DownloadTask> = self
+            .get_all_downloads()
+            .await
+            .into_iter()
+            .filter(|t| {
+                t.held_reason.as_deref() == Some(reason)
+                    && !matches!(t.status, DownloadStatus::Downloading | DownloadStatus::Completed)
+            })
+            .collect();
+        super::folder_queue::sort_for_schedule(&mut held, config.read().await.download.schedule_policy);
+        let held: Vec<Uuid> = held.iter().map(|t| t.id).collect();
+
+        let mut started = 0;
+        for id in held {
+            if self.start_download(id, script_sender.clone(), config.clone()).await.is_ok() {
+                started += 1;
+            }
+        }
+        started
+    }
+
     /// Get count of paused downloads
     pub async fn get_paused_count(&self) -> usize {
         let downloads = self.get_all_downloads().await;
@@ -1027,14 +1898,14 @@ impl DownloadManager {
         &self.circuit_breaker
     }
 
-    /// Reset circuit breaker for a specific domain
-    pub fn reset_circuit(&self, domain: &str) {
-        self.circuit_breaker.reset(domain);
+    /// Reset circuit breaker for a specific domain (false if it was not tracked)
+    pub fn reset_circuit(&self, domain: &str) -> bool {
+        self.circuit_breaker.reset(domain)
     }
 
-    /// Reset all circuit breakers
-    pub fn reset_all_circuits(&self) {
-        self.circuit_breaker.clear_all();
+    /// Reset all circuit breakers, returning how many were tracked
+    pub fn reset_all_circuits(&self) -> usize {
+        self.circuit_breaker.clear_all()
     }
 
     /// Get list of domains with open circuits
@@ -1075,6 +1946,8 @@ impl DownloadManager {
     }
 
     /// Deactivate folder if it has no pending or active downloads (O(1) operation)
+    ///
+    /// The freed slot goes to the folder that has waited longest.
     async fn deactivate_folder_if_empty(&self, folder_id: &str) {
         // Use O(1) counter check instead of O(n) queue iteration
         if !self.folder_has_active_tasks(folder_id).await {
@@ -1086,17 +1959,146 @@ impl DownloadManager {
                     active.len(),
                     self.parallel_folder_count
                 );
+                drop(active);
+                self.start_next_waiting_folder();
             }
         }
     }
 
+    /// Queue a download refused for lack of a folder slot
+    ///
+    /// A folder already waiting keeps its place in line.
+    fn wait_for_folder_slot(
+        &self,
+        folder_id: &str,
+        id: Uuid,
+        script_sender: Option<mpsc::Sender<ScriptRequest>>,
+        config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+    ) {
+        let mut waiting = self.waiting_folders.lock().unwrap();
+        match waiting.iter_mut().find(|w| w.folder_id == folder_id) {
+            Some(folder) => {
+                if !folder.tasks.contains(&id) {
+                    folder.tasks.push(id);
+                }
+                folder.script_sender = script_sender;
+                folder.config = config;
+            }
+            None => waiting.push_back(WaitingFolder {
+                folder_id: folder_id.to_string(),
+                tasks: vec![id],
+                script_sender,
+                config,
+            }),
+        }
+    }
+
+    /// Start the downloads of the folder that has waited longest for a slot
+    ///
+    /// Downloads that were started, paused or removed in the meantime are
+    /// skipped. If another folder took the slot first, the folder goes to the
+    /// back of the line again.
+    fn start_next_waiting_folder(&self) {
+        let Some(folder) = self.waiting_folders.lock().unwrap().pop_front() else {
+            return;
+        };
+        self.start_waiting(folder);
+    }
+
+    /// Queue a download held by a folder quota until the folder has room
+    fn wait_for_quota(
+        &self,
+        folder_id: &str,
+        id: Uuid,
+        script_sender: Option<mpsc::Sender<ScriptRequest>>,
+        config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+    ) {
+        let mut waiting = self.quota_waiting.lock().unwrap();
+        let folder = waiting.entry(folder_id.to_string()).or_insert_with(|| WaitingFolder {
+            folder_id: folder_id.to_string(),
+            tasks: Vec::new(),
+            script_sender: None,
+            config: config.clone(),
+        });
+        if !folder.tasks.contains(&id) {
+            folder.tasks.push(id);
+        }
+        folder.script_sender = script_sender;
+        folder.config = config;
+    }
+
+    /// Try the quota-held downloads of `folder_id` again (room may have freed)
+    ///
+    /// Those still over the quota are held and queued again.
+    fn start_quota_waiting(&self, folder_id: &str) {
+        let Some(folder) = self.quota_waiting.lock().unwrap().remove(folder_id) else {
+            return;
+        };
+        self.start_waiting(folder);
+    }
+
+    /// Start the downloads of a waiting folder, in the order they were refused
+    ///
+    /// A refused download keeps its status (a resumed one stays `Paused`) and
+    /// is recognized by its held reason, which `pause_download` clears.
+    fn start_waiting(&self, folder: WaitingFolder) {
+        tracing::info!("Starting {} waiting download(s) in folder '{}'", folder.tasks.len(), folder.folder_id);
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            for id in folder.tasks {
+                let waiting = manager.get_by_id(id).await.is_some_and(|task| {
+                    task.held_reason.is_some()
+                        && !matches!(task.status, DownloadStatus::Downloading | DownloadStatus::Completed)
+                });
+                if waiting {
+                    let _ = manager.start_download(id, folder.script_sender.clone(), folder.config.clone()).await;
+                }
+            }
+        });
+    }
+
+    /// Set the combined bandwidth limit in bytes/sec (0 = unlimited)
+    pub fn set_bandwidth_limit(&self, bytes_per_sec: u64) {
+        self.http_client.rate_limiter().set_limit(bytes_per_sec);
+    }
+
+    /// Current combined bandwidth limit in bytes/sec (0 = unlimited)
+    pub fn bandwidth_limit(&self) -> u64 {
+        self.http_client.rate_limiter().limit()
+    }
+
+    /// Change the application-wide concurrent download limit
+    ///
+    /// Takes effect immediately: raising it lets waiting downloads start,
+    /// lowering it lets running downloads finish and holds back new ones.
     pub async fn set_max_concurrent(&self, max: usize) {
         *self.max_concurrent.write().await = max;
-        // Note: Global semaphore cannot be resized, would need to recreate manager
+        self.global_limit.set_limit(max);
+    }
+
+    /// Change the limit on simultaneous HTTP connections across all downloads
+    ///
+    /// Resized like the download limit: running transfers keep their connection.
+    pub fn set_max_connections(&self, max: usize) {
+        self.connection_limit.set_limit(max);
+    }
+
+    /// Current limit on simultaneous HTTP connections
+    pub fn max_connections(&self) -> usize {
+        self.connection_limit.limit()
+    }
+
+    /// Change the concurrent download limit of every folder queue
+    pub async fn set_max_concurrent_per_folder(&self, max: usize) {
+        self.max_concurrent_per_folder.store(max, Ordering::SeqCst);
+        for queue in self.folder_queues.read().await.values() {
+            queue.set_max_concurrent(max);
+        }
     }
 
     pub async fn get_active_count(&self) -> usize {
-        self.active_downloads.read().await.len()
+        self.active_downloads.read().await.values().filter(|active| active.is_running()).count()
     }
 
     /// Save queue to file (legacy single-file format)
@@ -1122,29 +2124,180 @@ impl DownloadManager {
     }
 
     /// Save queue partitioned by folder to folder-specific TOML files
+    ///
+    /// Only folders modified since their last save (including download
+    /// progress) are rewritten.
     pub async fn save_queue_to_folders(&self) -> Result<()> {
-        let queues = self.folder_queues.read().await;
-        for queue in queues.values() {
-            queue.save().await?;
+        self.save_modified_queues(true).await
+    }
+
+    async fn save_modified_queues(&self, include_progress: bool) -> Result<()> {
+        let queues: Vec<FolderQueue> = self.folder_queues.read().await.values().cloned().collect();
+        let mut saved = 0;
+        for queue in queues {
+            if queue.save_if_dirty(include_progress).await? {
+                saved += 1;
+            }
+        }
+        if saved > 0 {
+            tracing::debug!("Saved {} modified folder queue(s)", saved);
         }
         Ok(())
     }
 
+    /// Spawn a background task that saves modified folder queues every
+    /// `QUEUE_SAVE_INTERVAL`
+    ///
+    /// Bursts of changes (e.g. a batch add) are coalesced into one write per
+    /// folder. Folders whose only change is download progress are written
+    /// every `PROGRESS_SAVE_EVERY` passes, which keeps resume offsets fresh
+    /// and lets `ggg status --watch` follow downloads from another process.
+    /// Abort the handle on exit and call `save_queue_to_folders` once more so
+    /// the last changes are flushed.
+    pub fn spawn_queue_saver(&self) -> JoinHandle<()> {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(QUEUE_SAVE_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut pass: u32 = 0;
+            loop {
+                interval.tick().await;
+                pass = pass.wrapping_add(1);
+                let include_progress = pass.is_multiple_of(PROGRESS_SAVE_EVERY);
+                if let Err(e) = manager.save_modified_queues(include_progress).await {
+                    tracing::error!("Failed to save queue: {}", e);
+                }
+            }
+        })
+    }
+
     /// Load queue from all folder-specific TOML files
-    pub async fn load_queue_from_folders(&self) -> Result<()> {
+    ///
+    /// With `recover_interrupted`, tasks still marked `Downloading` were
+    /// interrupted by a crash; they are reset (see
+    /// `DownloadTask::recover_interrupted`) since nothing is running yet. Pass
+    /// false when another instance holds the instance lock: its downloads are
+    /// still running. Only folders holding a reset task are marked modified,
+    /// so the next save leaves the other queue files alone.
+    pub async fn load_queue_from_folders(
+        &self,
+        config: &crate::app::config::Config,
+        recover_interrupted: bool,
+    ) -> Result<()> {
         let temp = DownloadQueue::new();
         temp.load_from_folder_files().await?;
         let tasks = temp.get_all().await;
+        let stamps = temp.file_stamps();
 
-        for task in tasks {
-            let folder_id = task.folder_id.clone();
-            let queue = self.get_or_create_folder_queue(&folder_id).await;
-            queue.add(task).await;
+        // Remember each file as read, so saves never overwrite another instance's version
+        for folder_id in stamps.keys() {
+            self.get_or_create_folder_queue(folder_id).await;
+        }
+        for (folder_id, queue) in self.folder_queues.read().await.iter() {
+            queue.set_file_stamp(stamps.get(folder_id).copied());
+        }
+
+        self.set_folder_quotas(config);
+        let mut recovered = 0;
+        for mut task in tasks {
+            let queue = self.get_or_create_folder_queue(&task.folder_id).await;
+            if recover_interrupted && Self::recover_loaded_task(config, &mut task) {
+                recovered += 1;
+                queue.add(task).await;
+            } else {
+                queue.add_saved(task).await;
+            }
+        }
+
+        if recovered > 0 {
+            tracing::warn!("Recovered {} download(s) interrupted by an unclean shutdown", recovered);
         }
 
         Ok(())
     }
 
+    /// Migrate a legacy single-file `queue.json` into per-folder `queue.toml` files
+    ///
+    /// Runs once: afterwards the legacy file is renamed to `queue.json.migrated`.
+    /// Skipped when the folder queues already hold tasks, so call this after
+    /// `load_queue_from_folders`. Returns the number of migrated tasks.
+    pub async fn migrate_legacy_queue(
+        &self,
+        legacy_path: &std::path::Path,
+        config: &crate::app::config::Config,
+    ) -> Result<usize> {
+        if !legacy_path.exists() {
+            return Ok(0);
+        }
+        if !self.get_all_downloads().await.is_empty() {
+            tracing::warn!(
+                "Legacy queue {} not migrated: folder queues already contain tasks",
+                legacy_path.display()
+            );
+            return Ok(0);
+        }
+
+        let legacy = DownloadQueue::new();
+        legacy.load_from_file(legacy_path).await?;
+        let tasks = legacy.get_all().await;
+
+        let mut per_folder: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+        for mut task in tasks {
+            Self::recover_loaded_task(config, &mut task);
+            *per_folder.entry(task.folder_id.clone()).or_default() += 1;
+            let queue = self.get_or_create_folder_queue(&task.folder_id).await;
+            queue.add(task).await;
+        }
+        self.save_queue_to_folders().await?;
+
+        let mut migrated_name = legacy_path.as_os_str().to_os_string();
+        migrated_name.push(".migrated");
+        let migrated_path = std::path::PathBuf::from(migrated_name);
+        std::fs::rename(legacy_path, &migrated_path)?;
+
+        let total: usize = per_folder.values().sum();
+        let summary = per_folder
+            .iter()
+            .map(|(folder, count)| format!("{}: {}", folder, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        tracing::info!(
+            "Migrated {} task(s) from legacy {} ({}); original kept as {}",
+            total,
+            legacy_path.display(),
+            summary,
+            migrated_path.display()
+        );
+
+        Ok(total)
+    }
+
+    /// Apply `DownloadTask::recover_interrupted` to a task read from disk
+    ///
+    /// Looks for a partial file in `download.temp_dir`, at the folder's resolved
+    /// save path, then at the task's own save path. Returns true if the task
+    /// was changed.
+    fn recover_loaded_task(config: &crate::app::config::Config, task: &mut DownloadTask) -> bool {
+        if task.status != DownloadStatus::Downloading {
+            return false;
+        }
+        let auto_start = config
+            .folders
+            .get(&task.folder_id)
+            .is_some_and(|f| f.auto_start_downloads);
+        let save_path = crate::app::settings::ResolvedSettings::resolve(config, &task.folder_id, task).save_path;
+        let partial_len = config
+            .download
+            .temp_dir
+            .as_ref()
+            .map(|dir| task.partial_path(dir))
+            .into_iter()
+            .chain([save_path.join(&task.filename), task.save_path.join(&task.filename)])
+            .find_map(|path| std::fs::metadata(path).ok())
+            .map(|m| m.len());
+        task.recover_interrupted(auto_start, partial_len)
+    }
+
     /// Get download by ID (searches all folder queues)
     pub async fn get_by_id(&self, id: Uuid) -> Option<DownloadTask> {
         let queues = self.folder_queues.read().await;
@@ -1158,7 +2311,7 @@ impl DownloadManager {
 
     /// Check if there are any active downloads
     pub async fn has_active_downloads(&self) -> bool {
-        !self.active_downloads.read().await.is_empty()
+        self.active_downloads.read().await.values().any(ActiveDownload::is_running)
     }
 
     /// Set priority for a download task
@@ -1172,6 +2325,22 @@ impl DownloadManager {
         Err(anyhow::anyhow!("Download not found"))
     }
 
+    /// Accept the size of a download held by `download.confirm_over_bytes`
+    ///
+    /// The download can then be started; it is asked about again only if the
+    /// server later reports a larger size.
+    pub async fn confirm_download(&self, id: Uuid) -> Result<()> {
+        let queue = self.queue_containing(id).await.ok_or_else(|| anyhow::anyhow!("Download not found"))?;
+        let Some(mut task) = queue.get_by_id(id).await else {
+            return Err(anyhow::anyhow!("Download not found"));
+        };
+        task.confirmed_size = task.size;
+        task.awaiting_confirmation = false;
+        task.log_info(format!("Size confirmed: {} bytes", task.size.unwrap_or(0)));
+        queue.update(task).await;
+        Ok(())
+    }
+
     /// Move download to top of queue
     pub async fn move_to_top(&self, id: Uuid) -> Result<()> {
         let queues = self.folder_queues.read().await;
@@ -1205,6 +2374,17 @@ impl DownloadManager {
         Err(anyhow::anyhow!("Download not found"))
     }
 
+    /// Move download after another download in queue
+    pub async fn move_after(&self, id: Uuid, after_id: Uuid) -> Result<()> {
+        let queues = self.folder_queues.read().await;
+        for queue in queues.values() {
+            if queue.move_after(id, after_id).await {
+                return Ok(());
+            }
+        }
+        Err(anyhow::anyhow!("Download not found"))
+    }
+
     // ============================================================
     // History Management Methods
     // ============================================================
@@ -1239,10 +2419,23 @@ impl DownloadManager {
         self.history.read().await.len()
     }
 
+    /// Set history retention limits and prune existing items
+    ///
+    /// Returns the number of items removed.
+    pub async fn set_history_retention(&self, max_entries: usize, max_age_days: u32) -> usize {
+        self.history.write().await.set_retention(super::history::HistoryRetention {
+            max_entries,
+            max_age_days,
+        })
+    }
+
     /// Load history from file
     pub async fn load_history(&self, path: &std::path::Path) -> Result<()> {
-        let history = DownloadHistory::load(path)?;
-        *self.history.write().await = history;
+        let mut history = DownloadHistory::load(path)?;
+        let mut current = self.history.write().await;
+        // Keep the configured retention across reloads
+        history.set_retention(current.retention());
+        *current = history;
         Ok(())
     }
 
@@ -1266,6 +2459,7 @@ impl DownloadManager {
         task.retry_count = 0;
         task.started_at = None;
         task.completed_at = None;
+        task.speed_stats = Default::default();
 
         // Update folder if specified
         if let Some(folder_id) = new_folder_id {
@@ -1294,7 +2488,8 @@ impl DownloadManager {
             None => return 0,
         };
 
-        let pending_tasks = queue.get_pending_tasks().await;
+        let policy = config.read().await.download.schedule_policy;
+        let pending_tasks = queue.pending_in_order(policy).await;
         let mut started = 0;
 
         for task in pending_tasks {
@@ -1306,6 +2501,36 @@ impl DownloadManager {
         started
     }
 
+    /// Restart all failed tasks, optionally limited to one folder
+    ///
+    /// Each task gets the full retry budget again (see `start_download`).
+    /// Returns the number of tasks restarted.
+    pub async fn retry_failed_tasks(
+        &self,
+        folder_id: Option<&str>,
+        script_sender: Option<mpsc::Sender<ScriptRequest>>,
+        config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
+    ) -> usize {
+        let downloads = match folder_id {
+            Some(folder_id) => self.get_folder_downloads(folder_id).await,
+            None => self.get_all_downloads().await,
+        };
+        let failed: Vec<Uuid> = downloads
+            .iter()
+            .filter(|t| t.status == DownloadStatus::Error)
+            .map(|t| t.id)
+            .collect();
+
+        let mut retried = 0;
+        for id in failed {
+            if self.start_download(id, script_sender.clone(), config.clone()).await.is_ok() {
+                retried += 1;
+            }
+        }
+
+        retried
+    }
+
     /// Stop (pause) all downloading tasks in a specific folder
     /// Returns the number of tasks stopped
     pub async fn stop_folder_tasks(&self, folder_id: &str) -> usize {
@@ -1338,12 +2563,14 @@ impl DownloadManager {
         script_sender: Option<mpsc::Sender<ScriptRequest>>,
         config: Arc<tokio::sync::RwLock<crate::app::config::Config>>,
     ) -> usize {
-        let downloads = self.get_all_downloads().await;
-        let pending: Vec<Uuid> = downloads
-            .iter()
+        let mut pending: Vec<DownloadTask> = self
+            .get_all_downloads()
+            .await
+            .into_iter()
             .filter(|t| t.status == DownloadStatus::Pending)
-            .map(|t| t.id)
             .collect();
+        super::folder_queue::sort_for_schedule(&mut pending, config.read().await.download.schedule_policy);
+        let pending: Vec<Uuid> = pending.iter().map(|t| t.id).collect();
 
         let mut started = 0;
         for id in pending {
@@ -1397,6 +2624,15 @@ mod tests {
     use crate::app::config::{Config, FolderConfig};
     use std::collections::HashMap;
 
+    #[test]
+    fn test_http_client_is_shared() {
+        let manager = DownloadManager::new().with_http_client(HttpClient::new().unwrap());
+        let clone = manager.clone();
+        // Downloads get clones of this Arc, never a freshly built client
+        assert!(Arc::ptr_eq(&manager.http_client(), &clone.http_client()));
+        assert!(Arc::ptr_eq(&manager.http_client(), &manager.http_client));
+    }
+
     #[tokio::test]
     async fn test_compute_effective_script_files_application_only() {
         // Setup: Application-level scripts only
@@ -1606,6 +2842,1088 @@ mod tests {
         assert_eq!(retrieved_task.status, DownloadStatus::Pending);
     }
 
+    #[tokio::test]
+    async fn test_manual_retry_resets_retry_budget() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(1, 1, 1, 2, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        // Each attempt sends exactly one HEAD request
+        let wait_for_error = async || {
+            for _ in 0..200 {
+                if manager.get_by_id(task_id).await.is_some_and(|t| t.status == DownloadStatus::Error) {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            panic!("download did not fail");
+        };
+        let head_count = async || {
+            mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|r| r.method.as_str() == "HEAD")
+                .count()
+        };
+
+        manager.start_download(task_id, None, config.clone()).await.unwrap();
+        wait_for_error().await;
+        assert_eq!(head_count().await, 2);
+        assert_eq!(manager.get_by_id(task_id).await.unwrap().retry_count, 2);
+
+        // A manual retry gets the full budget again instead of giving up after one attempt
+        manager.start_download(task_id, None, config).await.unwrap();
+        wait_for_error().await;
+        assert_eq!(head_count().await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_pause_interrupts_blocked_read() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Sends the first KB of a 1 MB body, then stalls
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sent = Arc::new(tokio::sync::Notify::new());
+        let notify = sent.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let notify = notify.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let head = b"HTTP/1.1 200 OK\r\nContent-Length: 1048576\r\n\r\n";
+                        if socket.write_all(head).await.is_err() {
+                            break;
+                        }
+                        if buf.starts_with(b"GET") {
+                            let _ = socket.write_all(&[b'x'; 1024]).await;
+                            notify.notify_one();
+                            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                        }
+                    }
+                });
+            }
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(1, 1, 1, 0, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new(format!("http://{}/slow.bin", addr), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+        manager.start_download(task_id, None, config).await.unwrap();
+
+        // Wait until the first KB is in and the read is blocked
+        let file_size = || -> u64 {
+            fn walk(dir: &std::path::Path) -> u64 {
+                std::fs::read_dir(dir)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|entry| {
+                        let path = entry.path();
+                        if path.is_dir() { walk(&path) } else { entry.metadata().map_or(0, |m| m.len()) }
+                    })
+                    .sum()
+            }
+            walk(temp_dir.path())
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), sent.notified()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(file_size(), 0, "first KB should still be buffered");
+
+        let started = std::time::Instant::now();
+        manager.pause_download(task_id).await.unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_millis(500), "pause took {:?}", started.elapsed());
+
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(task.status, DownloadStatus::Paused);
+        assert_eq!(manager.get_active_count().await, 0);
+        // Stopped rather than aborted: the received data was flushed for resume
+        assert_eq!(file_size(), 1024);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(1, 1, 1, 3, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new(format!("{}/missing.bin", mock_server.uri()), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        let wait_for_error = async || {
+            for _ in 0..200 {
+                if let Some(task) = manager.get_by_id(task_id).await.filter(|t| t.status == DownloadStatus::Error) {
+                    return task;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            panic!("download did not fail");
+        };
+        let get_count = async || {
+            mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|r| r.method.as_str() == "GET")
+                .count()
+        };
+
+        // A 404 fails after one attempt without using up the retry budget
+        manager.start_download(task_id, None, config.clone()).await.unwrap();
+        let task = wait_for_error().await;
+        assert_eq!(get_count().await, 1);
+        assert_eq!(task.retry_count, 0);
+        assert!(task.error_message.unwrap().contains("404"));
+
+        // Listed in retry_on, it is retried like a server error
+        config.write().await.download.retry_on = vec![404];
+        manager.start_download(task_id, None, config).await.unwrap();
+        let task = wait_for_error().await;
+        assert_eq!(get_count().await, 4);
+        assert_eq!(task.retry_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_replaces_backoff() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503).append_header("Retry-After", "0"))
+            .mount(&mock_server)
+            .await;
+
+        // A 60 second backoff would outlast the test; Retry-After: 0 retries at once
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(1, 1, 1, 2, 60);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new(format!("{}/busy.bin", mock_server.uri()), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+        manager.start_download(task_id, None, config).await.unwrap();
+
+        for _ in 0..200 {
+            if manager.get_by_id(task_id).await.is_some_and(|t| t.status == DownloadStatus::Error) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(task.status, DownloadStatus::Error);
+        assert_eq!(task.retry_count, 2);
+        assert!(task.logs.iter().any(|l| l.message == "Server asked to wait 0 seconds (Retry-After)"));
+    }
+
+    #[tokio::test]
+    async fn test_raising_limits_starts_waiting_downloads() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Slow responses keep every started download busy for the whole test
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(1, 1, 1, 0, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        for i in 0..3 {
+            let task = DownloadTask::new(format!("{}/file{}.bin", mock_server.uri(), i), temp_dir.path().to_path_buf());
+            let task_id = task.id;
+            manager.add_download(task).await;
+            manager.start_download(task_id, None, config.clone()).await.unwrap();
+        }
+
+        let wait_for_requests = async |expected: usize| {
+            for _ in 0..200 {
+                if mock_server.received_requests().await.unwrap().len() >= expected {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            mock_server.received_requests().await.unwrap().len()
+        };
+
+        // Only one slot: the other two wait
+        assert_eq!(wait_for_requests(1).await, 1);
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+
+        // Raising both limits lets the waiting downloads start without a restart
+        manager.set_max_concurrent(3).await;
+        manager.set_max_concurrent_per_folder(3).await;
+        assert_eq!(wait_for_requests(3).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_caps_running_downloads() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+
+        // Three download slots but only two connections, a config that validates
+        let mut config = Config::default();
+        config.download.max_concurrent = 3;
+        config.download.max_concurrent_per_folder = Some(3);
+        config.download.parallel_folder_count = Some(1);
+        config.download.max_connections = Some(2);
+        assert!(crate::app::settings::validate_folder_config(&config).is_ok());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::with_config(3, 3, 1, 0, 0);
+        manager.set_max_connections(config.download.max_connections.unwrap());
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+        for i in 0..3 {
+            let task = DownloadTask::new(format!("{}/file{}.bin", mock_server.uri(), i), temp_dir.path().to_path_buf());
+            let task_id = task.id;
+            manager.add_download(task).await;
+            manager.start_download(task_id, None, config.clone()).await.unwrap();
+        }
+
+        for _ in 0..200 {
+            if mock_server.received_requests().await.unwrap().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 2);
+        assert_eq!(manager.max_connections(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[serial_test::serial]
+    async fn test_slots_go_to_downloads_in_schedule_order() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // The first download holds the only slot for a while; every download fails at once after that
+        let mock_server = MockServer::start().await;
+        Mock::given(path("/blocker.bin"))
+            .respond_with(ResponseTemplate::new(404).set_delay(std::time::Duration::from_millis(300)))
+            .mount(&mock_server)
+            .await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        let manager = DownloadManager::with_config(1, 1, 1, 0, 0);
+        let mut config = Config::default();
+        config.download.schedule_policy = crate::app::config::SchedulePolicy::Priority;
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+
+        let blocker = DownloadTask::new(format!("{}/blocker.bin", mock_server.uri()), temp_dir.path().to_path_buf());
+        let blocker_id = blocker.id;
+        manager.add_download(blocker).await;
+        manager.start_download(blocker_id, None, config.clone()).await.unwrap();
+        for priority in [1, 5, 3, 4, 2] {
+            let mut task = DownloadTask::new(format!("{}/p{}.bin", mock_server.uri(), priority), temp_dir.path().to_path_buf());
+            task.priority = priority;
+            manager.add_download(task).await;
+        }
+        manager.start_all_tasks(None, config).await;
+
+        let mut order: Vec<String> = Vec::new();
+        for _ in 0..300 {
+            order = mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|request| request.method == wiremock::http::Method::HEAD)
+                .map(|request| request.url.path().trim_start_matches('/').to_string())
+                .collect();
+            if order.len() == 6 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        crate::util::paths::set_config_dir_override(None);
+
+        // Waiting downloads get the freed slot highest priority first
+        assert_eq!(order, ["blocker.bin", "p5.bin", "p4.bin", "p3.bin", "p2.bin", "p1.bin"]);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_waiting_folders_take_turns_for_free_slots() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Every download fails at once, freeing its folder slot
+        let mock_server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        // One folder slot shared by three folders
+        let manager = DownloadManager::with_config(3, 1, 1, 0, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let mut ids = Vec::new();
+        for folder in ["a", "b", "c"] {
+            let mut task = DownloadTask::new(format!("{}/{}.bin", mock_server.uri(), folder), temp_dir.path().to_path_buf());
+            task.folder_id = folder.to_string();
+            ids.push(task.id);
+            manager.add_download(task).await;
+        }
+
+        manager.start_all_tasks(None, config).await;
+
+        // Each folder gets the slot in turn, not only the first one
+        for _ in 0..300 {
+            let mut done = 0;
+            for id in &ids {
+                if manager.get_by_id(*id).await.is_some_and(|t| t.status == DownloadStatus::Error) {
+                    done += 1;
+                }
+            }
+            if done == ids.len() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        for id in &ids {
+            let task = manager.get_by_id(*id).await.unwrap();
+            assert_eq!(task.status, DownloadStatus::Error, "folder '{}' was never serviced", task.folder_id);
+            assert!(task.held_reason.is_none());
+        }
+        assert!(manager.waiting_folders.lock().unwrap().is_empty());
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_resumed_paused_downloads_wait_for_folder_slot() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Every download fails at once, freeing its folder slot
+        let mock_server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        // One folder slot shared by two folders
+        let manager = DownloadManager::with_config(2, 1, 1, 0, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let mut ids = Vec::new();
+        for folder in ["a", "b"] {
+            let mut task = DownloadTask::new(format!("{}/{}.bin", mock_server.uri(), folder), temp_dir.path().to_path_buf());
+            task.folder_id = folder.to_string();
+            task.status = DownloadStatus::Paused;
+            ids.push(task.id);
+            manager.add_download(task).await;
+        }
+
+        manager.resume_all(None, config).await;
+
+        // The folder refused a slot starts once the first one is done
+        for _ in 0..300 {
+            let mut done = 0;
+            for id in &ids {
+                if manager.get_by_id(*id).await.is_some_and(|t| t.status == DownloadStatus::Error) {
+                    done += 1;
+                }
+            }
+            if done == ids.len() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        for id in &ids {
+            let task = manager.get_by_id(*id).await.unwrap();
+            assert_eq!(task.status, DownloadStatus::Error, "folder '{}' was never resumed", task.folder_id);
+            assert!(task.held_reason.is_none());
+        }
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_pausing_a_folder_hands_its_slot_on() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Slow responses keep every started download busy for the whole test
+        let mock_server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(temp_dir.path().to_path_buf()));
+        // One folder slot shared by two folders
+        let manager = DownloadManager::with_config(2, 1, 1, 0, 0);
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let mut ids = Vec::new();
+        for folder in ["a", "b"] {
+            let mut task = DownloadTask::new(format!("{}/{}.bin", mock_server.uri(), folder), temp_dir.path().to_path_buf());
+            task.folder_id = folder.to_string();
+            ids.push(task.id);
+            manager.add_download(task).await;
+        }
+        manager.start_download(ids[0], None, config.clone()).await.unwrap();
+        assert!(manager.start_download(ids[1], None, config).await.is_err());
+
+        // Pausing folder a's only download lets folder b start
+        manager.pause_download(ids[0]).await.unwrap();
+        for _ in 0..300 {
+            if manager.get_by_id(ids[1]).await.is_some_and(|t| t.status == DownloadStatus::Downloading) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let started = manager.get_by_id(ids[1]).await.unwrap();
+        assert_eq!(started.status, DownloadStatus::Downloading);
+        assert!(manager.active_folders.read().await.contains("b"));
+        assert!(!manager.active_folders.read().await.contains("a"));
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    async fn test_before_request_cancel_pauses_without_error() {
+        // Stand-in script executor that cancels every download
+        let (script_tx, script_rx) = mpsc::channel::<ScriptRequest>();
+        std::thread::spawn(move || {
+            while let Ok(request) = script_rx.recv() {
+                if let ScriptRequest::BeforeRequest { mut ctx, response, .. } = request {
+                    ctx.cancel = true;
+                    ctx.cancel_reason = Some("blocked by test".to_string());
+                    let _ = response.send((ctx, Ok(()), Vec::new()));
+                }
+            }
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::new();
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new("http://127.0.0.1:9/file.bin".to_string(), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        manager.start_download(task_id, Some(script_tx), config).await.unwrap();
+
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(task.status, DownloadStatus::Paused);
+        assert!(task.error_message.is_none());
+        assert_eq!(task.retry_count, 0);
+        assert!(task.logs.iter().any(|l| l.message.contains("blocked by test")));
+        assert_eq!(manager.get_active_count().await, 0);
+        assert!(manager.active_folders.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_applies_scripts_and_routing() {
+        // Stand-in script executor that points the download at a mirror
+        let (script_tx, script_rx) = mpsc::channel::<ScriptRequest>();
+        std::thread::spawn(move || {
+            while let Ok(request) = script_rx.recv() {
+                if let ScriptRequest::BeforeRequest { mut ctx, response, .. } = request {
+                    ctx.url = ctx.url.replace("example.com", "mirror.example.net");
+                    ctx.headers.insert("X-Token".to_string(), "abc".to_string());
+                    let _ = response.send((ctx, Ok(()), Vec::new()));
+                }
+            }
+        });
+
+        let default_dir = tempfile::tempdir().unwrap();
+        let images_dir = tempfile::tempdir().unwrap();
+        std::fs::write(images_dir.path().join("photo.jpg"), b"existing").unwrap();
+
+        let mut config = Config::default();
+        config.folders.insert(
+            "images".to_string(),
+            FolderConfig { save_path: images_dir.path().to_path_buf(), ..Default::default() },
+        );
+        config.routing.rules.push(crate::app::config::RoutingRule {
+            folder: "images".to_string(),
+            extensions: vec!["jpg".to_string()],
+            content_types: Vec::new(),
+            hosts: Vec::new(),
+        });
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+
+        let manager = DownloadManager::new();
+        let task = DownloadTask::new("https://example.com/photo.jpg".to_string(), default_dir.path().to_path_buf());
+        let report = manager.dry_run(task, Some(script_tx), config).await.unwrap();
+
+        assert_eq!(report.url, "https://mirror.example.net/photo.jpg");
+        assert!(report.headers.contains(&("x-token".to_string(), "abc".to_string())));
+        assert_eq!(report.folder_id, "images");
+        assert_eq!(report.routing_rule, Some(1));
+        // The existing file would not be overwritten
+        assert_ne!(report.filename, "photo.jpg");
+        assert_eq!(report.target_path, images_dir.path().join(&report.filename));
+        assert!(report.cancelled.is_none());
+
+        // Nothing was queued or written
+        assert!(manager.get_all_downloads().await.is_empty());
+        assert_eq!(std::fs::read_dir(images_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_temp_dir_holds_partial_until_complete() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("finished body"))
+            .mount(&mock_server)
+            .await;
+
+        let save_dir = tempfile::tempdir().unwrap();
+        let scratch = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.download.temp_dir = Some(scratch.path().join("parts"));
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+
+        let manager = DownloadManager::new();
+        let task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), save_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+        manager.start_download(task_id, None, config).await.unwrap();
+
+        let target = save_dir.path().join("file.bin");
+        for _ in 0..100 {
+            if target.exists() && manager.get_active_count().await == 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "finished body");
+        // The partial file was moved, not copied
+        assert_eq!(std::fs::read_dir(scratch.path().join("parts")).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_duplicate_content_is_deleted_on_completion() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("same body"))
+            .mount(&mock_server)
+            .await;
+
+        let save_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.download.on_duplicate_content = Some(crate::app::config::DuplicateContentAction::Delete);
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+        let manager = DownloadManager::new();
+
+        for name in ["first.bin", "second.bin"] {
+            let task = DownloadTask::new(format!("{}/{}", mock_server.uri(), name), save_dir.path().to_path_buf());
+            let task_id = task.id;
+            manager.add_download(task).await;
+            manager.start_download(task_id, None, config.clone()).await.unwrap();
+            // Completed tasks leave the queue once the duplicate check has run
+            let mut completed = false;
+            for _ in 0..100 {
+                if manager.get_by_id(task_id).await.is_none() {
+                    completed = true;
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            assert!(completed, "{} did not complete", name);
+        }
+
+        assert!(save_dir.path().join("first.bin").exists());
+        assert!(!save_dir.path().join("second.bin").exists());
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_quota_held_download_starts_when_room_frees() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("body")
+                    .set_delay(std::time::Duration::from_millis(300)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let save_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        let folder = crate::app::config::FolderConfig { max_queued: Some(1), ..Default::default() };
+        config.folders.insert("default".to_string(), folder);
+        let manager = DownloadManager::new();
+        manager.set_folder_quotas(&config);
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+
+        let first = DownloadTask::new(format!("{}/first.bin", mock_server.uri()), save_dir.path().to_path_buf());
+        let second = DownloadTask::new(format!("{}/second.bin", mock_server.uri()), save_dir.path().to_path_buf());
+        let (first_id, second_id) = (first.id, second.id);
+        manager.add_download(first).await;
+        // The folder already has one queued download
+        manager.add_download(second).await;
+        let held = manager.get_by_id(second_id).await.unwrap();
+        assert!(held.held_reason.as_deref().is_some_and(|r| r.contains("max_queued = 1")), "{:?}", held.held_reason);
+
+        manager.start_download(first_id, None, config.clone()).await.unwrap();
+        manager.start_download(second_id, None, config.clone()).await.unwrap();
+        assert_eq!(manager.get_by_id(second_id).await.unwrap().status, DownloadStatus::Pending);
+
+        // Once the first finishes, the held one starts and finishes without another start
+        let mut completed = false;
+        for _ in 0..100 {
+            if manager.get_by_id(first_id).await.is_none() && manager.get_by_id(second_id).await.is_none() {
+                completed = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        crate::util::paths::set_config_dir_override(None);
+        assert!(completed, "held download did not start after room freed");
+        assert!(save_dir.path().join("second.bin").exists());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_size_confirmation_checks_get_response() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        // The HEAD answer is under the threshold; the GET is not
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).append_header("Content-Length", "5"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("a body of 24 bytes total"))
+            .mount(&mock_server)
+            .await;
+
+        let save_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.download.confirm_over_bytes = Some(10);
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+        let manager = DownloadManager::new();
+        let task = DownloadTask::new(format!("{}/sneaky.bin", mock_server.uri()), save_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        manager.start_download(task_id, None, config).await.unwrap();
+        for _ in 0..100 {
+            if manager.get_by_id(task_id).await.is_some_and(|t| t.awaiting_confirmation) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        crate::util::paths::set_config_dir_override(None);
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert!(task.awaiting_confirmation);
+        assert_eq!(task.status, DownloadStatus::Paused);
+        assert_eq!(task.size, Some(24));
+        assert!(!save_dir.path().join("sneaky.bin").exists());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_large_download_waits_for_confirmation() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        let body = "a body of 24 bytes total";
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).append_header("Content-Length", body.len().to_string()))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(&mock_server)
+            .await;
+
+        let save_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.download.confirm_over_bytes = Some(10);
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+        let manager = DownloadManager::new();
+        let task = DownloadTask::new(format!("{}/big.bin", mock_server.uri()), save_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        manager.start_download(task_id, None, config.clone()).await.unwrap();
+        for _ in 0..100 {
+            if manager.get_by_id(task_id).await.is_some_and(|t| t.awaiting_confirmation) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert!(task.awaiting_confirmation);
+        assert_eq!(task.status, DownloadStatus::Paused);
+        assert!(!save_dir.path().join("big.bin").exists());
+
+        // Bulk resume leaves it alone; confirming lets it finish
+        assert_eq!(manager.resume_all(None, config.clone()).await, 0);
+        manager.confirm_download(task_id).await.unwrap();
+        manager.start_download(task_id, None, config).await.unwrap();
+        for _ in 0..100 {
+            if manager.get_active_count().await == 0 && manager.get_by_id(task_id).await.is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(std::fs::read_to_string(save_dir.path().join("big.bin")).unwrap(), body);
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_fast_for_ftp() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::new();
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new("ftp://example.com/a.zip".to_string(), dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        let err = manager.start_download(task_id, None, config).await.unwrap_err();
+        assert!(err.to_string().contains("ftp:// downloads are not supported"), "{}", err);
+
+        let task = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(task.status, DownloadStatus::Error);
+        assert_eq!(task.retry_count, 0);
+        assert_eq!(manager.get_active_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_rotation_yields_to_explicit_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.download.user_agent_rotate = vec!["A/1.0".to_string(), "B/1.0".to_string(), "C/1.0".to_string()];
+        config.folders.insert(
+            "pinned".to_string(),
+            FolderConfig { user_agent: Some("Folder/1.0".to_string()), ..Default::default() },
+        );
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+
+        let manager = DownloadManager::new().with_user_agent_chooser(Arc::new(|len| len + 1));
+        let task = || DownloadTask::new("https://example.com/a.bin".to_string(), dir.path().to_path_buf());
+
+        // Index wraps into the list: (3 + 1) % 3 = 1
+        let report = manager.dry_run(task(), None, config.clone()).await.unwrap();
+        assert_eq!(report.user_agent, "B/1.0");
+        assert!(report.headers.contains(&("user-agent".to_string(), "B/1.0".to_string())));
+
+        let mut own = task();
+        own.user_agent = Some("Task/1.0".to_string());
+        let report = manager.dry_run(own, None, config.clone()).await.unwrap();
+        assert_eq!(report.user_agent, "Task/1.0");
+
+        let mut foldered = task();
+        foldered.folder_id = "pinned".to_string();
+        let report = manager.dry_run(foldered, None, config.clone()).await.unwrap();
+        assert_eq!(report.user_agent, "Folder/1.0");
+        assert!(report.headers.contains(&("user-agent".to_string(), "Folder/1.0".to_string())));
+
+        // An empty list leaves the app-wide user agent in place
+        config.write().await.download.user_agent_rotate.clear();
+        let report = manager.dry_run(task(), None, config.clone()).await.unwrap();
+        let app_agent = config.read().await.download.user_agent.clone();
+        assert_eq!(report.user_agent, app_agent);
+        assert!(report.headers.contains(&("user-agent".to_string(), app_agent)));
+    }
+
+    #[tokio::test]
+    async fn test_body_peek_reaches_hooks() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<!DOCTYPE html><p>Login required</p>"))
+            .mount(&mock_server)
+            .await;
+
+        // Stand-in script executor that records what the hooks saw
+        let (script_tx, script_rx) = mpsc::channel::<ScriptRequest>();
+        let (seen_tx, seen_rx) = mpsc::channel::<(&'static str, Option<String>)>();
+        std::thread::spawn(move || {
+            while let Ok(request) = script_rx.recv() {
+                match request {
+                    ScriptRequest::BeforeRequest { ctx, response, .. } => {
+                        let _ = response.send((ctx, Ok(()), Vec::new()));
+                    }
+                    ScriptRequest::HeadersReceived { ctx, response, .. } => {
+                        let _ = seen_tx.send(("headersReceived", ctx.body_peek));
+                        let _ = response.send((Ok(()), Vec::new()));
+                    }
+                    ScriptRequest::Completed { ctx, response, .. } => {
+                        let _ = seen_tx.send(("completed", ctx.body_peek.clone()));
+                        let _ = response.send((ctx, Ok(()), Vec::new()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.scripts.body_peek_bytes = 15;
+        let config = Arc::new(tokio::sync::RwLock::new(config));
+        let manager = DownloadManager::new();
+        let task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), temp_dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        manager.start_download(task_id, Some(script_tx), config).await.unwrap();
+
+        let recv = || seen_rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert_eq!(recv(), ("headersReceived", Some("<!DOCTYPE html>".to_string())));
+        assert_eq!(recv(), ("completed", Some("<!DOCTYPE html>".to_string())));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_load_queue_recovers_orphaned_downloading_tasks() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let save_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        // Resumable task with a partial file on disk
+        let mut partial = DownloadTask::new("https://example.com/partial.bin".to_string(), save_dir.path().to_path_buf());
+        partial.status = DownloadStatus::Downloading;
+        partial.resume_supported = true;
+        std::fs::write(save_dir.path().join(&partial.filename), vec![0u8; 1234]).unwrap();
+
+        // Task in an auto-start folder that never wrote anything
+        let mut fresh = DownloadTask::new("https://example.com/fresh.bin".to_string(), save_dir.path().to_path_buf());
+        fresh.folder_id = "auto".to_string();
+        fresh.status = DownloadStatus::Downloading;
+
+        let queue = DownloadQueue::new();
+        queue.add(partial.clone()).await;
+        queue.add(fresh.clone()).await;
+        queue.save_to_folder_files().await.unwrap();
+
+        let mut config = Config::default();
+        config.folders.insert(
+            "auto".to_string(),
+            FolderConfig {
+                save_path: save_dir.path().to_path_buf(),
+                auto_start_downloads: true,
+                ..Default::default()
+            },
+        );
+
+        let manager = DownloadManager::new();
+        manager.load_queue_from_folders(&config, true).await.unwrap();
+        crate::util::paths::set_config_dir_override(None);
+
+        let partial = manager.get_by_id(partial.id).await.unwrap();
+        assert_eq!(partial.status, DownloadStatus::Paused);
+        assert_eq!(partial.downloaded, 1234);
+
+        let fresh = manager.get_by_id(fresh.id).await.unwrap();
+        assert_eq!(fresh.status, DownloadStatus::Pending);
+        assert_eq!(fresh.downloaded, 0);
+
+        // Alongside another instance its downloads are left running
+        let alongside = DownloadManager::new();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+        alongside.load_queue_from_folders(&config, false).await.unwrap();
+        crate::util::paths::set_config_dir_override(None);
+        assert_eq!(alongside.get_by_id(partial.id).await.unwrap().status, DownloadStatus::Downloading);
+        assert!(alongside.folder_queues.read().await.values().all(|queue| !queue.is_dirty()));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_loading_queue_does_not_rewrite_folders() {
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        let mut image = DownloadTask::new("https://example.com/a.png".to_string(), config_dir.path().to_path_buf());
+        image.folder_id = "images".to_string();
+        let queue = DownloadQueue::new();
+        queue.add(image).await;
+        queue.add(DownloadTask::new("https://example.com/b.bin".to_string(), config_dir.path().to_path_buf())).await;
+        queue.save_to_folder_files().await.unwrap();
+
+        let manager = DownloadManager::new();
+        manager.load_queue_from_folders(&Config::default(), true).await.unwrap();
+
+        // Backdate the files so any rewrite shows up in their modification time
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        let paths = ["images", "default"].map(|folder| config_dir.path().join(folder).join("queue.toml"));
+        for path in &paths {
+            std::fs::File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+        }
+
+        manager.save_queue_to_folders().await.unwrap();
+        crate::util::paths::set_config_dir_override(None);
+
+        for path in &paths {
+            assert_eq!(std::fs::metadata(path).unwrap().modified().unwrap(), old, "{} was rewritten", path.display());
+        }
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_second_instance_does_not_overwrite_newer_queue_file() {
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+        let queue_path = config_dir.path().join("default").join("queue.toml");
+
+        let seed_task = DownloadTask::new("https://example.com/seed.bin".to_string(), config_dir.path().to_path_buf());
+        let seed = DownloadQueue::new();
+        seed.add(seed_task.clone()).await;
+        seed.save_to_folder_files().await.unwrap();
+
+        // Two instances read the same file, then both change it
+        let first = DownloadManager::new();
+        first.load_queue_from_folders(&Config::default(), true).await.unwrap();
+        let second = DownloadManager::new();
+        second.load_queue_from_folders(&Config::default(), false).await.unwrap();
+        let legacy = DownloadQueue::new();
+        legacy.load_from_folder_files().await.unwrap();
+
+        first.add_download(DownloadTask::new("https://example.com/first.bin".to_string(), config_dir.path().to_path_buf())).await;
+        first.save_queue_to_folders().await.unwrap();
+        let written = std::fs::read_to_string(&queue_path).unwrap();
+
+        second.add_download(DownloadTask::new("https://example.com/second.bin".to_string(), config_dir.path().to_path_buf())).await;
+        second.save_queue_to_folders().await.unwrap();
+        legacy.remove(seed_task.id).await;
+        legacy.save_to_folder_files().await.unwrap();
+        crate::util::paths::set_config_dir_override(None);
+
+        assert_eq!(std::fs::read_to_string(&queue_path).unwrap(), written);
+        assert!(written.contains("first.bin"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_migrate_legacy_queue_splits_by_folder() {
+        let config_dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(config_dir.path().to_path_buf()));
+
+        let mut first = DownloadTask::new("https://example.com/a.bin".to_string(), config_dir.path().to_path_buf());
+        first.folder_id = "images".to_string();
+        let second = DownloadTask::new("https://example.com/b.bin".to_string(), config_dir.path().to_path_buf());
+        let legacy_path = config_dir.path().join("queue.json");
+        std::fs::write(&legacy_path, serde_json::to_string(&vec![first.clone(), second.clone()]).unwrap()).unwrap();
+
+        let config = Config::default();
+        let manager = DownloadManager::new();
+        manager.load_queue_from_folders(&config, true).await.unwrap();
+        let migrated = manager.migrate_legacy_queue(&legacy_path, &config).await.unwrap();
+
+        assert_eq!(migrated, 2);
+        assert!(!legacy_path.exists());
+        assert!(config_dir.path().join("queue.json.migrated").exists());
+        assert!(config_dir.path().join("images").join("queue.toml").exists());
+        assert!(config_dir.path().join("default").join("queue.toml").exists());
+
+        // A second legacy file is ignored once folder queues hold data
+        std::fs::write(&legacy_path, serde_json::to_string(&vec![first]).unwrap()).unwrap();
+        let reloaded = DownloadManager::new();
+        reloaded.load_queue_from_folders(&config, true).await.unwrap();
+        assert_eq!(reloaded.migrate_legacy_queue(&legacy_path, &config).await.unwrap(), 0);
+        assert!(legacy_path.exists());
+        assert_eq!(reloaded.get_all_downloads().await.len(), 2);
+
+        crate::util::paths::set_config_dir_override(None);
+    }
+
     #[tokio::test]
     async fn test_add_download_sanitizes_filename() {
         use std::path::PathBuf;
@@ -1652,6 +3970,193 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_change_folder_moves_task_between_queues() {
+        use std::path::PathBuf;
+        let manager = DownloadManager::new();
+        let mut task = DownloadTask::new("https://example.com/a.bin".to_string(), PathBuf::from("/tmp/dl"));
+        task.folder_id = "images".to_string();
+        let id = task.id;
+        manager.add_download(task).await;
+
+        manager.change_folder(id, "videos".to_string()).await.unwrap();
+
+        assert!(manager.get_folder_downloads("images").await.is_empty());
+        let moved = manager.get_folder_downloads("videos").await;
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].folder_id, "videos");
+        // Only the folder changes; the save path stays as it was
+        assert_eq!(moved[0].save_path, PathBuf::from("/tmp/dl"));
+        assert_eq!(manager.get_folder_counts("videos").await.pending, 1);
+        assert_eq!(manager.get_folder_counts("images").await.pending, 0);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_move_task_to_folder_updates_both_queues() {
+        use crate::app::config::{Config, FolderConfig};
+        use std::path::PathBuf;
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(dir.path().to_path_buf()));
+
+        let mut config = Config::default();
+        config.folders.insert(
+            "videos".to_string(),
+            FolderConfig { save_path: PathBuf::from("/data/videos"), ..Default::default() },
+        );
+
+        let manager = DownloadManager::new();
+        let mut moved = DownloadTask::new("https://example.com/a.bin".to_string(), PathBuf::from("/tmp/dl"));
+        moved.folder_id = "images".to_string();
+        let mut started = DownloadTask::new("https://example.com/b.bin".to_string(), PathBuf::from("/tmp/dl"));
+        started.folder_id = "images".to_string();
+        started.status = DownloadStatus::Paused;
+        started.downloaded = 1024;
+        let (moved_id, started_id) = (moved.id, started.id);
+        manager.add_download(moved).await;
+        manager.add_download(started).await;
+
+        let task = manager.move_task_to_folder(moved_id, "videos", Some(&config)).await.unwrap();
+        assert_eq!(task.folder_id, "videos");
+        assert_eq!(task.save_path, PathBuf::from("/data/videos"));
+        assert_eq!(manager.get_folder_counts("images").await.pending, 0);
+        assert_eq!(manager.get_folder_counts("videos").await.pending, 1);
+        assert_eq!(manager.get_folder_downloads("images").await.len(), 1);
+
+        // A partially downloaded task keeps its path
+        let task = manager.move_task_to_folder(started_id, "videos", Some(&config)).await.unwrap();
+        assert_eq!(task.save_path, PathBuf::from("/tmp/dl"));
+        assert!(manager.get_folder_downloads("images").await.is_empty());
+        assert_eq!(manager.get_folder_downloads("videos").await.len(), 2);
+
+        // Both queue files reflect the move
+        let images = FolderQueue::new("images", 1);
+        images.load().await.unwrap();
+        assert!(images.get_all().await.is_empty());
+        let videos = FolderQueue::new("videos", 1);
+        videos.load().await.unwrap();
+        assert_eq!(videos.get_all().await.len(), 2);
+
+        assert!(manager.move_task_to_folder(Uuid::new_v4(), "videos", None).await.is_err());
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_move_task_that_failed() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(dir.path().to_path_buf()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let manager = DownloadManager::new();
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let mut task = DownloadTask::new(format!("{}/missing.bin", mock_server.uri()), dir.path().to_path_buf());
+        task.folder_id = "images".to_string();
+        let task_id = task.id;
+        manager.add_download(task).await;
+        manager.start_download(task_id, None, config).await.unwrap();
+
+        let mut failed = false;
+        for _ in 0..100 {
+            failed = manager.get_by_id(task_id).await.is_some_and(|t| t.status == DownloadStatus::Error);
+            if failed && manager.get_active_count().await == 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert!(failed, "download did not fail");
+        assert_eq!(manager.get_active_count().await, 0);
+        assert!(!manager.has_active_downloads().await);
+
+        // The exited download no longer counts as active
+        let task = manager.move_task_to_folder(task_id, "videos", None).await.unwrap();
+        assert_eq!(task.folder_id, "videos");
+        assert_eq!(task.status, DownloadStatus::Error);
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_held_downloads_start_when_released() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(dir.path().to_path_buf()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let manager = DownloadManager::new();
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        manager.hold_new_downloads("on battery");
+        manager.start_download(task_id, None, config.clone()).await.unwrap();
+        let held = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(held.status, DownloadStatus::Pending);
+        assert_eq!(held.held_reason.as_deref(), Some("on battery"));
+        assert_eq!(manager.get_active_count().await, 0);
+
+        assert_eq!(manager.release_held_downloads("on battery", None, config.clone()).await, 1);
+        let started = manager.get_by_id(task_id).await.unwrap();
+        assert_ne!(started.status, DownloadStatus::Pending);
+        assert!(started.held_reason.is_none());
+        // Nothing is held any more
+        assert_eq!(manager.release_held_downloads("on battery", None, config).await, 0);
+        crate::util::paths::set_config_dir_override(None);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_download_resumed_during_hold_starts_when_released() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(dir.path().to_path_buf()));
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let manager = DownloadManager::new();
+        let config = Arc::new(tokio::sync::RwLock::new(Config::default()));
+        let mut task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), dir.path().to_path_buf());
+        task.status = DownloadStatus::Paused;
+        let task_id = task.id;
+        manager.add_download(task).await;
+
+        // Resuming while held keeps the task paused, waiting for the hold to end
+        manager.hold_new_downloads("quiet window");
+        manager.start_download(task_id, None, config.clone()).await.unwrap();
+        let held = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(held.status, DownloadStatus::Paused);
+        assert_eq!(held.held_reason.as_deref(), Some("quiet window"));
+
+        assert_eq!(manager.release_held_downloads("quiet window", None, config).await, 1);
+        let started = manager.get_by_id(task_id).await.unwrap();
+        assert_ne!(started.status, DownloadStatus::Paused);
+        assert!(started.held_reason.is_none());
+        crate::util::paths::set_config_dir_override(None);
+    }
+
     #[tokio::test]
     async fn test_get_all_downloads_empty() {
         let manager = DownloadManager::new();
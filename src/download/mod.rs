@@ -1,9 +1,16 @@
 pub mod circuit_breaker;
 pub mod completion_log;
+pub mod concurrency;
+pub mod dedup;
 pub mod folder_queue;
 pub mod history;
 pub mod http_client;
 pub mod http_errors;
+pub mod lifetime;
 pub mod manager;
 pub mod queue;
+pub mod rate_limit;
+pub mod routing;
+pub mod stats;
 pub mod task;
+pub mod trash;
@@ -1,7 +1,8 @@
 use super::task::DownloadTask;
+use crate::util::file_lock::{content_stamp, file_stamp, queue_lock_path, FileLock, QUEUE_LOCK_TIMEOUT};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
 /// Wrapper for TOML serialization (TOML requires root to be a table, not an array)
@@ -13,15 +14,41 @@ struct QueueFile {
 #[derive(Clone)]
 pub struct DownloadQueue {
     pub(crate) tasks: Arc<RwLock<VecDeque<DownloadTask>>>,
+    /// `content_stamp` of each folder's queue file as last read or written here
+    file_stamps: Arc<Mutex<HashMap<String, u64>>>,
 }
 
 impl DownloadQueue {
     pub fn new() -> Self {
         Self {
             tasks: Arc::new(RwLock::new(VecDeque::new())),
+            file_stamps: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Stamps of the folder queue files read by `load_from_folder_files`, by folder ID
+    pub fn file_stamps(&self) -> HashMap<String, u64> {
+        self.file_stamps.lock().unwrap().clone()
+    }
+
+    /// True if another instance replaced `folder_id`'s queue file since it
+    /// was last read or written here
+    async fn changed_elsewhere(
+        &self,
+        folder_id: &str,
+        queue_path: &std::path::Path,
+    ) -> anyhow::Result<bool> {
+        let expected = self.file_stamps.lock().unwrap().get(folder_id).copied();
+        let changed = file_stamp(queue_path).await? != expected;
+        if changed {
+            tracing::warn!(
+                "Queue file {} was changed by another ggg instance; not overwritten",
+                queue_path.display()
+            );
+        }
+        Ok(changed)
+    }
+
     pub async fn add(&self, task: DownloadTask) {
         let mut tasks = self.tasks.write().await;
         tasks.push_back(task);
@@ -82,7 +109,8 @@ impl DownloadQueue {
 
     /// Save queue partitioned by folder_id to folder-specific TOML files
     ///
-    /// Each folder gets its own queue.toml file in {config_dir}/{folder_id}/queue.toml
+    /// Each folder gets its own queue.toml file in {config_dir}/{folder_id}/queue.toml.
+    /// Files another instance changed since `load_from_folder_files` are left as they are.
     ///
     /// # Errors
     ///
@@ -118,6 +146,17 @@ impl DownloadQueue {
 
                 // If queue.toml exists but folder has no tasks, delete it
                 if queue_file.exists() && !by_folder.contains_key(&folder_id) {
+                    let lock_path = queue_lock_path(&queue_file);
+                    let Ok(Some(_lock)) = FileLock::exclusive(&lock_path, QUEUE_LOCK_TIMEOUT).await else {
+                        tracing::warn!(
+                            "Queue file {} is locked by another ggg instance; not removed",
+                            queue_file.display()
+                        );
+                        continue;
+                    };
+                    if self.changed_elsewhere(&folder_id, &queue_file).await? {
+                        continue;
+                    }
                     if let Err(e) = tokio::fs::remove_file(&queue_file).await {
                         tracing::warn!(
                             "Failed to remove old queue file {}: {}",
@@ -125,6 +164,7 @@ impl DownloadQueue {
                             e
                         );
                     } else {
+                        self.file_stamps.lock().unwrap().remove(&folder_id);
                         tracing::debug!(
                             "Removed queue file for folder with no tasks: {}",
                             queue_file.display()
@@ -143,6 +183,19 @@ impl DownloadQueue {
                 tokio::fs::create_dir_all(parent).await?;
             }
 
+            // Another instance writing the same file: skip rather than clobber it
+            let Some(_lock) = FileLock::exclusive(&queue_lock_path(&queue_path), QUEUE_LOCK_TIMEOUT).await? else {
+                tracing::warn!(
+                    "Queue file {} is locked by another ggg instance; {} task(s) not saved",
+                    queue_path.display(),
+                    folder_tasks.len()
+                );
+                continue;
+            };
+            if self.changed_elsewhere(&folder_id, &queue_path).await? {
+                continue;
+            }
+
             // Serialize to TOML (wrap in QueueFile to satisfy TOML root table requirement)
             let task_count = folder_tasks.len();
             let queue_file = QueueFile { tasks: folder_tasks };
@@ -150,8 +203,12 @@ impl DownloadQueue {
 
             // Atomic write: temp file + rename
             let temp_path = queue_path.with_extension("toml.tmp");
-            tokio::fs::write(&temp_path, toml).await?;
+            tokio::fs::write(&temp_path, &toml).await?;
             tokio::fs::rename(&temp_path, &queue_path).await?;
+            self.file_stamps
+                .lock()
+                .unwrap()
+                .insert(folder_id, content_stamp(toml.as_bytes()));
 
             tracing::debug!(
                 "Saved {} tasks to folder queue: {}",
@@ -176,6 +233,7 @@ impl DownloadQueue {
     pub async fn load_from_folder_files(&self) -> anyhow::Result<()> {
         let config_dir = crate::util::paths::find_config_directory()?;
         let mut all_tasks = VecDeque::new();
+        let mut stamps = HashMap::new();
 
         // Scan for folder subdirectories
         let entries = std::fs::read_dir(&config_dir)?;
@@ -189,9 +247,22 @@ impl DownloadQueue {
                 continue;
             }
 
+            // Writes are atomic renames, so reading without the lock is still safe
+            let lock = FileLock::shared(&queue_lock_path(&queue_file), QUEUE_LOCK_TIMEOUT).await?;
+            if lock.is_none() {
+                tracing::warn!(
+                    "Queue file {} is locked by another ggg instance; loading the last saved version",
+                    queue_file.display()
+                );
+            }
+
             // Load this folder's queue (unwrap QueueFile wrapper)
             let content = tokio::fs::read_to_string(&queue_file).await?;
             let queue_file_data: QueueFile = toml::from_str(&content)?;
+            stamps.insert(
+                entry.file_name().to_string_lossy().to_string(),
+                content_stamp(content.as_bytes()),
+            );
 
             tracing::debug!(
                 "Loaded {} tasks from folder queue: {}",
@@ -203,6 +274,7 @@ impl DownloadQueue {
         }
 
         // Replace in-memory queue
+        *self.file_stamps.lock().unwrap() = stamps;
         let mut queue = self.tasks.write().await;
         *queue = all_tasks;
 
@@ -0,0 +1,290 @@
+//! Global bandwidth limiting
+//!
+//! A single limiter is shared by every download of an `HttpClient`, so the
+//! cap applies to the combined throughput. The limit can be changed at any
+//! time (e.g. by the quiet-window schedule) and takes effect with the next
+//! chunk.
+//!
+//! Each download registers a `Flow`, and chunks are granted by start-time
+//! fair queuing rather than in arrival order: every chunk gets a virtual
+//! start tag (the later of the flow's previous finish tag and the tag of the
+//! chunk last granted), the next chunk granted is the one with the smallest
+//! tag, and a flow's finish tag advances by its chunk size divided by its
+//! weight. Five equal flows under a 5 MB/s cap therefore get about 1 MB/s
+//! each whatever their chunk sizes, a flow with weight 3 gets three times
+//! the share of one with weight 1, and bandwidth a flow leaves unused goes
+//! to the others.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Virtual time units per byte at weight 1 (keeps tags integral for any weight)
+const TAG_SCALE: u128 = 1 << 16;
+
+/// Flow used by `RateLimiter::acquire`
+const SHARED_FLOW: u64 = 0;
+
+#[derive(Debug)]
+struct FlowState {
+    weight: u32,
+    /// Finish tag of the flow's latest chunk
+    finish: u128,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Bytes per second (0 = unlimited)
+    limit: u64,
+    /// When the next chunk may start
+    next_free: Instant,
+    /// Start tag of the chunk granted last
+    virtual_time: u128,
+    flows: HashMap<u64, FlowState>,
+    /// Waiting chunks by (start tag, ticket), with their size
+    waiting: BTreeMap<(u128, u64), usize>,
+    /// Tickets granted but not yet picked up by their waiter
+    granted: HashSet<u64>,
+    next_id: u64,
+}
+
+impl State {
+    /// Grant waiting chunks, smallest start tag first, while the link is free
+    fn dispatch(&mut self, now: Instant) {
+        if self.limit == 0 {
+            self.granted.extend(self.waiting.keys().map(|(_, ticket)| *ticket));
+            self.waiting.clear();
+            return;
+        }
+        while self.next_free <= now {
+            let Some(((start, ticket), bytes)) = self.waiting.pop_first() else {
+                break;
+            };
+            self.virtual_time = self.virtual_time.max(start);
+            self.next_free = self.next_free.max(now) + Duration::from_secs_f64(bytes as f64 / self.limit as f64);
+            self.granted.insert(ticket);
+        }
+    }
+}
+
+/// Shared bandwidth limiter (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+    /// Wakes waiters when the limit changes
+    changed: Arc<Notify>,
+}
+
+/// One download's share of a `RateLimiter`; unregistered on drop
+#[derive(Debug)]
+pub struct Flow {
+    limiter: RateLimiter,
+    id: u64,
+}
+
+/// A queued chunk; removed from the queue if the waiting future is dropped
+struct Pending<'a> {
+    limiter: &'a RateLimiter,
+    key: (u128, u64),
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        state.waiting.remove(&self.key);
+        state.granted.remove(&self.key.1);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `bytes_per_sec` (0 = unlimited)
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                limit: bytes_per_sec,
+                next_free: Instant::now(),
+                virtual_time: 0,
+                flows: HashMap::new(),
+                waiting: BTreeMap::new(),
+                granted: HashSet::new(),
+                next_id: SHARED_FLOW + 1,
+            })),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Current limit in bytes per second (0 = unlimited)
+    pub fn limit(&self) -> u64 {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Change the limit; pending reservations made under the old limit are dropped
+    pub fn set_limit(&self, bytes_per_sec: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.limit != bytes_per_sec {
+            state.limit = bytes_per_sec;
+            state.next_free = Instant::now();
+            self.changed.notify_waiters();
+        }
+    }
+
+    /// Register a flow with the given weight (at least 1)
+    pub fn flow(&self, weight: u32) -> Flow {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let finish = state.virtual_time;
+        state.flows.insert(id, FlowState { weight: weight.max(1), finish });
+        Flow { limiter: self.clone(), id }
+    }
+
+    /// Wait until `bytes` may be transferred, sharing a flow with other callers of this method
+    pub async fn acquire(&self, bytes: usize) {
+        self.acquire_for(SHARED_FLOW, bytes).await;
+    }
+
+    async fn acquire_for(&self, flow: u64, bytes: usize) {
+        let key = {
+            let mut state = self.state.lock().unwrap();
+            if state.limit == 0 {
+                return;
+            }
+            let ticket = state.next_id;
+            state.next_id += 1;
+            let virtual_time = state.virtual_time;
+            let flow = state.flows.entry(flow).or_insert(FlowState { weight: 1, finish: virtual_time });
+            let start = flow.finish.max(virtual_time);
+            flow.finish = start + bytes as u128 * TAG_SCALE / u128::from(flow.weight);
+            state.waiting.insert((start, ticket), bytes);
+            (start, ticket)
+        };
+        let _pending = Pending { limiter: self, key };
+
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let wake = {
+                let mut state = self.state.lock().unwrap();
+                state.dispatch(Instant::now());
+                if state.granted.remove(&key.1) {
+                    return;
+                }
+                state.next_free
+            };
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake) => {}
+                _ = notified => {}
+            }
+        }
+    }
+}
+
+impl Flow {
+    /// Wait until `bytes` of this flow may be transferred
+    pub async fn acquire(&self, bytes: usize) {
+        self.limiter.acquire_for(self.id, bytes).await;
+    }
+}
+
+impl Drop for Flow {
+    fn drop(&mut self) {
+        self.limiter.state.lock().unwrap().flows.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_spaces_chunks_by_limit() {
+        let limiter = RateLimiter::new(1000);
+        let started = Instant::now();
+
+        // First chunk goes immediately, the next waits for the first one's budget
+        limiter.acquire(500).await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        limiter.acquire(500).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+        limiter.acquire(500).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(1000));
+
+        // Unlimited never waits
+        limiter.set_limit(0);
+        limiter.acquire(1_000_000).await;
+        assert_eq!(started.elapsed(), Duration::from_millis(1000));
+    }
+
+    /// Bytes each flow transfers in ten seconds of saturated demand
+    async fn run_flows(limit: u64, flows: &[(u32, usize)]) -> Vec<u64> {
+        let limiter = RateLimiter::new(limit);
+        let counters: Vec<Arc<AtomicU64>> = flows.iter().map(|_| Arc::new(AtomicU64::new(0))).collect();
+        let handles: Vec<_> = flows
+            .iter()
+            .zip(&counters)
+            .map(|(&(weight, chunk), counter)| {
+                let flow = limiter.flow(weight);
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    loop {
+                        flow.acquire(chunk).await;
+                        counter.fetch_add(chunk as u64, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        for handle in handles {
+            handle.abort();
+        }
+        counters.iter().map(|c| c.load(Ordering::Relaxed)).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flows_share_the_limit_evenly() {
+        // Five downloads under 5000 B/s get about 1000 B/s each
+        let bytes = run_flows(5000, &[(1, 1000); 5]).await;
+        for total in &bytes {
+            assert!((9000..=11000).contains(total), "{:?}", bytes);
+        }
+
+        // Chunk size does not buy a larger share
+        let bytes = run_flows(5000, &[(1, 4000), (1, 500)]).await;
+        assert!(bytes[0].abs_diff(bytes[1]) <= 4000, "{:?}", bytes);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flows_share_by_weight() {
+        let bytes = run_flows(4000, &[(1, 1000), (3, 1000)]).await;
+        assert!((8000..=12000).contains(&bytes[0]), "{:?}", bytes);
+        assert!((28000..=32000).contains(&bytes[1]), "{:?}", bytes);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_raising_the_limit_wakes_waiters() {
+        let limiter = RateLimiter::new(1);
+        limiter.acquire(1000).await;
+
+        // Queued behind a 1000-second reservation until the limit goes up
+        let waiter = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(1000).await }
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!waiter.is_finished());
+        limiter.set_limit(1_000_000);
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+//! Auto-folder routing
+//!
+//! Picks a target folder for a download from the `[routing]` rules in
+//! `settings.toml`, matching on file extension, Content-Type or URL host.
+//! Rules are evaluated in order and the first match wins; when nothing
+//! matches the task stays in its folder.
+
+use crate::app::config::RoutingRule;
+
+/// A matched routing rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteMatch<'a> {
+    /// 1-based position of the rule in `[routing]`
+    pub rule_number: usize,
+    pub folder_id: &'a str,
+}
+
+/// Returns the first rule matching the download, if any
+///
+/// Within a rule every non-empty criterion must match; each criterion matches
+/// if any of its entries does. Rules without criteria never match.
+pub fn route<'a>(
+    rules: &'a [RoutingRule],
+    url: &str,
+    filename: &str,
+    content_type: Option<&str>,
+) -> Option<RouteMatch<'a>> {
+    let extension = std::path::Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()));
+    let mime = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase());
+
+    rules.iter().enumerate().find_map(|(index, rule)| {
+        let has_criteria =
+            !rule.extensions.is_empty() || !rule.content_types.is_empty() || !rule.hosts.is_empty();
+        let matches = has_criteria
+            && (rule.extensions.is_empty() || matches_extension(&rule.extensions, extension.as_deref()))
+            && (rule.content_types.is_empty() || matches_content_type(&rule.content_types, mime.as_deref()))
+            && (rule.hosts.is_empty() || matches_host(&rule.hosts, host.as_deref()));
+
+        matches.then_some(RouteMatch {
+            rule_number: index + 1,
+            folder_id: rule.folder.as_str(),
+        })
+    })
+}
+
+fn matches_extension(patterns: &[String], extension: Option<&str>) -> bool {
+    let Some(extension) = extension else {
+        return false;
+    };
+    patterns
+        .iter()
+        .any(|p| p.trim_start_matches('.').eq_ignore_ascii_case(extension))
+}
+
+/// `image/*` matches any image type
+fn matches_content_type(patterns: &[String], mime: Option<&str>) -> bool {
+    let Some(mime) = mime else {
+        return false;
+    };
+    patterns.iter().any(|p| match p.strip_suffix("/*") {
+        Some(top_level) => mime
+            .split_once('/')
+            .is_some_and(|(t, _)| t.eq_ignore_ascii_case(top_level)),
+        None => p.eq_ignore_ascii_case(mime),
+    })
+}
+
+/// `example.com` also matches its subdomains
+fn matches_host(patterns: &[String], host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    patterns.iter().any(|p| {
+        let p = p.to_ascii_lowercase();
+        host == p || host.ends_with(&format!(".{}", p))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(folder: &str, extensions: &[&str], content_types: &[&str], hosts: &[&str]) -> RoutingRule {
+        RoutingRule {
+            folder: folder.to_string(),
+            extensions: extensions.iter().map(|s| s.to_string()).collect(),
+            content_types: content_types.iter().map(|s| s.to_string()).collect(),
+            hosts: hosts.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![
+            rule("pixiv", &[], &[], &["pximg.net"]),
+            rule("images", &["jpg", ".PNG"], &[], &[]),
+            rule("videos", &[], &["video/*"], &[]),
+        ];
+
+        let m = route(&rules, "https://i.pximg.net/a.jpg", "a.jpg", None).unwrap();
+        assert_eq!((m.rule_number, m.folder_id), (1, "pixiv"));
+        assert_eq!(route(&rules, "https://example.com/b.png", "b.png", None).unwrap().folder_id, "images");
+        assert_eq!(
+            route(&rules, "https://example.com/watch", "watch", Some("video/mp4; codecs=avc1")).unwrap().folder_id,
+            "videos"
+        );
+        assert!(route(&rules, "https://example.com/c.zip", "c.zip", Some("application/zip")).is_none());
+    }
+
+    #[test]
+    fn test_rule_criteria_are_combined() {
+        let rules = vec![rule("images", &["jpg"], &[], &["example.com"]), rule("empty", &[], &[], &[])];
+
+        assert!(route(&rules, "https://example.com/a.jpg", "a.jpg", None).is_some());
+        assert!(route(&rules, "https://other.com/a.jpg", "a.jpg", None).is_none());
+        assert!(route(&rules, "https://notexample.com/a.jpg", "a.jpg", None).is_none());
+    }
+}
@@ -0,0 +1,325 @@
+//! Download statistics aggregation
+//!
+//! Shared by `ggg stats` and the TUI statistics panel so both report the same numbers.
+
+use super::completion_log::CompletedEntry;
+use super::task::{DownloadStatus, DownloadTask};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Time window for aggregation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsWindow {
+    /// All recorded history
+    All,
+    /// Since midnight UTC today (completion logs are bucketed by UTC date)
+    Today,
+    /// Since the given instant
+    Since(DateTime<Utc>),
+}
+
+impl StatsWindow {
+    /// Window covering the given duration up to now
+    pub fn last(duration: Duration) -> Self {
+        Self::Since(Utc::now() - duration)
+    }
+
+    /// Start of the window, or `None` for all history
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::All => None,
+            Self::Today => Some(
+                Utc::now()
+                    .date_naive()
+                    .and_hms_opt(0, 0, 0)
+                    .expect("midnight is always valid")
+                    .and_utc(),
+            ),
+            Self::Since(since) => Some(*since),
+        }
+    }
+}
+
+/// Parse a relative duration such as `30s`, `15m`, `12h`, `7d` or `2w`
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Missing unit in duration '{}' (use s, m, h, d or w)", input))?;
+    let (number, unit) = input.split_at(split);
+    let value: i64 = number
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration '{}'", input))?;
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(value),
+        "m" => Duration::try_minutes(value),
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        _ => return Err(anyhow!("Unknown duration unit '{}' (use s, m, h, d or w)", unit)),
+    };
+    duration.ok_or_else(|| anyhow!("Duration '{}' is too large", input))
+}
+
+/// Instant `input` (as accepted by [`parse_duration`]) before `now`
+pub fn cutoff_before(now: DateTime<Utc>, input: &str) -> Result<DateTime<Utc>> {
+    now.checked_sub_signed(parse_duration(input)?)
+        .ok_or_else(|| anyhow!("Duration '{}' is too large", input))
+}
+
+/// Current queue state
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueueStats {
+    pub total: usize,
+    pub pending: usize,
+    pub downloading: usize,
+    pub paused: usize,
+    pub error: usize,
+}
+
+/// Aggregates for a single folder
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FolderStats {
+    pub folder_id: String,
+    pub completed: usize,
+    pub failed: usize,
+    pub bytes: u64,
+}
+
+/// Number of completions on a single day
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub completed: usize,
+}
+
+/// Aggregated download statistics for a time window
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DownloadStats {
+    /// Start of the window (None = all history)
+    pub since: Option<DateTime<Utc>>,
+    pub completed: usize,
+    pub failed: usize,
+    /// completed / (completed + failed), None when nothing finished
+    pub success_rate: Option<f64>,
+    /// Bytes of completed downloads
+    pub total_bytes: u64,
+    /// Average speed over downloads with a known duration (bytes/sec)
+    pub avg_speed: Option<f64>,
+    pub avg_duration_secs: Option<f64>,
+    pub queue: QueueStats,
+    /// Per-folder breakdown, largest first
+    pub folders: Vec<FolderStats>,
+    /// Completions per day, oldest first
+    pub daily: Vec<DailyCount>,
+}
+
+/// Compute statistics from completion history and the current queue
+///
+/// `history` entries outside the window are ignored. Failed tasks still in the
+/// queue count as failures when they started inside the window. When `folder`
+/// is set, only that folder is included.
+pub fn compute(
+    history: &[CompletedEntry],
+    tasks: &[DownloadTask],
+    window: StatsWindow,
+    folder: Option<&str>,
+) -> DownloadStats {
+    let since = window.start();
+    let in_window = |at: Option<DateTime<Utc>>| match (since, at) {
+        (Some(since), Some(at)) => at >= since,
+        (Some(_), None) => false,
+        (None, _) => true,
+    };
+    let in_folder = |folder_id: &str| folder.is_none_or(|f| f == folder_id);
+
+    let mut stats = DownloadStats {
+        since,
+        ..Default::default()
+    };
+    let mut folders: HashMap<String, FolderStats> = HashMap::new();
+    let mut daily: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    let mut timed_bytes = 0u64;
+    let mut timed_secs = 0.0;
+    let mut timed_count = 0usize;
+
+    for entry in history {
+        if !in_folder(&entry.folder_id) || !in_window(entry.completed_at.or(entry.started_at)) {
+            continue;
+        }
+
+        let folder_stats = folders
+            .entry(entry.folder_id.clone())
+            .or_insert_with(|| FolderStats {
+                folder_id: entry.folder_id.clone(),
+                ..Default::default()
+            });
+
+        if entry.status == "completed" {
+            let bytes = entry.size.unwrap_or(0);
+            stats.completed += 1;
+            stats.total_bytes += bytes;
+            folder_stats.completed += 1;
+            folder_stats.bytes += bytes;
+
+            if let Some(completed_at) = entry.completed_at {
+                *daily.entry(completed_at.date_naive()).or_default() += 1;
+            }
+            if let Some(secs) = entry.duration_secs.filter(|d| *d > 0.0) {
+                timed_bytes += bytes;
+                timed_secs += secs;
+                timed_count += 1;
+            }
+        } else {
+            stats.failed += 1;
+            folder_stats.failed += 1;
+        }
+    }
+
+    for task in tasks.iter().filter(|t| in_folder(&t.folder_id)) {
+        stats.queue.total += 1;
+        match task.status {
+            DownloadStatus::Pending => stats.queue.pending += 1,
+            DownloadStatus::Downloading => stats.queue.downloading += 1,
+            DownloadStatus::Paused => stats.queue.paused += 1,
+            DownloadStatus::Error => {
+                stats.queue.error += 1;
+                if in_window(task.started_at.or(Some(task.created_at))) {
+                    stats.failed += 1;
+                    folders
+                        .entry(task.folder_id.clone())
+                        .or_insert_with(|| FolderStats {
+                            folder_id: task.folder_id.clone(),
+                            ..Default::default()
+                        })
+                        .failed += 1;
+                }
+            }
+            DownloadStatus::Completed | DownloadStatus::Deleted => {}
+        }
+    }
+
+    let finished = stats.completed + stats.failed;
+    if finished > 0 {
+        stats.success_rate = Some(stats.completed as f64 / finished as f64);
+    }
+    if timed_count > 0 {
+        stats.avg_speed = Some(timed_bytes as f64 / timed_secs);
+        stats.avg_duration_secs = Some(timed_secs / timed_count as f64);
+    }
+
+    stats.folders = folders.into_values().collect();
+    stats.folders.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then(b.completed.cmp(&a.completed))
+            .then(a.folder_id.cmp(&b.folder_id))
+    });
+    stats.daily = daily
+        .into_iter()
+        .map(|(date, completed)| DailyCount { date, completed })
+        .collect();
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn entry(folder_id: &str, status: &str, size: u64, days_ago: i64, duration: f64) -> CompletedEntry {
+        let completed_at = Utc::now() - Duration::days(days_ago);
+        CompletedEntry {
+            id: Uuid::new_v4(),
+            url: "https://example.com/file.bin".to_string(),
+            filename: "file.bin".to_string(),
+            folder_id: folder_id.to_string(),
+            size: Some(size),
+            started_at: Some(completed_at - Duration::milliseconds((duration * 1000.0) as i64)),
+            completed_at: Some(completed_at),
+            duration_secs: Some(duration),
+            status: status.to_string(),
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::seconds(30));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7y").is_err());
+        // Out of range for chrono instead of panicking
+        assert!(parse_duration("9999999999999999d").is_err());
+    }
+
+    #[test]
+    fn test_cutoff_before() {
+        let now = Utc::now();
+        assert_eq!(cutoff_before(now, "7d").unwrap(), now - Duration::days(7));
+        // Representable duration, but before the earliest representable date
+        assert!(cutoff_before(now, "99999999999d").is_err());
+        assert!(cutoff_before(now, "7").is_err());
+    }
+
+    #[test]
+    fn test_compute_totals_and_success_rate() {
+        let history = vec![
+            entry("a", "completed", 1000, 0, 2.0),
+            entry("a", "completed", 3000, 1, 2.0),
+            entry("b", "error", 0, 1, 0.0),
+        ];
+
+        let stats = compute(&history, &[], StatsWindow::All, None);
+
+        assert_eq!(stats.completed, 2);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.total_bytes, 4000);
+        assert_eq!(stats.avg_speed, Some(1000.0));
+        assert!((stats.success_rate.unwrap() - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.folders[0].folder_id, "a");
+        assert_eq!(stats.folders[0].bytes, 4000);
+        assert_eq!(stats.daily.len(), 2);
+    }
+
+    #[test]
+    fn test_compute_respects_window_and_folder() {
+        let history = vec![
+            entry("a", "completed", 1000, 0, 1.0),
+            entry("a", "completed", 1000, 10, 1.0),
+            entry("b", "completed", 5000, 0, 1.0),
+        ];
+
+        let stats = compute(&history, &[], StatsWindow::last(Duration::days(7)), None);
+        assert_eq!(stats.completed, 2);
+
+        let stats = compute(&history, &[], StatsWindow::All, Some("a"));
+        assert_eq!(stats.completed, 2);
+        assert_eq!(stats.total_bytes, 2000);
+        assert_eq!(stats.folders.len(), 1);
+    }
+
+    #[test]
+    fn test_compute_counts_queue_and_failed_tasks() {
+        let mut failed = DownloadTask::new("https://example.com/a".to_string(), PathBuf::from("/tmp"));
+        failed.status = DownloadStatus::Error;
+        let pending = DownloadTask::new("https://example.com/b".to_string(), PathBuf::from("/tmp"));
+
+        let stats = compute(&[], &[failed, pending], StatsWindow::Today, None);
+
+        assert_eq!(stats.queue.total, 2);
+        assert_eq!(stats.queue.pending, 1);
+        assert_eq!(stats.queue.error, 1);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.success_rate, Some(0.0));
+    }
+}
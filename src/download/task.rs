@@ -2,6 +2,14 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use crate::file::path_template::{expand_save_path_template, TemplateContext};
+use crate::script::events::ScriptRun;
+
+/// Script runs kept per task; older ones are dropped first
+pub const MAX_SCRIPT_RUNS: usize = 50;
+
+/// Extension of partial files kept in `download.temp_dir`
+pub const PARTIAL_EXTENSION: &str = "ggg-part";
 
 /// Log entry for download events
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +77,87 @@ pub struct DownloadTask {
     pub logs: Vec<LogEntry>,
     pub retry_count: u32,
     pub last_status_code: Option<u16>,
+    /// Final URL after following redirects (only set when it differs from `url`)
+    #[serde(default)]
+    pub resolved_url: Option<String>,
+    /// Folder the task was added to, when a routing rule moved it elsewhere
+    #[serde(default)]
+    pub routed_from: Option<String>,
+    /// `resume_supported` was confirmed by a Range probe (not re-probed at start)
+    #[serde(default)]
+    pub resume_probed: bool,
+    /// Why the last start was held back by a folder quota (cleared when it starts)
+    #[serde(default)]
+    pub held_reason: Option<String>,
+    /// Paused because its size is over `download.confirm_over_bytes`
+    #[serde(default)]
+    pub awaiting_confirmation: bool,
+    /// Largest size the user agreed to download (in the preview or by confirming)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed_size: Option<u64>,
+    /// Running average and peak speed, updated with every progress report
+    #[serde(default, skip_serializing_if = "SpeedStats::is_empty")]
+    pub speed_stats: SpeedStats,
+    /// Script handlers that ran for this download (beforeRequest, headersReceived,
+    /// authRequired and completed), oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub script_runs: Vec<ScriptRun>,
+}
+
+/// Running speed aggregates of a download
+///
+/// Each progress report adds the bytes and time since the previous one, so
+/// the average and peak cost O(1) per update. Time between runs (paused,
+/// waiting for a slot, not yet started) is not counted, nor are bytes resumed
+/// from an earlier run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeedStats {
+    /// Bytes transferred between progress reports
+    pub bytes: u64,
+    /// Seconds covered by those reports
+    pub secs: f64,
+    /// Highest speed between two consecutive reports, in bytes/sec
+    pub peak: f64,
+    /// Previous report (time, downloaded) of the current run
+    #[serde(skip)]
+    last: Option<(DateTime<Utc>, u64)>,
+}
+
+impl SpeedStats {
+    /// True if nothing has been measured yet
+    pub fn is_empty(&self) -> bool {
+        self.bytes == 0 && self.secs == 0.0
+    }
+
+    /// Start measuring a run that begins at `downloaded` bytes
+    pub fn begin_run(&mut self, now: DateTime<Utc>, downloaded: u64) {
+        self.last = Some((now, downloaded));
+    }
+
+    /// Add a progress report of the current run
+    pub fn record(&mut self, now: DateTime<Utc>, downloaded: u64) {
+        if let Some((at, previous)) = self.last {
+            let secs = now.signed_duration_since(at).num_milliseconds() as f64 / 1000.0;
+            // Progress going backwards means the run restarted from scratch
+            if secs > 0.0 && downloaded >= previous {
+                let bytes = downloaded - previous;
+                self.bytes += bytes;
+                self.secs += secs;
+                self.peak = self.peak.max(bytes as f64 / secs);
+            }
+        }
+        self.last = Some((now, downloaded));
+    }
+
+    /// Average speed over the measured time, in bytes/sec
+    pub fn average(&self) -> Option<f64> {
+        (self.secs > 0.0 && self.bytes > 0).then(|| self.bytes as f64 / self.secs)
+    }
+
+    /// Peak speed, in bytes/sec
+    pub fn peak(&self) -> Option<f64> {
+        (self.peak > 0.0).then_some(self.peak)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -115,6 +204,14 @@ impl DownloadTask {
             logs: Vec::new(),
             retry_count: 0,
             last_status_code: None,
+            resolved_url: None,
+            routed_from: None,
+            resume_probed: false,
+            held_reason: None,
+            awaiting_confirmation: false,
+            confirmed_size: None,
+            speed_stats: SpeedStats::default(),
+            script_runs: Vec::new(),
         };
         task.logs.push(LogEntry::info("Download task created"));
         task
@@ -127,11 +224,8 @@ impl DownloadTask {
         config: &crate::app::config::Config,
     ) -> Self {
         let folder_config = config.folders.get(&folder_id);
-
-        // Determine save_path from folder or app default
-        let save_path = folder_config
-            .map(|f| f.save_path.clone())
-            .unwrap_or_else(|| config.download.default_directory.clone());
+        let created_at = Utc::now();
+        let save_path = folder_save_path(config, &folder_id, &url, created_at);
 
         // Apply folder defaults for headers
         let headers = folder_config
@@ -160,7 +254,7 @@ impl DownloadTask {
             downloaded: 0,
             status: DownloadStatus::Pending,
             priority: 0,
-            created_at: Utc::now(),
+            created_at,
             started_at: None,
             completed_at: None,
             headers,
@@ -172,11 +266,73 @@ impl DownloadTask {
             logs: Vec::new(),
             retry_count: 0,
             last_status_code: None,
+            resolved_url: None,
+            routed_from: None,
+            resume_probed: false,
+            held_reason: None,
+            awaiting_confirmation: false,
+            confirmed_size: None,
+            speed_stats: SpeedStats::default(),
+            script_runs: Vec::new(),
         };
         task.logs.push(LogEntry::info(format!("Download task created in folder '{}'", folder_id)));
         task
     }
 
+    /// Normalize a task left `Downloading` by a run that did not exit cleanly
+    ///
+    /// `partial_len` is the size of the partial file on disk, if any. Resumable
+    /// partial downloads become `Paused` with their offset recorded; others become
+    /// `Pending` in auto-start folders and `Paused` otherwise. Returns true if the
+    /// task was changed.
+    pub fn recover_interrupted(&mut self, auto_start: bool, partial_len: Option<u64>) -> bool {
+        if self.status != DownloadStatus::Downloading {
+            return false;
+        }
+
+        match partial_len.filter(|len| self.resume_supported && *len > 0) {
+            Some(len) => {
+                self.status = DownloadStatus::Paused;
+                self.downloaded = len;
+                self.log_warn(format!("Recovered interrupted download (resume from {} bytes)", len));
+            }
+            None => {
+                self.status = if auto_start { DownloadStatus::Pending } else { DownloadStatus::Paused };
+                self.downloaded = 0;
+                self.log_warn("Recovered interrupted download".to_string());
+            }
+        }
+        true
+    }
+
+    /// True if the size is over `threshold` and more than the user already agreed to
+    ///
+    /// An unknown size never needs confirmation.
+    pub fn needs_size_confirmation(&self, threshold: Option<u64>) -> bool {
+        match (self.size, threshold) {
+            (Some(size), Some(threshold)) => {
+                size > threshold && self.confirmed_size.is_none_or(|confirmed| size > confirmed)
+            }
+            _ => false,
+        }
+    }
+
+    /// Partial file for this task in `download.temp_dir`
+    ///
+    /// Named by task ID, so renames and folder moves do not lose it.
+    pub fn partial_path(&self, temp_dir: &std::path::Path) -> PathBuf {
+        temp_dir.join(format!("{}.{}", self.id, PARTIAL_EXTENSION))
+    }
+
+    /// Append script runs, keeping the most recent `MAX_SCRIPT_RUNS`
+    pub fn record_script_runs(&mut self, runs: Vec<ScriptRun>) {
+        self.script_runs.extend(runs);
+        if self.script_runs.len() > MAX_SCRIPT_RUNS {
+            let excess = self.script_runs.len() - MAX_SCRIPT_RUNS;
+            self.script_runs.drain(..excess);
+        }
+    }
+
     /// Add an info log entry
     pub fn log_info(&mut self, message: String) {
         self.logs.push(LogEntry::info(message));
@@ -192,12 +348,25 @@ impl DownloadTask {
         self.logs.push(LogEntry::error(message));
     }
 
-    /// Calculate current download speed in bytes per second
-    pub fn speed(&self) -> Option<f64> {
+    /// Seconds the download has been running: from the latest start to
+    /// completion, or to `now` while it is downloading
+    ///
+    /// None if it never started or is paused or failed.
+    pub fn elapsed_secs(&self, now: DateTime<Utc>) -> Option<f64> {
         let started = self.started_at?;
-        let elapsed = Utc::now().signed_duration_since(started);
-        let elapsed_secs = elapsed.num_milliseconds() as f64 / 1000.0;
-        
+        let end = match self.completed_at {
+            Some(completed) => completed,
+            None if self.status == DownloadStatus::Downloading => now,
+            None => return None,
+        };
+        Some(end.signed_duration_since(started).num_milliseconds().max(0) as f64 / 1000.0)
+    }
+
+    /// Average download speed in bytes per second since the latest start
+    /// (over the whole transfer once completed)
+    pub fn speed(&self) -> Option<f64> {
+        let elapsed_secs = self.elapsed_secs(Utc::now())?;
+
         if elapsed_secs > 0.0 && self.downloaded > 0 {
             Some(self.downloaded as f64 / elapsed_secs)
         } else {
@@ -205,6 +374,12 @@ impl DownloadTask {
         }
     }
 
+    /// Average speed while transferring (pauses excluded), falling back to
+    /// `speed()` for tasks measured before speed stats were kept
+    pub fn average_speed(&self) -> Option<f64> {
+        self.speed_stats.average().or_else(|| self.speed())
+    }
+
     /// Calculate estimated time remaining in seconds
     /// Returns None if speed is zero, size is unknown, or already completed
     pub fn eta_seconds(&self) -> Option<u64> {
@@ -234,6 +409,32 @@ impl DownloadTask {
     }
 }
 
+/// Save path for a download of `url` in a folder: the folder's save path (or
+/// the app default for unknown folders) with its path template expanded
+pub fn folder_save_path(
+    config: &crate::app::config::Config,
+    folder_id: &str,
+    url: &str,
+    created_at: DateTime<Utc>,
+) -> PathBuf {
+    let folder_config = config.folders.get(folder_id);
+    let base_path = folder_config
+        .map(|f| f.save_path.clone())
+        .unwrap_or_else(|| config.download.default_directory.clone());
+    match folder_config.and_then(|f| f.effective_save_path_template()) {
+        Some(template) => expand_save_path_template(
+            template,
+            &base_path,
+            &TemplateContext {
+                url,
+                folder_id,
+                created_at,
+            },
+        ),
+        None => base_path,
+    }
+}
+
 /// Format duration in seconds to human-readable string
 pub fn format_duration(seconds: u64) -> String {
     if seconds < 60 {
@@ -256,3 +457,96 @@ pub fn format_duration(seconds: u64) -> String {
         }
     }
 }
+
+/// Minimum length of a short download ID
+pub const SHORT_ID_LEN: usize = 8;
+
+/// Length of the shortest ID prefix that is unique among `ids` (at least `SHORT_ID_LEN`)
+///
+/// The prefix is taken from the hyphenated UUID, so it can be passed back to
+/// CLI commands that accept ID prefixes.
+pub fn short_id_len(ids: impl IntoIterator<Item = Uuid>) -> usize {
+    let mut ids: Vec<String> = ids.into_iter().map(|id| id.to_string()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let shared = ids
+        .windows(2)
+        .map(|pair| pair[0].bytes().zip(pair[1].bytes()).take_while(|(a, b)| a == b).count())
+        .max()
+        .unwrap_or(0);
+    let mut len = (shared + 1).max(SHORT_ID_LEN);
+    // A prefix ending in a hyphen is no more specific than one without it
+    if ids.first().is_some_and(|id| id.as_bytes().get(len - 1) == Some(&b'-')) {
+        len += 1;
+    }
+    len.min(36)
+}
+
+/// First `len` characters of the hyphenated ID
+pub fn short_id(id: Uuid, len: usize) -> String {
+    let mut id = id.to_string();
+    id.truncate(len);
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_script_runs_keep_most_recent() {
+        use crate::script::events::HookEvent;
+
+        let mut task = DownloadTask::new("https://example.com/a.zip".to_string(), PathBuf::from("dl"));
+        let runs = |count: usize, script: &str| {
+            (0..count).map(|_| ScriptRun::new(script, HookEvent::BeforeRequest, false, None)).collect::<Vec<_>>()
+        };
+        task.record_script_runs(runs(MAX_SCRIPT_RUNS - 1, "old.js"));
+        task.record_script_runs(runs(2, "new.js"));
+
+        assert_eq!(task.script_runs.len(), MAX_SCRIPT_RUNS);
+        assert_eq!(task.script_runs.iter().filter(|run| run.script == "old.js").count(), MAX_SCRIPT_RUNS - 2);
+        assert_eq!(task.script_runs.last().unwrap().script, "new.js");
+    }
+
+    #[test]
+    fn test_speed_stats_track_average_and_peak() {
+        let start = Utc::now();
+        let mut stats = SpeedStats::default();
+        // Reports before a run starts only set the baseline
+        stats.record(start, 0);
+        assert!(stats.is_empty());
+
+        stats.record(start + Duration::seconds(1), 1000);
+        stats.record(start + Duration::seconds(2), 4000);
+        assert_eq!(stats.peak(), Some(3000.0));
+        assert_eq!(stats.average(), Some(2000.0));
+
+        // Resumed after a pause: the gap and the resumed offset are not counted
+        stats.begin_run(start + Duration::seconds(60), 4000);
+        stats.record(start + Duration::seconds(62), 6000);
+        assert_eq!(stats.bytes, 6000);
+        assert_eq!(stats.secs, 4.0);
+        assert_eq!(stats.average(), Some(1500.0));
+        assert_eq!(stats.peak(), Some(3000.0));
+    }
+
+    #[test]
+    fn test_needs_size_confirmation() {
+        let mut task = DownloadTask::new("https://example.com/a.iso".to_string(), PathBuf::from("dl"));
+        assert!(!task.needs_size_confirmation(Some(1000)));
+
+        task.size = Some(5000);
+        assert!(!task.needs_size_confirmation(None));
+        assert!(!task.needs_size_confirmation(Some(5000)));
+        assert!(task.needs_size_confirmation(Some(1000)));
+
+        // Confirmed once, it only asks again if the size grows
+        task.confirmed_size = Some(5000);
+        assert!(!task.needs_size_confirmation(Some(1000)));
+        task.size = Some(6000);
+        assert!(task.needs_size_confirmation(Some(1000)));
+    }
+}
@@ -0,0 +1,283 @@
+//! Trash for deleted downloads
+//!
+//! Deleted tasks are kept in `{config_dir}/trash.json` so they can be restored
+//! after a restart (`ggg restore <id>`, TUI undo). Entries expire after
+//! `history.trash_retention_days`.
+//!
+//! Entries removed by one bulk action (folder Delete All) share a batch ID
+//! and form a single undo step in the TUI.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+use super::task::{DownloadStatus, DownloadTask};
+
+/// A deleted task awaiting restore or expiry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Deleted task (status is `Deleted`)
+    pub task: DownloadTask,
+    /// Status before deletion, restored on undo
+    pub previous_status: DownloadStatus,
+    pub deleted_at: DateTime<Utc>,
+    /// Shared by every entry deleted in the same bulk action
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<Uuid>,
+}
+
+impl TrashEntry {
+    /// Returns the task ready to be re-queued
+    ///
+    /// Downloads that were active when deleted come back paused.
+    pub fn into_task(self) -> DownloadTask {
+        let mut task = self.task;
+        task.status = match self.previous_status {
+            DownloadStatus::Downloading | DownloadStatus::Deleted => DownloadStatus::Paused,
+            status => status,
+        };
+        task
+    }
+}
+
+/// Deleted tasks, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Trash {
+    pub entries: Vec<TrashEntry>,
+}
+
+impl Trash {
+    /// Loads the trash from a JSON file (empty if the file does not exist)
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Saves the trash to a JSON file (atomic write)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Loads the trash from `{config_dir}/trash.json`
+    pub fn load_default() -> Result<Self> {
+        Self::load(&crate::util::paths::get_trash_path()?)
+    }
+
+    /// Saves the trash to `{config_dir}/trash.json`
+    pub fn save_default(&self) -> Result<()> {
+        self.save(&crate::util::paths::get_trash_path()?)
+    }
+
+    /// Loads `{config_dir}/trash.json`, applies `f` and saves the result
+    pub fn update_default<T>(f: impl FnOnce(&mut Self) -> T) -> Result<T> {
+        let mut trash = Self::load_default()?;
+        let result = f(&mut trash);
+        trash.save_default()?;
+        Ok(result)
+    }
+
+    /// Moves a task into the trash, marking it `Deleted`
+    pub fn push(&mut self, task: DownloadTask) {
+        self.push_entry(task, None);
+    }
+
+    /// Moves tasks into the trash as one batch, undone together
+    pub fn push_batch(&mut self, tasks: impl IntoIterator<Item = DownloadTask>) {
+        let batch = Uuid::new_v4();
+        for task in tasks {
+            self.push_entry(task, Some(batch));
+        }
+    }
+
+    fn push_entry(&mut self, mut task: DownloadTask, batch: Option<Uuid>) {
+        let previous_status = task.status;
+        task.status = DownloadStatus::Deleted;
+        self.entries.retain(|e| e.task.id != task.id);
+        self.entries.push(TrashEntry {
+            task,
+            previous_status,
+            deleted_at: Utc::now(),
+            batch,
+        });
+    }
+
+    /// Removes and returns the most recently deleted entry
+    pub fn pop(&mut self) -> Option<TrashEntry> {
+        self.entries.pop()
+    }
+
+    /// Removes and returns the entry matching a full ID or unique ID prefix
+    pub fn take(&mut self, id: &str) -> Result<TrashEntry> {
+        let matches: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.task.id.to_string().starts_with(id))
+            .map(|(i, _)| i)
+            .collect();
+
+        match matches.as_slice() {
+            [index] => Ok(self.entries.remove(*index)),
+            [] => Err(anyhow!("No deleted download matches '{}'", id)),
+            _ => Err(anyhow!("ID prefix '{}' is ambiguous ({} matches)", id, matches.len())),
+        }
+    }
+
+    /// Removes the entry with the given ID, if present
+    pub fn remove(&mut self, id: Uuid) -> Option<TrashEntry> {
+        let index = self.entries.iter().position(|e| e.task.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Drops entries deleted more than `days` ago (0 = never expire)
+    ///
+    /// Returns the number of entries removed. A retention too long to
+    /// represent as a date expires nothing.
+    pub fn expire(&mut self, days: u32) -> usize {
+        if days == 0 {
+            return 0;
+        }
+        let Some(cutoff) = Utc::now().checked_sub_signed(chrono::Duration::days(days as i64)) else {
+            return 0;
+        };
+        let before = self.entries.len();
+        self.entries.retain(|e| e.deleted_at >= cutoff);
+        before - self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// True if `entries[index]` begins an undo step (a single entry or the first of a batch)
+fn starts_group(entries: &[TrashEntry], index: usize) -> bool {
+    index == 0 || entries[index].batch.is_none() || entries[index - 1].batch != entries[index].batch
+}
+
+/// Number of undo steps in `entries`; a batch counts once
+pub fn group_count(entries: &[TrashEntry]) -> usize {
+    (0..entries.len()).filter(|&index| starts_group(entries, index)).count()
+}
+
+/// Removes and returns the most recent undo step: the last entry and the rest of its batch
+pub fn pop_group(entries: &mut Vec<TrashEntry>) -> Vec<TrashEntry> {
+    let start = (0..entries.len()).rev().find(|&index| starts_group(entries, index));
+    match start {
+        Some(start) => entries.split_off(start),
+        None => Vec::new(),
+    }
+}
+
+/// Drops the oldest undo steps until at most `max` remain; batches are kept whole
+pub fn trim_groups(entries: &mut Vec<TrashEntry>, max: usize) {
+    let excess = group_count(entries).saturating_sub(max);
+    let cut = (0..entries.len())
+        .filter(|&index| starts_group(entries, index))
+        .nth(excess)
+        .unwrap_or(entries.len());
+    entries.drain(..cut);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn task(status: DownloadStatus) -> DownloadTask {
+        let mut task = DownloadTask::new("https://example.com/file.zip".to_string(), PathBuf::from("/tmp"));
+        task.status = status;
+        task
+    }
+
+    #[test]
+    fn test_push_marks_deleted_and_restores_previous_status() {
+        let mut trash = Trash::default();
+        trash.push(task(DownloadStatus::Error));
+        trash.push(task(DownloadStatus::Downloading));
+
+        assert!(trash.entries.iter().all(|e| e.task.status == DownloadStatus::Deleted));
+        assert_eq!(trash.pop().unwrap().into_task().status, DownloadStatus::Paused);
+        assert_eq!(trash.pop().unwrap().into_task().status, DownloadStatus::Error);
+    }
+
+    #[test]
+    fn test_batches_are_one_undo_step() {
+        let mut trash = Trash::default();
+        trash.push(task(DownloadStatus::Pending));
+        trash.push_batch([task(DownloadStatus::Pending), task(DownloadStatus::Paused), task(DownloadStatus::Error)]);
+        trash.push(task(DownloadStatus::Completed));
+        trash.push_batch([task(DownloadStatus::Pending), task(DownloadStatus::Pending)]);
+        assert_eq!(group_count(&trash.entries), 4);
+
+        let mut entries = trash.entries.clone();
+        assert_eq!(pop_group(&mut entries).len(), 2);
+        assert_eq!(pop_group(&mut entries).len(), 1);
+        assert_eq!(pop_group(&mut entries).len(), 3);
+        assert_eq!(pop_group(&mut entries).len(), 1);
+        assert!(pop_group(&mut entries).is_empty());
+
+        // Trimming removes whole steps, oldest first
+        let mut entries = trash.entries.clone();
+        trim_groups(&mut entries, 2);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(group_count(&entries), 2);
+        trim_groups(&mut entries, 5);
+        assert_eq!(entries.len(), 3);
+
+        // Entries saved before batches existed load without one
+        let json = serde_json::to_string(&trash.entries[0]).unwrap();
+        assert!(!json.contains("batch"));
+        let entry: TrashEntry = serde_json::from_str(&json).unwrap();
+        assert!(entry.batch.is_none());
+    }
+
+    #[test]
+    fn test_take_by_prefix() {
+        let mut trash = Trash::default();
+        let t = task(DownloadStatus::Pending);
+        let id = t.id;
+        trash.push(t);
+
+        assert!(trash.take("zzzz").is_err());
+        let entry = trash.take(&id.to_string()[..8]).unwrap();
+        assert_eq!(entry.task.id, id);
+        assert!(trash.is_empty());
+    }
+
+    #[test]
+    fn test_expire_and_persist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("trash.json");
+
+        let mut trash = Trash::default();
+        trash.push(task(DownloadStatus::Pending));
+        trash.push(task(DownloadStatus::Pending));
+        trash.entries[0].deleted_at = Utc::now() - chrono::Duration::days(10);
+
+        assert_eq!(trash.expire(0), 0);
+        assert_eq!(trash.expire(u32::MAX), 0);
+        assert_eq!(trash.expire(7), 1);
+        trash.save(&path).unwrap();
+
+        let loaded = Trash::load(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(Trash::load(&temp_dir.path().join("missing.json")).unwrap().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod naming;
 pub mod metadata;
 pub mod manager;
+pub mod path_template;
@@ -34,6 +34,207 @@ pub fn sanitize_filename(name: &str) -> String {
     result
 }
 
+/// Extracts the filename from a `Content-Disposition` header value.
+///
+/// Prefers the RFC 5987 `filename*` parameter (e.g. `filename*=UTF-8''%E6%97%A5.txt`)
+/// over the plain `filename` parameter. Returns `None` when neither parameter is
+/// present or usable. The result is not sanitized.
+///
+/// # Examples
+///
+/// ```
+/// use ggg::file::naming::parse_content_disposition_filename;
+///
+/// let name = parse_content_disposition_filename("attachment; filename=\"report.pdf\"");
+/// assert_eq!(name, Some("report.pdf".to_string()));
+/// ```
+pub fn parse_content_disposition_filename(header: &str) -> Option<String> {
+    let mut plain = None;
+    let mut extended = None;
+
+    for param in split_header_params(header) {
+        let Some((name, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "filename*" => extended = decode_ext_value(value),
+            "filename" => plain = Some(unquote(value)),
+            _ => {}
+        }
+    }
+
+    extended
+        .or(plain)
+        .filter(|name| !name.trim().is_empty())
+}
+
+/// Extracts the last path segment of a URL, ignoring query and fragment.
+///
+/// Returns `None` when the URL has no usable path segment (e.g. `https://example.com/`).
+pub fn filename_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let path = path.split_once("://").map(|(_, rest)| rest).unwrap_or(path);
+    // Skip the host part
+    let (_, path) = path.split_once('/')?;
+    let segment = path.rsplit('/').next().unwrap_or("");
+    if segment.is_empty() {
+        return None;
+    }
+    let decoded = percent_decode(segment).and_then(|bytes| String::from_utf8(bytes).ok());
+    Some(decoded.unwrap_or_else(|| segment.to_string()))
+}
+
+/// Extensions for common MIME types, used when a filename has none
+const MIME_EXTENSIONS: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/avif", "avif"),
+    ("image/bmp", "bmp"),
+    ("image/svg+xml", "svg"),
+    ("video/mp4", "mp4"),
+    ("video/webm", "webm"),
+    ("video/x-matroska", "mkv"),
+    ("video/quicktime", "mov"),
+    ("audio/mpeg", "mp3"),
+    ("audio/mp4", "m4a"),
+    ("audio/ogg", "ogg"),
+    ("audio/flac", "flac"),
+    ("audio/wav", "wav"),
+    ("audio/x-wav", "wav"),
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("application/x-7z-compressed", "7z"),
+    ("application/vnd.rar", "rar"),
+    ("application/x-rar-compressed", "rar"),
+    ("application/gzip", "gz"),
+    ("application/x-tar", "tar"),
+    ("application/epub+zip", "epub"),
+    ("application/x-bittorrent", "torrent"),
+    ("application/json", "json"),
+    ("application/xml", "xml"),
+    ("text/xml", "xml"),
+    ("text/html", "html"),
+    ("text/css", "css"),
+    ("text/csv", "csv"),
+    ("text/plain", "txt"),
+];
+
+/// Returns the usual extension (without dot) for a `Content-Type` header value.
+///
+/// Parameters such as `; charset=utf-8` are ignored. Generic types like
+/// `application/octet-stream` have no mapping.
+pub fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    MIME_EXTENSIONS
+        .iter()
+        .find(|(known, _)| *known == mime)
+        .map(|(_, ext)| *ext)
+}
+
+/// Appends an extension derived from `content_type` to a filename that has none.
+///
+/// Returns `None` when the filename already has an extension or the type is
+/// unknown, so callers keep the original name.
+///
+/// # Examples
+///
+/// ```
+/// use ggg::file::naming::infer_extension;
+///
+/// assert_eq!(infer_extension("file", Some("image/png")), Some("file.png".to_string()));
+/// assert_eq!(infer_extension("photo.jpg", Some("image/png")), None);
+/// ```
+pub fn infer_extension(filename: &str, content_type: Option<&str>) -> Option<String> {
+    if std::path::Path::new(filename).extension().is_some() {
+        return None;
+    }
+    let ext = extension_for_content_type(content_type?)?;
+    Some(format!("{}.{}", filename, ext))
+}
+
+/// Splits header parameters on `;`, ignoring separators inside quoted strings
+fn split_header_params(header: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in header.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                params.push(&header[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&header[start..]);
+    params
+}
+
+/// Removes surrounding quotes and unescapes a quoted-string
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"') else {
+        return value.to_string();
+    };
+    let inner = inner.strip_suffix('"').unwrap_or(inner);
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Decodes an RFC 5987 ext-value: `charset'[language]'percent-encoded`
+fn decode_ext_value(value: &str) -> Option<String> {
+    let value = value.trim_matches('"');
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+    let bytes = percent_decode(encoded)?;
+
+    match charset.to_ascii_lowercase().as_str() {
+        "utf-8" => String::from_utf8(bytes).ok(),
+        // ISO-8859-1 maps each byte directly to the same code point
+        "iso-8859-1" => Some(bytes.into_iter().map(char::from).collect()),
+        _ => None,
+    }
+}
+
+/// Decodes `%XX` escapes into raw bytes. Returns `None` on a malformed escape.
+fn percent_decode(input: &str) -> Option<Vec<u8>> {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            result.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(result)
+}
+
 /// Adds Unix time in milliseconds to filename before the extension.
 ///
 /// # Examples
@@ -144,6 +345,27 @@ mod tests {
         assert_eq!(sanitize_filename("COM1"), "_COM1");
     }
 
+    #[test]
+    fn test_infer_extension_from_content_type() {
+        assert_eq!(infer_extension("file", Some("image/png")), Some("file.png".to_string()));
+        assert_eq!(infer_extension("download", Some("image/jpeg")), Some("download.jpg".to_string()));
+        assert_eq!(infer_extension("doc", Some("application/pdf")), Some("doc.pdf".to_string()));
+        assert_eq!(
+            infer_extension("page", Some("Text/HTML; charset=UTF-8")),
+            Some("page.html".to_string())
+        );
+        // Dotfiles have no extension either
+        assert_eq!(infer_extension(".hidden", Some("text/plain")), Some(".hidden.txt".to_string()));
+    }
+
+    #[test]
+    fn test_infer_extension_no_op() {
+        // Existing extensions are kept even if they disagree with the type
+        assert_eq!(infer_extension("archive.zip", Some("image/png")), None);
+        assert_eq!(infer_extension("file", Some("application/octet-stream")), None);
+        assert_eq!(infer_extension("file", None), None);
+    }
+
     #[test]
     fn test_sanitize_empty() {
         assert_eq!(sanitize_filename(""), "_");
@@ -208,4 +430,59 @@ mod tests {
         assert_eq!(sanitize_filename("path/NUL:file?.txt"), "path_NUL_file_.txt");
         assert_eq!(sanitize_filename("   "), "_");
     }
+
+    #[test]
+    fn test_content_disposition_quoted_filename() {
+        assert_eq!(
+            parse_content_disposition_filename("attachment; filename=\"my report.pdf\""),
+            Some("my report.pdf".to_string())
+        );
+        assert_eq!(
+            parse_content_disposition_filename("attachment; filename=\"a; b \\\"c\\\".txt\""),
+            Some("a; b \"c\".txt".to_string())
+        );
+        assert_eq!(
+            parse_content_disposition_filename("inline; filename=plain.zip"),
+            Some("plain.zip".to_string())
+        );
+    }
+
+    #[test]
+    fn test_content_disposition_rfc5987_filename() {
+        assert_eq!(
+            parse_content_disposition_filename("attachment; filename*=UTF-8''%E6%97%A5%E6%9C%AC%E8%AA%9E.txt"),
+            Some("日本語.txt".to_string())
+        );
+        // filename* takes precedence regardless of parameter order
+        assert_eq!(
+            parse_content_disposition_filename(
+                "attachment; filename*=utf-8'en'na%C3%AFve.txt; filename=\"naive.txt\""
+            ),
+            Some("naïve.txt".to_string())
+        );
+        assert_eq!(
+            parse_content_disposition_filename("attachment; filename*=iso-8859-1''caf%E9.txt"),
+            Some("café.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_content_disposition_malformed_falls_back() {
+        // Broken filename* falls back to plain filename
+        assert_eq!(
+            parse_content_disposition_filename("attachment; filename*=UTF-8''bad%ZZ.txt; filename=\"ok.txt\""),
+            Some("ok.txt".to_string())
+        );
+        assert_eq!(parse_content_disposition_filename("attachment"), None);
+        assert_eq!(parse_content_disposition_filename("attachment; filename=\"\""), None);
+        assert_eq!(parse_content_disposition_filename("attachment; filename*=UTF-8''%E6"), None);
+    }
+
+    #[test]
+    fn test_filename_from_url() {
+        assert_eq!(filename_from_url("https://example.com/files/archive.zip?token=1"), Some("archive.zip".to_string()));
+        assert_eq!(filename_from_url("https://example.com/a/my%20file.txt#top"), Some("my file.txt".to_string()));
+        assert_eq!(filename_from_url("https://example.com/"), None);
+        assert_eq!(filename_from_url("https://example.com"), None);
+    }
 }
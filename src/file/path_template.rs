@@ -0,0 +1,100 @@
+//! Save-path templates
+//!
+//! A folder's `save_path_template` (e.g. `{base}/{yyyy}/{mm}/{host}`) is
+//! expanded when a task is created. Supported placeholders:
+//!
+//! | Placeholder | Value |
+//! |-------------|-------|
+//! | `{base}`    | Folder `save_path` (first segment only) |
+//! | `{yyyy}`    | Year, 4 digits |
+//! | `{mm}`      | Month, 2 digits |
+//! | `{dd}`      | Day, 2 digits |
+//! | `{date}`    | `YYYYMMDD` (same as `auto_date_directory`) |
+//! | `{host}`    | Host name of the download URL |
+//! | `{folder}`  | Folder ID |
+//!
+//! Templates are always resolved below the folder's `save_path`, whether or
+//! not they start with `{base}`. Each segment is sanitized like a filename, so
+//! placeholder values cannot introduce separators or `..`.
+
+use chrono::{DateTime, Local, Utc};
+use std::path::{Path, PathBuf};
+
+use super::naming::sanitize_filename;
+
+/// Template equivalent to `auto_date_directory = true`
+pub const AUTO_DATE_TEMPLATE: &str = "{base}/{date}";
+
+/// Values substituted into a save-path template
+#[derive(Debug, Clone)]
+pub struct TemplateContext<'a> {
+    pub url: &'a str,
+    pub folder_id: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Expands `template` below `base`
+pub fn expand_save_path_template(template: &str, base: &Path, ctx: &TemplateContext) -> PathBuf {
+    let local = ctx.created_at.with_timezone(&Local);
+    let host = url::Url::parse(ctx.url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_else(|| "unknown-host".to_string());
+
+    let mut path = base.to_path_buf();
+    for (index, segment) in template.split(['/', '\\']).enumerate() {
+        let segment = segment.trim();
+        if segment.is_empty() || (index == 0 && segment == "{base}") {
+            continue;
+        }
+
+        let expanded = segment
+            .replace("{yyyy}", &local.format("%Y").to_string())
+            .replace("{mm}", &local.format("%m").to_string())
+            .replace("{dd}", &local.format("%d").to_string())
+            .replace("{date}", &local.format("%Y%m%d").to_string())
+            .replace("{host}", &host)
+            .replace("{folder}", ctx.folder_id);
+        path.push(sanitize_filename(&expanded));
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ctx(url: &str) -> TemplateContext<'_> {
+        TemplateContext {
+            url,
+            folder_id: "images",
+            created_at: Local.with_ymd_and_hms(2024, 3, 7, 12, 0, 0).unwrap().with_timezone(&Utc),
+        }
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let base = PathBuf::from("downloads");
+        let path = expand_save_path_template(
+            "{base}/{yyyy}/{mm}/{host}",
+            &base,
+            &ctx("https://cdn.example.com/a/file.zip"),
+        );
+        assert_eq!(path, base.join("2024").join("03").join("cdn.example.com"));
+
+        let path = expand_save_path_template("{folder}-{date}", &base, &ctx("https://example.com/x"));
+        assert_eq!(path, base.join("images-20240307"));
+        assert_eq!(
+            expand_save_path_template(AUTO_DATE_TEMPLATE, &base, &ctx("https://example.com/x")),
+            base.join("20240307")
+        );
+    }
+
+    #[test]
+    fn test_segments_are_sanitized() {
+        let base = PathBuf::from("downloads");
+        let path = expand_save_path_template("../{host}/a:b", &base, &ctx("not a url"));
+        assert_eq!(path, base.join("_").join("unknown-host").join("a_b"));
+    }
+}
@@ -1,11 +1,15 @@
 use anyhow::Result;
 use clap::Parser;
 use ggg::{
-    app::{config::Config, state::AppState},
+    app::{
+        config::{ConsoleFormat, Config, LogRotation},
+        state::AppState,
+    },
     cli::{self, Cli},
-    download::manager::DownloadManager,
+    download::{http_client::HttpClient, manager::DownloadManager},
     tui::run_tui,
 };
+use std::io::IsTerminal;
 use std::path::PathBuf;
 use tracing_subscriber::prelude::*;
 
@@ -14,23 +18,71 @@ async fn main() -> Result<()> {
     // Parse CLI arguments first to get verbose flag
     let cli = Cli::parse();
 
+    // Set config directory override if --config flag was used
+    if let Some(ref config_dir) = cli.config {
+        ggg::util::paths::set_config_dir_override(Some(config_dir.clone()));
+    }
+
+    // Load configuration before logging is set up so [logging] applies
+    let (config, config_error, env_problems) = match Config::load() {
+        Ok(config) => {
+            let env_problems = config.env_override_problems().to_vec();
+            (config, None, env_problems)
+        }
+        Err(e) => {
+            // Environment overrides still apply on top of the defaults
+            let mut config = Config::default();
+            let (_, env_problems) = config.apply_env_overrides();
+            (config, Some(e), env_problems)
+        }
+    };
+
     // Get logs directory (creates if needed)
     let logs_dir = ggg::util::paths::get_logs_dir().unwrap_or_else(|_| PathBuf::from("."));
     std::fs::create_dir_all(&logs_dir).ok();
 
-    // Set up daily rotating file appender (YYYYMMDD.jsonl format)
-    let file_appender = tracing_appender::rolling::daily(&logs_dir, "app.jsonl");
+    // Drop old log files before the appender opens a new one
+    let pruned_logs = cli::logs::prune_log_files(&logs_dir, config.logging.max_files);
+
+    // Set up rotating file appender (app.jsonl.YYYY-MM-DD by default)
+    let prefix = cli::logs::LOG_FILE_PREFIX;
+    let file_appender = match config.logging.rotation {
+        LogRotation::Daily => tracing_appender::rolling::daily(&logs_dir, prefix),
+        LogRotation::Hourly => tracing_appender::rolling::hourly(&logs_dir, prefix),
+        LogRotation::Never => tracing_appender::rolling::never(&logs_dir, prefix),
+    };
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
-    // Set log level based on verbose flag
+    // Set log level: --verbose, then [logging] level, then INFO
+    let configured_level = cli::logs::parse_level(&config.logging.level);
     let log_level = if cli.verbose {
         tracing::Level::TRACE
     } else {
-        tracing::Level::INFO
+        *configured_level.as_ref().unwrap_or(&tracing::Level::INFO)
     };
 
+    // Daemon mode also logs to stderr; the TUI owns the terminal and CLI
+    // commands print their own output. --quiet keeps only errors there.
+    let console_level = if cli.quiet { tracing::Level::ERROR } else { log_level };
+    let console_layer = (cli.headless && cli.command.is_none()).then(|| {
+        let layer = match config.logging.console_format {
+            ConsoleFormat::Json => tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(std::io::stderr)
+                .with_ansi(false)
+                .boxed(),
+            ConsoleFormat::Pretty => tracing_subscriber::fmt::layer()
+                .compact()
+                .with_writer(std::io::stderr)
+                .with_ansi(std::io::stderr().is_terminal())
+                .boxed(),
+        };
+        layer.with_filter(tracing_subscriber::filter::LevelFilter::from_level(console_level))
+    });
+
     // Initialize logging with JSON format for structured logs
     tracing_subscriber::registry()
+        .with(console_layer)
         .with(
             tracing_subscriber::fmt::layer()
                 .json()
@@ -48,15 +100,39 @@ async fn main() -> Result<()> {
     }
     tracing::trace!("CLI arguments: {:?}", cli);
 
-    // Set config directory override if --config flag was used
     if let Some(ref config_dir) = cli.config {
         tracing::info!("Using config directory override: {:?}", config_dir);
-        ggg::util::paths::set_config_dir_override(Some(config_dir.clone()));
     }
-
-    // Load configuration
-    tracing::trace!("Loading configuration from file...");
-    let config = Config::load().unwrap_or_default();
+    // Report what was ignored instead of quietly running on defaults
+    let mut config_problems = Vec::new();
+    if let Some(ref e) = config_error {
+        config_problems.push(format!("Failed to load configuration, using defaults: {:#}", e));
+    }
+    config_problems.extend(env_problems);
+    match Config::check_files() {
+        Ok(issues) => {
+            // A broken settings.toml is already reported by the load error
+            if config_error.is_none() {
+                config_problems.extend(issues.errors.iter().map(|e| format!("Ignored folder config {}", e)));
+            }
+            config_problems.extend(issues.unknown_keys.iter().map(|key| format!("Unknown setting ignored: {}", key)));
+        }
+        Err(e) => tracing::debug!("Could not check configuration files: {}", e),
+    }
+    if let Some(ref temp_dir) = config.download.temp_dir {
+        config_problems.extend(ggg::util::fs::check_temp_dir(temp_dir, &config.download.default_directory));
+    }
+    for problem in &config_problems {
+        tracing::warn!("{}", problem);
+    }
+    if let Err(e) = configured_level {
+        tracing::warn!("logging.level: {}; using info", e);
+    }
+    match pruned_logs {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!("Removed {} old log file(s)", removed),
+        Err(e) => tracing::warn!("Failed to prune old log files: {}", e),
+    }
     tracing::info!("Config loaded: {:?}", config);
     tracing::trace!("Configuration details: max_concurrent={}, retry_count={}",
         config.download.max_concurrent,
@@ -66,6 +142,25 @@ async fn main() -> Result<()> {
     let language = config.general.language.clone();
     let state = AppState::new_with_scripts(config.clone(), &language).await?;
 
+    // Warn if another instance is already managing the same queue files
+    let instance_lock = match ggg::util::paths::find_config_directory()
+        .and_then(|dir| ggg::util::file_lock::FileLock::try_exclusive(&dir.join("instance.lock")))
+    {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            let message = "Another ggg instance is using this config directory; queue changes may be overwritten by it";
+            tracing::warn!("{}", message);
+            if cli.headless && !cli.quiet {
+                eprintln!("Warning: {}", message);
+            }
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Failed to take instance lock: {}", e);
+            None
+        }
+    };
+
     // Initialize download manager with folder slot configuration
     let max_concurrent = config.download.max_concurrent;
     let max_concurrent_per_folder = config.download.max_concurrent_per_folder.unwrap_or(max_concurrent);
@@ -77,18 +172,58 @@ async fn main() -> Result<()> {
         parallel_folder_count,
         config.download.retry_count,
         config.download.retry_delay,
-    );
+    )
+    .with_http_client(HttpClient::from_config(&config.download)?);
+    download_manager.set_max_connections(config.download.max_connections.unwrap_or(max_concurrent));
+    let download_manager = match ggg::util::paths::get_circuit_breaker_path() {
+        Ok(path) => download_manager.with_circuit_store(path),
+        Err(e) => {
+            tracing::warn!("Circuit breaker state will not be saved: {}", e);
+            download_manager
+        }
+    };
 
-    // Load queue from folder-based files
-    if let Err(e) = download_manager.load_queue_from_folders().await {
+    // The TUI shows these in its status bar instead (`config validate` prints them itself)
+    let validating = matches!(cli.command, Some(cli::Commands::Config { action: cli::ConfigAction::Validate }));
+    if (cli.headless || cli.command.is_some()) && !cli.quiet && !validating {
+        for problem in &config_problems {
+            eprintln!("Warning: {}", problem);
+        }
+    }
+    if config.download.danger_accept_invalid_certs && (cli.headless || cli.command.is_some()) && !cli.quiet {
+        eprintln!("Warning: TLS certificate verification is disabled (download.danger_accept_invalid_certs)");
+    }
+
+    // Load queue from folder-based files; downloads still marked Downloading
+    // only belong to a crashed process if no other instance holds the lock
+    if let Err(e) = download_manager.load_queue_from_folders(&config, instance_lock.is_some()).await {
         tracing::warn!("Failed to load queue from folder files: {}", e);
     } else {
         tracing::info!("Queue loaded from folder files");
     }
 
-    // Warn about legacy queue.json
-    if PathBuf::from("queue.json").exists() {
-        tracing::warn!("Legacy queue.json detected. New queues are stored in config/{{folder_id}}/queue.toml");
+    // Apply history retention policy
+    let history_cutoff = (config.history.max_age_days > 0)
+        .then(|| chrono::Utc::now().checked_sub_signed(chrono::Duration::days(config.history.max_age_days as i64)))
+        .flatten();
+    match ggg::download::completion_log::prune(history_cutoff, config.history.max_entries) {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!("Pruned {} completion log entries", removed),
+        Err(e) => tracing::warn!("Failed to prune completion log: {}", e),
+    }
+    let removed = download_manager
+        .set_history_retention(config.history.max_entries, config.history.max_age_days)
+        .await;
+    if removed > 0 {
+        tracing::info!("Pruned {} history entries", removed);
+    }
+
+    // Migrate legacy queue.json into per-folder queue files (one-time)
+    if let Err(e) = download_manager
+        .migrate_legacy_queue(&PathBuf::from("queue.json"), &config)
+        .await
+    {
+        tracing::warn!("Failed to migrate legacy queue.json: {}", e);
     }
 
     // Route based on CLI arguments
@@ -106,10 +241,10 @@ async fn main() -> Result<()> {
         None => {
             if cli.headless {
                 // Headless daemon mode
-                cli::daemon::run_daemon(download_manager).await?;
+                cli::daemon::run_daemon(state, download_manager).await?;
             } else {
                 // TUI mode (default)
-                run_tui(state, download_manager).await?;
+                run_tui(state, download_manager, config_problems).await?;
             }
         }
     }
@@ -1,17 +1,176 @@
 //! JavaScript API bindings (ggg.*)
 //!
-//! This module will implement the JavaScript global API that scripts can use:
+//! JavaScript global API that scripts can use:
 //! - ggg.on(eventName, callback, filter?) - Register event handlers
-//! - ggg.log(message) - Logging from scripts
+//! - ggg.log(message) - Logging from scripts (same as ggg.log.info)
+//! - ggg.log.info/warn/error(message) - Leveled logging from scripts
 //! - ggg.config.get(key) - Access configuration
 //!
+//! Log calls are buffered on the JavaScript side and forwarded to `tracing`
+//! (target `script`) by the engine after each handler, so they land in the
+//! daily JSONL logs together with the originating script's filename.
+//!
 //! Event object methods (attached to event context):
 //! - e.setUrl(url) - Modify URL (beforeRequest)
 //! - e.setHeader(key, value) - Set header (beforeRequest)
 //! - e.setUserAgent(ua) - Set user agent (beforeRequest)
 //! - e.rename(filename) - Rename file (completed)
 //! - e.moveTo(path) - Move file (completed)
-//!
-//! Phase 3 implementation
 
-// TODO: Implement JavaScript API bindings
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// `ggg.log` and `console.*` bindings, evaluated after the `ggg` object exists
+///
+/// Entries are pushed to `ggg._logBuffer` as `{ level, message }`.
+pub const LOG_BINDINGS: &str = r#"
+    (function() {
+        function push(level, args) {
+            ggg._logBuffer.push({ level: level, message: Array.prototype.map.call(args, String).join(' ') });
+        }
+
+        ggg._logBuffer = [];
+        ggg.log = function() { push('info', arguments); };
+        ggg.log.info = function() { push('info', arguments); };
+        ggg.log.warn = function() { push('warn', arguments); };
+        ggg.log.error = function() { push('error', arguments); };
+
+        // Redirect console output to the same buffer
+        // Prevents Deno core console from writing directly to stdout
+        globalThis.console = {
+            log: function() { push('info', arguments); },
+            info: function() { push('info', arguments); },
+            warn: function() { push('warn', arguments); },
+            error: function() { push('error', arguments); },
+            debug: function() { push('debug', arguments); },
+        };
+    })();
+"#;
+
+/// Maximum log lines a single script may emit per `LOG_RATE_WINDOW`
+pub const LOG_RATE_LIMIT: u32 = 100;
+
+/// Rate-limit window for script log lines
+pub const LOG_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Severity of a script log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A buffered `ggg.log` call
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogEntry {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Per-script window state
+#[derive(Debug)]
+struct RateWindow {
+    started: Instant,
+    emitted: u32,
+    dropped: u32,
+}
+
+/// Forwards script log entries to `tracing`, limiting each script to
+/// `LOG_RATE_LIMIT` lines per `LOG_RATE_WINDOW`
+///
+/// Dropped lines are counted and reported once the window ends, so a chatty
+/// script cannot flood the log files.
+#[derive(Debug, Default)]
+pub struct ScriptLogger {
+    windows: HashMap<PathBuf, RateWindow>,
+}
+
+impl ScriptLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emit `entries` logged by `script_path`
+    pub fn emit(&mut self, script_path: &Path, entries: Vec<LogEntry>) {
+        let now = Instant::now();
+        for entry in entries {
+            if self.allow(script_path, now) {
+                log_entry(script_path, &entry);
+            }
+        }
+    }
+
+    /// Count one line against the script's window; false if over the limit
+    fn allow(&mut self, script_path: &Path, now: Instant) -> bool {
+        let window = self
+            .windows
+            .entry(script_path.to_path_buf())
+            .or_insert(RateWindow { started: now, emitted: 0, dropped: 0 });
+
+        if now.duration_since(window.started) >= LOG_RATE_WINDOW {
+            if window.dropped > 0 {
+                tracing::warn!(
+                    target: "script",
+                    script = %script_name(script_path),
+                    "Suppressed {} log line(s) (limit {} per {}s)",
+                    window.dropped,
+                    LOG_RATE_LIMIT,
+                    LOG_RATE_WINDOW.as_secs()
+                );
+            }
+            *window = RateWindow { started: now, emitted: 0, dropped: 0 };
+        }
+
+        if window.emitted < LOG_RATE_LIMIT {
+            window.emitted += 1;
+            true
+        } else {
+            window.dropped += 1;
+            false
+        }
+    }
+}
+
+fn script_name(script_path: &Path) -> String {
+    script_path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| script_path.display().to_string())
+}
+
+fn log_entry(script_path: &Path, entry: &LogEntry) {
+    let script = script_name(script_path);
+    match entry.level {
+        LogLevel::Debug => tracing::debug!(target: "script", script = %script, "{}", entry.message),
+        LogLevel::Info => tracing::info!(target: "script", script = %script, "{}", entry.message),
+        LogLevel::Warn => tracing::warn!(target: "script", script = %script, "{}", entry.message),
+        LogLevel::Error => tracing::error!(target: "script", script = %script, "{}", entry.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_per_script_and_window() {
+        let mut logger = ScriptLogger::new();
+        let chatty = Path::new("chatty.js");
+        let quiet = Path::new("quiet.js");
+        let start = Instant::now();
+
+        for _ in 0..LOG_RATE_LIMIT {
+            assert!(logger.allow(chatty, start));
+        }
+        assert!(!logger.allow(chatty, start));
+        assert!(logger.allow(quiet, start));
+
+        // New window resets the budget
+        assert!(logger.allow(chatty, start + LOG_RATE_WINDOW));
+    }
+}
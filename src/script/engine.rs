@@ -1,5 +1,7 @@
+use crate::script::api::{self, LogEntry, ScriptLogger};
 use crate::script::error::{ScriptError, ScriptResult};
-use crate::script::events::{EventContext, HookEvent};
+use crate::script::error_log::ScriptErrorLog;
+use crate::script::events::{EventContext, HookEvent, ScriptRun};
 use deno_core::{v8, JsRuntime, RuntimeOptions};
 use regex::Regex;
 use serde::Deserialize;
@@ -20,6 +22,12 @@ pub struct ScriptEngine {
     runtime: JsRuntime,
     handlers: Arc<Mutex<HashMap<HookEvent, Vec<EventHandler>>>>,
     timeout: Duration,
+    /// Recent errors shown in the TUI (optional)
+    error_log: Option<ScriptErrorLog>,
+    /// Forwards ggg.log() output to tracing (rate-limited)
+    logger: ScriptLogger,
+    /// Handlers run since the last `take_runs`
+    runs: Vec<ScriptRun>,
 }
 
 /// Registered event handler
@@ -166,12 +174,6 @@ impl ScriptEngine {
                     return true;
                 },
 
-                // Logging function (buffered, flushed to tracing by Rust)
-                _logBuffer: [],
-                log: function(message) {
-                    ggg._logBuffer.push(String(message));
-                },
-
                 // Config access (stub for now)
                 config: {
                     get: function(key) {
@@ -179,26 +181,6 @@ impl ScriptEngine {
                     }
                 }
             };
-
-            // Override console methods to redirect output to ggg._logBuffer
-            // Prevents Deno core console from writing directly to stdout
-            globalThis.console = {
-                log: function(...args) {
-                    ggg._logBuffer.push(args.map(String).join(' '));
-                },
-                warn: function(...args) {
-                    ggg._logBuffer.push('[WARN] ' + args.map(String).join(' '));
-                },
-                error: function(...args) {
-                    ggg._logBuffer.push('[ERROR] ' + args.map(String).join(' '));
-                },
-                info: function(...args) {
-                    ggg._logBuffer.push(args.map(String).join(' '));
-                },
-                debug: function(...args) {
-                    ggg._logBuffer.push('[DEBUG] ' + args.map(String).join(' '));
-                },
-            };
         "#;
 
         runtime
@@ -207,13 +189,44 @@ impl ScriptEngine {
                 ScriptError::RuntimeInitError(format!("Failed to register ggg API: {}", e))
             })?;
 
+        // Logging functions (buffered, flushed to tracing by Rust)
+        runtime
+            .execute_script("<ggg:log-api>", api::LOG_BINDINGS.to_string())
+            .map_err(|e| {
+                ScriptError::RuntimeInitError(format!("Failed to register ggg.log API: {}", e))
+            })?;
+
         Ok(Self {
             runtime,
             handlers,
             timeout,
+            error_log: None,
+            logger: ScriptLogger::new(),
+            runs: Vec::new(),
         })
     }
 
+    /// Record script errors into `log` in addition to tracing
+    pub fn set_error_log(&mut self, log: ScriptErrorLog) {
+        self.error_log = Some(log);
+    }
+
+    /// Add an error to the error log, if one is attached
+    pub fn record_error(&self, script_path: &Path, event: Option<HookEvent>, message: &str) {
+        if let Some(ref log) = self.error_log {
+            let script = script_path
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_else(|| script_path.display().to_string());
+            log.push(script, event.map(|e| e.name()), message);
+        }
+    }
+
+    /// Handlers run since the last call, in order
+    pub fn take_runs(&mut self) -> Vec<ScriptRun> {
+        std::mem::take(&mut self.runs)
+    }
+
     /// Load and compile a script file
     pub fn load_script(&mut self, path: &Path) -> ScriptResult<()> {
         // Read script file
@@ -223,11 +236,13 @@ impl ScriptEngine {
         })?;
 
         // Execute script to register handlers (with timeout)
-        self.execute_with_timeout("<ggg:load>", script_content)
-            .map_err(|e| ScriptError::CompilationError {
-                path: path.to_owned(),
-                message: e.to_string(),
-            })?;
+        let load_result = self.execute_with_timeout("<ggg:load>", script_content);
+        // Top-level ggg.log() calls belong to this script
+        self.flush_log_buffer(path);
+        load_result.map_err(|e| ScriptError::CompilationError {
+            path: path.to_owned(),
+            message: e.to_string(),
+        })?;
 
         // Extract registered handlers from JavaScript
         let global = self
@@ -285,6 +300,55 @@ impl ScriptEngine {
         Ok(())
     }
 
+    /// Compile a script file without running it
+    ///
+    /// V8 compiles the file as a classic script but never runs it, so no
+    /// top-level code executes and no handlers are registered.
+    pub fn validate_script(&mut self, path: &Path) -> ScriptResult<()> {
+        let script_content = std::fs::read_to_string(path).map_err(|e| ScriptError::FileReadError {
+            path: path.to_owned(),
+            source: e,
+        })?;
+
+        deno_core::scope!(scope, self.runtime);
+        let (Some(source), Some(name)) = (
+            v8::String::new(scope, &script_content),
+            v8::String::new(scope, "<ggg:validate>"),
+        ) else {
+            return Err(ScriptError::CompilationError {
+                path: path.to_owned(),
+                message: "script is too large".to_string(),
+            });
+        };
+        let origin = v8::ScriptOrigin::new(scope, name.into(), 0, 0, false, 0, None, false, false, false, None);
+
+        v8::tc_scope!(let tc_scope, scope);
+        if v8::Script::compile(tc_scope, source, Some(&origin)).is_some() {
+            return Ok(());
+        }
+
+        let Some(error) = tc_scope.message() else {
+            return Err(ScriptError::CompilationError {
+                path: path.to_owned(),
+                message: "compilation failed".to_string(),
+            });
+        };
+        let message = error.get(tc_scope).to_rust_string_lossy(tc_scope);
+        Err(match error.get_line_number(tc_scope) {
+            Some(line) => ScriptError::SyntaxError {
+                path: path.to_owned(),
+                line: line as u32,
+                // V8 columns are 0-based
+                column: error.get_start_column() as u32 + 1,
+                message,
+            },
+            None => ScriptError::CompilationError {
+                path: path.to_owned(),
+                message,
+            },
+        })
+    }
+
     /// Execute handlers for a specific event
     pub fn execute_handlers<C: EventContext>(
         &mut self,
@@ -352,7 +416,9 @@ impl ScriptEngine {
                             "Script deserialization error: {}",
                             e
                         );
+                        self.record_error(&handler.script_path, Some(event), &e.to_string());
                         self.flush_log_buffer(&handler.script_path);
+                        self.runs.push(ScriptRun::new(filename, event, false, Some(e.to_string())));
                         continue;
                     }
                 },
@@ -362,7 +428,9 @@ impl ScriptEngine {
                         "Script execution error: {}",
                         e
                     );
+                    self.record_error(&handler.script_path, Some(event), &e.to_string());
                     self.flush_log_buffer(&handler.script_path);
+                    self.runs.push(ScriptRun::new(filename, event, false, Some(e.to_string())));
                     continue; // Continue to next handler on error
                 }
             };
@@ -374,6 +442,8 @@ impl ScriptEngine {
             if let Some(modified_ctx) = result.get("ctx") {
                 *ctx = C::from_json(modified_ctx.clone())?;
             }
+            let modified = ctx.to_json()? != ctx_json;
+            self.runs.push(ScriptRun::new(filename, event, modified, None));
 
             // Check if handler returned false (stop propagation)
             if let Some(handler_result) = result.get("result") {
@@ -400,10 +470,8 @@ impl ScriptEngine {
             Ok(g) => g,
             Err(_) => return,
         };
-        let messages: Vec<String> = self.deserialize_v8(global).unwrap_or_default();
-        for msg in messages {
-            tracing::info!(script = ?script_path, "[Script] {}", msg);
-        }
+        let entries: Vec<LogEntry> = self.deserialize_v8(global).unwrap_or_default();
+        self.logger.emit(script_path, entries);
     }
 
     /// Get handler count for an event (for testing)
@@ -473,6 +541,115 @@ mod tests {
         std::fs::remove_file(script_path).ok();
     }
 
+    /// Collects formatted tracing output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLog(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_ggg_log_produces_log_line() {
+        let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script_path = temp_dir.path().join("logging.js");
+        std::fs::write(
+            &script_path,
+            r#"
+            ggg.on('beforeRequest', function(e) {
+                ggg.log.warn('checking ' + e.url);
+                return true;
+            });
+            "#,
+        )
+        .unwrap();
+        engine.load_script(&script_path).unwrap();
+
+        let captured = CapturedLog::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .finish();
+
+        let mut ctx = BeforeRequestContext {
+            url: "https://example.com/file.zip".to_string(),
+            headers: HashMap::new(),
+            user_agent: None,
+            download_id: None,
+            cancel: false,
+            cancel_reason: None,
+        };
+        tracing::subscriber::with_default(subscriber, || {
+            engine
+                .execute_handlers(HookEvent::BeforeRequest, &mut ctx, &HashMap::new())
+                .unwrap();
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let line = output
+            .lines()
+            .find(|l| l.contains("checking https://example.com/file.zip"))
+            .unwrap_or_else(|| panic!("no script log line in: {}", output));
+        assert!(line.contains("WARN"));
+        assert!(line.contains("script"));
+        assert!(line.contains("logging.js"));
+    }
+
+    #[test]
+    fn test_validate_script_reports_location() {
+        let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let good = temp_dir.path().join("good.js");
+        std::fs::write(&good, "ggg.on('beforeRequest', function(e) { return true; });\n").unwrap();
+        assert!(engine.validate_script(&good).is_ok());
+        // Validation never registers handlers
+        assert_eq!(engine.handler_count(HookEvent::BeforeRequest), 0);
+
+        let bad = temp_dir.path().join("bad.js");
+        std::fs::write(&bad, "// comment\nggg.on('beforeRequest', function(e) {\n    return true;\n);\n").unwrap();
+        match engine.validate_script(&bad) {
+            Err(ScriptError::SyntaxError { line, message, .. }) => {
+                assert_eq!(line, 4);
+                assert!(message.contains("SyntaxError"), "message: {}", message);
+            }
+            other => panic!("expected syntax error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_script_does_not_run_it() {
+        let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let side_effect = temp_dir.path().join("side_effect.js");
+        std::fs::write(&side_effect, "globalThis.ran = true;\n").unwrap();
+        assert!(engine.validate_script(&side_effect).is_ok());
+        let ran = engine.runtime.execute_script("<test>", "typeof globalThis.ran".to_string()).unwrap();
+        assert_eq!(engine.deserialize_v8::<String>(ran).unwrap(), "undefined");
+
+        // Breaking out of a wrapper function is just a syntax error
+        let escape = temp_dir.path().join("escape.js");
+        std::fs::write(&escape, "}); ggg.on('beforeRequest', function(e) { return true; }); (function() {\n").unwrap();
+        assert!(engine.validate_script(&escape).is_err());
+        assert_eq!(engine.handler_count(HookEvent::BeforeRequest), 0);
+
+        // Top-level return is not valid in a script
+        let top_level_return = temp_dir.path().join("return.js");
+        std::fs::write(&top_level_return, "return;\n").unwrap();
+        assert!(engine.validate_script(&top_level_return).is_err());
+    }
+
     #[test]
     fn test_execute_handler_modifies_context() {
         let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
@@ -497,6 +674,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         // Execute handlers
@@ -512,6 +691,40 @@ mod tests {
         std::fs::remove_file(script_path).ok();
     }
 
+    #[test]
+    fn test_runs_record_effect_of_each_handler() {
+        let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
+
+        let test_script = r#"
+            ggg.on('beforeRequest', function(e) { return true; });
+            ggg.on('beforeRequest', function(e) { e.headers['X-Run'] = '1'; return true; });
+            ggg.on('beforeRequest', function(e) { throw new Error('boom'); });
+        "#;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script_path = temp_dir.path().join("runs.js");
+        std::fs::write(&script_path, test_script).unwrap();
+        engine.load_script(&script_path).unwrap();
+
+        let mut ctx = BeforeRequestContext {
+            url: "https://example.com/file.zip".to_string(),
+            headers: HashMap::new(),
+            user_agent: None,
+            download_id: None,
+            cancel: false,
+            cancel_reason: None,
+        };
+        engine.execute_handlers(HookEvent::BeforeRequest, &mut ctx, &HashMap::new()).unwrap();
+
+        let runs = engine.take_runs();
+        assert_eq!(runs.len(), 3);
+        assert!(runs.iter().all(|run| run.script == "runs.js" && run.hook == "beforeRequest"));
+        assert!(!runs[0].modified && runs[0].error.is_none());
+        assert!(runs[1].modified);
+        assert!(runs[2].error.as_deref().is_some_and(|e| e.contains("boom")));
+        assert!(engine.take_runs().is_empty());
+    }
+
     #[test]
     fn test_handler_stop_propagation() {
         let mut engine = ScriptEngine::new(Duration::from_secs(30)).unwrap();
@@ -540,6 +753,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = std::collections::HashMap::new();
@@ -576,6 +791,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = HashMap::new();
@@ -590,6 +807,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         engine
@@ -19,6 +19,15 @@ pub enum ScriptError {
     #[error("Failed to compile script {path}: {message}")]
     CompilationError { path: PathBuf, message: String },
 
+    /// Script has a syntax error at a known location
+    #[error("Syntax error in {}:{line}:{column}: {message}", .path.display())]
+    SyntaxError {
+        path: PathBuf,
+        line: u32,
+        column: u32,
+        message: String,
+    },
+
     /// Script execution failed
     #[error("Script execution error in {script}: {message}")]
     ExecutionError { script: String, message: String },
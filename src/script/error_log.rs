@@ -0,0 +1,109 @@
+//! Recent script errors
+//!
+//! A bounded, shared buffer of errors raised by user scripts (load failures
+//! and exceptions thrown inside hook handlers). The script executor thread
+//! feeds it and the TUI reads it to show an indicator and an error viewer,
+//! so failing hooks are visible without tailing the logs.
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Number of errors kept before the oldest are dropped
+pub const DEFAULT_CAPACITY: usize = 50;
+
+/// A single script error
+#[derive(Debug, Clone)]
+pub struct ScriptErrorEntry {
+    pub timestamp: DateTime<Local>,
+    /// Script filename (e.g. "referer.js")
+    pub script: String,
+    /// Hook that was running, or None for load errors
+    pub event: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: VecDeque<ScriptErrorEntry>,
+    capacity: usize,
+    /// Errors recorded since startup, including dropped ones
+    total: u64,
+}
+
+/// Shared ring buffer of recent script errors (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct ScriptErrorLog {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for ScriptErrorLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ScriptErrorLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                entries: VecDeque::with_capacity(capacity),
+                capacity: capacity.max(1),
+                total: 0,
+            })),
+        }
+    }
+
+    /// Record an error, dropping the oldest one when full
+    pub fn push(&self, script: impl Into<String>, event: Option<&str>, message: impl Into<String>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.entries.len() >= inner.capacity {
+            inner.entries.pop_front();
+        }
+        inner.entries.push_back(ScriptErrorEntry {
+            timestamp: Local::now(),
+            script: script.into(),
+            event: event.map(str::to_string),
+            message: message.into(),
+        });
+        inner.total += 1;
+    }
+
+    /// Buffered errors, oldest first
+    pub fn entries(&self) -> Vec<ScriptErrorEntry> {
+        self.inner.lock().unwrap().entries.iter().cloned().collect()
+    }
+
+    /// Number of errors recorded since startup (used to detect new ones)
+    pub fn total(&self) -> u64 {
+        self.inner.lock().unwrap().total
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let log = ScriptErrorLog::new(2);
+        log.push("a.js", Some("beforeRequest"), "first");
+        log.push("b.js", None, "second");
+        log.push("c.js", Some("completed"), "third");
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].script, "b.js");
+        assert_eq!(entries[0].event, None);
+        assert_eq!(entries[1].message, "third");
+        assert_eq!(log.total(), 3);
+
+        log.clear();
+        assert!(log.entries().is_empty());
+        assert_eq!(log.total(), 3);
+    }
+}
@@ -72,6 +72,33 @@ pub trait EventContext: Serialize + for<'de> Deserialize<'de> {
     }
 }
 
+/// One script handler that ran for a download (kept in `DownloadTask::script_runs`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptRun {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Script file name
+    pub script: String,
+    /// Hook name as used in `ggg.on()`
+    pub hook: String,
+    /// The handler changed the event object
+    pub modified: bool,
+    /// Error thrown by the handler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScriptRun {
+    pub fn new(script: impl Into<String>, event: HookEvent, modified: bool, error: Option<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now(),
+            script: script.into(),
+            hook: event.name().to_string(),
+            modified,
+            error,
+        }
+    }
+}
+
 /// Context for beforeRequest hook
 /// JavaScript can modify: url, headers, user_agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +114,12 @@ pub struct BeforeRequestContext {
     /// Download ID (read-only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub download_id: Option<String>,
+    /// Set to true to cancel the download (the task is paused, not failed)
+    #[serde(default)]
+    pub cancel: bool,
+    /// Optional reason recorded in the task log when cancelled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_reason: Option<String>,
 }
 
 impl EventContext for BeforeRequestContext {
@@ -114,6 +147,9 @@ pub struct HeadersReceivedContext {
     pub last_modified: Option<String>,
     /// Content-Type if present
     pub content_type: Option<String>,
+    /// First `scripts.body_peek_bytes` of the body (UTF-8, lossy); None when disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_peek: Option<String>,
 }
 
 impl EventContext for HeadersReceivedContext {
@@ -160,8 +196,14 @@ pub struct CompletedContext {
     pub move_to_path: Option<String>,
     /// Download size in bytes
     pub size: u64,
+    /// HTTP status of the final response, if known
+    #[serde(default)]
+    pub status_code: Option<u16>,
     /// Download duration in seconds
     pub duration: Option<f64>,
+    /// First `scripts.body_peek_bytes` of the file (UTF-8, lossy); None when disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_peek: Option<String>,
 }
 
 impl EventContext for CompletedContext {
@@ -261,6 +303,8 @@ mod tests {
             headers,
             user_agent: Some("GGG/1.0".to_string()),
             download_id: Some("test-id".to_string()),
+            cancel: false,
+            cancel_reason: None,
         };
 
         // Serialize to JSON
@@ -273,6 +317,15 @@ mod tests {
         let ctx2: BeforeRequestContext = BeforeRequestContext::from_json(json).unwrap();
         assert_eq!(ctx2.url, ctx.url);
         assert_eq!(ctx2.user_agent, ctx.user_agent);
+        assert!(!ctx2.cancel);
+
+        // Scripts cancel with e.cancel / e.cancelReason
+        let mut json = ctx.to_json().unwrap();
+        json["cancel"] = serde_json::json!(true);
+        json["cancelReason"] = serde_json::json!("blocked host");
+        let cancelled = BeforeRequestContext::from_json(json).unwrap();
+        assert!(cancelled.cancel);
+        assert_eq!(cancelled.cancel_reason.as_deref(), Some("blocked host"));
     }
 
     #[test]
@@ -288,11 +341,13 @@ mod tests {
             etag: Some("\"abc123\"".to_string()),
             last_modified: None,
             content_type: Some("application/zip".to_string()),
+            body_peek: Some("PK".to_string()),
         };
 
         let json = ctx.to_json().unwrap();
         assert_eq!(json["status"], 200);
         assert_eq!(json["contentLength"], 1024);
+        assert_eq!(json["bodyPeek"], "PK");
 
         let ctx2: HeadersReceivedContext = HeadersReceivedContext::from_json(json).unwrap();
         assert_eq!(ctx2.status, ctx.status);
@@ -308,13 +363,18 @@ mod tests {
             new_filename: Some("renamed.zip".to_string()),
             move_to_path: Some("/archive".to_string()),
             size: 1024,
+            status_code: Some(200),
             duration: Some(5.5),
+            body_peek: None,
         };
 
         let json = ctx.to_json().unwrap();
         assert_eq!(json["filename"], "file.zip");
         assert_eq!(json["newFilename"], "renamed.zip");
         assert_eq!(json["size"], 1024);
+        assert_eq!(json["statusCode"], 200);
+
+        assert!(json.get("bodyPeek").is_none());
 
         let ctx2: CompletedContext = CompletedContext::from_json(json).unwrap();
         assert_eq!(ctx2.new_filename, Some("renamed.zip".to_string()));
@@ -372,6 +432,8 @@ mod tests {
             headers: headers.clone(),
             user_agent: Some("test".to_string()),
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let json = serde_json::to_string(&before_req).unwrap();
@@ -37,7 +37,7 @@ pub fn script_executor_loop(
                 if let Err(e) = &result {
                     tracing::error!("beforeRequest hook error: {}", e);
                 }
-                let _ = response.send((ctx, result));
+                let _ = response.send((ctx, result, script_manager.take_runs()));
             }
 
             ScriptRequest::HeadersReceived { ctx, effective_script_files, response } => {
@@ -45,7 +45,7 @@ pub fn script_executor_loop(
                 if let Err(e) = &result {
                     tracing::error!("headersReceived hook error: {}", e);
                 }
-                let _ = response.send(result);
+                let _ = response.send((result, script_manager.take_runs()));
             }
 
             ScriptRequest::Completed { mut ctx, effective_script_files, response } => {
@@ -53,21 +53,23 @@ pub fn script_executor_loop(
                 if let Err(e) = &result {
                     tracing::error!("completed hook error: {}", e);
                 }
-                let _ = response.send((ctx, result));
+                let _ = response.send((ctx, result, script_manager.take_runs()));
             }
 
             ScriptRequest::Error { ctx, effective_script_files } => {
-                // Fire-and-forget
+                // Fire-and-forget: nobody is waiting for the runs
                 if let Err(e) = script_manager.trigger_error(&ctx, &effective_script_files) {
                     tracing::error!("error hook error: {}", e);
                 }
+                script_manager.take_runs();
             }
 
             ScriptRequest::Progress { ctx, effective_script_files } => {
-                // Fire-and-forget
+                // Fire-and-forget: nobody is waiting for the runs
                 if let Err(e) = script_manager.trigger_progress(&ctx, &effective_script_files) {
                     tracing::error!("progress hook error: {}", e);
                 }
+                script_manager.take_runs();
             }
 
             ScriptRequest::AuthRequired { mut ctx, effective_script_files, response } => {
@@ -75,7 +77,7 @@ pub fn script_executor_loop(
                 if let Err(e) = &result {
                     tracing::error!("authRequired hook error: {}", e);
                 }
-                let _ = response.send((ctx, result));
+                let _ = response.send((ctx, result, script_manager.take_runs()));
             }
 
             ScriptRequest::Reload { response } => {
@@ -116,6 +118,8 @@ mod tests {
             enabled: true,
             directory: PathBuf::from("./scripts"),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: HashMap::new(),
         };
 
@@ -140,6 +144,8 @@ mod tests {
             enabled: true,
             directory: PathBuf::from("./nonexistent_test_dir"),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: HashMap::new(),
         };
 
@@ -156,6 +162,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let (response_tx, response_rx) = std::sync::mpsc::channel();
@@ -168,8 +176,9 @@ mod tests {
         .unwrap();
 
         // Should receive response
-        let (ctx, result) = response_rx.recv().unwrap();
+        let (ctx, result, runs) = response_rx.recv().unwrap();
         assert!(result.is_ok());
+        assert!(runs.is_empty());
         assert_eq!(ctx.url, "https://example.com");
     }
 }
@@ -18,7 +18,7 @@ pub enum ScriptRequest {
     BeforeRequest {
         ctx: BeforeRequestContext,
         effective_script_files: std::collections::HashMap<String, bool>,
-        response: mpsc::Sender<(BeforeRequestContext, ScriptResult<()>)>,
+        response: mpsc::Sender<(BeforeRequestContext, ScriptResult<()>, Vec<ScriptRun>)>,
     },
 
     /// Execute headersReceived hook
@@ -27,7 +27,7 @@ pub enum ScriptRequest {
     HeadersReceived {
         ctx: HeadersReceivedContext,
         effective_script_files: std::collections::HashMap<String, bool>,
-        response: mpsc::Sender<ScriptResult<()>>,
+        response: mpsc::Sender<(ScriptResult<()>, Vec<ScriptRun>)>,
     },
 
     /// Execute completed hook
@@ -36,7 +36,7 @@ pub enum ScriptRequest {
     Completed {
         ctx: CompletedContext,
         effective_script_files: std::collections::HashMap<String, bool>,
-        response: mpsc::Sender<(CompletedContext, ScriptResult<()>)>,
+        response: mpsc::Sender<(CompletedContext, ScriptResult<()>, Vec<ScriptRun>)>,
     },
 
     /// Execute error hook (fire-and-forget)
@@ -61,7 +61,7 @@ pub enum ScriptRequest {
     AuthRequired {
         ctx: AuthRequiredContext,
         effective_script_files: std::collections::HashMap<String, bool>,
-        response: mpsc::Sender<(AuthRequiredContext, ScriptResult<()>)>,
+        response: mpsc::Sender<(AuthRequiredContext, ScriptResult<()>, Vec<ScriptRun>)>,
     },
 
     /// Reload all scripts from disk
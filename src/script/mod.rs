@@ -10,7 +10,10 @@
 /// - ScriptLoader: Loads scripts from filesystem
 /// - events: Event types and context structures
 /// - api: JavaScript API bindings (ggg.*)
+/// - template: Starter scripts for `ggg script new`
+/// - watcher: Automatic reload on file changes
 /// - error: Error types
+/// - error_log: Recent script errors for the TUI
 ///
 /// # Usage
 ///
@@ -30,18 +33,22 @@
 pub mod api;
 pub mod engine;
 pub mod error;
+pub mod error_log;
 pub mod events;
 pub mod executor;
 pub mod loader;
 pub mod message;
 pub mod sender;
+pub mod template;
+pub mod watcher;
 
 use crate::app::config::ScriptConfig;
 use crate::script::engine::ScriptEngine;
 use crate::script::error::ScriptResult;
+use crate::script::error_log::ScriptErrorLog;
 use crate::script::events::{
     AuthRequiredContext, BeforeRequestContext, CompletedContext, ErrorContext,
-    HeadersReceivedContext, HookEvent, ProgressContext,
+    HeadersReceivedContext, HookEvent, ProgressContext, ScriptRun,
 };
 use crate::script::loader::ScriptLoader;
 use std::time::Duration;
@@ -67,6 +74,16 @@ impl ScriptManager {
         })
     }
 
+    /// Record load and handler errors into `log` (shown in the TUI)
+    pub fn set_error_log(&mut self, log: ScriptErrorLog) {
+        self.engine.set_error_log(log);
+    }
+
+    /// Handlers run since the last call (see `ScriptEngine::take_runs`)
+    pub fn take_runs(&mut self) -> Vec<ScriptRun> {
+        self.engine.take_runs()
+    }
+
     /// Load all scripts from scripts directory
     /// Loads all .js files regardless of config (filtering happens at execution time)
     /// Clears existing handlers before loading
@@ -79,6 +96,7 @@ impl ScriptManager {
         for script_path in scripts {
             if let Err(e) = self.engine.load_script(&script_path) {
                 tracing::error!("Failed to load script {:?}: {}", script_path, e);
+                self.engine.record_error(&script_path, None, &e.to_string());
                 // Continue loading other scripts even if one fails
             }
         }
@@ -172,6 +190,8 @@ mod tests {
             enabled: true,
             directory: PathBuf::from("./scripts"),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
         assert_eq!(config.timeout, 30);
@@ -186,6 +206,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -230,6 +252,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -242,6 +266,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = HashMap::new(); // All scripts enabled by default
@@ -282,6 +308,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -293,6 +321,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = HashMap::new(); // All scripts enabled by default
@@ -333,6 +363,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -344,6 +376,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = HashMap::new(); // All scripts enabled by default
@@ -365,6 +399,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -412,6 +448,8 @@ mod tests {
             enabled: true,
             directory: temp_dir.clone(),
             timeout: 30,
+            auto_reload: false,
+            body_peek_bytes: 0,
             script_files: std::collections::HashMap::new(),
         };
 
@@ -426,6 +464,8 @@ mod tests {
             headers: HashMap::new(),
             user_agent: None,
             download_id: None,
+            cancel: false,
+            cancel_reason: None,
         };
 
         let script_files = HashMap::new(); // All scripts enabled by default
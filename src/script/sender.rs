@@ -8,6 +8,7 @@ use std::sync::mpsc;
 
 use super::message::ScriptRequest;
 use super::error::ScriptResult;
+use super::events::ScriptRun;
 
 /// Send a script request with context modification and wait for response
 ///
@@ -28,11 +29,12 @@ use super::error::ScriptResult;
 ///
 /// # Returns
 ///
-/// Returns the modified context and script result, or an error string if communication fails
+/// Returns the modified context, script result and the handlers that ran, or an
+/// error string if communication fails
 pub async fn send_script_request_with_context<C>(
     sender: &mpsc::Sender<ScriptRequest>,
-    request_builder: impl FnOnce(mpsc::Sender<(C, ScriptResult<()>)>) -> ScriptRequest + Send + 'static,
-) -> Result<(C, ScriptResult<()>), String>
+    request_builder: impl FnOnce(mpsc::Sender<(C, ScriptResult<()>, Vec<ScriptRun>)>) -> ScriptRequest + Send + 'static,
+) -> Result<(C, ScriptResult<()>, Vec<ScriptRun>), String>
 where
     C: Send + 'static,
 {
@@ -59,11 +61,12 @@ where
 ///
 /// # Returns
 ///
-/// Returns only the script result, or an error string if communication fails
+/// Returns the script result and the handlers that ran, or an error string if
+/// communication fails
 pub async fn send_script_request_no_context(
     sender: &mpsc::Sender<ScriptRequest>,
-    request_builder: impl FnOnce(mpsc::Sender<ScriptResult<()>>) -> ScriptRequest + Send + 'static,
-) -> Result<ScriptResult<()>, String>
+    request_builder: impl FnOnce(mpsc::Sender<(ScriptResult<()>, Vec<ScriptRun>)>) -> ScriptRequest + Send + 'static,
+) -> Result<(ScriptResult<()>, Vec<ScriptRun>), String>
 {
     let (response_tx, response_rx) = mpsc::channel();
     let sender_clone = sender.clone();
@@ -0,0 +1,126 @@
+//! Script scaffolding for `ggg script new`
+//!
+//! Generates a starter `.js` file wired to one hook, with the event object's
+//! fields listed as comments.
+
+use super::events::HookEvent;
+
+/// Context fields shown in the template: (field, description)
+fn context_fields(event: HookEvent) -> &'static [(&'static str, &'static str)] {
+    match event {
+        HookEvent::BeforeRequest => &[
+            ("url", "Download URL (modifiable)"),
+            ("headers", "HTTP headers object (modifiable)"),
+            ("userAgent", "User-Agent string (modifiable)"),
+            ("downloadId", "Unique download ID (read-only)"),
+            ("cancel", "Set to true to cancel the download (modifiable)"),
+            ("cancelReason", "Reason shown in the task log (modifiable)"),
+        ],
+        HookEvent::HeadersReceived => &[
+            ("url", "Request URL"),
+            ("status", "HTTP status code"),
+            ("headers", "Response headers object"),
+            ("contentLength", "File size in bytes (if known)"),
+            ("etag", "ETag header (if present)"),
+            ("lastModified", "Last-Modified header (if present)"),
+            ("contentType", "Content-Type header (if present)"),
+            ("bodyPeek", "Start of the body (if scripts.body_peek_bytes > 0)"),
+        ],
+        HookEvent::AuthRequired => &[
+            ("url", "URL requiring authentication"),
+            ("realm", "Authentication realm (if sent)"),
+            ("username", "Set to provide a username (modifiable)"),
+            ("password", "Set to provide a password (modifiable)"),
+        ],
+        HookEvent::Completed => &[
+            ("url", "Original download URL"),
+            ("filename", "Current filename"),
+            ("savePath", "Current directory path"),
+            ("size", "File size in bytes"),
+            ("statusCode", "HTTP status of the final response (if known)"),
+            ("duration", "Download duration in seconds"),
+            ("bodyPeek", "Start of the file (if scripts.body_peek_bytes > 0)"),
+            ("newFilename", "Set to rename the file (modifiable)"),
+            ("moveToPath", "Set to move the file (modifiable)"),
+        ],
+        HookEvent::ErrorOccurred => &[
+            ("url", "Download URL"),
+            ("filename", "Filename (if known)"),
+            ("error", "Error message"),
+            ("retryCount", "Number of retries attempted"),
+            ("statusCode", "HTTP status code (if applicable)"),
+        ],
+        HookEvent::Progress => &[
+            ("url", "Download URL"),
+            ("filename", "Filename"),
+            ("downloaded", "Bytes downloaded so far"),
+            ("total", "Total bytes (if known)"),
+            ("speed", "Download speed (bytes/sec)"),
+            ("percentage", "Download percentage (0-100, if total known)"),
+        ],
+    }
+}
+
+/// Example handler body for each hook
+fn example_body(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::BeforeRequest => "    // e.headers['Referer'] = 'https://example.com/';\n",
+        HookEvent::HeadersReceived => "    // ggg.log('Type: ' + e.contentType);\n",
+        HookEvent::AuthRequired => "    // e.username = 'user';\n    // e.password = 'secret';\n",
+        HookEvent::Completed => "    // e.newFilename = e.filename.toLowerCase();\n",
+        HookEvent::ErrorOccurred => "    // ggg.log('Failed: ' + e.url + ' (' + e.error + ')');\n",
+        HookEvent::Progress => "    // ggg.log(e.filename + ': ' + e.percentage + '%');\n",
+    }
+}
+
+/// Renders a starter script named `name` for `event`
+pub fn render(name: &str, event: HookEvent) -> String {
+    let event_name = event.name();
+    let mut script = format!(
+        "// {name}\n\
+         // Hook: {event_name} (see docs/Script_UserGuide.md)\n\
+         //\n\
+         // Event object fields:\n"
+    );
+
+    let width = context_fields(event).iter().map(|(field, _)| field.len()).max().unwrap_or(0);
+    for (field, description) in context_fields(event) {
+        script.push_str(&format!("//   e.{:<width$}  {}\n", field, description, width = width));
+    }
+
+    script.push_str(&format!(
+        "\n\
+         ggg.on('{event_name}', function(e) {{\n\
+         {body}\n    \
+         // Return true to run the next handler, false to stop here\n    \
+         return true;\n\
+         }}); // Optional third argument: URL filter (substring or regex)\n",
+        body = example_body(event),
+    ));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_wires_the_requested_hook() {
+        for event in [
+            HookEvent::BeforeRequest,
+            HookEvent::HeadersReceived,
+            HookEvent::AuthRequired,
+            HookEvent::Completed,
+            HookEvent::ErrorOccurred,
+            HookEvent::Progress,
+        ] {
+            let script = render("my_script", event);
+            assert!(script.starts_with("// my_script\n"));
+            assert!(script.contains(&format!("ggg.on('{}', function(e) {{", event.name())));
+            assert!(script.contains("return true;"));
+        }
+
+        let script = render("referer", HookEvent::BeforeRequest);
+        assert!(script.contains("e.userAgent"));
+    }
+}
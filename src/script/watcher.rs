@@ -0,0 +1,186 @@
+//! Automatic script reloading
+//!
+//! Polls the scripts directory while the TUI is running and reloads all
+//! scripts when a `.js` file is added, removed or modified. Reloads wait until
+//! the directory has been unchanged for `DEBOUNCE`, so an editor that is still
+//! writing a file does not trigger a reload of half-written code. Controlled
+//! by `scripts.auto_reload`, which is re-read on every poll.
+
+use crate::app::state::AppState;
+use crate::script::loader::ScriptLoader;
+use crate::script::message::ScriptRequest;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the scripts directory is scanned
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the directory must stay unchanged before reloading
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Modification time and size of each script file
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Scan `.js` files in `dir`
+fn scan(dir: &Path) -> Snapshot {
+    ScriptLoader::new(dir)
+        .list_scripts()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some((path, (metadata.modified().ok(), metadata.len())))
+        })
+        .collect()
+}
+
+/// Tracks directory changes and decides when a reload is due
+#[derive(Debug)]
+struct Debouncer {
+    /// Snapshot scripts were last loaded from
+    loaded: Snapshot,
+    /// Latest differing snapshot and when it was first seen
+    pending: Option<(Snapshot, Instant)>,
+}
+
+impl Debouncer {
+    fn new(loaded: Snapshot) -> Self {
+        Self { loaded, pending: None }
+    }
+
+    /// Returns true when `current` differs from the loaded scripts and has
+    /// been stable for `DEBOUNCE`
+    fn observe(&mut self, current: Snapshot, now: Instant) -> bool {
+        if current == self.loaded {
+            self.pending = None;
+            return false;
+        }
+
+        match self.pending {
+            Some((ref pending, since)) if *pending == current => {
+                if now.duration_since(since) >= DEBOUNCE {
+                    self.loaded = current;
+                    self.pending = None;
+                    true
+                } else {
+                    false
+                }
+            }
+            // First change, or still being written: restart the timer
+            _ => {
+                self.pending = Some((current, now));
+                false
+            }
+        }
+    }
+}
+
+/// Ask the script executor to reload all scripts
+async fn reload(sender: mpsc::Sender<ScriptRequest>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let (response_tx, response_rx) = mpsc::channel();
+        sender
+            .send(ScriptRequest::Reload { response: response_tx })
+            .map_err(|e| e.to_string())?;
+        response_rx
+            .recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Spawn the background watcher
+///
+/// Does nothing while scripts are disabled (no executor to reload) or
+/// `scripts.auto_reload` is off. Only the `Send` handles of `app_state` are
+/// moved into the task; the localization manager is not thread-safe.
+pub fn spawn_watcher(app_state: &AppState) -> tokio::task::JoinHandle<()> {
+    let config = app_state.config.clone();
+    let script_sender = app_state.script_sender.clone();
+    let script_errors = app_state.script_errors.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut debouncer: Option<Debouncer> = None;
+
+        loop {
+            interval.tick().await;
+
+            let (auto_reload, directory) = {
+                let config = config.read().await;
+                (config.scripts.auto_reload, config.scripts.directory.clone())
+            };
+            let Some(sender) = script_sender.clone().filter(|_| auto_reload) else {
+                // Start from a fresh baseline when re-enabled
+                debouncer = None;
+                continue;
+            };
+
+            let current = tokio::task::spawn_blocking(move || scan(&directory))
+                .await
+                .unwrap_or_default();
+            let Some(ref mut state) = debouncer else {
+                debouncer = Some(Debouncer::new(current));
+                continue;
+            };
+            if !state.observe(current, Instant::now()) {
+                continue;
+            }
+
+            tracing::info!("Script files changed, reloading scripts");
+            let errors_before = script_errors.total();
+            match reload(sender).await {
+                Ok(()) => {
+                    let new_errors = script_errors.total().saturating_sub(errors_before);
+                    if new_errors > 0 {
+                        tracing::warn!("Scripts reloaded with {} script error(s)", new_errors);
+                    } else {
+                        tracing::info!("Scripts reloaded");
+                    }
+                }
+                Err(error) => {
+                    tracing::error!("Automatic script reload failed: {}", error);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debouncer_waits_for_stable_snapshot() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script = temp_dir.path().join("a.js");
+        std::fs::write(&script, "ggg.log('v1');").unwrap();
+
+        let mut debouncer = Debouncer::new(scan(temp_dir.path()));
+        let start = Instant::now();
+        assert!(!debouncer.observe(scan(temp_dir.path()), start));
+
+        std::fs::write(&script, "ggg.log('version 2');").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+        let changed = scan(temp_dir.path());
+        assert_eq!(changed.len(), 1);
+
+        // Change seen, but not yet stable
+        assert!(!debouncer.observe(changed.clone(), start));
+        assert!(!debouncer.observe(changed.clone(), start + DEBOUNCE / 2));
+        // Stable long enough: reload once
+        assert!(debouncer.observe(changed.clone(), start + DEBOUNCE));
+        assert!(!debouncer.observe(changed, start + DEBOUNCE * 2));
+
+        // Removing the file is a change too
+        std::fs::remove_file(&script).unwrap();
+        let removed = scan(temp_dir.path());
+        assert!(removed.is_empty());
+        assert!(!debouncer.observe(removed.clone(), start));
+        assert!(debouncer.observe(removed, start + DEBOUNCE));
+    }
+}
@@ -1,9 +1,11 @@
 use super::events::TuiEvent;
-use super::state::{DetailsPosition, FocusPane, TuiState, UiMode};
+use super::state::{ChangeTarget, DetailsPosition, FocusPane, FolderTreeItem, TuiState, UiMode};
 use crate::app::keybindings::KeyAction;
 use crate::app::state::AppState;
+use crate::download::http_client::HttpClient;
 use crate::download::manager::DownloadManager;
 use crate::download::task::DownloadStatus;
+use crate::download::trash::{self, Trash};
 use anyhow::Result;
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
@@ -26,6 +28,12 @@ use tokio::sync::mpsc;
 /// URLs can be up to 2048 chars (common browser limit)
 const MAX_INPUT_LENGTH: usize = 2048;
 
+/// Maximum number of undo steps kept (a folder Delete All counts as one)
+const MAX_UNDO_HISTORY: usize = 10;
+
+/// Minimum interval between download snapshots taken from the manager
+const UI_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Main TUI application
 pub struct TuiApp {
     pub state: TuiState,
@@ -61,13 +69,11 @@ impl TuiApp {
     pub async fn handle_event(&mut self, event: TuiEvent) -> Result<()> {
         match event {
             TuiEvent::Tick => {
-                // Debounce UI updates: only update every 250ms to reduce CPU usage
                 let now = std::time::Instant::now();
-                if now.duration_since(self.last_update_time) >= Duration::from_millis(250) {
-                    self.state.update_downloads(&self.manager).await;
-                    self.last_update_time = now;
+                if self.refresh_downloads_if_due(now).await {
                     self.state.mark_dirty();  // Mark for redraw after data update
                 }
+                self.state.expire_status_message(now);
 
                 // Check for pending URL input (drag & drop detection)
                 // NOTE: This is a workaround for crossterm not firing Event::Paste on Windows Terminal
@@ -82,7 +88,7 @@ impl TuiApp {
                     if Self::is_valid_download_url(&pending) {
                         tracing::info!("Auto-detected URL from rapid input (D&D): {}", pending);
                         if let Err(e) = self.add_download_from_paste(&pending).await {
-                            tracing::error!("Failed to add download from auto-detected URL: {}", e);
+                            self.state.show_error("toast-add-failed", e);
                         }
                         self.state.mark_dirty();  // Mark for redraw after adding download
                     } else {
@@ -91,25 +97,80 @@ impl TuiApp {
                 }
             }
             TuiEvent::Input(input) => {
+                // Actions on downloads must not work from a stale snapshot
+                // (e.g. pausing a task that has finished since)
+                if self.input_acts_on_downloads(&input) {
+                    self.refresh_downloads(std::time::Instant::now()).await;
+                }
                 self.handle_input(input).await?;
-                // Force update after user input for immediate feedback
-                self.state.update_downloads(&self.manager).await;
-                self.last_update_time = std::time::Instant::now();
+                // Refresh right away unless a snapshot was just taken; bursts of
+                // input are caught up by the next tick
+                self.refresh_downloads_if_due(std::time::Instant::now()).await;
                 self.state.mark_dirty();  // Mark for redraw after input handling
             }
             #[cfg(windows)]
             TuiEvent::IpcUrl(url) => {
                 tracing::info!("IPC URL received from ggg-dnd: {}", url);
                 if let Err(e) = self.add_download_from_paste(&url).await {
-                    tracing::error!("Failed to add download from IPC: {}", e);
+                    self.state.show_error("toast-add-failed", e);
                 }
-                self.state.update_downloads(&self.manager).await;
+                self.refresh_downloads_if_due(std::time::Instant::now()).await;
                 self.state.mark_dirty();
             }
         }
         Ok(())
     }
 
+    /// Take a new download snapshot if `UI_UPDATE_INTERVAL` has passed since the last one
+    ///
+    /// All event types go through this, so the manager is queried at most
+    /// once per interval no matter how many events arrive. Returns true if
+    /// the snapshot was refreshed.
+    async fn refresh_downloads_if_due(&mut self, now: std::time::Instant) -> bool {
+        if now.duration_since(self.last_update_time) < UI_UPDATE_INTERVAL {
+            return false;
+        }
+        self.refresh_downloads(now).await;
+        true
+    }
+
+    /// Take a new download snapshot regardless of when the last one was taken
+    async fn refresh_downloads(&mut self, now: std::time::Instant) {
+        self.state.update_downloads(&self.manager).await;
+        self.last_update_time = now;
+    }
+
+    /// Whether `event` deletes, pauses, resumes, retries or moves downloads
+    ///
+    /// Navigation keys are left out so held-down scrolling stays coalesced.
+    fn input_acts_on_downloads(&self, event: &Event) -> bool {
+        let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. }) = event else {
+            return false;
+        };
+        match self.state.ui_mode {
+            UiMode::Normal => matches!(
+                self.state.keybinding_resolver.resolve(*code, *modifiers),
+                Some(
+                    KeyAction::DeleteDownload
+                        | KeyAction::ToggleDownload
+                        | KeyAction::RetryDownload
+                        | KeyAction::PauseAll
+                        | KeyAction::ResumeAll
+                        | KeyAction::RetryAllFailed
+                        | KeyAction::Undo
+                        | KeyAction::Refresh
+                )
+            ),
+            // Dialogs that act on the tasks they were opened for
+            UiMode::ConfirmDelete
+            | UiMode::ContextMenu
+            | UiMode::FolderContextMenu
+            | UiMode::ChangeFolder
+            | UiMode::ConfirmLargeDownload => true,
+            _ => false,
+        }
+    }
+
     /// Handle keyboard input
     async fn handle_input(&mut self, event: Event) -> Result<()> {
         match event {
@@ -132,6 +193,11 @@ impl TuiApp {
                     UiMode::ConfirmDelete => self.handle_confirm_delete_mode(code).await?,
                     UiMode::ContextMenu => self.handle_context_menu_mode(code).await?,
                     UiMode::FolderContextMenu => self.handle_folder_context_menu_mode(code).await?,
+                    UiMode::Stats => self.handle_stats_mode(code).await,
+                    UiMode::ScriptErrors => self.handle_script_errors_mode(code),
+                    UiMode::ConfirmConcurrencyAdjustment => self.handle_confirm_concurrency_mode(code).await?,
+                    UiMode::ConfirmCircuitReset => self.handle_confirm_circuit_reset_mode(code).await?,
+                    UiMode::ConfirmLargeDownload => self.handle_confirm_large_download_mode(code).await?,
                 }
             }
             Event::Paste(text) => {
@@ -142,12 +208,17 @@ impl TuiApp {
                 match self.state.ui_mode {
                     // AddDownload mode: always add to input buffer
                     UiMode::AddDownload => {
+                        let buffer = if self.state.add_referer_focused {
+                            &mut self.state.add_referer
+                        } else {
+                            &mut self.state.input_buffer
+                        };
                         // Prevent buffer overflow by limiting total length
-                        let available_space = MAX_INPUT_LENGTH.saturating_sub(self.state.input_buffer.len());
+                        let available_space = MAX_INPUT_LENGTH.saturating_sub(buffer.len());
                         if available_space > 0 {
                             // Use char-based slicing to avoid breaking UTF-8 sequences
                             let text_to_add: String = text.chars().take(available_space).collect();
-                            self.state.input_buffer.push_str(&text_to_add);
+                            buffer.push_str(&text_to_add);
                             self.state.mark_dirty();  // Mark for redraw after paste
                         }
                     }
@@ -161,7 +232,7 @@ impl TuiApp {
                         if Self::is_valid_download_url(trimmed) {
                             tracing::info!("Valid download URL detected in mode {:?}, adding to queue", self.state.ui_mode);
                             if let Err(e) = self.add_download_from_paste(trimmed).await {
-                                tracing::error!("Failed to add download from paste: {}", e);
+                                self.state.show_error("toast-add-failed", e);
                             }
                         } else {
                             tracing::debug!("Paste ignored in mode {:?}: not a valid download URL", self.state.ui_mode);
@@ -368,8 +439,9 @@ impl TuiApp {
                 let label = label.clone();
                 drop(regions);
                 if label == "yes" {
-                    self.delete_download().await?;
+                    self.confirm_delete().await?;
                 }
+                self.state.pending_folder_action = None;
                 self.state.ui_mode = UiMode::Normal;
                 return Ok(());
             }
@@ -386,22 +458,23 @@ impl TuiApp {
                 drop(regions);
                 if label == "confirm" && !self.state.input_buffer.is_empty() {
                     // Confirm and add download (same logic as Enter key)
-                    let url = self.state.input_buffer.clone();
                     let config = self.state.app_state.config.read().await;
-                    let task = crate::download::task::DownloadTask::new_with_folder(
-                        url,
-                        self.state.current_folder_id.clone(),
-                        &config,
-                    );
+                    let mut task = self.new_task_from_input(&config);
                     drop(config);
+                    self.apply_preview_probe(&mut task);
+                    if !self.prepare_save_dirs(std::slice::from_ref(&task)).await {
+                        return Ok(());
+                    }
                     self.add_download_with_auto_start(task).await?;
                     self.state.ui_mode = UiMode::Normal;
                     self.state.input_buffer.clear();
                     self.state.preview_info = None;
+                    self.state.preview_headers.clear();
                 } else {
                     // Cancel - return to add download mode
                     self.state.ui_mode = UiMode::AddDownload;
                     self.state.preview_info = None;
+                    self.state.preview_headers.clear();
                 }
                 return Ok(());
             }
@@ -608,14 +681,20 @@ impl TuiApp {
                         FocusPane::DownloadList | FocusPane::DetailsPanel => {
                             self.state.set_focus(FocusPane::FolderTree);
                         }
-                        FocusPane::FolderTree => {}
+                        FocusPane::FolderTree => {
+                            // Collapse the nested folder, or go up to its parent
+                            self.state.collapse_tree_node();
+                        }
                     }
                     return Ok(());
                 }
                 KeyAction::FocusRight => {
                     match self.state.focus_pane {
                         FocusPane::FolderTree => {
-                            self.state.set_focus(FocusPane::DownloadList);
+                            // Expand a collapsed folder first, then move to the list
+                            if !self.state.expand_tree_node() {
+                                self.state.set_focus(FocusPane::DownloadList);
+                            }
                         }
                         FocusPane::DownloadList => {
                             if self.state.details_position != DetailsPosition::Hidden {
@@ -626,13 +705,22 @@ impl TuiApp {
                     }
                     return Ok(());
                 }
+                KeyAction::NextFolder | KeyAction::PrevFolder => {
+                    // The list is redrawn from the already loaded snapshot
+                    self.state.cycle_folder(action == KeyAction::NextFolder);
+                    return Ok(());
+                }
 
                 // Selection
                 KeyAction::SelectItem => {
                     match self.state.focus_pane {
                         FocusPane::FolderTree => {
-                            // Enter on FolderTree = confirm folder selection
-                            self.state.sync_current_folder_from_tree();
+                            // Enter on FolderTree = confirm folder selection (groups expand/collapse)
+                            if matches!(self.state.selected_tree_item(), Some(FolderTreeItem::Group(_))) {
+                                self.state.toggle_tree_node();
+                            } else {
+                                self.state.sync_current_folder_from_tree();
+                            }
                         }
                         _ => {
                             // Enter on other panes = view details
@@ -659,6 +747,8 @@ impl TuiApp {
                 KeyAction::AddDownload => {
                     self.state.ui_mode = UiMode::AddDownload;
                     self.state.input_buffer.clear();
+                    self.state.add_referer.clear();
+                    self.state.add_referer_focused = false;
                     return Ok(());
                 }
                 KeyAction::DeleteDownload => {
@@ -677,6 +767,22 @@ impl TuiApp {
                     self.retry_download().await?;
                     return Ok(());
                 }
+                KeyAction::RetryAllFailed => {
+                    // Limit to the folder selected in the tree, if any
+                    let folder_id = self.state.selected_folder_id_from_tree().map(str::to_string);
+                    let retried = self
+                        .manager
+                        .retry_failed_tasks(
+                            folder_id.as_deref(),
+                            self.state.app_state.script_sender.clone(),
+                            self.state.app_state.config.clone(),
+                        )
+                        .await;
+                    if retried > 0 {
+                        tracing::info!("Retried {} failed downloads", retried);
+                    }
+                    return Ok(());
+                }
                 KeyAction::ResumeAll => {
                     let resumed = self
                         .manager
@@ -703,8 +809,7 @@ impl TuiApp {
                     return Ok(());
                 }
                 KeyAction::EditItem => {
-                    self.state.ui_mode = UiMode::ChangeFolder;
-                    self.state.input_buffer.clear();
+                    self.state.open_change_dialog(ChangeTarget::Folder);
                     return Ok(());
                 }
 
@@ -734,10 +839,24 @@ impl TuiApp {
                     self.state.folder_picker_index = 0;
                     return Ok(());
                 }
+                KeyAction::OpenStats => {
+                    self.refresh_stats().await;
+                    self.state.ui_mode = UiMode::Stats;
+                    return Ok(());
+                }
+                KeyAction::OpenScriptErrors => {
+                    self.state.script_errors_seen = self.state.app_state.script_errors.total();
+                    self.state.ui_mode = UiMode::ScriptErrors;
+                    return Ok(());
+                }
+                KeyAction::ToggleBandwidthGraph => {
+                    self.toggle_bandwidth_graph().await;
+                    return Ok(());
+                }
 
                 // System
                 KeyAction::Refresh => {
-                    // Refresh - already happens on tick
+                    // Snapshot was already refreshed before handling the key
                     return Ok(());
                 }
             }
@@ -776,23 +895,34 @@ impl TuiApp {
 
     /// Handle input mode (for Add Download dialog)
     async fn handle_input_mode(&mut self, key: KeyCode, mods: KeyModifiers) -> Result<()> {
+        let adding = self.state.ui_mode == UiMode::AddDownload;
+        let buffer = if adding && self.state.add_referer_focused {
+            &mut self.state.add_referer
+        } else {
+            &mut self.state.input_buffer
+        };
+
         // Handle Ctrl+u first (before Char match)
         if matches!(key, KeyCode::Char('u')) && mods.contains(KeyModifiers::CONTROL) {
-            self.state.input_buffer.clear();
+            buffer.clear();
             return Ok(());
         }
 
         match key {
             KeyCode::Char(c) => {
                 // Prevent buffer overflow
-                if self.state.input_buffer.len() < MAX_INPUT_LENGTH {
-                    self.state.input_buffer.push(c);
+                if buffer.len() < MAX_INPUT_LENGTH {
+                    buffer.push(c);
                 }
                 // Clear validation error on new input
                 self.state.validation_error = None;
             }
             KeyCode::Backspace => {
-                self.state.input_buffer.pop();
+                buffer.pop();
+            }
+            // Switch between the URL and Referer fields of the add dialog
+            KeyCode::Tab | KeyCode::BackTab if adding => {
+                self.state.add_referer_focused = !self.state.add_referer_focused;
             }
             KeyCode::Enter => {
                 // Check if editing application setting
@@ -802,6 +932,14 @@ impl TuiApp {
                 } else if !self.state.input_buffer.is_empty() {
                     let url = self.state.input_buffer.clone();
 
+                    let referer = match self.add_referer_header() {
+                        Ok(referer) => referer,
+                        Err(e) => {
+                            self.state.validation_error = Some(format!("Invalid referer: {}", e));
+                            return Ok(());
+                        }
+                    };
+
                     // Shift+Enter: Expand URL patterns like [1-10] or [001-010]
                     // Normal Enter: Add URL as-is ([] is valid in URLs)
                     let expand_patterns = mods.contains(KeyModifiers::SHIFT);
@@ -818,6 +956,15 @@ impl TuiApp {
                         vec![url]
                     };
 
+                    let rejected = {
+                        let config = self.state.app_state.config.read().await;
+                        urls_to_add.iter().find_map(|url| config.download.check_url_scheme(url).err())
+                    };
+                    if let Some(e) = rejected {
+                        self.state.validation_error = Some(e.to_string());
+                        return Ok(());
+                    }
+
                     // Check if preview should be skipped
                     let skip_preview = {
                         let config = self.state.app_state.config.read().await;
@@ -836,15 +983,21 @@ impl TuiApp {
                             urls_to_add
                                 .iter()
                                 .map(|url| {
-                                    crate::download::task::DownloadTask::new_with_folder(
+                                    let mut task = crate::download::task::DownloadTask::new_with_folder(
                                         url.clone(),
                                         folder_id.clone(),
                                         &config,
-                                    )
+                                    );
+                                    HttpClient::merge_headers(&mut task.headers, referer.clone());
+                                    task
                                 })
                                 .collect()
                         };
 
+                        if !self.prepare_save_dirs(&tasks).await {
+                            return Ok(());
+                        }
+
                         // Now add all tasks (config lock is released)
                         for task in tasks {
                             self.add_download_with_auto_start(task).await?;
@@ -859,7 +1012,11 @@ impl TuiApp {
                     } else {
                         // Single URL with preview
                         let single_url = urls_to_add.into_iter().next().unwrap();
-                        match self.fetch_download_info(&single_url).await {
+                        let headers = {
+                            let config = self.state.app_state.config.read().await;
+                            Self::preview_request_headers(&config, &self.state.current_folder_id, &single_url, referer)
+                        };
+                        match self.fetch_download_info(&single_url, &headers).await {
                             Ok(info) => {
                                 self.state.preview_info = Some(info);
                                 self.state.ui_mode = UiMode::DownloadPreview;
@@ -872,6 +1029,7 @@ impl TuiApp {
                                 self.state.ui_mode = UiMode::DownloadPreview;
                             }
                         }
+                        self.state.preview_headers = headers;
                     }
                 } else {
                     self.state.ui_mode = UiMode::Normal;
@@ -915,7 +1073,7 @@ impl TuiApp {
                 if Self::is_valid_download_url(&query) {
                     tracing::info!("Search input detected as URL, adding to download queue: {}", query);
                     if let Err(e) = self.add_download_from_paste(&query).await {
-                        tracing::error!("Failed to add download from search: {}", e);
+                        self.state.show_error("toast-add-failed", e);
                     }
                     // Clear search and return to normal mode
                     self.state.input_buffer.clear();
@@ -939,6 +1097,50 @@ impl TuiApp {
         }
     }
 
+    /// Handle statistics panel
+    async fn handle_stats_mode(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state.stats = None;
+                self.state.ui_mode = UiMode::Normal;
+            }
+            // Cycle time range
+            KeyCode::Tab => {
+                self.state.stats_window = self.state.stats_window.next();
+                self.refresh_stats().await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle script error viewer
+    fn handle_script_errors_mode(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state.ui_mode = UiMode::Normal;
+            }
+            KeyCode::Char('c') => {
+                self.state.app_state.script_errors.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Recompute statistics for the selected time range
+    async fn refresh_stats(&mut self) {
+        let window = self.state.stats_window.window();
+        let history = crate::download::completion_log::read_entries(window.start())
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to read completion history: {}", e);
+                Vec::new()
+            });
+        let tasks = self.manager.get_all_downloads().await;
+        self.state.stats = Some(crate::download::stats::compute(&history, &tasks, window, None));
+        self.state.lifetime_stats = crate::download::lifetime::LifetimeStats::load_default()
+            .map_err(|e| tracing::error!("Failed to read lifetime stats: {}", e))
+            .ok();
+    }
+
     /// Handle settings mode
     async fn handle_settings_mode(&mut self, key: KeyCode) -> Result<()> {
         use super::state::{ApplicationSettingsField, SettingsSection};
@@ -967,8 +1169,9 @@ impl TuiApp {
                     tracing::info!("Configuration reloaded successfully");
                 }
                 crate::ui::commands::CommandResponse::Error { error } => {
-                    tracing::error!("Failed to reload config: {}", error);
+                    self.state.show_error("toast-reload-failed", error);
                 }
+                crate::ui::commands::CommandResponse::NeedsConfirmation { .. } => {}
             }
             return Ok(());
         }
@@ -1398,8 +1601,16 @@ impl TuiApp {
                                 folder.user_agent = None;
                                 tracing::info!("Cleared user_agent for folder '{}'", folder_id);
                             } else {
-                                folder.user_agent = Some(self.state.input_buffer.clone());
-                                tracing::info!("Updated user_agent to '{}' for folder '{}'", self.state.input_buffer, folder_id);
+                                match crate::app::user_agent::expand(&self.state.input_buffer) {
+                                    Ok(user_agent) => {
+                                        folder.user_agent = Some(user_agent);
+                                        tracing::info!("Updated user_agent to '{}' for folder '{}'", self.state.input_buffer, folder_id);
+                                    }
+                                    Err(e) => {
+                                        self.state.validation_error = Some(e.to_string());
+                                        tracing::warn!("Invalid user agent: {}", e);
+                                    }
+                                }
                             }
                         }
                         _ => {}
@@ -1680,22 +1891,52 @@ impl TuiApp {
                 if self.state.input_buffer.len() < MAX_INPUT_LENGTH {
                     self.state.input_buffer.push(c);
                 }
+                self.state.change_folder_index = 0;
+                self.state.validation_error = None;
             }
             KeyCode::Backspace => {
                 self.state.input_buffer.pop();
+                self.state.change_folder_index = 0;
+            }
+            KeyCode::Tab if self.state.change_target == ChangeTarget::Folder => {
+                self.state.complete_folder_name();
+                self.state.validation_error = None;
+            }
+            KeyCode::Up | KeyCode::Down if self.state.change_target == ChangeTarget::Folder => {
+                self.state.move_change_folder_selection(key == KeyCode::Down);
+            }
+            KeyCode::Enter if self.state.change_target == ChangeTarget::Folder => {
+                // Move to the highlighted folder (a download not yet started takes its save path)
+                let Some(folder_id) = self.state.selected_change_folder() else {
+                    let args = fluent::fluent_args! {
+                        "input" => self.state.input_buffer.clone(),
+                    };
+                    self.state.validation_error = Some(self.state.t_with_args("validation-no-folder-match", Some(&args)));
+                    return Ok(());
+                };
+                let selected = self.state.get_selected_download().map(|task| (task.id, task.folder_id.clone()));
+                if let Some((id, current_folder)) = selected
+                    && current_folder != folder_id
+                {
+                    let config = self.state.app_state.config.read().await;
+                    let result = self.manager.move_task_to_folder(id, &folder_id, Some(&config)).await;
+                    drop(config);
+                    if let Err(e) = result {
+                        self.state.show_error("toast-change-folder-failed", e);
+                    }
+                }
+                self.state.ui_mode = UiMode::Normal;
+                self.state.input_buffer.clear();
             }
             KeyCode::Enter => {
-                // Submit new path
+                // Submit new path (the download stays in its folder)
                 if !self.state.input_buffer.is_empty() {
                     if let Some(task) = self.state.get_selected_download() {
                         let new_path = std::path::PathBuf::from(&self.state.input_buffer);
 
                         // Change the save path
                         if let Err(e) = self.manager.change_save_path(task.id, new_path).await {
-                            // Store error message for display (future enhancement)
-                            tracing::warn!("Failed to change path: {}", e);
-                        } else {
-                            self.save_queue().await?;
+                            self.state.show_error("toast-change-path-failed", e);
                         }
                     }
                 }
@@ -1705,6 +1946,7 @@ impl TuiApp {
             KeyCode::Esc => {
                 self.state.ui_mode = UiMode::Normal;
                 self.state.input_buffer.clear();
+                self.state.validation_error = None;
             }
             _ => {}
         }
@@ -1755,12 +1997,13 @@ impl TuiApp {
     async fn handle_confirm_delete_mode(&mut self, key: KeyCode) -> Result<()> {
         match key {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                // Confirm - delete the download
-                self.delete_download().await?;
+                // Confirm - delete the download(s)
+                self.confirm_delete().await?;
                 self.state.ui_mode = UiMode::Normal;
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 // Cancel - return to normal mode
+                self.state.pending_folder_action = None;
                 self.state.ui_mode = UiMode::Normal;
             }
             _ => {}
@@ -1768,6 +2011,17 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Carry out what the delete confirmation dialog asked about
+    async fn confirm_delete(&mut self) -> Result<()> {
+        match self.state.pending_folder_action.take() {
+            Some(action) => {
+                self.run_destructive_folder_action(action).await;
+                Ok(())
+            }
+            None => self.delete_download().await,
+        }
+    }
+
     /// Handle context menu mode
     async fn handle_context_menu_mode(&mut self, key: KeyCode) -> Result<()> {
         use super::state::ContextMenuAction;
@@ -1840,12 +2094,10 @@ impl TuiApp {
                 self.state.ui_mode = UiMode::ConfirmDelete;
             }
             ContextMenuAction::ChangeFolder => {
-                self.state.ui_mode = UiMode::ChangeFolder;
-                self.state.input_buffer.clear();
+                self.state.open_change_dialog(ChangeTarget::Folder);
             }
             ContextMenuAction::ChangeSavePath => {
-                self.state.ui_mode = UiMode::ChangeFolder;
-                self.state.input_buffer.clear();
+                self.state.open_change_dialog(ChangeTarget::SavePath);
             }
             ContextMenuAction::CopyUrl => {
                 // Copy URL to clipboard
@@ -1970,30 +2222,53 @@ impl TuiApp {
                 }
                 self.state.ui_mode = UiMode::Normal;
             }
+            FolderContextMenuAction::DeleteAll | FolderContextMenuAction::ClearHistory => {
+                // Destructive: ask first (see confirm_delete)
+                if self.state.folder_action_count(action) > 0 {
+                    self.state.pending_folder_action = Some(action);
+                    self.state.ui_mode = UiMode::ConfirmDelete;
+                } else {
+                    self.state.ui_mode = UiMode::Normal;
+                }
+            }
+            FolderContextMenuAction::Cancel => {
+                self.state.ui_mode = UiMode::Normal;
+            }
+        }
+
+        self.state.reset_folder_context_menu();
+        Ok(())
+    }
+
+    /// Run a folder action confirmed in the delete dialog
+    async fn run_destructive_folder_action(&mut self, action: super::state::FolderContextMenuAction) {
+        use super::state::FolderContextMenuAction;
+
+        match action {
             FolderContextMenuAction::DeleteAll => {
                 // Delete all downloads in the current folder
-                if let Some(folder_id) = self.state.selected_folder_id_from_tree() {
-                    if let Some(tasks) = self.state.folder_downloads.get(folder_id) {
-                        let ids: Vec<_> = tasks.iter().map(|t| t.id).collect();
-                        for id in ids {
-                            self.manager.remove_download(id).await;
+                let ids: Option<Vec<_>> = self
+                    .state
+                    .selected_folder_id_from_tree()
+                    .and_then(|folder_id| self.state.folder_downloads.get(folder_id))
+                    .map(|tasks| tasks.iter().map(|t| t.id).collect());
+                if let Some(ids) = ids {
+                    let mut deleted = Vec::new();
+                    for id in ids {
+                        if let Some(task) = self.manager.remove_download(id).await {
+                            deleted.push(task);
                         }
                     }
+                    // One undo step restores the whole folder
+                    self.record_deleted(deleted, true).await;
                 }
-                self.state.ui_mode = UiMode::Normal;
             }
             FolderContextMenuAction::ClearHistory => {
                 // Clear all history items
                 self.manager.clear_history().await;
-                self.state.ui_mode = UiMode::Normal;
-            }
-            FolderContextMenuAction::Cancel => {
-                self.state.ui_mode = UiMode::Normal;
             }
+            _ => {}
         }
-
-        self.state.reset_folder_context_menu();
-        Ok(())
     }
 
     /// Toggle download (start/pause) - supports multi-selection
@@ -2009,131 +2284,314 @@ impl TuiApp {
                             self.manager.pause_download(id).await?;
                         }
                         DownloadStatus::Pending | DownloadStatus::Paused | DownloadStatus::Error => {
-                            self.manager.start_download(id, self.state.app_state.script_sender.clone(), self.state.app_state.config.clone()).await?;
+                            self.start_or_confirm_circuit_reset(id).await?;
                         }
                         _ => {}
                     }
                 }
             }
-            self.save_queue().await?;
-        } else if let Some(task) = self.state.get_selected_download() {
+        } else if let Some((id, status)) = self.state.get_selected_download().map(|task| (task.id, task.status)) {
             // No multi-selection, toggle current item
-            match task.status {
+            match status {
                 DownloadStatus::Downloading => {
-                    self.manager.pause_download(task.id).await?;
+                    self.manager.pause_download(id).await?;
                 }
                 DownloadStatus::Pending | DownloadStatus::Paused | DownloadStatus::Error => {
-                    self.manager.start_download(task.id, self.state.app_state.script_sender.clone(), self.state.app_state.config.clone()).await?;
+                    self.start_or_confirm_circuit_reset(id).await?;
                 }
                 _ => {}
             }
-            self.save_queue().await?;
         }
         Ok(())
     }
 
     /// Delete selected download(s) - supports multi-selection
     async fn delete_download(&mut self) -> Result<()> {
-        const MAX_UNDO_HISTORY: usize = 10;
+        let mut deleted = Vec::new();
 
         // If there are selected downloads, delete all of them
         if !self.state.selected_downloads.is_empty() {
             let ids_to_delete = self.state.get_selected_download_ids();
             for id in ids_to_delete {
                 // Save to undo history before deleting
-                if let Some(mut task) = self.manager.get_by_id(id).await {
-                    // Mark as deleted and add to history
-                    task.status = DownloadStatus::Deleted;
-                    self.manager.add_to_history(task.clone()).await;
-                    self.state.delete_history.push(task);
+                if let Some(task) = self.manager.get_by_id(id).await {
+                    deleted.push(task);
                 }
                 self.manager.remove_download(id).await;
             }
             self.state.clear_selections();
-            self.save_queue().await?;
             self.state.adjust_selection_after_delete();
         } else if let Some(task) = self.state.get_selected_download() {
             // Get ID first to avoid borrow issues
             let task_id = task.id;
-            let mut task_clone = task.clone();
-
-            // Mark as deleted and add to history
-            task_clone.status = DownloadStatus::Deleted;
-            self.manager.add_to_history(task_clone.clone()).await;
-
-            // Save to undo history before deleting
-            self.state.delete_history.push(task_clone);
+            deleted.push(task.clone());
 
             // No multi-selection, delete current item
             self.manager.remove_download(task_id).await;
-            self.save_queue().await?;
             self.state.adjust_selection_after_delete();
         }
 
-        // Limit history size to prevent excessive memory usage
-        if self.state.delete_history.len() > MAX_UNDO_HISTORY {
-            self.state.delete_history.drain(0..self.state.delete_history.len() - MAX_UNDO_HISTORY);
+        self.record_deleted(deleted, false).await;
+        Ok(())
+    }
+
+    /// Add deleted tasks to history, trash.json and the undo stack
+    ///
+    /// With `batch`, the tasks form a single undo step.
+    async fn record_deleted(&mut self, deleted: Vec<crate::download::task::DownloadTask>, batch: bool) {
+        if deleted.is_empty() {
+            return;
         }
 
-        Ok(())
+        // Persist to trash so the delete can be undone after a restart
+        // (pushing marks the tasks as deleted)
+        let mut trash = Trash::default();
+        if batch {
+            trash.push_batch(deleted);
+        } else {
+            for task in deleted {
+                trash.push(task);
+            }
+        }
+        for entry in &trash.entries {
+            self.manager.add_to_history(entry.task.clone()).await;
+        }
+        if let Err(e) = Trash::update_default(|t| t.entries.extend(trash.entries.iter().cloned())) {
+            self.state.show_error("toast-trash-failed", e);
+        }
+        self.state.delete_history.extend(trash.entries);
+
+        // Limit history size to prevent excessive memory usage
+        trash::trim_groups(&mut self.state.delete_history, MAX_UNDO_HISTORY);
     }
 
-    /// Undo last delete operation
+    /// Undo last delete operation (a whole batch for folder Delete All)
     async fn undo_delete(&mut self) -> Result<()> {
-        if let Some(task) = self.state.delete_history.pop() {
-            self.add_download_with_auto_start(task).await?;
-            tracing::info!("Undid delete operation");
+        let entries = trash::pop_group(&mut self.state.delete_history);
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<uuid::Uuid> = entries.iter().map(|entry| entry.task.id).collect();
+        if let Err(e) = Trash::update_default(|t| t.entries.retain(|entry| !ids.contains(&entry.task.id))) {
+            tracing::warn!("Failed to update trash: {}", e);
         }
+        for entry in entries {
+            self.add_download_with_auto_start(entry.into_task()).await?;
+        }
+        tracing::info!("Undid delete operation ({} download(s))", ids.len());
         Ok(())
     }
 
+    /// Load undo history from trash.json, dropping expired entries
+    async fn load_trash(&mut self) {
+        let retention_days = self.state.app_state.config.read().await.history.trash_retention_days;
+        match Trash::update_default(|t| {
+            let expired = t.expire(retention_days);
+            (expired, t.entries.clone())
+        }) {
+            Ok((expired, entries)) => {
+                if expired > 0 {
+                    tracing::info!("Expired {} trash entries", expired);
+                }
+                self.state.delete_history = entries;
+                trash::trim_groups(&mut self.state.delete_history, MAX_UNDO_HISTORY);
+            }
+            Err(e) => tracing::warn!("Failed to load trash: {}", e),
+        }
+    }
+
     /// Retry failed download
     async fn retry_download(&mut self) -> Result<()> {
-        if let Some(task) = self.state.get_selected_download() {
-            if task.status == DownloadStatus::Error {
-                self.manager.start_download(task.id, self.state.app_state.script_sender.clone(), self.state.app_state.config.clone()).await?;
-                self.save_queue().await?;
+        if let Some(id) = self
+            .state
+            .get_selected_download()
+            .filter(|task| task.status == DownloadStatus::Error)
+            .map(|task| task.id)
+        {
+            self.start_or_confirm_circuit_reset(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Start a download, asking to reset its circuit instead of failing if the circuit is open
+    ///
+    /// A download held by `download.confirm_over_bytes` asks for its size to be
+    /// confirmed first. With several downloads blocked at once (multi-selection)
+    /// only the first is offered; the rest are logged.
+    async fn start_or_confirm_circuit_reset(&mut self, id: uuid::Uuid) -> Result<()> {
+        use crate::download::circuit_breaker::CircuitOpenError;
+
+        if let Some(task) = self.manager.get_by_id(id).await.filter(|task| task.awaiting_confirmation) {
+            if self.state.pending_size_confirmation.is_some() || self.state.pending_circuit_reset.is_some() {
+                tracing::warn!("{} is waiting for size confirmation", task.filename);
+            } else {
+                self.state.pending_size_confirmation = Some((id, task.filename, task.size.unwrap_or(0)));
+                self.state.ui_mode = UiMode::ConfirmLargeDownload;
             }
+            return Ok(());
+        }
+
+        let result = self
+            .manager
+            .start_download(id, self.state.app_state.script_sender.clone(), self.state.app_state.config.clone())
+            .await;
+        match result.map_err(|e| e.downcast::<CircuitOpenError>()) {
+            Ok(()) => Ok(()),
+            Err(Ok(blocked)) => {
+                if self.state.pending_circuit_reset.is_some() {
+                    tracing::warn!("{}", blocked);
+                } else {
+                    self.state.pending_circuit_reset = Some((blocked.key, id));
+                    self.state.ui_mode = UiMode::ConfirmCircuitReset;
+                }
+                Ok(())
+            }
+            Err(Err(e)) => Err(e),
+        }
+    }
+
+    /// Handle the circuit reset confirmation (Y resets the circuit and starts the download)
+    async fn handle_confirm_circuit_reset_mode(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.state.ui_mode = UiMode::Normal;
+                if let Some((circuit, id)) = self.state.pending_circuit_reset.take() {
+                    self.manager.reset_circuit(&circuit);
+                    self.start_or_confirm_circuit_reset(id).await?;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                // Leave the circuit to its cooldown
+                self.state.pending_circuit_reset = None;
+                self.state.ui_mode = UiMode::Normal;
+            }
+            _ => {}
         }
         Ok(())
     }
 
-    /// Save queue to folder-based files
-    pub async fn save_queue(&self) -> Result<()> {
-        self.manager.save_queue_to_folders().await
+    /// Handle the size confirmation (Y accepts the size and starts the download)
+    async fn handle_confirm_large_download_mode(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.state.ui_mode = UiMode::Normal;
+                if let Some((id, _, _)) = self.state.pending_size_confirmation.take() {
+                    self.manager.confirm_download(id).await?;
+                    self.start_or_confirm_circuit_reset(id).await?;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                // Leave it paused and waiting
+                self.state.pending_size_confirmation = None;
+                self.state.ui_mode = UiMode::Normal;
+            }
+            _ => {}
+        }
+        Ok(())
     }
 
-    /// Fetch download information from URL
-    async fn fetch_download_info(&self, url: &str) -> Result<crate::download::http_client::DownloadInfo> {
+    /// Fetch download information from URL, sending the preview's request headers
+    async fn fetch_download_info(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+    ) -> Result<crate::download::http_client::DownloadInfo> {
         use crate::download::http_client::HttpClient;
 
-        let config = self.state.app_state.config.read().await;
-        let user_agent = config.download.user_agent.clone();
-        drop(config);
+        // Shares the download client's connection pool
+        let client = self.manager.http_client();
 
-        let client = HttpClient::with_user_agent(&user_agent)?;
-        let headers = HttpClient::build_headers(Some(&user_agent), None, &std::collections::HashMap::new())?;
+        let headers = HttpClient::build_headers(None, None, &headers.iter().cloned().collect())?;
 
         client.get_info(url, &headers).await
     }
 
+    /// Carry the preview's Range probe result over so the download doesn't probe again
+    ///
+    /// The size shown in the preview also counts as confirmed for
+    /// `download.confirm_over_bytes`, since adding from the preview accepts it.
+    fn apply_preview_probe(&self, task: &mut crate::download::task::DownloadTask) {
+        if let Some(ref info) = self.state.preview_info {
+            task.confirmed_size = info.size;
+            if info.range_probed {
+                task.resume_supported = info.resume_supported;
+                task.resume_probed = true;
+            }
+        }
+    }
+
+    /// Referer entered in the add dialog, as a header (None if the field is empty)
+    fn add_referer_header(&self) -> Result<Option<(String, String)>> {
+        if self.state.add_referer.trim().is_empty() {
+            return Ok(None);
+        }
+        HttpClient::referer_header(&self.state.add_referer).map(Some)
+    }
+
+    /// Create a task for the URL in the add dialog, with the dialog's Referer
+    fn new_task_from_input(&self, config: &crate::app::config::Config) -> crate::download::task::DownloadTask {
+        let mut task = crate::download::task::DownloadTask::new_with_folder(
+            self.state.input_buffer.clone(),
+            self.state.current_folder_id.clone(),
+            config,
+        );
+        // Validated when the dialog was submitted
+        if let Ok(referer) = self.add_referer_header() {
+            HttpClient::merge_headers(&mut task.headers, referer);
+        }
+        task
+    }
+
+    /// Request headers a new download of `url` in `folder_id` would send
+    ///
+    /// Mirrors the download path: the effective user agent (task > folder >
+    /// app) first, then the referrer policy's Referer unless a default header
+    /// or the add dialog's `referer` already sets one, then the custom
+    /// headers sorted by name.
+    fn preview_request_headers(
+        config: &crate::app::config::Config,
+        folder_id: &str,
+        url: &str,
+        referer: Option<(String, String)>,
+    ) -> Vec<(String, String)> {
+        use crate::app::settings::ResolvedSettings;
+
+        let mut task = crate::download::task::DownloadTask::new_with_folder(url.to_string(), folder_id.to_string(), config);
+        HttpClient::merge_headers(&mut task.headers, referer);
+        let resolved = ResolvedSettings::resolve(config, folder_id, &task);
+
+        let mut headers = vec![("User-Agent".to_string(), resolved.user_agent)];
+
+        let has_referer = resolved.headers.keys().any(|k| k.eq_ignore_ascii_case("referer"));
+        if !has_referer
+            && let Some(referer) = resolved.referrer_policy.compute(url)
+        {
+            headers.push(("Referer".to_string(), referer));
+        }
+
+        let mut custom: Vec<(String, String)> = resolved.headers.into_iter().collect();
+        custom.sort();
+        headers.extend(custom);
+        headers
+    }
+
     /// Handle download preview mode
     async fn handle_download_preview_mode(&mut self, key: KeyCode) -> Result<()> {
         match key {
             KeyCode::Enter => {
                 // Confirm and add download
                 if !self.state.input_buffer.is_empty() {
-                    let url = self.state.input_buffer.clone();
                     let config = self.state.app_state.config.read().await;
 
-                    // Use new_with_folder to apply folder defaults
-                    let task = crate::download::task::DownloadTask::new_with_folder(
-                        url,
-                        self.state.current_folder_id.clone(),
-                        &config,
-                    );
+                    // Apply folder defaults and the dialog's Referer
+                    let mut task = self.new_task_from_input(&config);
                     drop(config); // Release read lock before async operations
+                    self.apply_preview_probe(&mut task);
 
+                    if !self.prepare_save_dirs(std::slice::from_ref(&task)).await {
+                        return Ok(());
+                    }
                     self.add_download_with_auto_start(task).await?;
                 }
 
@@ -2141,11 +2599,13 @@ impl TuiApp {
                 self.state.ui_mode = UiMode::Normal;
                 self.state.input_buffer.clear();
                 self.state.preview_info = None;
+                self.state.preview_headers.clear();
             }
             KeyCode::Esc => {
                 // Cancel and return to add download mode
                 self.state.ui_mode = UiMode::AddDownload;
                 self.state.preview_info = None;
+                self.state.preview_headers.clear();
             }
             _ => {}
         }
@@ -2172,6 +2632,7 @@ impl TuiApp {
             name: display_name,
             save_path: config.download.default_directory.clone(),
             auto_date_directory: false,
+            save_path_template: None,
             auto_start_downloads: false,
             scripts_enabled: None,
             script_files: None,
@@ -2179,6 +2640,8 @@ impl TuiApp {
             user_agent: None,
             referrer_policy: None,
             default_headers: std::collections::HashMap::new(),
+            max_queued: None,
+            max_inflight_bytes: None,
         };
 
         config.folders.insert(new_folder_id.clone(), new_folder);
@@ -2274,7 +2737,8 @@ impl TuiApp {
                 folder_config.name = new_name.clone();
             }
             if let Err(e) = config.save() {
-                tracing::error!("Failed to save config after folder rename: {}", e);
+                drop(config);
+                self.state.show_error("toast-save-config-failed", e);
             }
         }
 
@@ -2301,10 +2765,11 @@ impl TuiApp {
             config.folders.remove(&folder_id_owned);
 
             // Save config to persist the removal
-            if let Err(e) = config.save() {
-                tracing::error!("Failed to save config after folder deletion: {}", e);
-            }
+            let saved = config.save();
             drop(config);
+            if let Err(e) = saved {
+                self.state.show_error("toast-save-config-failed", e);
+            }
 
             // Delete folder config directory from filesystem
             if let Ok(config_dir) = crate::util::paths::find_config_directory() {
@@ -2327,6 +2792,17 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Show or hide the bandwidth graph and save the choice
+    async fn toggle_bandwidth_graph(&mut self) {
+        let mut config = self.state.app_state.config.write().await;
+        config.general.show_bandwidth_graph = !config.general.show_bandwidth_graph;
+        tracing::info!("Toggled show_bandwidth_graph to {}", config.general.show_bandwidth_graph);
+        if let Err(e) = config.save() {
+            drop(config);
+            self.state.show_error("toast-save-config-failed", e);
+        }
+    }
+
     /// Save configuration to file
     async fn save_config(&self) -> Result<()> {
         let config = self.state.app_state.config.read().await;
@@ -2394,6 +2870,13 @@ impl TuiApp {
                     .map(|v| v.to_string())
                     .unwrap_or_default()
             }
+            ApplicationSettingsField::MaxConnections => {
+                config
+                    .download
+                    .max_connections
+                    .map(|v| v.to_string())
+                    .unwrap_or_default()
+            }
             ApplicationSettingsField::MaxRedirects => {
                 config.download.max_redirects.to_string()
             }
@@ -2483,6 +2966,21 @@ impl TuiApp {
                 };
                 Command::UpdateMaxActiveFolders { value }
             }
+            ApplicationSettingsField::MaxConnections => {
+                let value = if value_str.is_empty() {
+                    None
+                } else if let Ok(v) = value_str.parse::<usize>() {
+                    Some(v)
+                } else {
+                    self.state.validation_error = Some(format!(
+                        "Invalid number: '{}'. Expected a positive integer or leave empty.",
+                        value_str
+                    ));
+                    tracing::error!("Invalid value for MaxConnections: {}", value_str);
+                    return Ok(());
+                };
+                Command::UpdateMaxConnections { value }
+            }
             ApplicationSettingsField::MaxRedirects => {
                 if let Ok(value) = value_str.parse::<u32>() {
                     Command::UpdateMaxRedirects { value }
@@ -2515,16 +3013,17 @@ impl TuiApp {
                 unreachable!("Toggle/cycle fields are handled in start_app_settings_edit()")
             }
             ApplicationSettingsField::Language => {
-                let value = value_str.to_lowercase();
-                if value != "en" && value != "ja" {
+                let available = crate::util::i18n::LocalizationManager::available_locales();
+                if crate::util::i18n::resolve_locale(value_str, &available).is_none() {
                     self.state.validation_error = Some(format!(
-                        "Invalid language: '{}'. Use 'en' or 'ja'.",
-                        value_str
+                        "Invalid language: '{}'. Available: {}.",
+                        value_str,
+                        available.join(", ")
                     ));
-                    tracing::error!("Invalid language: {}. Use 'en' or 'ja'.", value_str);
+                    tracing::error!("Invalid language: {}. Available: {}", value_str, available.join(", "));
                     return Ok(());
                 }
-                Command::UpdateLanguage { value }
+                Command::UpdateLanguage { value: value_str.trim().to_string() }
             }
         };
 
@@ -2551,6 +3050,15 @@ impl TuiApp {
                 self.state.validation_error = Some(error.clone());
                 tracing::error!("Failed to update setting: {}", error);
             }
+            crate::ui::commands::CommandResponse::NeedsConfirmation { adjustment, message } => {
+                // Nothing saved yet; ask before applying the proposed limits
+                tracing::info!("Concurrency limits need adjusting: {}", message);
+                self.state.validation_error = None;
+                self.state.pending_concurrency_adjustment = Some((adjustment, message));
+                self.state.ui_mode = UiMode::ConfirmConcurrencyAdjustment;
+                self.state.input_buffer.clear();
+                return Ok(());
+            }
         }
 
         // Return to settings screen
@@ -2560,18 +3068,84 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Handle the concurrency adjustment confirmation (Y applies the proposed limits)
+    async fn handle_confirm_concurrency_mode(&mut self, key: KeyCode) -> Result<()> {
+        use crate::ui::commands::{Command, CommandResponse, handle_command};
+
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Some((adjustment, _)) = self.state.pending_concurrency_adjustment.take() {
+                    let response = handle_command(
+                        Command::ApplyConcurrencyAdjustment { adjustment },
+                        self.state.app_state.clone(),
+                        self.manager.clone(),
+                    )
+                    .await;
+                    match response {
+                        CommandResponse::Success { .. } => {
+                            self.state.validation_error = None;
+                            tracing::info!(
+                                "Concurrency limits adjusted: per_folder={}, active_folders={}",
+                                adjustment.max_concurrent_per_folder,
+                                adjustment.parallel_folder_count
+                            );
+                        }
+                        CommandResponse::Error { error } | CommandResponse::NeedsConfirmation { message: error, .. } => {
+                            self.state.validation_error = Some(error.clone());
+                            tracing::error!("Failed to adjust concurrency limits: {}", error);
+                        }
+                    }
+                }
+                self.state.ui_mode = UiMode::Settings;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                // Keep the previous limits
+                self.state.pending_concurrency_adjustment = None;
+                self.state.ui_mode = UiMode::Settings;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Check if text is a valid URL with a scheme that can be downloaded
-    /// Uses url crate to validate, accepts schemes that reqwest can handle
+    /// Uses url crate to validate; pasted text with another scheme is ignored.
+    /// `download.allowed_schemes` is checked when the URL is added, so a URL
+    /// with a disabled scheme gets an error instead of being dropped silently.
     fn is_valid_download_url(text: &str) -> bool {
         match url::Url::parse(text) {
-            Ok(parsed) => {
-                // Check if scheme is supported by our HTTP client (reqwest)
-                matches!(parsed.scheme(), "http" | "https" | "ftp" | "ftps")
-            }
+            Ok(parsed) => crate::app::config::DEFAULT_ALLOWED_SCHEMES.contains(&parsed.scheme()),
             Err(_) => false,
         }
     }
 
+    /// Create the save directories of tasks about to be added
+    ///
+    /// On failure (e.g. permission denied) the add dialog is shown with the
+    /// error and false is returned; the caller should not add the tasks.
+    async fn prepare_save_dirs(&mut self, tasks: &[crate::download::task::DownloadTask]) -> bool {
+        let result = {
+            let config = self.state.app_state.config.read().await;
+            let dirs: std::collections::BTreeSet<PathBuf> = tasks
+                .iter()
+                .map(|task| crate::app::settings::ResolvedSettings::resolve(&config, &task.folder_id, task).save_path)
+                .collect();
+            dirs.iter().try_for_each(|dir| crate::util::fs::ensure_save_directory(dir))
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Download not added: {}", e);
+                self.state.validation_error = Some(e.to_string());
+                self.state.preview_info = None;
+                self.state.preview_headers.clear();
+                self.state.ui_mode = UiMode::AddDownload;
+                false
+            }
+        }
+    }
+
     /// Add download task and auto-start if folder setting enabled
     async fn add_download_with_auto_start(&mut self, task: crate::download::task::DownloadTask) -> Result<()> {
         let folder_id = task.folder_id.clone();
@@ -2592,17 +3166,10 @@ impl TuiApp {
 
         // Auto-start if enabled
         if should_auto_start {
-            self.manager
-                .start_download(
-                    task_id,
-                    self.state.app_state.script_sender.clone(),
-                    self.state.app_state.config.clone(),
-                )
-                .await?;
+            self.start_or_confirm_circuit_reset(task_id).await?;
             tracing::info!("Auto-started download in folder '{}'", folder_id);
         }
 
-        self.save_queue().await?;
         Ok(())
     }
 
@@ -2615,6 +3182,13 @@ impl TuiApp {
         let urls = crate::util::url_expansion::expand_url(url);
         let urls = if urls.is_empty() { vec![url.to_string()] } else { urls };
 
+        {
+            let config = self.state.app_state.config.read().await;
+            for u in &urls {
+                config.download.check_url_scheme(u)?;
+            }
+        }
+
         for u in &urls {
             let task = {
                 let config = self.state.app_state.config.read().await;
@@ -2624,6 +3198,10 @@ impl TuiApp {
                     &config,
                 )
             };
+            if !self.prepare_save_dirs(std::slice::from_ref(&task)).await {
+                self.state.input_buffer = u.clone();
+                return Ok(());
+            }
             self.add_download_with_auto_start(task).await?;
         }
 
@@ -2641,6 +3219,7 @@ impl TuiApp {
 pub async fn run_tui(
     app_state: AppState,
     manager: DownloadManager,
+    config_problems: Vec<String>,
 ) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
@@ -2659,6 +3238,7 @@ pub async fn run_tui(
 
     // Create app
     let mut app = TuiApp::new(app_state, manager, &keybindings);
+    app.state.config_problems = config_problems;
 
     // Set initial current_folder_id to the "default" folder's UUID
     {
@@ -2672,6 +3252,7 @@ pub async fn run_tui(
 
     // Load downloads initially
     app.state.update_downloads(&app.manager).await;
+    app.load_trash().await;
 
     // Event channel
     let (tx, mut rx) = mpsc::channel(100);
@@ -2732,6 +3313,20 @@ pub async fn run_tui(
         }
     }
 
+    // Pause/resume downloads based on battery and metered connection state
+    let power_monitor_handle =
+        crate::util::power::spawn_monitor(app.manager.clone(), app.state.app_state.clone());
+
+    // Pause or throttle downloads during [schedule] quiet windows
+    let schedule_monitor_handle =
+        crate::util::schedule::spawn_monitor(app.manager.clone(), app.state.app_state.clone());
+
+    // Reload scripts when files change (scripts.auto_reload)
+    let script_watcher_handle = crate::script::watcher::spawn_watcher(&app.state.app_state);
+
+    // Save modified folder queues in the background (at most once per second)
+    let queue_saver_handle = app.manager.spawn_queue_saver();
+
     // Track whether mouse capture is currently active
     let mut mouse_captured = true;
 
@@ -2764,6 +3359,13 @@ pub async fn run_tui(
         }
     }
 
+    // Let active downloads flush and record their resume offsets
+    power_monitor_handle.abort();
+    schedule_monitor_handle.abort();
+    script_watcher_handle.abort();
+    queue_saver_handle.abort();
+    app.manager.shutdown(crate::download::manager::DEFAULT_SHUTDOWN_GRACE).await;
+
     // Cleanup terminal
     disable_raw_mode()?;
     terminal.backend_mut().execute(DisableMouseCapture)?;
@@ -2771,8 +3373,8 @@ pub async fn run_tui(
     terminal.backend_mut().execute(DisableBracketedPaste)?;
     terminal.show_cursor()?;
 
-    // Save queue on exit
-    app.save_queue().await?;
+    // Flush changes made since the last background save
+    app.manager.save_queue_to_folders().await?;
 
     Ok(())
 }
@@ -2845,6 +3447,15 @@ mod tests {
         assert!(!TuiApp::is_valid_download_url("mailto:user@example.com"));
     }
 
+    #[test]
+    fn test_disallowed_scheme_still_detected_as_url() {
+        // Detection ignores download.allowed_schemes so the add flow can explain the rejection
+        let mut download = crate::app::config::Config::default().download;
+        download.allowed_schemes = vec!["https".to_string()];
+        assert!(TuiApp::is_valid_download_url("ftp://example.com/file.zip"));
+        assert!(download.check_url_scheme("ftp://example.com/file.zip").is_err());
+    }
+
     #[test]
     fn test_is_valid_download_url_malformed() {
         assert!(!TuiApp::is_valid_download_url("not a url"));
@@ -2853,6 +3464,41 @@ mod tests {
         assert!(!TuiApp::is_valid_download_url(""));
     }
 
+    #[test]
+    fn test_preview_request_headers() {
+        let mut config = crate::app::config::Config::default();
+        config.download.user_agent = "ggg-test/1.0".to_string();
+        let mut folder = crate::app::config::FolderConfig::default();
+        folder.default_headers.insert("X-Token".to_string(), "secret".to_string());
+        folder.default_headers.insert("Accept".to_string(), "*/*".to_string());
+        config.folders.insert("media".to_string(), folder);
+
+        let headers = TuiApp::preview_request_headers(&config, "media", "https://example.com/file.zip", None);
+        assert_eq!(headers[0], ("User-Agent".to_string(), "ggg-test/1.0".to_string()));
+        let custom: Vec<&str> = headers
+            .iter()
+            .filter(|(name, _)| name != "User-Agent" && name != "Referer")
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(custom, ["Accept", "X-Token"]);
+
+        // A folder default Referer replaces the policy's
+        config.folders.get_mut("media").unwrap()
+            .default_headers.insert("Referer".to_string(), "https://ref.example/".to_string());
+        let headers = TuiApp::preview_request_headers(&config, "media", "https://example.com/file.zip", None);
+        assert_eq!(headers.iter().filter(|(name, _)| name.eq_ignore_ascii_case("referer")).count(), 1);
+
+        // The add dialog's Referer replaces the folder default
+        let referer = Some(("Referer".to_string(), "https://gallery.example/".to_string()));
+        let headers = TuiApp::preview_request_headers(&config, "media", "https://example.com/file.zip", referer);
+        let referers: Vec<&str> = headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("referer"))
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(referers, ["https://gallery.example/"]);
+    }
+
     #[test]
     fn test_is_valid_download_url_with_query_and_fragment() {
         assert!(TuiApp::is_valid_download_url(
@@ -0,0 +1,154 @@
+//! Hierarchical folder tree for the TUI
+//!
+//! Folder names containing `/` (e.g. `media/images`) are shown as a tree:
+//! each segment becomes a level, and parents that are not folders themselves
+//! appear as group nodes. The config stays flat; the tree is built from the
+//! display names every time the folder list is refreshed, and which nodes are
+//! collapsed is kept in `TuiState` by path.
+
+use super::state::FolderTreeItem;
+use std::collections::{BTreeMap, HashSet};
+
+/// One visible row of the folder tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderTreeRow {
+    pub item: FolderTreeItem,
+    /// Nesting level (0 = top level)
+    pub depth: usize,
+    /// Last path segment, shown next to the icon
+    pub label: String,
+    /// Full slash-delimited path (the folder name for folders, empty for the completed node)
+    pub path: String,
+    /// True if the node has children (whether or not they are visible)
+    pub has_children: bool,
+    /// True if the node's children are hidden
+    pub collapsed: bool,
+}
+
+impl FolderTreeRow {
+    /// A top-level row for a non-folder node such as the completed node
+    pub fn leaf(item: FolderTreeItem, label: String) -> Self {
+        Self { item, depth: 0, label, path: String::new(), has_children: false, collapsed: false }
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    folder_id: Option<String>,
+    children: BTreeMap<String, Node>,
+}
+
+/// Path segments of a folder name
+///
+/// Names with empty segments (`a//b`, `/a`, `a/`) are not nested, so every
+/// folder keeps a unique path.
+fn segments(name: &str) -> Vec<&str> {
+    let parts: Vec<&str> = name.split('/').collect();
+    if parts.iter().any(|part| part.trim().is_empty()) {
+        vec![name]
+    } else {
+        parts
+    }
+}
+
+/// Build the visible rows from `(folder_id, display_name)` pairs
+///
+/// Siblings are sorted by name; children of paths in `collapsed` are skipped.
+pub fn build_folder_tree(folders: &[(String, String)], collapsed: &HashSet<String>) -> Vec<FolderTreeRow> {
+    let mut root = Node::default();
+    for (id, name) in folders {
+        let mut node = &mut root;
+        for segment in segments(name) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.folder_id = Some(id.clone());
+    }
+
+    let mut rows = Vec::new();
+    push_rows(&root, "", 0, collapsed, &mut rows);
+    rows
+}
+
+fn push_rows(node: &Node, parent_path: &str, depth: usize, collapsed: &HashSet<String>, rows: &mut Vec<FolderTreeRow>) {
+    for (segment, child) in &node.children {
+        let path = if parent_path.is_empty() { segment.clone() } else { format!("{}/{}", parent_path, segment) };
+        let is_collapsed = !child.children.is_empty() && collapsed.contains(&path);
+        let item = match child.folder_id {
+            Some(ref id) => FolderTreeItem::Folder(id.clone()),
+            None => FolderTreeItem::Group(path.clone()),
+        };
+
+        rows.push(FolderTreeRow {
+            item,
+            depth,
+            label: segment.clone(),
+            path: path.clone(),
+            has_children: !child.children.is_empty(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            push_rows(child, &path, depth + 1, collapsed, rows);
+        }
+    }
+}
+
+/// Parent path of a tree path (None at the top level)
+pub fn parent_path(path: &str) -> Option<&str> {
+    path.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// True if the folder named `name` is inside the group at `path`
+pub fn is_under(name: &str, path: &str) -> bool {
+    name.strip_prefix(path).is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folders(names: &[&str]) -> Vec<(String, String)> {
+        names.iter().map(|name| (format!("id-{}", name), name.to_string())).collect()
+    }
+
+    fn labels(rows: &[FolderTreeRow]) -> Vec<String> {
+        rows.iter().map(|row| format!("{}{}", "  ".repeat(row.depth), row.label)).collect()
+    }
+
+    #[test]
+    fn test_build_nested_tree() {
+        let folders = folders(&["media/images", "default", "media", "work/docs/2026", "a//b"]);
+        let rows = build_folder_tree(&folders, &HashSet::new());
+
+        assert_eq!(
+            labels(&rows),
+            ["a//b", "default", "media", "  images", "work", "  docs", "    2026"]
+        );
+        // "media" is a folder with children; "work" and "work/docs" are groups
+        assert_eq!(rows[2].item, FolderTreeItem::Folder("id-media".to_string()));
+        assert!(rows[2].has_children);
+        assert_eq!(rows[4].item, FolderTreeItem::Group("work".to_string()));
+        assert_eq!(rows[5].item, FolderTreeItem::Group("work/docs".to_string()));
+        assert_eq!(rows[6].path, "work/docs/2026");
+    }
+
+    #[test]
+    fn test_collapsed_nodes_hide_children() {
+        let folders = folders(&["media", "media/images", "media/video/clips", "other"]);
+        let collapsed = HashSet::from(["media".to_string(), "other".to_string()]);
+        let rows = build_folder_tree(&folders, &collapsed);
+
+        assert_eq!(labels(&rows), ["media", "other"]);
+        assert!(rows[0].collapsed);
+        // Leaves are never shown as collapsed
+        assert!(!rows[1].collapsed);
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(parent_path("media/images/raw"), Some("media/images"));
+        assert_eq!(parent_path("media"), None);
+        assert!(is_under("media/images", "media"));
+        assert!(!is_under("media2/images", "media"));
+        assert!(!is_under("media", "media"));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod events;
+pub mod folder_tree;
 pub mod state;
 pub mod ui;
 
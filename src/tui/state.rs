@@ -1,13 +1,72 @@
 use crate::app::state::AppState;
-use crate::download::manager::DownloadManager;
-use crate::download::task::DownloadTask;
+use crate::download::manager::{DownloadManager, FolderTaskCounts};
+use crate::download::task::{DownloadStatus, DownloadTask};
 use crate::util::i18n::LocalizationManager;
+use super::folder_tree::{self, FolderTreeRow};
 use ratatui::layout::Rect;
 use ratatui::widgets::TableState;
 use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
 use uuid::Uuid;
 
+/// Weight of the newest measurement in the smoothed per-task speed
+const SPEED_SMOOTHING: f64 = 0.5;
+
+/// Seconds of total throughput kept for the bandwidth graph
+pub const BANDWIDTH_HISTORY_SECS: usize = 60;
+
+/// How long a status message stays in the status bar
+pub const STATUS_MESSAGE_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Transient message shown in the status bar, e.g. for a failed action
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    /// Cleared by the first tick after this
+    pub expires_at: Instant,
+}
+
+/// Progress of a downloading task at the last UI update
+///
+/// Speeds are derived from consecutive snapshots, so however many progress
+/// updates happen between two refreshes only the latest one is used.
+#[derive(Debug, Clone, Copy)]
+struct SpeedSample {
+    at: Instant,
+    downloaded: u64,
+    /// Smoothed speed in bytes/sec (None until a second snapshot is seen)
+    speed: Option<f64>,
+}
+
+/// Time left for all running downloads at their current total speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueEta {
+    pub seconds: u64,
+    /// Some running downloads have an unknown size and are not counted
+    pub partial: bool,
+}
+
+/// What the change dialog (`UiMode::ChangeFolder`) edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeTarget {
+    /// Move the selected download to another folder (Tab completes folder names)
+    #[default]
+    Folder,
+    /// Free-text save path of the selected download
+    SavePath,
+}
+
+/// Tab completion state of the change-folder dialog
+#[derive(Debug, Clone, Default)]
+pub struct FolderCompletion {
+    /// Folder names matching the text typed before the first Tab
+    pub candidates: Vec<String>,
+    /// Candidate currently in the input buffer
+    pub index: usize,
+}
+
 /// UI mode determines what the TUI is currently doing
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiMode {
@@ -37,6 +96,16 @@ pub enum UiMode {
     ContextMenu,
     /// Folder context menu (popup actions for folder tree)
     FolderContextMenu,
+    /// Statistics panel overlay
+    Stats,
+    /// Recent script errors overlay
+    ScriptErrors,
+    /// Confirm adjusted concurrency limits proposed after a settings edit
+    ConfirmConcurrencyAdjustment,
+    /// Confirm resetting the open circuit that blocked a download from starting
+    ConfirmCircuitReset,
+    /// Confirm starting a download held by `download.confirm_over_bytes`
+    ConfirmLargeDownload,
 }
 
 impl UiMode {
@@ -51,6 +120,49 @@ impl UiMode {
     }
 }
 
+/// Time range shown in the statistics panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsRange {
+    #[default]
+    All,
+    Today,
+    Week,
+    Month,
+}
+
+impl StatsRange {
+    /// Cycle to the next range: All -> Today -> Week -> Month -> All
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Today,
+            Self::Today => Self::Week,
+            Self::Week => Self::Month,
+            Self::Month => Self::All,
+        }
+    }
+
+    /// Convert to the aggregation window used by `stats::compute`
+    pub fn window(self) -> crate::download::stats::StatsWindow {
+        use crate::download::stats::StatsWindow;
+        match self {
+            Self::All => StatsWindow::All,
+            Self::Today => StatsWindow::Today,
+            Self::Week => StatsWindow::last(chrono::Duration::days(7)),
+            Self::Month => StatsWindow::last(chrono::Duration::days(30)),
+        }
+    }
+
+    /// i18n key for the range label
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::All => "stats-range-all",
+            Self::Today => "stats-range-today",
+            Self::Week => "stats-range-week",
+            Self::Month => "stats-range-month",
+        }
+    }
+}
+
 /// Active pane in the 3-pane layout
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FocusPane {
@@ -68,6 +180,8 @@ pub enum FocusPane {
 pub enum FolderTreeItem {
     /// Regular folder (folder_id)
     Folder(String),
+    /// Parent of nested folders that is not a folder itself (slash-delimited path)
+    Group(String),
     /// Special "Completed" node showing history
     CompletedNode,
 }
@@ -97,6 +211,7 @@ pub enum ApplicationSettingsField {
     MaxConcurrent,
     MaxConcurrentPerFolder,
     MaxActiveFolders,
+    MaxConnections,
     MaxRedirects,
     RetryCount,
     UserAgent,
@@ -113,6 +228,7 @@ impl ApplicationSettingsField {
             Self::MaxConcurrent,
             Self::MaxConcurrentPerFolder,
             Self::MaxActiveFolders,
+            Self::MaxConnections,
             Self::MaxRedirects,
             Self::RetryCount,
             Self::UserAgent,
@@ -130,6 +246,7 @@ impl ApplicationSettingsField {
             Self::MaxConcurrent => "settings-app-max-concurrent",
             Self::MaxConcurrentPerFolder => "settings-app-max-concurrent-per-folder",
             Self::MaxActiveFolders => "settings-app-max-active-folders",
+            Self::MaxConnections => "settings-app-max-connections",
             Self::MaxRedirects => "settings-app-max-redirects",
             Self::RetryCount => "settings-app-retry-count",
             Self::UserAgent => "settings-app-user-agent",
@@ -147,6 +264,7 @@ impl ApplicationSettingsField {
             Self::MaxConcurrent => "settings-app-max-concurrent-desc",
             Self::MaxConcurrentPerFolder => "settings-app-max-concurrent-per-folder-desc",
             Self::MaxActiveFolders => "settings-app-max-active-folders-desc",
+            Self::MaxConnections => "settings-app-max-connections-desc",
             Self::MaxRedirects => "settings-app-max-redirects-desc",
             Self::RetryCount => "settings-app-retry-count-desc",
             Self::UserAgent => "settings-app-user-agent-desc",
@@ -327,6 +445,8 @@ pub struct TuiState {
 
     /// Internationalization manager
     pub i18n: Arc<LocalizationManager>,
+    /// Number/date conventions of the UI language
+    pub format: crate::util::format::LocaleFormat,
 
     /// Per-folder download tasks (folder_id -> tasks)
     pub folder_downloads: std::collections::HashMap<String, Vec<DownloadTask>>,
@@ -335,6 +455,9 @@ pub struct TuiState {
     /// Updated every tick from config
     pub folder_names: std::collections::HashMap<String, String>,
 
+    /// Active/queued task counts per folder (folder_id -> counts), for the folder tree
+    pub folder_counts: HashMap<String, FolderTaskCounts>,
+
     /// Download history items (completed, failed, deleted)
     pub history_items: Vec<DownloadTask>,
 
@@ -347,8 +470,11 @@ pub struct TuiState {
     /// Currently focused pane in 3-pane layout
     pub focus_pane: FocusPane,
 
-    /// Items in the folder tree
-    pub tree_items: Vec<FolderTreeItem>,
+    /// Visible rows of the folder tree
+    pub tree_items: Vec<FolderTreeRow>,
+
+    /// Paths of collapsed folder tree nodes
+    pub collapsed_folders: std::collections::HashSet<String>,
 
     /// Selected index in the folder tree
     pub tree_selected_index: usize,
@@ -380,6 +506,15 @@ pub struct TuiState {
     /// Folder picker: selected folder index
     pub folder_picker_index: usize,
 
+    /// Change dialog: folder or save path
+    pub change_target: ChangeTarget,
+
+    /// Change dialog: folder names being cycled with Tab
+    pub folder_completion: Option<FolderCompletion>,
+
+    /// Change dialog: highlighted entry in the list of matching folders
+    pub change_folder_index: usize,
+
     /// Settings screen: selected folder ID
     pub selected_folder_id: Option<String>,
 
@@ -407,9 +542,25 @@ pub struct TuiState {
     /// Validation/error message to display (None = no error)
     pub validation_error: Option<String>,
 
+    /// Settings screen: adjusted concurrency limits awaiting confirmation, with the reason
+    pub pending_concurrency_adjustment: Option<(crate::app::settings::ConcurrencyAdjustment, String)>,
+
+    /// Circuit key and download blocked by the open circuit, awaiting confirmation to reset it
+    pub pending_circuit_reset: Option<(String, uuid::Uuid)>,
+
+    /// Download (ID, filename, size) held for size confirmation, awaiting the user's answer
+    pub pending_size_confirmation: Option<(uuid::Uuid, String, u64)>,
+
+    /// Destructive folder menu action shown in the delete confirmation dialog
+    /// (None while the dialog asks about the selected downloads)
+    pub pending_folder_action: Option<FolderContextMenuAction>,
+
     /// Rendering optimization: flag to indicate if UI needs redraw
     pub needs_redraw: bool,
 
+    /// Error message shown in place of the status bar hints until it expires
+    pub status_message: Option<StatusMessage>,
+
     /// Settings screen: script files list selection index
     pub script_files_index: usize,
 
@@ -425,12 +576,47 @@ pub struct TuiState {
     /// Context menu: selected menu item index
     pub context_menu_index: usize,
 
-    /// Undo/Redo: stack of deleted downloads for undo functionality
-    pub delete_history: Vec<DownloadTask>,
+    /// Undo/Redo: stack of deleted downloads for undo functionality (mirrors trash.json)
+    pub delete_history: Vec<crate::download::trash::TrashEntry>,
 
     /// Download preview: information fetched from server
     pub preview_info: Option<crate::download::http_client::DownloadInfo>,
 
+    /// Download preview: request headers the download will send (name, value)
+    pub preview_headers: Vec<(String, String)>,
+
+    /// Add-download dialog: Referer to send with the new download(s)
+    pub add_referer: String,
+
+    /// Add-download dialog: true while typing in the Referer field
+    pub add_referer_focused: bool,
+
+    /// Statistics panel: aggregates computed when the panel is opened
+    pub stats: Option<crate::download::stats::DownloadStats>,
+    /// Lifetime totals shown under the statistics panel totals
+    pub lifetime_stats: Option<crate::download::lifetime::LifetimeStats>,
+
+    /// Statistics panel: selected time window
+    pub stats_window: StatsRange,
+
+    /// Script error count (`ScriptErrorLog::total`) when the viewer was last opened
+    pub script_errors_seen: u64,
+
+    /// Configuration problems found at startup (shown in the status bar for the session)
+    pub config_problems: Vec<String>,
+
+    /// Per-task speed samples for downloading tasks, keyed by task ID
+    speed_samples: HashMap<Uuid, SpeedSample>,
+
+    /// Total speed of all downloads in bytes/sec, one entry per second (oldest first)
+    pub bandwidth_history: VecDeque<u64>,
+
+    /// Overall ETA of the running downloads (None while nothing can be estimated)
+    pub queue_eta: Option<QueueEta>,
+
+    /// When the last bandwidth history entry was recorded
+    bandwidth_recorded_at: Option<Instant>,
+
     /// Table state for ratatui widget (RefCell for interior mutability)
     table_state: RefCell<TableState>,
 
@@ -444,6 +630,10 @@ pub struct TuiState {
     /// NOTE: Cache is no longer used for folder downloads since we access them directly
     filtered_cache: RefCell<FilterCache>,
 
+    /// Free space for the selected settings folder: (save_path, bytes)
+    /// Cleared when the folder selection changes to avoid a syscall per frame
+    free_space_cache: RefCell<Option<(std::path::PathBuf, Option<u64>)>>,
+
     /// Keyboard shortcut resolver
     pub keybinding_resolver: crate::app::keybindings::KeybindingResolver,
 
@@ -480,16 +670,24 @@ impl TuiState {
         let keybinding_resolver =
             crate::app::keybindings::KeybindingResolver::from_config(keybindings);
 
+        let format = crate::util::format::LocaleFormat::for_language(i18n.current_locale());
+
         Self {
             app_state,
             i18n,
+            format,
             folder_downloads: std::collections::HashMap::new(),
             folder_names: std::collections::HashMap::new(),
+            folder_counts: HashMap::new(),
             history_items: Vec::new(),
             selected_index: 0,
             scroll_offset: 0,
             focus_pane: FocusPane::DownloadList,
-            tree_items: vec![FolderTreeItem::Folder("default".to_string()), FolderTreeItem::CompletedNode],
+            tree_items: vec![
+                FolderTreeRow::leaf(FolderTreeItem::Folder("default".to_string()), "default".to_string()),
+                FolderTreeRow::leaf(FolderTreeItem::CompletedNode, String::new()),
+            ],
+            collapsed_folders: std::collections::HashSet::new(),
             tree_selected_index: 0,
             details_position: DetailsPosition::Bottom,
             search_query: String::new(),
@@ -500,6 +698,9 @@ impl TuiState {
             input_prompt: String::new(),
             current_folder_id: "default".to_string(),
             folder_picker_index: 0,
+            change_target: ChangeTarget::default(),
+            folder_completion: None,
+            change_folder_index: 0,
             selected_folder_id: None,
             settings_edit_field: None,
             settings_folder_index: 0,
@@ -509,7 +710,12 @@ impl TuiState {
             is_editing_app_setting: false,
             renaming_folder_id: None,
             validation_error: None,
+            pending_concurrency_adjustment: None,
+            pending_circuit_reset: None,
+            pending_size_confirmation: None,
+            pending_folder_action: None,
             needs_redraw: true,  // Initial render needed
+            status_message: None,
             script_files_index: 0,
             app_scripts_expanded: false,
             folder_scripts_expanded: false,
@@ -517,10 +723,23 @@ impl TuiState {
             context_menu_index: 0,
             delete_history: Vec::new(),
             preview_info: None,
+            preview_headers: Vec::new(),
+            add_referer: String::new(),
+            add_referer_focused: false,
+            stats: None,
+            lifetime_stats: None,
+            stats_window: StatsRange::default(),
+            script_errors_seen: 0,
+            config_problems: Vec::new(),
+            speed_samples: HashMap::new(),
+            bandwidth_history: VecDeque::with_capacity(BANDWIDTH_HISTORY_SECS),
+            queue_eta: None,
+            bandwidth_recorded_at: None,
             table_state: RefCell::new(table_state),
             click_regions: RefCell::new(ClickableRegions::default()),
             folder_context_menu_index: 0,
             filtered_cache: RefCell::new(FilterCache::default()),
+            free_space_cache: RefCell::new(None),
             keybinding_resolver,
             #[cfg(windows)]
             ipc_pipe_name: None,
@@ -531,6 +750,10 @@ impl TuiState {
     pub async fn update_downloads(&mut self, manager: &DownloadManager) {
         // Get all downloads and group by folder_id
         let all_downloads = manager.get_all_downloads().await;
+        let now = Instant::now();
+        self.sample_speeds(&all_downloads, now);
+        self.record_bandwidth(now);
+        self.queue_eta = self.estimate_queue_eta(&all_downloads);
         self.folder_downloads.clear();
         for task in all_downloads {
             self.folder_downloads
@@ -549,19 +772,138 @@ impl TuiState {
             let name = if fc.name.is_empty() { id.clone() } else { fc.name.clone() };
             self.folder_names.insert(id.clone(), name);
         }
-        let entries = config.sorted_folder_entries();
         drop(config);
 
-        self.tree_items = entries
-            .into_iter()
-            .map(|(id, _name)| FolderTreeItem::Folder(id))
-            .chain(std::iter::once(FolderTreeItem::CompletedNode))
-            .collect();
+        self.folder_counts.clear();
+        for id in self.folder_names.keys() {
+            self.folder_counts.insert(id.clone(), manager.get_folder_counts(id).await);
+        }
+
+        self.rebuild_tree();
+    }
+
+    /// Active/queued counts shown next to a tree node (groups sum their subfolders)
+    pub fn tree_item_counts(&self, item: &FolderTreeItem) -> FolderTaskCounts {
+        match item {
+            FolderTreeItem::Folder(id) => self.folder_counts.get(id).cloned().unwrap_or_default(),
+            FolderTreeItem::Group(path) => self
+                .folder_names
+                .iter()
+                .filter(|(_, name)| folder_tree::is_under(name, path))
+                .filter_map(|(id, _)| self.folder_counts.get(id))
+                .fold(FolderTaskCounts::default(), |sum, counts| FolderTaskCounts {
+                    pending: sum.pending + counts.pending,
+                    downloading: sum.downloading + counts.downloading,
+                }),
+            FolderTreeItem::CompletedNode => FolderTaskCounts::default(),
+        }
+    }
+
+    /// Rebuild the folder tree rows from the folder names, keeping the selected node
+    pub fn rebuild_tree(&mut self) {
+        let selected = self.selected_tree_item().cloned();
+        let folders: Vec<(String, String)> =
+            self.folder_names.iter().map(|(id, name)| (id.clone(), name.clone())).collect();
+
+        self.tree_items = folder_tree::build_folder_tree(&folders, &self.collapsed_folders);
+        self.tree_items.push(FolderTreeRow::leaf(FolderTreeItem::CompletedNode, String::new()));
+
+        if let Some(index) = selected.and_then(|item| self.tree_items.iter().position(|row| row.item == item)) {
+            self.tree_selected_index = index;
+        }
+        self.tree_selected_index = self.tree_selected_index.min(self.tree_items.len() - 1);
+    }
+
+    /// Update per-task speeds from the latest snapshot
+    ///
+    /// Samples of tasks that are no longer downloading are dropped.
+    fn sample_speeds(&mut self, tasks: &[DownloadTask], now: Instant) {
+        let mut samples = HashMap::with_capacity(self.speed_samples.len());
+        for task in tasks.iter().filter(|t| t.status == DownloadStatus::Downloading) {
+            let sample = match self.speed_samples.get(&task.id) {
+                Some(prev) if task.downloaded >= prev.downloaded => {
+                    let elapsed = now.duration_since(prev.at).as_secs_f64();
+                    if elapsed > 0.0 {
+                        let current = (task.downloaded - prev.downloaded) as f64 / elapsed;
+                        let speed = match prev.speed {
+                            Some(speed) => speed + SPEED_SMOOTHING * (current - speed),
+                            None => current,
+                        };
+                        SpeedSample { at: now, downloaded: task.downloaded, speed: Some(speed) }
+                    } else {
+                        *prev
+                    }
+                }
+                // First snapshot, or progress went backwards (restarted from scratch)
+                _ => SpeedSample { at: now, downloaded: task.downloaded, speed: None },
+            };
+            samples.insert(task.id, sample);
+        }
+        self.speed_samples = samples;
+    }
+
+    /// Remaining bytes of the running downloads over their total speed
+    ///
+    /// Downloads of unknown size are left out of both the speed and the
+    /// remaining bytes, so the estimate is marked partial; with no known sizes
+    /// or no measured speed there is no estimate.
+    fn estimate_queue_eta(&self, tasks: &[DownloadTask]) -> Option<QueueEta> {
+        let running: Vec<&DownloadTask> = tasks.iter().filter(|t| t.status == DownloadStatus::Downloading).collect();
+        let sized: Vec<&DownloadTask> = running.iter().copied().filter(|t| t.size.is_some()).collect();
+        let speed: f64 = sized.iter().filter_map(|task| self.task_speed(task)).sum();
+        if sized.is_empty() || speed <= 0.0 {
+            return None;
+        }
+        let remaining: u64 = sized
+            .iter()
+            .map(|t| t.size.unwrap_or_default().saturating_sub(t.downloaded))
+            .sum();
+        Some(QueueEta {
+            seconds: (remaining as f64 / speed).ceil() as u64,
+            partial: sized.len() < running.len(),
+        })
+    }
+
+    /// Add the current total speed to the bandwidth history
+    ///
+    /// Snapshots are taken several times a second; one entry is kept per
+    /// whole second, and seconds without a snapshot repeat the current total.
+    fn record_bandwidth(&mut self, now: Instant) {
+        let total: f64 = self.speed_samples.values().filter_map(|sample| sample.speed).sum();
+        let seconds = match self.bandwidth_recorded_at {
+            Some(at) => now.duration_since(at).as_secs(),
+            None => 1,
+        };
+        if seconds == 0 {
+            return;
+        }
+
+        for _ in 0..(seconds as usize).min(BANDWIDTH_HISTORY_SECS) {
+            if self.bandwidth_history.len() == BANDWIDTH_HISTORY_SECS {
+                self.bandwidth_history.pop_front();
+            }
+            self.bandwidth_history.push_back(total as u64);
+        }
+        self.bandwidth_recorded_at = Some(match self.bandwidth_recorded_at {
+            Some(at) => at + std::time::Duration::from_secs(seconds),
+            None => now,
+        });
+    }
+
+    /// Current speed of a task in bytes/sec
+    ///
+    /// Uses the sampled speed once available and falls back to the average
+    /// speed since the download started.
+    pub fn task_speed(&self, task: &DownloadTask) -> Option<f64> {
+        self.speed_samples
+            .get(&task.id)
+            .and_then(|sample| sample.speed)
+            .or_else(|| task.speed())
     }
 
     /// Get the currently selected tree item
     pub fn selected_tree_item(&self) -> Option<&FolderTreeItem> {
-        self.tree_items.get(self.tree_selected_index)
+        self.tree_items.get(self.tree_selected_index).map(|row| &row.item)
     }
 
     /// Check if currently viewing the Completed node
@@ -577,6 +919,21 @@ impl TuiState {
         }
     }
 
+    /// Number of downloads a destructive folder action would remove
+    ///
+    /// `DeleteAll` counts the selected folder's queue, `ClearHistory` the
+    /// history; other actions remove nothing.
+    pub fn folder_action_count(&self, action: FolderContextMenuAction) -> usize {
+        match action {
+            FolderContextMenuAction::DeleteAll => self
+                .selected_folder_id_from_tree()
+                .and_then(|folder_id| self.folder_downloads.get(folder_id))
+                .map_or(0, Vec::len),
+            FolderContextMenuAction::ClearHistory => self.history_items.len(),
+            _ => 0,
+        }
+    }
+
     /// Get downloads for the currently selected folder/node
     /// 
     /// - For folder nodes: returns tasks from that folder directly (no filtering)
@@ -590,14 +947,28 @@ impl TuiState {
                 .collect()
         } else {
             // Direct folder access - no filtering needed
-            match self.selected_folder_id_from_tree() {
-                Some(folder_id) => {
+            match self.selected_tree_item() {
+                Some(FolderTreeItem::Folder(folder_id)) => {
                     self.folder_downloads
                         .get(folder_id)
                         .map(|tasks| tasks.iter().collect())
                         .unwrap_or_default()
                 }
-                None => Vec::new(),
+                // Groups list the downloads of every folder below them
+                Some(FolderTreeItem::Group(path)) => {
+                    let mut folders: Vec<(&String, &String)> = self
+                        .folder_names
+                        .iter()
+                        .filter(|(_, name)| folder_tree::is_under(name, path))
+                        .collect();
+                    folders.sort_by(|a, b| a.1.cmp(b.1));
+                    folders
+                        .into_iter()
+                        .filter_map(|(id, _)| self.folder_downloads.get(id))
+                        .flatten()
+                        .collect()
+                }
+                _ => Vec::new(),
             }
         }
     }
@@ -624,6 +995,12 @@ impl TuiState {
         }
     }
 
+    /// Length of the short ID prefix that is unique among queued and history downloads
+    pub fn short_id_len(&self) -> usize {
+        let queued = self.folder_downloads.values().flatten();
+        crate::download::task::short_id_len(queued.chain(&self.history_items).map(|task| task.id))
+    }
+
     /// Get total count of downloads across all folders
     pub fn total_download_count(&self) -> usize {
         self.folder_downloads.values().map(|v| v.len()).sum()
@@ -723,6 +1100,172 @@ impl TuiState {
         }
     }
 
+    /// Open the change dialog for the selected download
+    pub fn open_change_dialog(&mut self, target: ChangeTarget) {
+        self.ui_mode = UiMode::ChangeFolder;
+        self.change_target = target;
+        self.input_buffer.clear();
+        self.folder_completion = None;
+        self.change_folder_index = 0;
+        self.validation_error = None;
+    }
+
+    /// Move the highlight in the change dialog's folder list, wrapping around
+    pub fn move_change_folder_selection(&mut self, down: bool) {
+        let count = self.folder_candidates(&self.input_buffer).len();
+        if count == 0 {
+            return;
+        }
+        let index = self.change_folder_index.min(count - 1);
+        self.change_folder_index = if down { (index + 1) % count } else { (index + count - 1) % count };
+    }
+
+    /// Folder ID of the highlighted entry in the change dialog's folder list
+    pub fn selected_change_folder(&self) -> Option<String> {
+        let candidates = self.folder_candidates(&self.input_buffer);
+        let name = candidates.get(self.change_folder_index.min(candidates.len().saturating_sub(1)))?;
+        self.resolve_folder_input(name)
+    }
+
+    /// Folder names starting with `prefix` (ignoring ASCII case), sorted ignoring case
+    pub fn folder_candidates(&self, prefix: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .folder_names
+            .values()
+            .filter(|name| starts_with_ignore_case(name, prefix))
+            .cloned()
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        names
+    }
+
+    /// Complete the input buffer to the next matching folder name
+    ///
+    /// The first Tab collects the names matching what was typed; further
+    /// presses cycle through them. Typing starts a new completion.
+    pub fn complete_folder_name(&mut self) {
+        let cycling = self
+            .folder_completion
+            .as_ref()
+            .is_some_and(|completion| completion.candidates.get(completion.index) == Some(&self.input_buffer));
+        if cycling {
+            let completion = self.folder_completion.as_mut().unwrap();
+            completion.index = (completion.index + 1) % completion.candidates.len();
+        } else {
+            let candidates = self.folder_candidates(&self.input_buffer);
+            if candidates.is_empty() {
+                self.folder_completion = None;
+                return;
+            }
+            self.folder_completion = Some(FolderCompletion { candidates, index: 0 });
+        }
+        let completion = self.folder_completion.as_ref().unwrap();
+        self.input_buffer = completion.candidates[completion.index].clone();
+        self.change_folder_index = 0;
+    }
+
+    /// Rest of the first folder name that completes the input buffer, shown inline
+    pub fn folder_suggestion(&self) -> Option<String> {
+        if self.input_buffer.is_empty() {
+            return None;
+        }
+        self.folder_candidates(&self.input_buffer)
+            .into_iter()
+            .find(|name| name.len() > self.input_buffer.len())
+            .map(|name| name[self.input_buffer.len()..].to_string())
+    }
+
+    /// Folder ID for a name typed in the change dialog (exact name, then ignoring case, then ID)
+    pub fn resolve_folder_input(&self, input: &str) -> Option<String> {
+        let input = input.trim();
+        self.folder_names
+            .iter()
+            .find(|(_, name)| name.as_str() == input)
+            .or_else(|| self.folder_names.iter().find(|(_, name)| name.eq_ignore_ascii_case(input)))
+            .map(|(id, _)| id.clone())
+            .or_else(|| self.folder_names.contains_key(input).then(|| input.to_string()))
+    }
+
+    /// Select the next (or previous) folder in the tree, wrapping around
+    ///
+    /// Groups and the completed node are skipped, so repeated presses cycle
+    /// through the visible folders only. Returns false if there is no other
+    /// folder to move to.
+    pub fn cycle_folder(&mut self, forward: bool) -> bool {
+        let count = self.tree_items.len();
+        let found = (1..=count)
+            .map(|step| {
+                if forward {
+                    (self.tree_selected_index + step) % count
+                } else {
+                    (self.tree_selected_index + count * step - step) % count
+                }
+            })
+            .find(|&index| matches!(self.tree_items[index].item, FolderTreeItem::Folder(_)));
+        match found {
+            Some(index) if index != self.tree_selected_index => {
+                self.tree_selected_index = index;
+                self.sync_current_folder_from_tree();
+                self.selected_index = 0;
+                self.table_state.borrow_mut().select(Some(0));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Collapse the selected tree node, or select its parent if it is already collapsed or a leaf
+    ///
+    /// Returns false if nothing changed (a top-level leaf).
+    pub fn collapse_tree_node(&mut self) -> bool {
+        let Some(row) = self.tree_items.get(self.tree_selected_index).cloned() else {
+            return false;
+        };
+        if row.has_children && !row.collapsed {
+            self.collapsed_folders.insert(row.path);
+            self.rebuild_tree();
+            return true;
+        }
+        let Some(index) = folder_tree::parent_path(&row.path)
+            .and_then(|parent| self.tree_items.iter().position(|r| r.path == parent))
+        else {
+            return false;
+        };
+        self.tree_selected_index = index;
+        self.selected_index = 0;
+        self.table_state.borrow_mut().select(Some(0));
+        true
+    }
+
+    /// Expand the selected tree node; returns false if it was not collapsed
+    pub fn expand_tree_node(&mut self) -> bool {
+        let Some(row) = self.tree_items.get(self.tree_selected_index) else {
+            return false;
+        };
+        if !row.collapsed {
+            return false;
+        }
+        let path = row.path.clone();
+        self.collapsed_folders.remove(&path);
+        self.rebuild_tree();
+        true
+    }
+
+    /// Expand or collapse the selected tree node if it has children
+    pub fn toggle_tree_node(&mut self) {
+        let Some(row) = self.tree_items.get(self.tree_selected_index) else {
+            return;
+        };
+        if !row.has_children {
+            return;
+        }
+        let path = row.path.clone();
+        if !self.collapsed_folders.remove(&path) {
+            self.collapsed_folders.insert(path);
+        }
+        self.rebuild_tree();
+    }
+
     /// Sync current_folder_id with tree selection (if a folder is selected)
     pub fn sync_current_folder_from_tree(&mut self) {
         if let Some(FolderTreeItem::Folder(folder_id)) = self.selected_tree_item() {
@@ -781,6 +1324,7 @@ impl TuiState {
         if folder_count > 0 {
             self.settings_folder_index = (self.settings_folder_index + 1).min(folder_count - 1);
         }
+        self.free_space_cache.replace(None);
     }
 
     /// Move folder selection up in settings screen
@@ -788,6 +1332,20 @@ impl TuiState {
         if self.settings_folder_index > 0 {
             self.settings_folder_index -= 1;
         }
+        self.free_space_cache.replace(None);
+    }
+
+    /// Free space on the volume of a folder's save path (cached until the selection changes)
+    pub fn folder_free_space(&self, save_path: &std::path::Path) -> Option<u64> {
+        let mut cache = self.free_space_cache.borrow_mut();
+        match cache.as_ref() {
+            Some((path, bytes)) if path == save_path => *bytes,
+            _ => {
+                let bytes = crate::util::fs::available_space(save_path);
+                *cache = Some((save_path.to_path_buf(), bytes));
+                bytes
+            }
+        }
     }
 
     /// Reset settings screen state
@@ -795,6 +1353,7 @@ impl TuiState {
         self.selected_folder_id = None;
         self.settings_edit_field = None;
         self.settings_folder_index = 0;
+        self.free_space_cache.replace(None);
         self.settings_field_index = 0;
         self.settings_section = SettingsSection::Application;
         self.app_settings_field_index = 0;
@@ -962,6 +1521,32 @@ impl TuiState {
         self.needs_redraw = true;
     }
 
+    /// Show a failed action in the status bar for `STATUS_MESSAGE_DURATION`
+    ///
+    /// `key` is a translation taking the error as `$error`; the error is
+    /// also logged.
+    pub fn show_error(&mut self, key: &str, error: impl std::fmt::Display) {
+        let error = error.to_string();
+        let args = fluent::fluent_args! {
+            "error" => error.as_str(),
+        };
+        let text = self.t_with_args(key, Some(&args));
+        tracing::warn!("{}", text);
+        self.status_message = Some(StatusMessage { text, expires_at: Instant::now() + STATUS_MESSAGE_DURATION });
+        self.mark_dirty();
+    }
+
+    /// Drop the status message once it has expired
+    ///
+    /// Called on every tick; only marks the UI dirty when a message is
+    /// actually removed, so an idle screen is not redrawn.
+    pub fn expire_status_message(&mut self, now: Instant) {
+        if self.status_message.as_ref().is_some_and(|message| now >= message.expires_at) {
+            self.status_message = None;
+            self.mark_dirty();
+        }
+    }
+
     /// Check if UI needs redraw
     pub fn needs_redraw(&self) -> bool {
         self.needs_redraw
@@ -972,3 +1557,225 @@ impl TuiState {
         self.needs_redraw = false;
     }
 }
+
+/// True if `text` starts with `prefix`, ignoring ASCII case
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len()).is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::Config;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn downloading_task(index: usize, downloaded: u64) -> DownloadTask {
+        let mut task = DownloadTask::new(
+            format!("https://example.com/file{}.bin", index),
+            PathBuf::from("/tmp"),
+        );
+        task.folder_id = format!("folder{}", index % 5);
+        task.status = DownloadStatus::Downloading;
+        task.size = Some(100 * 1024 * 1024);
+        task.downloaded = downloaded;
+        task
+    }
+
+    #[test]
+    fn test_speed_uses_latest_snapshot_only() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        let mut task = downloading_task(0, 0);
+        let start = Instant::now();
+
+        state.sample_speeds(std::slice::from_ref(&task), start);
+        assert!(state.speed_samples[&task.id].speed.is_none());
+
+        // Intermediate progress between refreshes is never observed; only the
+        // delta between the two snapshots counts
+        task.downloaded = 1000;
+        state.sample_speeds(std::slice::from_ref(&task), start + Duration::from_secs(1));
+        assert_eq!(state.task_speed(&task), Some(1000.0));
+
+        task.downloaded = 4000;
+        state.sample_speeds(std::slice::from_ref(&task), start + Duration::from_secs(2));
+        assert_eq!(state.task_speed(&task), Some(2000.0));
+
+        // Finished tasks are dropped
+        task.status = DownloadStatus::Completed;
+        state.sample_speeds(std::slice::from_ref(&task), start + Duration::from_secs(3));
+        assert!(state.speed_samples.is_empty());
+    }
+
+    #[test]
+    fn test_bandwidth_history_keeps_one_minute() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        let mut task = downloading_task(0, 0);
+        let start = Instant::now();
+
+        state.sample_speeds(std::slice::from_ref(&task), start);
+        state.record_bandwidth(start);
+        assert_eq!(state.bandwidth_history, [0]);
+
+        // Snapshots within the same second don't add entries
+        task.downloaded = 500;
+        state.sample_speeds(std::slice::from_ref(&task), start + Duration::from_millis(500));
+        state.record_bandwidth(start + Duration::from_millis(500));
+        assert_eq!(state.bandwidth_history.len(), 1);
+
+        // A gap of three seconds fills three entries with the current total
+        task.downloaded = 2000;
+        let later = start + Duration::from_millis(3500);
+        state.sample_speeds(std::slice::from_ref(&task), later);
+        state.record_bandwidth(later);
+        assert_eq!(state.bandwidth_history, [0, 750, 750, 750]);
+
+        state.record_bandwidth(later + Duration::from_secs(120));
+        assert_eq!(state.bandwidth_history.len(), BANDWIDTH_HISTORY_SECS);
+    }
+
+    #[test]
+    fn test_queue_eta_counts_known_sizes() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        let start = Instant::now();
+        let mut tasks = vec![downloading_task(0, 0), downloading_task(1, 0)];
+        state.sample_speeds(&tasks, start);
+        // No speed measured yet
+        assert_eq!(state.estimate_queue_eta(&tasks), None);
+
+        // 1 MiB/s each, 2 x (100 MiB - 1 MiB) left
+        for task in &mut tasks {
+            task.downloaded = 1024 * 1024;
+        }
+        state.sample_speeds(&tasks, start + Duration::from_secs(1));
+        assert_eq!(state.estimate_queue_eta(&tasks), Some(QueueEta { seconds: 99, partial: false }));
+
+        // An unknown size is left out of both the speed and the remaining bytes:
+        // 99 MiB left at 1 MiB/s
+        tasks[1].size = None;
+        assert_eq!(state.estimate_queue_eta(&tasks), Some(QueueEta { seconds: 99, partial: true }));
+
+        tasks[0].size = None;
+        assert_eq!(state.estimate_queue_eta(&tasks), None);
+    }
+
+    #[test]
+    fn test_cycle_folder_skips_groups_and_completed_node() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        for (id, name) in [("a", "alpha"), ("m", "media/images"), ("z", "zeta")] {
+            state.folder_names.insert(id.to_string(), name.to_string());
+        }
+        state.rebuild_tree();
+        // alpha, media (group), media/images, zeta, completed
+        state.tree_selected_index = 0;
+
+        assert!(state.cycle_folder(true));
+        assert_eq!(state.current_folder_id, "m");
+        assert!(state.cycle_folder(true));
+        assert_eq!(state.current_folder_id, "z");
+        // Wraps past the completed node back to the first folder
+        assert!(state.cycle_folder(true));
+        assert_eq!(state.current_folder_id, "a");
+        assert!(state.cycle_folder(false));
+        assert_eq!(state.current_folder_id, "z");
+
+        // From the completed node, the next folder is the first one
+        state.tree_selected_index = state.tree_items.len() - 1;
+        assert!(state.cycle_folder(true));
+        assert_eq!(state.current_folder_id, "a");
+    }
+
+    #[test]
+    fn test_complete_folder_name_cycles_matches() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        for (id, name) in [("1", "images"), ("2", "Imports"), ("3", "videos"), ("4", "images/raw")] {
+            state.folder_names.insert(id.to_string(), name.to_string());
+        }
+        state.open_change_dialog(ChangeTarget::Folder);
+
+        state.input_buffer = "im".to_string();
+        assert_eq!(state.folder_suggestion().as_deref(), Some("ages"));
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "images");
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "images/raw");
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "Imports");
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "images");
+
+        // Editing the buffer starts over from the new text
+        state.input_buffer = "v".to_string();
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "videos");
+        assert_eq!(state.folder_suggestion(), None);
+
+        state.input_buffer = "zzz".to_string();
+        state.complete_folder_name();
+        assert_eq!(state.input_buffer, "zzz");
+
+        // The list shows every match of the typed text; Enter takes the highlighted one
+        state.input_buffer = "im".to_string();
+        state.change_folder_index = 0;
+        assert_eq!(state.selected_change_folder().as_deref(), Some("1"));
+        state.move_change_folder_selection(false);
+        assert_eq!(state.selected_change_folder().as_deref(), Some("2"));
+        state.move_change_folder_selection(true);
+        state.move_change_folder_selection(true);
+        assert_eq!(state.selected_change_folder().as_deref(), Some("4"));
+        state.input_buffer = "music".to_string();
+        assert_eq!(state.selected_change_folder(), None);
+
+        assert_eq!(state.resolve_folder_input("IMAGES").as_deref(), Some("1"));
+        assert_eq!(state.resolve_folder_input("3").as_deref(), Some("3"));
+        assert_eq!(state.resolve_folder_input("music"), None);
+    }
+
+    #[test]
+    fn test_status_message_expires() {
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+        state.show_error("toast-change-path-failed", "permission denied");
+        let message = state.status_message.clone().unwrap();
+        assert!(message.text.contains("permission denied"));
+
+        state.needs_redraw = false;
+        state.expire_status_message(message.expires_at - Duration::from_secs(1));
+        assert!(state.status_message.is_some());
+        assert!(!state.needs_redraw());
+
+        state.expire_status_message(message.expires_at);
+        assert!(state.status_message.is_none());
+        assert!(state.needs_redraw());
+    }
+
+    #[tokio::test]
+    async fn test_update_downloads_stays_bounded_with_many_tasks() {
+        let manager = DownloadManager::new();
+        let mut state = TuiState::new(AppState::new(Config::default(), "en"), &Default::default());
+
+        let mut tasks: Vec<DownloadTask> = (0..50).map(|i| downloading_task(i, 0)).collect();
+        for task in &tasks {
+            manager.add_download(task.clone()).await;
+        }
+
+        let mut slowest = Duration::ZERO;
+        for round in 1..=20u64 {
+            // Simulate progress on every task between refreshes
+            for task in &mut tasks {
+                task.downloaded = round * 64 * 1024;
+                manager.remove_download(task.id).await;
+                manager.add_download(task.clone()).await;
+            }
+
+            let started = Instant::now();
+            state.update_downloads(&manager).await;
+            slowest = slowest.max(started.elapsed());
+        }
+
+        assert!(slowest < Duration::from_millis(250), "update took {:?}", slowest);
+        let shown: usize = state.folder_downloads.values().map(Vec::len).sum();
+        assert_eq!(shown, 50);
+        assert_eq!(state.speed_samples.len(), 50);
+        assert!(tasks.iter().all(|task| state.speed_samples[&task.id].speed.is_some()));
+    }
+}
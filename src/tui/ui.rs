@@ -1,13 +1,17 @@
 use super::app::TuiApp;
-use super::state::{DetailsPosition, FocusPane, FolderTreeItem, UiMode};
+use super::state::{ChangeTarget, DetailsPosition, FocusPane, FolderTreeItem, UiMode};
 use crate::download::task::{DownloadStatus, LogLevel};
 use crate::download::http_errors::HttpErrorInfo;
+use crate::util::schedule::ScheduleMode;
 use fluent::fluent_args;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, Tabs, Wrap},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table,
+        Tabs, Wrap,
+    },
     Frame,
 };
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
@@ -21,7 +25,8 @@ pub fn render(app: &TuiApp, f: &mut Frame) {
         app.state.ui_mode,
         UiMode::Normal | UiMode::AddDownload | UiMode::DownloadPreview |
         UiMode::Search | UiMode::ChangeFolder | UiMode::SwitchFolder |
-        UiMode::ConfirmDelete | UiMode::ContextMenu | UiMode::Help
+        UiMode::ConfirmDelete | UiMode::ContextMenu | UiMode::Help | UiMode::Stats |
+        UiMode::ScriptErrors
     ) || (matches!(app.state.ui_mode, UiMode::EditingField) && !app.state.is_editing_app_setting);
 
     // Main layout: content area + status bar
@@ -35,7 +40,9 @@ pub fn render(app: &TuiApp, f: &mut Frame) {
 
     // Render main area (overlays handled separately)
     match app.state.ui_mode {
-        UiMode::Settings | UiMode::FolderEdit => render_settings(app, f, main_chunks[0]),
+        UiMode::Settings | UiMode::FolderEdit | UiMode::ConfirmConcurrencyAdjustment => {
+            render_settings(app, f, main_chunks[0])
+        }
         UiMode::EditingField if app.state.is_editing_app_setting => render_settings(app, f, main_chunks[0]),
         _ if is_main_screen => render_three_pane_layout(app, f, main_chunks[0]),
         _ => render_three_pane_layout(app, f, main_chunks[0]),
@@ -47,6 +54,8 @@ pub fn render(app: &TuiApp, f: &mut Frame) {
     // Render input dialogs (overlays)
     match app.state.ui_mode {
         UiMode::Help => render_help(app, f, size),
+        UiMode::Stats => render_stats(app, f, size),
+        UiMode::ScriptErrors => render_script_errors(app, f, size),
         UiMode::AddDownload => render_add_download_dialog(app, f, size),
         UiMode::EditingField => render_input_dialog(app, f, size),
         UiMode::DownloadPreview => render_download_preview_dialog(app, f, size),
@@ -54,6 +63,9 @@ pub fn render(app: &TuiApp, f: &mut Frame) {
         UiMode::ChangeFolder => render_change_folder_dialog(app, f, size),
         UiMode::SwitchFolder => render_switch_folder_dialog(app, f, size),
         UiMode::ConfirmDelete => render_confirm_delete_dialog(app, f, size),
+        UiMode::ConfirmConcurrencyAdjustment => render_confirm_concurrency_dialog(app, f, size),
+        UiMode::ConfirmCircuitReset => render_confirm_circuit_reset_dialog(app, f, size),
+        UiMode::ConfirmLargeDownload => render_confirm_large_download_dialog(app, f, size),
         UiMode::ContextMenu => render_context_menu(app, f, size),
         UiMode::FolderContextMenu => render_folder_context_menu(app, f, size),
         _ => {}
@@ -143,18 +155,22 @@ fn render_folder_tree(app: &TuiApp, f: &mut Frame, area: Rect) {
 
     // Build list items from tree_items
     let completed_label = t("tree-completed-node");
-    // Pre-compute folder display names for tree items
-    let folder_tree_names: Vec<String> = app.state.tree_items.iter().map(|item| {
-        match item {
-            FolderTreeItem::Folder(id) => app.state.folder_display_name(id),
-            FolderTreeItem::CompletedNode => completed_label.clone(),
-        }
-    }).collect();
-
-    let items: Vec<ListItem> = app.state.tree_items.iter().enumerate().map(|(i, item)| {
-        let (icon, name) = match item {
-            FolderTreeItem::Folder(_) => ("📁", folder_tree_names[i].as_str()),
-            FolderTreeItem::CompletedNode => ("📋", folder_tree_names[i].as_str()),
+    // Reserve a column for expand/collapse markers only when some folder is nested
+    let nested = app.state.tree_items.iter().any(|row| row.has_children);
+    // Inner width minus borders, highlight symbol and leading space
+    let label_area_width = (area.width as usize).saturating_sub(5);
+
+    let items: Vec<ListItem> = app.state.tree_items.iter().enumerate().map(|(i, row)| {
+        let (icon, name) = match row.item {
+            FolderTreeItem::Folder(_) => ("📁", row.label.as_str()),
+            FolderTreeItem::Group(_) => ("📂", row.label.as_str()),
+            FolderTreeItem::CompletedNode => ("📋", completed_label.as_str()),
+        };
+        let marker = match (nested, row.has_children, row.collapsed) {
+            (false, _, _) => "",
+            (true, true, true) => "▸ ",
+            (true, true, false) => "▾ ",
+            (true, false, _) => "  ",
         };
 
         let style = if i == app.state.tree_selected_index {
@@ -165,7 +181,11 @@ fn render_folder_tree(app: &TuiApp, f: &mut Frame, area: Rect) {
             Style::default().fg(Color::Rgb(200, 200, 210))
         };
 
-        ListItem::new(format!(" {} {}", icon, name)).style(style)
+        let prefix = format!("{}{}{} ", "  ".repeat(row.depth), marker, icon);
+        let counts = folder_count_suffix(&app.state.tree_item_counts(&row.item));
+        let label = tree_label(name, &counts, label_area_width.saturating_sub(prefix.width()));
+
+        ListItem::new(format!(" {}{}", prefix, label)).style(style)
     }).collect();
 
     let border_style = if is_focused {
@@ -223,31 +243,81 @@ fn render_folder_tree(app: &TuiApp, f: &mut Frame, area: Rect) {
 }
 
 /// Render download list table
+/// Rows taken by the bandwidth graph (title line plus two rows of bars)
+const BANDWIDTH_GRAPH_HEIGHT: u16 = 3;
+
+/// Render total throughput over the last minute, one bar per second (newest on the right)
+fn render_bandwidth_graph(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let history = &app.state.bandwidth_history;
+    let current = history.back().copied().unwrap_or(0);
+    let peak = history.iter().copied().max().unwrap_or(0);
+    let current = app.state.format.speed(current as f64);
+    let peak = app.state.format.speed(peak as f64);
+    let args = fluent_args! {
+        "current" => current.as_str(),
+        "peak" => peak.as_str(),
+    };
+
+    // Show the most recent samples that fit
+    let data: Vec<u64> = history.iter().copied().collect();
+    let data = &data[data.len().saturating_sub(area.width as usize)..];
+
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(app.state.t_with_args("bandwidth-graph-title", Some(&args))),
+        )
+        .data(data)
+        .style(Style::default().fg(Color::Green));
+    f.render_widget(sparkline, area);
+}
+
 fn render_download_list(app: &TuiApp, f: &mut Frame, area: Rect) {
     let t = |key: &str| app.state.t(key);
+
+    // Optional bandwidth graph below the list
+    let show_graph = app.state.app_state.config.try_read().is_ok_and(|config| config.general.show_bandwidth_graph);
+    let area = if show_graph && area.height > BANDWIDTH_GRAPH_HEIGHT * 2 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(BANDWIDTH_GRAPH_HEIGHT)])
+            .split(area);
+        render_bandwidth_graph(app, f, chunks[1]);
+        chunks[0]
+    } else {
+        area
+    };
     let is_focused = app.state.focus_pane == FocusPane::DownloadList;
     let is_viewing_history = app.state.is_viewing_completed_node();
 
     let filtered = app.state.filtered_downloads();
     let count = filtered.len();
 
+    // Optional ID column with short prefixes usable in CLI commands
+    let show_ids = app.state.app_state.config.try_read().is_ok_and(|config| config.general.show_short_ids);
+    let id_len = if show_ids { app.state.short_id_len() } else { 0 };
+
     // Create table header with inverted colors for better visibility
-    let header = Row::new(vec![
-        Cell::from(t("column-sel")),
-        Cell::from(t("column-status")),
+    let mut header_cells = vec![Cell::from(t("column-sel")), Cell::from(t("column-status"))];
+    if show_ids {
+        header_cells.push(Cell::from(t("column-id")));
+    }
+    header_cells.extend([
         Cell::from(t("column-filename")),
         Cell::from(t("column-size")),
         Cell::from(t("column-progress")),
         Cell::from(t("column-speed")),
         Cell::from(t("column-eta")),
-    ])
-    .style(
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Rgb(100, 100, 120))
-            .add_modifier(Modifier::BOLD),
-    )
-    .height(1);
+    ]);
+    let header = Row::new(header_cells)
+        .style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(100, 100, 120))
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1);
 
     // Create table rows
     // Note: ratatui's Table handles viewport rendering internally,
@@ -255,12 +325,12 @@ fn render_download_list(app: &TuiApp, f: &mut Frame, area: Rect) {
     let rows: Vec<Row> = filtered
         .iter()
         .map(|task| {
-            let status_icon = status_icon(app, &task.status);
+            let status_icon = task_status_icon(app, task);
             // Use red for failed items in history view
             let status_color = if is_viewing_history && task.status == DownloadStatus::Error {
                 Color::Red
             } else {
-                status_color(&task.status)
+                task_status_color(task)
             };
 
             // Selection indicator
@@ -279,36 +349,50 @@ fn render_download_list(app: &TuiApp, f: &mut Frame, area: Rect) {
             let progress_text = format_progress_with_bar(task.downloaded, task.size);
 
             // Calculate speed display
-            let speed_text = task.speed()
-                .map(|s| format_speed(s))
+            let speed_text = app.state.task_speed(task)
+                .map(|s| app.state.format.speed(s))
                 .unwrap_or_else(|| "-".to_string());
             
             // Calculate ETA display
             let eta_text = task.eta_display()
                 .unwrap_or_else(|| "-".to_string());
 
-            Row::new(vec![
+            let mut cells = vec![
                 Cell::from(sel_indicator).style(Style::default().fg(sel_color)),
                 Cell::from(status_icon).style(Style::default().fg(status_color)),
+            ];
+            if show_ids {
+                cells.push(
+                    Cell::from(crate::download::task::short_id(task.id, id_len))
+                        .style(Style::default().fg(Color::DarkGray)),
+                );
+            }
+            cells.extend([
                 Cell::from(truncate_filename(&task.filename, 50)),
-                Cell::from(format_size(total_size)),
+                Cell::from(app.state.format.size(total_size)),
                 Cell::from(progress_text),
                 Cell::from(speed_text),
                 Cell::from(eta_text),
-            ])
+            ]);
+            Row::new(cells)
         })
         .collect();
 
     // Create table widget
-    let widths = [
+    let mut widths = vec![
         Constraint::Length(5),   // Selection column
         Constraint::Length(15),  // Status (wider for emoji)
+    ];
+    if show_ids {
+        widths.push(Constraint::Length(id_len as u16)); // Short ID
+    }
+    widths.extend([
         Constraint::Min(20),     // Filename
         Constraint::Length(10),  // Size
         Constraint::Length(16),  // Progress (with bar)
         Constraint::Length(10),  // Speed
         Constraint::Length(10),  // ETA
-    ];
+    ]);
 
     // Build title based on context
     let selection_count = app.state.selected_downloads.len();
@@ -437,8 +521,8 @@ fn render_task_info(app: &TuiApp, task: &crate::download::task::DownloadTask, f:
                 Style::default().add_modifier(Modifier::BOLD)
             ),
             Span::styled(
-                status_icon(app, &task.status),
-                Style::default().fg(status_color(&task.status)).add_modifier(Modifier::BOLD),
+                task_status_icon(app, task),
+                Style::default().fg(task_status_color(task)).add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(""),
@@ -449,6 +533,31 @@ fn render_task_info(app: &TuiApp, task: &crate::download::task::DownloadTask, f:
             ),
         ]),
         Line::from(Span::raw(&task.url)),
+    ];
+
+    // Show where the URL actually resolved to after redirects
+    if let Some(ref resolved_url) = task.resolved_url {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-resolved-url")),
+                Style::default().add_modifier(Modifier::BOLD)
+            ),
+        ]));
+        details.push(Line::from(Span::styled(resolved_url.as_str(), Style::default().fg(Color::DarkGray))));
+    }
+
+    // Show which folder a routing rule moved this download out of
+    if let Some(ref routed_from) = task.routed_from {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-routed")),
+                Style::default().add_modifier(Modifier::BOLD)
+            ),
+            Span::raw(format!("{} → {}", routed_from, task.folder_id)),
+        ]));
+    }
+
+    details.extend([
         Line::from(""),
         Line::from(vec![
             Span::styled(
@@ -463,22 +572,110 @@ fn render_task_info(app: &TuiApp, task: &crate::download::task::DownloadTask, f:
                 format!("{} ", app.state.t("details-label-size")),
                 Style::default().add_modifier(Modifier::BOLD)
             ),
-            Span::raw(format_size(total_size)),
+            Span::raw(app.state.format.size(total_size)),
         ]),
         Line::from(vec![
             Span::styled(
                 format!("{} ", app.state.t("details-label-downloaded")),
                 Style::default().add_modifier(Modifier::BOLD)
             ),
-            Span::raw(format_size(task.downloaded)),
+            Span::raw(app.state.format.size(task.downloaded)),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::styled("Progress: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{:.1}%", progress)),
+            Span::raw(app.state.format.percent(progress)),
         ]),
         Line::from(Span::raw(format_progress_bar(task.downloaded, task.size, 30))),
-    ];
+    ]);
+
+    // Completion time of finished downloads (local time, UI language's date format)
+    if let Some(completed_at) = task.completed_at {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-completed-at")),
+                Style::default().add_modifier(Modifier::BOLD)
+            ),
+            Span::raw(app.state.format.datetime(&completed_at.with_timezone(&chrono::Local))),
+        ]));
+    }
+
+    // Time spent so far (total time once completed)
+    if let Some(elapsed) = task.elapsed_secs(chrono::Utc::now()) {
+        let label = if task.completed_at.is_some() { "details-label-total-time" } else { "details-label-elapsed" };
+        details.push(Line::from(vec![
+            Span::styled(format!("{} ", app.state.t(label)), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(crate::download::task::format_duration(elapsed as u64)),
+        ]));
+    }
+
+    // Average and peak speed: a steady download has the two close together, a bursty one far apart
+    if let Some(speed) = task.average_speed() {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-average-speed")),
+                Style::default().add_modifier(Modifier::BOLD)
+            ),
+            Span::raw(app.state.format.speed(speed)),
+        ]));
+    }
+    if let Some(peak) = task.speed_stats.peak() {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-peak-speed")),
+                Style::default().add_modifier(Modifier::BOLD)
+            ),
+            Span::raw(app.state.format.speed(peak)),
+        ]));
+    }
+
+    // Held by download.confirm_over_bytes until the size is confirmed
+    if task.awaiting_confirmation {
+        let args = fluent_args! {
+            "size" => app.state.format.size(task.size.unwrap_or(0)),
+        };
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-confirm")),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            ),
+            Span::styled(
+                app.state.t_with_args("details-confirm-size", Some(&args)),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]));
+    }
+
+    // Reason a folder quota kept the download from starting
+    if let Some(ref reason) = task.held_reason {
+        details.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", app.state.t("details-label-held")),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            ),
+            Span::styled(reason.clone(), Style::default().fg(Color::Yellow)),
+        ]));
+    }
+
+    // Script handlers that ran for this download and what they did
+    if !task.script_runs.is_empty() {
+        details.push(Line::from(""));
+        details.push(Line::from(Span::styled(
+            format!("{} ", app.state.t("details-label-scripts")),
+            Style::default().add_modifier(Modifier::BOLD)
+        )));
+        for run in &task.script_runs {
+            let (effect, color) = match run.error {
+                Some(ref error) => (format!("{} ({})", app.state.t("details-script-failed"), error), Color::Red),
+                None if run.modified => (app.state.t("details-script-modified"), Color::Green),
+                None => (app.state.t("details-script-unchanged"), Color::DarkGray),
+            };
+            details.push(Line::from(vec![
+                Span::raw(format!("  {} {} ", run.hook, run.script)),
+                Span::styled(effect, Style::default().fg(color)),
+            ]));
+        }
+    }
 
     // Add error message if present - enhanced display with visual prominence
     if let Some(ref error) = task.error_message {
@@ -555,7 +752,7 @@ fn render_task_info(app: &TuiApp, task: &crate::download::task::DownloadTask, f:
 }
 
 /// Render task logs section
-fn render_task_logs(_app: &TuiApp, task: &crate::download::task::DownloadTask, f: &mut Frame, area: Rect, border_style: Style) {
+fn render_task_logs(app: &TuiApp, task: &crate::download::task::DownloadTask, f: &mut Frame, area: Rect, border_style: Style) {
     let mut log_lines = Vec::new();
 
     if task.logs.is_empty() {
@@ -569,7 +766,7 @@ fn render_task_logs(_app: &TuiApp, task: &crate::download::task::DownloadTask, f
         let start_idx = task.logs.len().saturating_sub(max_logs);
 
         for log in &task.logs[start_idx..] {
-            let timestamp_str = log.timestamp.format("%H:%M:%S").to_string();
+            let timestamp_str = app.state.format.time(&log.timestamp);
 
             let (level_str, level_color) = match log.level {
                 LogLevel::Info => ("INFO ", Color::White),
@@ -615,12 +812,12 @@ fn render_status_bar(app: &TuiApp, f: &mut Frame, area: Rect) {
     let t = |key: &str| app.state.t(key);
     let t_args = |key: &str, args: Option<&fluent_bundle::FluentArgs>| app.state.t_with_args(key, args);
 
-    let (left_content, right_content) = match app.state.ui_mode {
+    let (mut left_content, right_content) = match app.state.ui_mode {
         UiMode::Normal => {
             // Quick actions for main screen
             let undo_hint = if !app.state.delete_history.is_empty() {
                 let args = fluent_args! {
-                    "count" => app.state.delete_history.len(),
+                    "count" => crate::download::trash::group_count(&app.state.delete_history),
                 };
                 format!(" | {}", t_args("status-normal-undo", Some(&args)))
             } else {
@@ -642,12 +839,24 @@ fn render_status_bar(app: &TuiApp, f: &mut Frame, area: Rect) {
             let version_args = fluent_args! {
                 "version" => env!("CARGO_PKG_VERSION"),
             };
-            let right = t_args("app-version", Some(&version_args));
+            let version = t_args("app-version", Some(&version_args));
+            // Overall ETA of the running downloads, left of the version
+            let right = match app.state.queue_eta {
+                Some(eta) => {
+                    let duration = crate::download::task::format_duration(eta.seconds);
+                    let args = fluent_args! {
+                        "eta" => duration.as_str(),
+                    };
+                    let key = if eta.partial { "status-queue-eta-partial" } else { "status-queue-eta" };
+                    format!("{} | {}", t_args(key, Some(&args)), version)
+                }
+                None => version,
+            };
             (left, right)
         }
         // For other screens, show hints on left, nothing on right
         UiMode::AddDownload => {
-            (t("status-hint-cancel"), String::new())
+            (t("status-hint-add-download"), String::new())
         }
         UiMode::EditingField => {
             (t("status-hint-cancel"), String::new())
@@ -667,13 +876,22 @@ fn render_status_bar(app: &TuiApp, f: &mut Frame, area: Rect) {
         UiMode::Help => {
             (t("status-hint-close"), String::new())
         }
+        UiMode::Stats => {
+            (t("status-hint-stats"), String::new())
+        }
+        UiMode::ScriptErrors => {
+            (t("status-hint-script-errors"), String::new())
+        }
         UiMode::Settings => {
             (t("status-hint-settings"), String::new())
         }
         UiMode::FolderEdit => {
             (t("status-hint-folder-edit"), String::new())
         }
-        UiMode::ConfirmDelete => {
+        UiMode::ConfirmDelete
+        | UiMode::ConfirmConcurrencyAdjustment
+        | UiMode::ConfirmCircuitReset
+        | UiMode::ConfirmLargeDownload => {
             (t("status-hint-confirm-yn"), String::new())
         }
         UiMode::ContextMenu => {
@@ -684,14 +902,73 @@ fn render_status_bar(app: &TuiApp, f: &mut Frame, area: Rect) {
         }
     };
 
+    // Script errors raised since the viewer was last opened
+    let new_script_errors = app
+        .state
+        .app_state
+        .script_errors
+        .total()
+        .saturating_sub(app.state.script_errors_seen);
+    let mut alert_content = if app.state.ui_mode == UiMode::Normal && new_script_errors > 0 {
+        let args = fluent_args! {
+            "count" => new_script_errors,
+        };
+        format!("{} ", t_args("status-script-errors", Some(&args)))
+    } else {
+        String::new()
+    };
+    // Ignored configuration; details are in the log and `ggg config validate`
+    if !app.state.config_problems.is_empty() {
+        let args = fluent_args! {
+            "count" => app.state.config_problems.len(),
+        };
+        alert_content.push_str(&format!("{} ", t_args("status-config-problems", Some(&args))));
+    }
+    // Shown for the whole session while certificate checks are off
+    if app.manager.http_client().accepts_invalid_certs() {
+        alert_content.push_str(&format!("{} ", t("status-insecure-tls")));
+    }
+
+    // Active [schedule] quiet window
+    let schedule_content = match app.state.app_state.schedule.get() {
+        _ if app.state.ui_mode != UiMode::Normal => String::new(),
+        ScheduleMode::Inactive => String::new(),
+        ScheduleMode::Paused { until } => {
+            let until = until.format("%H:%M").to_string();
+            let args = fluent_args! {
+                "until" => until.as_str(),
+            };
+            format!("{} ", t_args("status-schedule-paused", Some(&args)))
+        }
+        ScheduleMode::Limited { bytes_per_sec, until } => {
+            let until = until.format("%H:%M").to_string();
+            let speed = app.state.format.speed(bytes_per_sec as f64);
+            let args = fluent_args! {
+                "speed" => speed.as_str(),
+                "until" => until.as_str(),
+            };
+            format!("{} ", t_args("status-schedule-limited", Some(&args)))
+        }
+    };
+
+    // A recent failure replaces the hints until it expires
+    let mut left_style = Style::default().fg(Color::Cyan);
+    if let Some(ref message) = app.state.status_message {
+        left_content = message.text.clone();
+        left_style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    }
+
     // Create a single line without border
-    let padding_width = area.width.saturating_sub(
-        (left_content.chars().count() + right_content.chars().count() + 2) as u16
+    let padding_width = status_bar_padding(
+        area.width,
+        &[left_content.as_str(), &schedule_content, &alert_content, &right_content],
     );
 
     let status_line = Line::from(vec![
-        Span::styled(left_content, Style::default().fg(Color::Cyan)),
+        Span::styled(left_content, left_style),
         Span::raw(" ".repeat(padding_width as usize)),
+        Span::styled(schedule_content, Style::default().fg(Color::Magenta)),
+        Span::styled(alert_content, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
         Span::styled(right_content, Style::default().fg(Color::Yellow)),
     ]);
 
@@ -699,6 +976,38 @@ fn render_status_bar(app: &TuiApp, f: &mut Frame, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
+/// Spaces between the left part of the status bar and the right-aligned parts
+///
+/// Measured in display columns so CJK text and emoji (2 cells each) don't
+/// push the right side past the edge.
+fn status_bar_padding(total_width: u16, parts: &[&str]) -> u16 {
+    let used: usize = parts.iter().map(|part| part.width()).sum::<usize>() + 2;
+    total_width.saturating_sub(used.min(u16::MAX as usize) as u16)
+}
+
+/// Click regions of tabs rendered by `Tabs` starting at (`x`, `y`)
+///
+/// Each tab spans its title's display width plus one padding cell per side,
+/// and tabs are separated by the 3-cell divider " │ ".
+fn tab_hit_regions(x: u16, y: u16, titles: &[&str]) -> Vec<(usize, Rect)> {
+    let mut current_x = x;
+    titles
+        .iter()
+        .enumerate()
+        .map(|(idx, title)| {
+            let tab_width = title.width() as u16 + 2;
+            let rect = Rect {
+                x: current_x,
+                y,
+                width: tab_width,
+                height: 1,
+            };
+            current_x += tab_width + 3;
+            (idx, rect)
+        })
+        .collect()
+}
+
 /// Render help screen overlay as centered popup
 fn render_help(app: &TuiApp, f: &mut Frame, area: Rect) {
     let t = |key: &str| app.state.t(key);
@@ -732,6 +1041,7 @@ fn render_help(app: &TuiApp, f: &mut Frame, area: Rect) {
         Line::from(format!("  {}", t("help-key-m"))),
         Line::from(format!("  {}", t("help-key-e"))),
         Line::from(format!("  {}", t("help-key-r"))),
+        Line::from(format!("  {}", t("help-key-ctrl-r"))),
         Line::from(format!("  {}", t("help-key-shift-s"))),
         Line::from(format!("  {}", t("help-key-shift-p"))),
         Line::from(""),
@@ -751,6 +1061,7 @@ fn render_help(app: &TuiApp, f: &mut Frame, area: Rect) {
         Line::from(Span::styled(t("help-section-panes"), Style::default().add_modifier(Modifier::BOLD))),
         Line::from(format!("  {}", t("help-key-prev-pane"))),
         Line::from(format!("  {}", t("help-key-next-pane"))),
+        Line::from(format!("  {}", t("help-key-brackets"))),
         Line::from(""),
         Line::from(Span::styled(t("help-section-search"), Style::default().add_modifier(Modifier::BOLD))),
         Line::from(format!("  {}", t("help-key-slash"))),
@@ -759,7 +1070,10 @@ fn render_help(app: &TuiApp, f: &mut Frame, area: Rect) {
         Line::from(Span::styled(t("help-section-ui"), Style::default().add_modifier(Modifier::BOLD))),
         Line::from(format!("  {}", t("help-key-question"))),
         Line::from(format!("  {}", t("help-key-x"))),
+        Line::from(format!("  {}", t("help-key-t-shift"))),
+        Line::from(format!("  {}", t("help-key-e-shift"))),
         Line::from(format!("  {}", t("help-key-i"))),
+        Line::from(format!("  {}", t("help-key-b-shift"))),
         Line::from(format!("  {}", t("help-key-r-shift"))),
         Line::from(""),
         Line::from(Span::styled(t("help-section-settings"), Style::default().add_modifier(Modifier::BOLD))),
@@ -800,6 +1114,217 @@ fn render_help(app: &TuiApp, f: &mut Frame, area: Rect) {
     f.render_widget(paragraph, dialog_area);
 }
 
+/// Render statistics panel overlay as centered popup
+fn render_stats(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let t = |key: &str| app.state.t(key);
+
+    let dialog_width = 80;
+    let dialog_height = 32;
+    let dialog_area = Rect {
+        x: (area.width.saturating_sub(dialog_width)) / 2,
+        y: (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width.min(area.width),
+        height: dialog_height.min(area.height),
+    };
+
+    let title = format!(
+        "{} - {}",
+        t("dialog-stats"),
+        t(app.state.stats_window.label_key())
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(dialog_area);
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(block, dialog_area);
+
+    let Some(ref stats) = app.state.stats else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(9),  // Totals
+            Constraint::Min(4),     // Per-folder bars
+            Constraint::Length(10), // Daily histogram
+        ])
+        .split(inner);
+
+    // Totals
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let success_rate = stats
+        .success_rate
+        .map(|r| app.state.format.percent(r * 100.0))
+        .unwrap_or_else(|| "-".to_string());
+    let avg_speed = stats
+        .avg_speed
+        .map(|s| app.state.format.speed(s))
+        .unwrap_or_else(|| "-".to_string());
+    let lifetime_line = match app.state.lifetime_stats {
+        Some(ref lifetime) => Line::from(vec![
+            Span::styled(format!("{} ", t("stats-label-lifetime")), bold),
+            Span::raw(format!(
+                "{} {} / {} / {}",
+                app.state.format.integer(lifetime.files),
+                t("stats-lifetime-files"),
+                app.state.format.size(lifetime.bytes),
+                crate::download::task::format_duration(lifetime.seconds as u64)
+            )),
+        ]),
+        None => Line::from(""),
+    };
+    let totals = vec![
+        Line::from(vec![
+            Span::styled(format!("{} ", t("stats-label-completed")), bold),
+            Span::styled(app.state.format.integer(stats.completed as u64), Style::default().fg(Color::Green)),
+            Span::raw("   "),
+            Span::styled(format!("{} ", t("stats-label-failed")), bold),
+            Span::styled(app.state.format.integer(stats.failed as u64), Style::default().fg(Color::Red)),
+            Span::raw("   "),
+            Span::styled(format!("{} ", t("stats-label-success-rate")), bold),
+            Span::raw(success_rate),
+        ]),
+        Line::from(vec![
+            Span::styled(format!("{} ", t("stats-label-total-bytes")), bold),
+            Span::raw(app.state.format.size(stats.total_bytes)),
+            Span::raw("   "),
+            Span::styled(format!("{} ", t("stats-label-avg-speed")), bold),
+            Span::raw(avg_speed),
+        ]),
+        lifetime_line,
+        Line::from(""),
+        Line::from(Span::styled(t("stats-section-queue"), bold)),
+        Line::from(format!(
+            "  {} {}  {} {}  {} {}  {} {}",
+            t("stats-label-pending"), stats.queue.pending,
+            t("stats-label-downloading"), stats.queue.downloading,
+            t("stats-label-paused"), stats.queue.paused,
+            t("stats-label-error"), stats.queue.error,
+        )),
+        Line::from(""),
+        Line::from(Span::styled(t("stats-section-folders"), bold)),
+    ];
+    f.render_widget(Paragraph::new(totals), chunks[0]);
+
+    // Per-folder bars (relative to the largest folder)
+    let config = app.state.app_state.config.try_read().ok();
+    let max_bytes = stats.folders.iter().map(|s| s.bytes).max().unwrap_or(0);
+    let bar_width = 24;
+    let folder_lines: Vec<Line> = if stats.folders.is_empty() {
+        vec![Line::from(format!("  {}", t("stats-empty")))]
+    } else {
+        stats
+            .folders
+            .iter()
+            .map(|folder_stats| {
+                let name = config
+                    .as_ref()
+                    .map(|c| c.folder_name(&folder_stats.folder_id))
+                    .unwrap_or_else(|| folder_stats.folder_id.clone());
+                Line::from(vec![
+                    Span::raw(format!("  {} ", pad_to_width(&truncate_filename(&name, 16), 16))),
+                    Span::styled(
+                        format_progress_bar(folder_stats.bytes, Some(max_bytes), bar_width),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(format!(
+                        " {:>10}  {}/{}",
+                        app.state.format.size(folder_stats.bytes),
+                        folder_stats.completed,
+                        folder_stats.completed + folder_stats.failed
+                    )),
+                ])
+            })
+            .collect()
+    };
+    f.render_widget(Paragraph::new(folder_lines), chunks[1]);
+
+    // Daily completions histogram (most recent days that fit)
+    let max_days = (chunks[2].width.saturating_sub(2) / 6).max(1) as usize;
+    let days = &stats.daily[stats.daily.len().saturating_sub(max_days)..];
+    let bars: Vec<Bar> = days
+        .iter()
+        .map(|day| {
+            Bar::default()
+                .value(day.completed as u64)
+                .label(Line::from(app.state.format.month_day(day.date)))
+        })
+        .collect();
+    let chart = BarChart::default()
+        .block(Block::default().borders(Borders::TOP).title(t("stats-section-daily")))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Green))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Green));
+    f.render_widget(chart, chunks[2]);
+}
+
+/// Render recent script errors overlay as centered popup
+fn render_script_errors(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let t = |key: &str| app.state.t(key);
+
+    let dialog_width = 100;
+    let dialog_height = 24;
+    let dialog_area = Rect {
+        x: (area.width.saturating_sub(dialog_width)) / 2,
+        y: (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width.min(area.width),
+        height: dialog_height.min(area.height),
+    };
+
+    let entries = app.state.app_state.script_errors.entries();
+    let lines: Vec<Line> = if entries.is_empty() {
+        vec![Line::from(format!("  {}", t("script-errors-empty")))]
+    } else {
+        // Newest first
+        entries
+            .iter()
+            .rev()
+            .flat_map(|entry| {
+                let hook = entry
+                    .event
+                    .clone()
+                    .unwrap_or_else(|| t("script-errors-load"));
+                [
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} ", app.state.format.time(&entry.timestamp)),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            entry.script.clone(),
+                            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(" [{}]", hook)),
+                    ]),
+                    Line::from(Span::styled(
+                        format!("  {}", entry.message),
+                        Style::default().fg(Color::Red),
+                    )),
+                ]
+            })
+            .collect()
+    };
+
+    let title = format!("{} ({})", t("dialog-script-errors"), entries.len());
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(Style::default().bg(Color::Black)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
 /// Render settings screen with tabs (Application / Folder)
 fn render_settings(app: &TuiApp, f: &mut Frame, area: Rect) {
     use crate::tui::state::SettingsSection;
@@ -916,25 +1441,7 @@ fn render_settings_tabs(app: &TuiApp, f: &mut Frame, area: Rect) {
     let inner_x = area.x + 1;
     let inner_y = area.y + 1;
 
-    // Calculate tab positions (tabs are separated by " | " which is 3 chars)
-    // Each tab has some padding around the text
-    let mut tab_rects = Vec::new();
-    let mut current_x = inner_x;
-
-    for (idx, title) in titles.iter().enumerate() {
-        // Tab width: title length + 2 for padding
-        let tab_width = title.len() as u16 + 2;
-        let tab_rect = Rect {
-            x: current_x,
-            y: inner_y,
-            width: tab_width,
-            height: 1,
-        };
-        tab_rects.push((idx, tab_rect));
-
-        // Move to next tab position (tab width + separator " | " = 3)
-        current_x += tab_width + 3;
-    }
+    let tab_rects = tab_hit_regions(inner_x, inner_y, &titles);
 
     {
         let mut regions = app.state.click_regions.borrow_mut();
@@ -993,6 +1500,11 @@ fn render_application_settings(app: &TuiApp, f: &mut Frame, area: Rect) {
                     .parallel_folder_count
                     .map(|v| v.to_string())
                     .unwrap_or_else(|| app.state.t("settings-value-not-set")),
+                ApplicationSettingsField::MaxConnections => config
+                    .download
+                    .max_connections
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| app.state.t("settings-value-not-set")),
                 ApplicationSettingsField::MaxRedirects => {
                     config.download.max_redirects.to_string()
                 }
@@ -1358,6 +1870,17 @@ fn render_folder_details(app: &TuiApp, f: &mut Frame, area: Rect) {
                     folder_config.save_path.display().to_string(),
                 ));
 
+                // Free space on the save path's volume (not an editable field)
+                let free_space_str = app
+                    .state
+                    .folder_free_space(&folder_config.save_path)
+                    .map(|s| app.state.format.size(s))
+                    .unwrap_or_else(|| app.state.t("settings-value-unknown"));
+                detail_lines.push(Line::from(Span::styled(
+                    format!("    {}: {}", app.state.t("settings-folder-free-space"), free_space_str),
+                    Style::default().fg(muted_color),
+                )));
+
                 // Field 1: Auto-Date Directory
                 let auto_date_str = if folder_config.auto_date_directory {
                     app.state.t("settings-value-enabled")
@@ -1642,8 +2165,9 @@ fn render_field_edit_dialog(app: &TuiApp, f: &mut Frame, area: Rect, field: supe
 
 /// Render add download dialog (centered overlay)
 fn render_add_download_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let has_error = app.state.validation_error.is_some();
     let dialog_width = 60;
-    let dialog_height = 5;
+    let dialog_height = if has_error { 9 } else { 6 };  // Expand for error
 
     let dialog_area = Rect {
         x: (area.width.saturating_sub(dialog_width)) / 2,
@@ -1652,7 +2176,35 @@ fn render_add_download_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
         height: dialog_height,
     };
 
-    let text = format!("{} {}", app.state.t("prompt-url"), app.state.input_buffer);
+    let chunks = if has_error {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Length(3)])
+            .split(dialog_area)
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0)])
+            .split(dialog_area)
+    };
+
+    // URL and optional Referer fields; Tab moves between them
+    let field = |prompt: &str, value: &str, focused: bool| {
+        let style = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        Line::from(vec![
+            Span::styled(format!("{} ", prompt), style),
+            Span::raw(value.to_string()),
+        ])
+    };
+    let referer_focused = app.state.add_referer_focused;
+    let text = vec![
+        field(&app.state.t("prompt-url"), &app.state.input_buffer, !referer_focused),
+        field(&app.state.t("prompt-referer"), &app.state.add_referer, referer_focused),
+    ];
     let paragraph = Paragraph::new(text)
         .block(
             Block::default()
@@ -1661,7 +2213,21 @@ fn render_add_download_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
                 .style(Style::default().bg(Color::Black)),
         );
 
-    f.render_widget(paragraph, dialog_area);
+    f.render_widget(paragraph, chunks[0]);
+
+    // Render error message if present (e.g. save directory cannot be created)
+    if let Some(ref error_msg) = app.state.validation_error {
+        let error_para = Paragraph::new(error_msg.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+                    .style(Style::default().bg(Color::Black))
+            )
+            .style(Style::default().fg(Color::Red))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, chunks[1]);
+        f.render_widget(error_para, chunks[1]);
+    }
 }
 
 /// Render generic input dialog with custom title and prompt (centered overlay)
@@ -1720,7 +2286,21 @@ fn render_input_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
 /// Render download preview dialog (centered overlay)
 fn render_download_preview_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
     let dialog_width = 80;
-    let dialog_height = 18;
+    // Header section: blank line, title, one line per header
+    let header_lines = if app.state.preview_headers.is_empty() {
+        0
+    } else {
+        app.state.preview_headers.len() as u16 + 2
+    };
+    let confirm_over_bytes = app.state.app_state.config.try_read().ok().and_then(|config| config.download.confirm_over_bytes);
+    let over_threshold = app
+        .state
+        .preview_info
+        .as_ref()
+        .and_then(|info| info.size)
+        .zip(confirm_over_bytes)
+        .is_some_and(|(size, threshold)| size > threshold);
+    let dialog_height = (18 + header_lines + u16::from(over_threshold)).min(area.height);
 
     let dialog_area = Rect {
         x: (area.width.saturating_sub(dialog_width)) / 2,
@@ -1761,7 +2341,7 @@ fn render_download_preview_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
 
         // File size
         if let Some(size) = info.size {
-            let size_str = format_size(size);
+            let size_str = app.state.format.size(size);
             lines.push(Line::from(vec![
                 Span::styled(
                     format!("{} ", app.state.t("details-label-size-icon")),
@@ -1769,6 +2349,15 @@ fn render_download_preview_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
                 ),
                 Span::raw(size_str),
             ]));
+            if over_threshold {
+                let args = fluent_args! {
+                    "limit" => app.state.format.size(confirm_over_bytes.unwrap_or(0)),
+                };
+                lines.push(Line::from(Span::styled(
+                    app.state.t_with_args("preview-over-confirm-threshold", Some(&args)),
+                    Style::default().fg(Color::Rgb(255, 160, 60)),
+                )));
+            }
         } else {
             lines.push(Line::from(vec![
                 Span::styled(
@@ -1814,6 +2403,24 @@ fn render_download_preview_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
         ]));
     }
 
+    // Request headers (user agent, referer, folder defaults)
+    if !app.state.preview_headers.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            app.state.t("preview-label-headers"),
+            Style::default().add_modifier(Modifier::BOLD).fg(Color::White),
+        )));
+        // Inner width minus indent and ": "
+        let available = dialog_width as usize - 2 - 4;
+        for (name, value) in &app.state.preview_headers {
+            let value_width = available.saturating_sub(name.width()).max(10);
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", name), Style::default().fg(Color::DarkGray)),
+                Span::raw(truncate_end(value, value_width)),
+            ]));
+        }
+    }
+
     lines.push(Line::from(""));
     lines.push(Line::from(vec![
         Span::styled("Press ", Style::default().fg(Color::DarkGray)),
@@ -1874,8 +2481,15 @@ fn render_download_preview_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
 
 /// Render change folder dialog (centered overlay)
 fn render_change_folder_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
+    /// Folders listed below the input when moving a download
+    const MAX_LISTED_FOLDERS: usize = 8;
+
+    let moving = app.state.change_target == ChangeTarget::Folder;
+    let candidates = if moving { app.state.folder_candidates(&app.state.input_buffer) } else { Vec::new() };
+    let listed = candidates.len().min(MAX_LISTED_FOLDERS);
+
     let dialog_width = 80;
-    let dialog_height = 7;
+    let dialog_height = if moving { 6 + listed.max(1) as u16 } else { 7 };
 
     let dialog_area = Rect {
         x: (area.width.saturating_sub(dialog_width)) / 2,
@@ -1884,30 +2498,80 @@ fn render_change_folder_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
         height: dialog_height,
     };
 
-    // Get current path from selected download
-    let current_path = app
+    // Current folder or path of the selected download
+    let current = app
         .state
         .get_selected_download()
-        .map(|task| task.save_path.to_string_lossy().to_string())
+        .map(|task| {
+            if moving {
+                app.state.folder_display_name(&task.folder_id)
+            } else {
+                task.save_path.to_string_lossy().to_string()
+            }
+        })
         .unwrap_or_else(|| "No download selected".to_string());
 
-    let lines = vec![
+    // Folder names complete inline (gray) and with Tab
+    let mut new_line = vec![
+        Span::styled("New: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(app.state.input_buffer.as_str()),
+    ];
+    if moving {
+        if let Some(suggestion) = app.state.folder_suggestion() {
+            new_line.push(Span::styled(suggestion, Style::default().fg(Color::DarkGray)));
+        }
+        if let Some(ref completion) = app.state.folder_completion
+            && completion.candidates.len() > 1
+            && completion.candidates.get(completion.index) == Some(&app.state.input_buffer)
+        {
+            new_line.push(Span::styled(
+                format!("  ({}/{})", completion.index + 1, completion.candidates.len()),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+    }
+
+    let status_line = match app.state.validation_error {
+        Some(ref error) => Line::from(Span::styled(error.as_str(), Style::default().fg(Color::Red))),
+        None => Line::from(""),
+    };
+
+    let mut lines = vec![
         Line::from(vec![
             Span::styled("Current: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(&current_path),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("New: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(&app.state.input_buffer),
+            Span::raw(&current),
         ]),
+        status_line,
+        Line::from(new_line),
     ];
 
+    // Matching folders; Up/Down moves the highlight, Enter picks it
+    if moving {
+        lines.push(Line::from(""));
+        let selected = app.state.change_folder_index.min(candidates.len().saturating_sub(1));
+        let first = (selected + 1).saturating_sub(MAX_LISTED_FOLDERS);
+        for (index, name) in candidates.iter().enumerate().skip(first).take(MAX_LISTED_FOLDERS) {
+            let line = if index == selected {
+                Line::from(Span::styled(
+                    format!("> {}", name),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(format!("  {}", name))
+            };
+            lines.push(line);
+        }
+        if candidates.is_empty() {
+            lines.push(Line::from(Span::styled("  (no matching folder)", Style::default().fg(Color::DarkGray))));
+        }
+    }
+
+    let title = if moving { "dialog-change-folder" } else { "dialog-change-save-path" };
     let paragraph = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(app.state.t("dialog-change-save-path"))
+                .title(app.state.t(title))
                 .style(Style::default().bg(Color::Black)),
         );
 
@@ -1926,23 +2590,56 @@ fn render_confirm_delete_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
         height: dialog_height,
     };
 
-    // Get filename of selected download
-    let filename = app
-        .state
-        .get_selected_download()
-        .map(|task| truncate_filename(&task.filename, 50))
-        .unwrap_or_else(|| "Unknown".to_string());
+    let (question, subject) = match app.state.pending_folder_action {
+        // Folder menu: name the folder and how many downloads go
+        Some(action) => {
+            let count = app.state.folder_action_count(action);
+            if action == super::state::FolderContextMenuAction::ClearHistory {
+                let args = fluent_args! {
+                    "count" => count,
+                };
+                (
+                    app.state.t_with_args("confirm-clear-history", Some(&args)),
+                    Line::from(app.state.t("confirm-clear-history-note")),
+                )
+            } else {
+                let folder = app
+                    .state
+                    .selected_folder_id_from_tree()
+                    .map(|id| app.state.folder_display_name(id))
+                    .unwrap_or_default();
+                let args = fluent_args! {
+                    "count" => count,
+                    "folder" => truncate_filename(&folder, 20),
+                };
+                (
+                    app.state.t_with_args("confirm-folder-delete-all", Some(&args)),
+                    Line::from(app.state.t("confirm-folder-delete-all-note")),
+                )
+            }
+        }
+        None => {
+            // Get filename of selected download
+            let filename = app
+                .state
+                .get_selected_download()
+                .map(|task| truncate_filename(&task.filename, 50))
+                .unwrap_or_else(|| "Unknown".to_string());
+            let subject = Line::from(vec![
+                Span::styled("File: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(filename),
+            ]);
+            ("Are you sure you want to delete this download?".to_string(), subject)
+        }
+    };
 
     let lines = vec![
         Line::from(Span::styled(
-            "Are you sure you want to delete this download?",
+            question,
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("File: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(&filename),
-        ]),
+        subject,
         Line::from(""),
         Line::from(Span::styled(
             "Press Y to confirm, N or Esc to cancel",
@@ -2000,6 +2697,169 @@ fn render_confirm_delete_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
     }
 }
 
+/// Render the confirmation for a download held by `download.confirm_over_bytes`
+fn render_confirm_large_download_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let Some((_, ref filename, size)) = app.state.pending_size_confirmation else {
+        return;
+    };
+
+    let dialog_width = 64.min(area.width);
+    let dialog_height = 8;
+    let dialog_area = Rect {
+        x: (area.width.saturating_sub(dialog_width)) / 2,
+        y: (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let args = fluent_args! {
+        "filename" => filename.as_str(),
+        "size" => app.state.format.size(size),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            app.state.t_with_args("confirm-large-download-size", Some(&args)),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(app.state.t("confirm-large-download-question")),
+        Line::from(""),
+        Line::from(Span::styled(app.state.t("status-hint-confirm-yn"), Style::default().fg(Color::Cyan))),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(app.state.t("dialog-confirm-large-download"))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
+/// Render the confirmation for resetting the open circuit that blocked a download
+fn render_confirm_circuit_reset_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let Some((ref key, _)) = app.state.pending_circuit_reset else {
+        return;
+    };
+
+    let dialog_width = 64.min(area.width);
+    let dialog_height = 8;
+    let dialog_area = Rect {
+        x: (area.width.saturating_sub(dialog_width)) / 2,
+        y: (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let args = fluent_args! {
+        "key" => key.as_str(),
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            app.state.t_with_args("confirm-circuit-reset-blocked", Some(&args)),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(app.state.t("confirm-circuit-reset-question")),
+        Line::from(""),
+        Line::from(Span::styled(app.state.t("status-hint-confirm-yn"), Style::default().fg(Color::Cyan))),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(app.state.t("dialog-confirm-circuit-reset"))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
+/// Render the confirmation for concurrency limits adjusted to fit the global limit
+fn render_confirm_concurrency_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
+    let Some((adjustment, ref message)) = app.state.pending_concurrency_adjustment else {
+        return;
+    };
+
+    let dialog_width = 64.min(area.width);
+    let dialog_height = 10;
+    let dialog_area = Rect {
+        x: (area.width.saturating_sub(dialog_width)) / 2,
+        y: (area.height.saturating_sub(dialog_height)) / 2,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let change = |key: &str, from: usize, to: usize| {
+        let args = fluent_args! {
+            "from" => from,
+            "to" => to,
+        };
+        let style = if from == to {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+        };
+        Line::from(Span::styled(app.state.t_with_args(key, Some(&args)), style))
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(message.as_str(), Style::default().fg(Color::Yellow))),
+        Line::from(""),
+        Line::from(app.state.t("confirm-concurrency-proposed")),
+        change(
+            "confirm-concurrency-per-folder",
+            adjustment.requested_per_folder,
+            adjustment.max_concurrent_per_folder,
+        ),
+        change(
+            "confirm-concurrency-folders",
+            adjustment.requested_parallel_folders,
+            adjustment.parallel_folder_count,
+        ),
+        Line::from(""),
+        Line::from(Span::styled(app.state.t("status-hint-confirm-yn"), Style::default().fg(Color::Cyan))),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(app.state.t("dialog-confirm-concurrency"))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(paragraph, dialog_area);
+}
+
+/// Status icon for a task, telling a download held for size confirmation apart from a paused one
+fn task_status_icon(app: &TuiApp, task: &crate::download::task::DownloadTask) -> String {
+    if task.awaiting_confirmation {
+        return app.state.t("status-awaiting-confirmation");
+    }
+    status_icon(app, &task.status)
+}
+
+/// Status color for a task (see `task_status_icon`)
+fn task_status_color(task: &crate::download::task::DownloadTask) -> Color {
+    if task.awaiting_confirmation {
+        return Color::Rgb(255, 160, 60); // Amber
+    }
+    status_color(&task.status)
+}
+
 /// Get status icon for download status
 fn status_icon(app: &TuiApp, status: &DownloadStatus) -> String {
     match status {
@@ -2024,42 +2884,6 @@ fn status_color(status: &DownloadStatus) -> Color {
     }
 }
 
-/// Format bytes to human-readable size
-fn format_size(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    let mut size = bytes as f64;
-    let mut unit_idx = 0;
-
-    while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
-        size /= 1024.0;
-        unit_idx += 1;
-    }
-
-    if unit_idx == 0 {
-        format!("{} {}", bytes, UNITS[unit_idx])
-    } else {
-        format!("{:.2} {}", size, UNITS[unit_idx])
-    }
-}
-
-/// Format speed (bytes per second) to human-readable format
-fn format_speed(bytes_per_sec: f64) -> String {
-    const UNITS: &[&str] = &["B/s", "KB/s", "MB/s", "GB/s"];
-    let mut speed = bytes_per_sec;
-    let mut unit_idx = 0;
-
-    while speed >= 1024.0 && unit_idx < UNITS.len() - 1 {
-        speed /= 1024.0;
-        unit_idx += 1;
-    }
-
-    if unit_idx == 0 {
-        format!("{:.0} {}", speed, UNITS[unit_idx])
-    } else {
-        format!("{:.1} {}", speed, UNITS[unit_idx])
-    }
-}
-
 /// Truncate filename with ellipsis if too long, preserving extension
 /// Uses unicode-width for accurate display width (handles Japanese/CJK correctly)
 fn truncate_filename(filename: &str, max_width: usize) -> String {
@@ -2100,11 +2924,56 @@ fn truncate_filename(filename: &str, max_width: usize) -> String {
     }
 
     // Fallback: simple truncation with ellipsis at end
+    truncate_end(filename, max_width)
+}
+
+/// Active/queued counts for a folder tree label, e.g. ` (2↓ 5•)` (empty when idle)
+fn folder_count_suffix(counts: &crate::download::manager::FolderTaskCounts) -> String {
+    let mut parts = Vec::new();
+    if counts.downloading > 0 {
+        parts.push(format!("{}↓", counts.downloading));
+    }
+    if counts.pending > 0 {
+        parts.push(format!("{}•", counts.pending));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(" "))
+    }
+}
+
+/// Fit a folder name and its count suffix into `max_width` display columns
+///
+/// The name is truncated first; the counts are dropped only when there is no
+/// room for them next to a few characters of the name.
+fn tree_label(name: &str, counts: &str, max_width: usize) -> String {
+    const MIN_NAME_WIDTH: usize = 4;
+    if counts.is_empty() || max_width < counts.width() + MIN_NAME_WIDTH {
+        return truncate_end(name, max_width);
+    }
+    format!("{}{}", truncate_end(name, max_width - counts.width()), counts)
+}
+
+/// Pad text with trailing spaces to `width` display columns
+///
+/// `format!("{:<N}")` pads by character count, which leaves wide (CJK)
+/// text misaligned.
+fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
+/// Truncate text with a trailing ellipsis if wider than `max_width` display columns
+fn truncate_end(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
     let target_width = max_width.saturating_sub(3);
     let mut truncated = String::new();
     let mut current_width = 0;
 
-    for ch in filename.chars() {
+    for ch in text.chars() {
         let ch_width = ch.width().unwrap_or(1);
         if current_width + ch_width > target_width {
             break;
@@ -2178,7 +3047,7 @@ fn render_switch_folder_dialog(app: &TuiApp, f: &mut Frame, area: Rect) {
     // Calculate dialog dimensions
     let max_folder_width = folder_entries
         .iter()
-        .map(|(_id, name)| name.len())
+        .map(|(_id, name)| name.width())
         .max()
         .unwrap_or(20);
 
@@ -2242,7 +3111,7 @@ fn render_context_menu(app: &TuiApp, f: &mut Frame, area: Rect) {
     // Calculate menu dimensions
     let max_label_width = menu_items
         .iter()
-        .map(|item| app.state.t(item.label_key()).len() + item.key_hint().len() + 6) // +6 for spacing and brackets
+        .map(|item| app.state.t(item.label_key()).width() + item.key_hint().width() + 6) // +6 for spacing and brackets
         .max()
         .unwrap_or(40);
 
@@ -2338,7 +3207,7 @@ fn render_folder_context_menu(app: &TuiApp, f: &mut Frame, area: Rect) {
     let max_label_width = menu_items
         .iter()
         .map(|item| {
-            app.state.t(item.label_key()).len() + item.key_hint().len() + 6
+            app.state.t(item.label_key()).width() + item.key_hint().width() + 6
         })
         .max()
         .unwrap_or(40);
@@ -2424,3 +3293,57 @@ fn render_folder_context_menu(app: &TuiApp, f: &mut Frame, area: Rect) {
         regions.context_menu_items = menu_item_rects;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_label_fits_counts() {
+        use crate::download::manager::FolderTaskCounts;
+
+        let counts = folder_count_suffix(&FolderTaskCounts { pending: 5, downloading: 2 });
+        assert_eq!(counts, " (2↓ 5•)");
+        assert_eq!(folder_count_suffix(&FolderTaskCounts { pending: 3, downloading: 0 }), " (3•)");
+        assert_eq!(folder_count_suffix(&FolderTaskCounts::default()), "");
+
+        assert_eq!(tree_label("images", &counts, 30), "images (2↓ 5•)");
+        // The name is truncated before the counts
+        assert_eq!(tree_label("wallpapers", &counts, 14), "wal... (2↓ 5•)");
+        // Too narrow for both: the name wins
+        assert_eq!(tree_label("wallpapers", &counts, 8), "wallp...");
+    }
+
+    #[test]
+    fn test_pad_to_width_counts_display_columns() {
+        assert_eq!(pad_to_width("abc", 6), "abc   ");
+        // Each CJK character is two columns wide
+        assert_eq!(pad_to_width("日本", 6), "日本  ");
+        assert_eq!(pad_to_width("日本語です", 6), "日本語です");
+        assert_eq!(pad_to_width(&truncate_filename("とても長いフォルダ名前", 10), 10).width(), 10);
+    }
+
+    #[test]
+    fn test_status_bar_padding_with_wide_text() {
+        assert_eq!(status_bar_padding(40, &["abcd", "", "efgh"]), 30);
+        // "⚠ TLS 証明書検証オフ" is 20 columns but only 13 chars
+        assert_eq!(status_bar_padding(40, &["abcd", "⚠ TLS 証明書検証オフ"]), 14);
+        assert_eq!(status_bar_padding(10, &["ダウンロード中です"]), 0);
+    }
+
+    #[test]
+    fn test_tab_hit_regions_with_wide_titles() {
+        let regions = tab_hit_regions(1, 1, &["Application", "フォルダ"]);
+        assert_eq!(regions[0].1, Rect { x: 1, y: 1, width: 13, height: 1 });
+        // The second tab starts after the first one and the " │ " divider
+        assert_eq!(regions[1].1, Rect { x: 17, y: 1, width: 10, height: 1 });
+    }
+
+    #[test]
+    fn test_truncate_end_wide_text() {
+        assert_eq!(truncate_end("short", 10), "short");
+        let truncated = truncate_end("日本語のとても長い値", 9);
+        assert!(truncated.width() <= 9);
+        assert!(truncated.ends_with("..."));
+    }
+}
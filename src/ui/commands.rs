@@ -1,5 +1,6 @@
 use crate::AppState;
-use crate::app::config::ReferrerPolicy;
+use crate::app::config::{DownloadConfig, ReferrerPolicy};
+use crate::app::settings::{ConcurrencyAdjustment, ConcurrencyField};
 use crate::download::{manager::DownloadManager, task::DownloadTask};
 use fluent::fluent_args;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,9 @@ pub enum Command {
     UpdateMaxConcurrent { value: usize },
     UpdateMaxConcurrentPerFolder { value: Option<usize> },
     UpdateMaxActiveFolders { value: Option<usize> },
+    UpdateMaxConnections { value: Option<usize> },
+    /// Apply limits proposed by a `NeedsConfirmation` response
+    ApplyConcurrencyAdjustment { adjustment: ConcurrencyAdjustment },
     UpdateMaxRedirects { value: u32 },
     UpdateRetryCount { value: u32 },
     UpdateScriptsEnabled { value: bool },
@@ -50,6 +54,9 @@ pub enum Command {
 pub enum CommandResponse {
     Success { data: serde_json::Value },
     Error { error: String },
+    /// The new value breaks `(per_folder * active_folders) <= max_concurrent`;
+    /// nothing was saved, and `adjustment` holds values that would fit
+    NeedsConfirmation { adjustment: ConcurrencyAdjustment, message: String },
 }
 
 pub async fn handle_command(
@@ -60,6 +67,9 @@ pub async fn handle_command(
     match command {
         Command::AddDownload { urls } => {
             let config = state.config.read().await;
+            if let Some(e) = urls.iter().find_map(|url| config.download.check_url_scheme(url).err()) {
+                return CommandResponse::Error { error: e.to_string() };
+            }
             for url in urls {
                 let task = DownloadTask::new(url, config.download.default_directory.clone());
                 download_manager.add_download(task).await;
@@ -122,7 +132,8 @@ pub async fn handle_command(
         }
         Command::ChangeFolder { id, folder_id } => {
             if let Ok(uuid) = uuid::Uuid::parse_str(&id) {
-                match download_manager.change_folder(uuid, folder_id).await {
+                let config = state.config.read().await;
+                match download_manager.move_task_to_folder(uuid, &folder_id, Some(&config)).await {
                     Ok(_) => CommandResponse::Success {
                         data: serde_json::json!({"status": "ok"}),
                     },
@@ -156,6 +167,7 @@ pub async fn handle_command(
                 }
 
                 *state_config = new_config;
+                apply_concurrency_limits(&download_manager, &state_config.download).await;
                 // Save to disk
                 if let Err(e) = state_config.save() {
                     return CommandResponse::Error {
@@ -175,37 +187,32 @@ pub async fn handle_command(
         }
 
         Command::UpdateMaxConcurrent { value } => {
-            let mut config = state.config.write().await;
-            config.download.max_concurrent = value;
-
-            // Validate constraints
-            if let Err(errors) = crate::app::settings::validate_folder_config(&config) {
-                let error_str = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
-                return CommandResponse::Error {
-                    error: state.t_with_args("cmd-error-validation-failed",
-                        Some(&fluent_args!["error" => error_str])),
-                };
-            }
+            update_concurrency_limit(&state, &download_manager, ConcurrencyField::MaxConcurrent, serde_json::json!(value), |download| {
+                download.max_concurrent = value;
+            })
+            .await
+        }
 
-            // Save to disk
-            if let Err(e) = config.save() {
-                return CommandResponse::Error {
-                    error: state.t_with_args("cmd-error-save-config",
-                        Some(&fluent_args!["error" => e.to_string()])),
-                };
-            }
+        Command::UpdateMaxConcurrentPerFolder { value } => {
+            update_concurrency_limit(&state, &download_manager, ConcurrencyField::MaxConcurrentPerFolder, serde_json::json!(value), |download| {
+                download.max_concurrent_per_folder = value;
+            })
+            .await
+        }
 
-            CommandResponse::Success {
-                data: serde_json::json!({"status": "ok", "value": value}),
-            }
+        Command::UpdateMaxActiveFolders { value } => {
+            update_concurrency_limit(&state, &download_manager, ConcurrencyField::ParallelFolderCount, serde_json::json!(value), |download| {
+                download.parallel_folder_count = value;
+            })
+            .await
         }
 
-        Command::UpdateMaxConcurrentPerFolder { value } => {
+        Command::UpdateMaxConnections { value } => {
             let mut config = state.config.write().await;
-            config.download.max_concurrent_per_folder = value;
+            let mut candidate = config.clone();
+            candidate.download.max_connections = value;
 
-            // Validate constraints
-            if let Err(errors) = crate::app::settings::validate_folder_config(&config) {
+            if let Err(errors) = crate::app::settings::validate_folder_config(&candidate) {
                 let error_str = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
                 return CommandResponse::Error {
                     error: state.t_with_args("cmd-error-validation-failed",
@@ -213,7 +220,8 @@ pub async fn handle_command(
                 };
             }
 
-            // Save to disk
+            *config = candidate;
+            apply_concurrency_limits(&download_manager, &config.download).await;
             if let Err(e) = config.save() {
                 return CommandResponse::Error {
                     error: state.t_with_args("cmd-error-save-config",
@@ -226,12 +234,14 @@ pub async fn handle_command(
             }
         }
 
-        Command::UpdateMaxActiveFolders { value } => {
+        Command::ApplyConcurrencyAdjustment { adjustment } => {
             let mut config = state.config.write().await;
-            config.download.parallel_folder_count = value;
+            let mut candidate = config.clone();
+            candidate.download.max_concurrent = adjustment.max_concurrent;
+            candidate.download.max_concurrent_per_folder = Some(adjustment.max_concurrent_per_folder);
+            candidate.download.parallel_folder_count = Some(adjustment.parallel_folder_count);
 
-            // Validate constraints
-            if let Err(errors) = crate::app::settings::validate_folder_config(&config) {
+            if let Err(errors) = crate::app::settings::validate_folder_config(&candidate) {
                 let error_str = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
                 return CommandResponse::Error {
                     error: state.t_with_args("cmd-error-validation-failed",
@@ -239,7 +249,8 @@ pub async fn handle_command(
                 };
             }
 
-            // Save to disk
+            *config = candidate;
+            apply_concurrency_limits(&download_manager, &config.download).await;
             if let Err(e) = config.save() {
                 return CommandResponse::Error {
                     error: state.t_with_args("cmd-error-save-config",
@@ -248,7 +259,7 @@ pub async fn handle_command(
             }
 
             CommandResponse::Success {
-                data: serde_json::json!({"status": "ok", "value": value}),
+                data: serde_json::json!({"status": "ok", "adjustment": adjustment}),
             }
         }
 
@@ -352,8 +363,17 @@ pub async fn handle_command(
         }
 
         Command::UpdateUserAgent { value } => {
+            let user_agent = match crate::app::user_agent::expand(&value) {
+                Ok(user_agent) => user_agent,
+                Err(e) => {
+                    return CommandResponse::Error {
+                        error: state.t_with_args("cmd-error-validation-failed",
+                            Some(&fluent_args!["error" => e.to_string()])),
+                    };
+                }
+            };
             let mut config = state.config.write().await;
-            config.download.user_agent = value.clone();
+            config.download.user_agent = user_agent;
 
             if let Err(e) = config.save() {
                 return CommandResponse::Error {
@@ -417,6 +437,15 @@ pub async fn handle_command(
         }
 
         Command::UpdateFolderUserAgent { folder_id, value } => {
+            let value = match value.as_deref().map(crate::app::user_agent::expand).transpose() {
+                Ok(value) => value,
+                Err(e) => {
+                    return CommandResponse::Error {
+                        error: state.t_with_args("cmd-error-validation-failed",
+                            Some(&fluent_args!["error" => e.to_string()])),
+                    };
+                }
+            };
             let mut config = state.config.write().await;
 
             let folder_config = config
@@ -424,7 +453,7 @@ pub async fn handle_command(
                 .entry(folder_id.clone())
                 .or_insert_with(crate::app::config::FolderConfig::default);
 
-            folder_config.user_agent = value.clone();
+            folder_config.user_agent = value;
 
             if let Err(e) = config.save() {
                 return CommandResponse::Error {
@@ -595,6 +624,7 @@ pub async fn handle_command(
                     // Update application state
                     let mut config = state.config.write().await;
                     *config = new_config;
+                    apply_concurrency_limits(&download_manager, &config.download).await;
 
                     CommandResponse::Success {
                         data: serde_json::json!({
@@ -611,3 +641,64 @@ pub async fn handle_command(
         }
     }
 }
+
+/// Change one of the three concurrency limits
+///
+/// If the result breaks `(per_folder * active_folders) <= max_concurrent`, the
+/// config is left untouched and the response proposes adjusted values for the
+/// user to confirm (applied with `ApplyConcurrencyAdjustment`).
+async fn update_concurrency_limit(
+    state: &AppState,
+    download_manager: &DownloadManager,
+    field: ConcurrencyField,
+    value: serde_json::Value,
+    apply: impl FnOnce(&mut DownloadConfig),
+) -> CommandResponse {
+    let mut config = state.config.write().await;
+    let mut candidate = config.clone();
+    apply(&mut candidate.download);
+
+    if let Some(adjustment) = crate::app::settings::suggest_concurrency_adjustment(&candidate.download, field) {
+        let args = fluent_args![
+            "per_folder" => adjustment.requested_per_folder,
+            "folders" => adjustment.requested_parallel_folders,
+            "max" => adjustment.max_concurrent,
+        ];
+        return CommandResponse::NeedsConfirmation {
+            message: state.t_with_args("cmd-confirm-concurrency-adjustment", Some(&args)),
+            adjustment,
+        };
+    }
+
+    // Validate remaining constraints (e.g. folder limits above the global one)
+    if let Err(errors) = crate::app::settings::validate_folder_config(&candidate) {
+        let error_str = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        return CommandResponse::Error {
+            error: state.t_with_args("cmd-error-validation-failed",
+                Some(&fluent_args!["error" => error_str])),
+        };
+    }
+
+    *config = candidate;
+    apply_concurrency_limits(download_manager, &config.download).await;
+    // Save to disk
+    if let Err(e) = config.save() {
+        return CommandResponse::Error {
+            error: state.t_with_args("cmd-error-save-config",
+                Some(&fluent_args!["error" => e.to_string()])),
+        };
+    }
+
+    CommandResponse::Success {
+        data: serde_json::json!({"status": "ok", "value": value}),
+    }
+}
+
+/// Resize the running manager's limits so new values apply without a restart
+async fn apply_concurrency_limits(download_manager: &DownloadManager, download: &DownloadConfig) {
+    download_manager.set_max_concurrent(download.max_concurrent).await;
+    download_manager
+        .set_max_concurrent_per_folder(download.max_concurrent_per_folder.unwrap_or(download.max_concurrent))
+        .await;
+    download_manager.set_max_connections(download.max_connections.unwrap_or(download.max_concurrent));
+}
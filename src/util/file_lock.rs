@@ -0,0 +1,140 @@
+//! Advisory file locks
+//!
+//! Guards queue files against concurrent writes from multiple ggg processes.
+//! Locks are advisory: they only coordinate processes that also take them.
+//! A lock is released when the `FileLock` is dropped.
+//!
+//! A lock only lasts for one write, so it cannot stop two instances from
+//! replacing each other's files. Writers also remember a `content_stamp` of
+//! the file as they last read or wrote it and refuse to overwrite a file
+//! whose stamp has changed since.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait for another process to release a queue file lock
+pub const QUEUE_LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Interval between lock attempts while waiting
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Held advisory lock on a file
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Try to take an exclusive lock without waiting
+    ///
+    /// Returns `None` if another process holds the lock.
+    pub fn try_exclusive(path: &Path) -> Result<Option<Self>> {
+        Self::try_lock(path, false)
+    }
+
+    /// Try to take a shared lock without waiting
+    ///
+    /// Returns `None` if another process holds an exclusive lock.
+    pub fn try_shared(path: &Path) -> Result<Option<Self>> {
+        Self::try_lock(path, true)
+    }
+
+    /// Take an exclusive lock, waiting up to `timeout`
+    pub async fn exclusive(path: &Path, timeout: Duration) -> Result<Option<Self>> {
+        Self::wait(path, false, timeout).await
+    }
+
+    /// Take a shared lock, waiting up to `timeout`
+    pub async fn shared(path: &Path, timeout: Duration) -> Result<Option<Self>> {
+        Self::wait(path, true, timeout).await
+    }
+
+    async fn wait(path: &Path, shared: bool, timeout: Duration) -> Result<Option<Self>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_lock(path, shared)? {
+                return Ok(Some(lock));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    fn try_lock(path: &Path, shared: bool) -> Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let result = if shared { file.try_lock_shared() } else { file.try_lock() };
+        match result {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock {}", path.display()))
+            }
+        }
+    }
+}
+
+/// Path of the per-folder queue lock file
+pub fn queue_lock_path(queue_path: &Path) -> std::path::PathBuf {
+    queue_path.with_extension("lock")
+}
+
+/// Fingerprint of a queue file's contents
+pub fn content_stamp(content: &[u8]) -> u64 {
+    use std::hash::{DefaultHasher, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(content);
+    hasher.finish()
+}
+
+/// `content_stamp` of the file at `path`, `None` if it does not exist
+pub async fn file_stamp(path: &Path) -> Result<Option<u64>> {
+    match tokio::fs::read(path).await {
+        Ok(content) => Ok(Some(content_stamp(&content))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusive_lock_blocks_second_holder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("queue.lock");
+
+        let lock = FileLock::try_exclusive(&path).unwrap();
+        assert!(lock.is_some());
+        assert!(FileLock::try_exclusive(&path).unwrap().is_none());
+        assert!(FileLock::try_shared(&path).unwrap().is_none());
+
+        drop(lock);
+        assert!(FileLock::try_exclusive(&path).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_shared_locks_coexist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("queue.lock");
+
+        let first = FileLock::try_shared(&path).unwrap();
+        let second = FileLock::try_shared(&path).unwrap();
+        assert!(first.is_some() && second.is_some());
+        assert!(FileLock::try_exclusive(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! Locale-aware display formatting
+//!
+//! Sizes, speeds, counts and dates shown in the TUI follow the conventions of
+//! `general.language`: decimal and digit-group separators and the order of
+//! day, month and year. Only display strings go through here; JSON output,
+//! logs and stored values keep raw numbers and RFC 3339 timestamps.
+
+use chrono::{DateTime, NaiveDate, TimeZone};
+use std::fmt::Display;
+
+/// Number and date conventions of one language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormat {
+    /// Decimal separator
+    pub decimal: char,
+    /// Digit-group separator for integer parts of four or more digits
+    pub group: char,
+    /// Full date (strftime pattern)
+    pub date: &'static str,
+    /// Month and day only, for compact labels
+    pub month_day: &'static str,
+    /// Time of day
+    pub time: &'static str,
+}
+
+/// English conventions (also used for unknown languages)
+pub const ENGLISH: LocaleFormat = LocaleFormat {
+    decimal: '.',
+    group: ',',
+    date: "%Y-%m-%d",
+    month_day: "%m/%d",
+    time: "%H:%M:%S",
+};
+
+/// No-break space, used as the group separator where a space is customary
+const NBSP: char = '\u{a0}';
+
+impl Default for LocaleFormat {
+    fn default() -> Self {
+        ENGLISH
+    }
+}
+
+impl LocaleFormat {
+    /// Conventions for a `general.language` value (locale ID or language code)
+    pub fn for_language(language: &str) -> Self {
+        let lang = language.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        match lang.as_str() {
+            "ja" | "zh" => Self { date: "%Y/%m/%d", ..ENGLISH },
+            "de" | "da" | "tr" => Self { decimal: ',', group: '.', date: "%d.%m.%Y", month_day: "%d.%m.", ..ENGLISH },
+            "es" | "it" | "pt" | "id" => Self { decimal: ',', group: '.', date: "%d/%m/%Y", month_day: "%d/%m", ..ENGLISH },
+            "fr" => Self { decimal: ',', group: NBSP, date: "%d/%m/%Y", month_day: "%d/%m", ..ENGLISH },
+            "ru" | "uk" | "pl" | "cs" | "fi" | "nb" => {
+                Self { decimal: ',', group: NBSP, date: "%d.%m.%Y", month_day: "%d.%m.", ..ENGLISH }
+            }
+            _ => ENGLISH,
+        }
+    }
+
+    /// Format a number with `decimals` fractional digits
+    ///
+    /// ```
+    /// use ggg::util::format::LocaleFormat;
+    ///
+    /// assert_eq!(LocaleFormat::for_language("en").number(1234.5, 1), "1,234.5");
+    /// assert_eq!(LocaleFormat::for_language("de").number(1234.5, 1), "1.234,5");
+    /// ```
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (int_part, frac_part) = match formatted.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut out = String::new();
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            out.push('-');
+        }
+        out.push_str(&self.group_digits(int_part));
+        if let Some(frac_part) = frac_part {
+            out.push(self.decimal);
+            out.push_str(frac_part);
+        }
+        out
+    }
+
+    /// Format an integer with digit grouping
+    pub fn integer(&self, value: u64) -> String {
+        self.group_digits(&value.to_string())
+    }
+
+    /// Human-readable size (B, KB, MB, GB, TB; powers of 1024)
+    pub fn size(&self, bytes: u64) -> String {
+        const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+        let mut size = bytes as f64;
+        let mut unit_idx = 0;
+
+        while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit_idx += 1;
+        }
+
+        if unit_idx == 0 {
+            format!("{} {}", bytes, UNITS[unit_idx])
+        } else {
+            format!("{} {}", self.number(size, 2), UNITS[unit_idx])
+        }
+    }
+
+    /// Human-readable transfer rate (B/s, KB/s, MB/s, GB/s)
+    pub fn speed(&self, bytes_per_sec: f64) -> String {
+        const UNITS: &[&str] = &["B/s", "KB/s", "MB/s", "GB/s"];
+        let mut speed = bytes_per_sec;
+        let mut unit_idx = 0;
+
+        while speed >= 1024.0 && unit_idx < UNITS.len() - 1 {
+            speed /= 1024.0;
+            unit_idx += 1;
+        }
+
+        if unit_idx == 0 {
+            format!("{} {}", self.number(speed, 0), UNITS[unit_idx])
+        } else {
+            format!("{} {}", self.number(speed, 1), UNITS[unit_idx])
+        }
+    }
+
+    /// Percentage (0-100) with one decimal
+    pub fn percent(&self, percent: f64) -> String {
+        format!("{}%", self.number(percent, 1))
+    }
+
+    /// Date and time of day
+    pub fn datetime<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        format!("{} {}", time.format(self.date), time.format(self.time))
+    }
+
+    /// Time of day
+    pub fn time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        time.format(self.time).to_string()
+    }
+
+    /// Month and day, for compact labels such as chart axes
+    pub fn month_day(&self, date: NaiveDate) -> String {
+        date.format(self.month_day).to_string()
+    }
+
+    fn group_digits(&self, digits: &str) -> String {
+        let len = digits.len();
+        let mut out = String::with_capacity(len + len / 3);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (len - i).is_multiple_of(3) {
+                out.push(self.group);
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_numbers_follow_language() {
+        let en = LocaleFormat::for_language("en");
+        assert_eq!(en.integer(1234567), "1,234,567");
+        assert_eq!(en.integer(999), "999");
+        assert_eq!(en.number(-0.04, 1), "0.0");
+        assert_eq!(en.number(-12.5, 1), "-12.5");
+
+        let de = LocaleFormat::for_language("de-DE");
+        assert_eq!(de.size(1536), "1,50 KB");
+        assert_eq!(de.speed(2.5 * 1024.0 * 1024.0), "2,5 MB/s");
+        assert_eq!(de.percent(42.3), "42,3%");
+
+        assert_eq!(LocaleFormat::for_language("fr").integer(12345), "12\u{a0}345");
+        // Unknown languages use English conventions
+        assert_eq!(LocaleFormat::for_language("xx"), ENGLISH);
+    }
+
+    #[test]
+    fn test_english_matches_previous_output() {
+        let en = LocaleFormat::default();
+        assert_eq!(en.size(512), "512 B");
+        assert_eq!(en.size(1536), "1.50 KB");
+        assert_eq!(en.size(5 * 1024 * 1024 * 1024 * 1024), "5.00 TB");
+        assert_eq!(en.speed(100.0), "100 B/s");
+        assert_eq!(en.speed(1536.0), "1.5 KB/s");
+    }
+
+    #[test]
+    fn test_dates_follow_language() {
+        let time = Utc.with_ymd_and_hms(2026, 3, 9, 14, 5, 7).unwrap();
+        let date = time.date_naive();
+
+        let ja = LocaleFormat::for_language("ja");
+        assert_eq!(ja.datetime(&time), "2026/03/09 14:05:07");
+        assert_eq!(ja.month_day(date), "03/09");
+
+        let de = LocaleFormat::for_language("de");
+        assert_eq!(de.datetime(&time), "09.03.2026 14:05:07");
+        assert_eq!(de.month_day(date), "09.03.");
+
+        assert_eq!(ENGLISH.datetime(&time), "2026-03-09 14:05:07");
+        assert_eq!(ENGLISH.time(&time), "14:05:07");
+    }
+}
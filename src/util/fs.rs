@@ -0,0 +1,175 @@
+//! Filesystem helpers
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Makes sure `path` is a usable save directory, creating it if missing
+///
+/// Errors name the directory and the cause (e.g. permission denied) so they
+/// can be shown to the user as-is.
+pub fn ensure_save_directory(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        return Ok(());
+    }
+    if path.exists() {
+        bail!("Save path {} exists but is not a directory", path.display());
+    }
+    if let Err(e) = std::fs::create_dir_all(path) {
+        bail!("Cannot create save directory {}: {}", path.display(), e);
+    }
+    tracing::info!("Created save directory {}", path.display());
+    Ok(())
+}
+
+/// Free space available to the current user on the volume containing `path`
+///
+/// `path` does not need to exist yet: the nearest existing ancestor is
+/// queried instead. Returns `None` if no ancestor exists or the query fails.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| !p.as_os_str().is_empty() && p.exists())?;
+    match fs4::available_space(existing) {
+        Ok(bytes) => Some(bytes),
+        Err(e) => {
+            tracing::debug!("Failed to query free space for {}: {}", existing.display(), e);
+            None
+        }
+    }
+}
+
+/// Move a file, copying and deleting it when `to` is on another filesystem
+///
+/// Other rename failures (missing source, permissions) are returned as they are.
+pub fn move_file(from: &Path, to: &Path) -> Result<()> {
+    match std::fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => copy_then_rename(from, to),
+        Err(e) => Err(e).with_context(|| format!("Cannot move {} to {}", from.display(), to.display())),
+    }
+}
+
+/// Copy `from` to a temp name next to `to`, rename it into place and delete `from`
+///
+/// `to` never holds a partly copied file, even if the copy is interrupted.
+fn copy_then_rename(from: &Path, to: &Path) -> Result<()> {
+    let mut temp_name = to.as_os_str().to_os_string();
+    temp_name.push(".ggg-move");
+    let temp = std::path::PathBuf::from(temp_name);
+    let copied = std::fs::copy(from, &temp).and_then(|_| std::fs::rename(&temp, to));
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Cannot move {} to {}", from.display(), to.display()));
+    }
+    std::fs::remove_file(from).with_context(|| format!("Cannot remove {} after copying it", from.display()))?;
+    Ok(())
+}
+
+/// Whether `a` and `b` are on the same filesystem
+///
+/// Missing paths are checked through their nearest existing ancestor.
+/// Returns `None` if that cannot be told.
+pub fn same_filesystem(a: &Path, b: &Path) -> Option<bool> {
+    let existing = |path: &Path| path.ancestors().find(|p| !p.as_os_str().is_empty() && p.exists()).map(Path::to_path_buf);
+    let (a, b) = (existing(a)?, existing(b)?);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(std::fs::metadata(a).ok()?.dev() == std::fs::metadata(b).ok()?.dev())
+    }
+    #[cfg(windows)]
+    {
+        // Compare the drive or UNC share
+        let volume = |path: std::path::PathBuf| {
+            let path = path.canonicalize().ok()?;
+            path.components().next().map(|c| c.as_os_str().to_ascii_lowercase())
+        };
+        Some(volume(a)? == volume(b)?)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        None
+    }
+}
+
+/// Problems with `download.temp_dir`, for the startup warnings
+///
+/// The directory must be creatable and writable. One on a different
+/// filesystem than `target` works, but finished downloads are then copied
+/// into place instead of renamed, which is slow for large files.
+pub fn check_temp_dir(temp_dir: &Path, target: &Path) -> Vec<String> {
+    if let Err(e) = ensure_save_directory(temp_dir) {
+        return vec![format!("download.temp_dir: {}", e)];
+    }
+    let probe = temp_dir.join(format!(".ggg-probe-{}", std::process::id()));
+    if let Err(e) = std::fs::write(&probe, b"").and_then(|()| std::fs::remove_file(&probe)) {
+        return vec![format!("download.temp_dir {} is not writable ({})", temp_dir.display(), e)];
+    }
+    match same_filesystem(temp_dir, target) {
+        Some(false) => vec![format!(
+            "download.temp_dir {} is on a different filesystem than {}; finished downloads are copied instead of moved",
+            temp_dir.display(),
+            target.display()
+        )],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_save_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        ensure_save_directory(&nested).unwrap();
+        assert!(nested.is_dir());
+
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        let err = ensure_save_directory(&file).unwrap_err().to_string();
+        assert!(err.contains("not a directory"));
+        assert!(ensure_save_directory(&file.join("child")).is_err());
+    }
+
+    #[test]
+    fn test_move_file_and_temp_dir_checks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let from = temp_dir.path().join("a.ggg-part");
+        let to = temp_dir.path().join("done").join("a.bin");
+        std::fs::write(&from, "data").unwrap();
+        std::fs::create_dir(to.parent().unwrap()).unwrap();
+
+        move_file(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "data");
+        assert!(move_file(&from, &temp_dir.path().join("b.bin")).is_err());
+
+        // The cross-filesystem path leaves no temp file behind, even on failure
+        std::fs::write(&from, "copied").unwrap();
+        copy_then_rename(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "copied");
+        assert!(!temp_dir.path().join("done").join("a.bin.ggg-move").exists());
+        assert!(copy_then_rename(&from, &to).is_err());
+        assert!(!temp_dir.path().join("done").join("a.bin.ggg-move").exists());
+
+        // A missing temp dir is created; same filesystem gives no warning
+        let scratch = temp_dir.path().join("scratch");
+        assert!(check_temp_dir(&scratch, temp_dir.path()).is_empty());
+        assert!(scratch.is_dir());
+        assert_eq!(same_filesystem(&scratch, &temp_dir.path().join("missing")), Some(true));
+
+        let err = check_temp_dir(&to, temp_dir.path());
+        assert!(err[0].contains("not a directory"), "{:?}", err);
+    }
+
+    #[test]
+    fn test_available_space_uses_existing_ancestor() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir.path().join("not").join("created");
+
+        assert!(available_space(temp_dir.path()).is_some());
+        assert_eq!(available_space(&missing).is_some(), available_space(temp_dir.path()).is_some());
+    }
+}
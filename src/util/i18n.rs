@@ -1,20 +1,24 @@
 use fluent::{FluentBundle, FluentResource};
 use fluent_bundle::FluentArgs;
+use fluent_syntax::ast;
 use rust_embed::RustEmbed;
+use std::collections::{BTreeSet, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use unic_langid::LanguageIdentifier;
 
 /// Embedded locale files shipped with the binary.
 ///
-/// Currently includes only en-US as the built-in fallback.
-/// To embed additional locales, add their directory names to the
-/// `#[include = "..."]` list (e.g. `#[include = "ja-JP/*"]`).
+/// Every `locales/<locale>/*.ftl` directory is embedded, so a new
+/// translation only needs its directory; en-US is the built-in fallback.
 #[derive(RustEmbed)]
 #[folder = "locales/"]
-#[include = "en-US/*"]
+#[include = "*/*.ftl"]
 struct EmbeddedLocales;
 
+/// Locale used when the configured one is unavailable or lacks a key
+pub const FALLBACK_LOCALE: &str = "en-US";
+
 /// Manages localization resources and provides translation API.
 ///
 /// Resource resolution order (per locale):
@@ -25,45 +29,47 @@ struct EmbeddedLocales;
 /// a locale folder (e.g. `ja-JP/`), all .ftl files for that locale are
 /// loaded from the external directory and the embedded resources are
 /// ignored for that locale.
+///
+/// Keys missing from the active locale resolve from en-US; each such key is
+/// logged once at debug level (visible with `--verbose`) so translators can
+/// find the gaps.
 pub struct LocalizationManager {
     bundle: FluentBundle<Arc<FluentResource>>,
     fallback_bundle: Option<FluentBundle<Arc<FluentResource>>>,
     current_locale: String,
+    /// Keys already reported as missing
+    reported_missing: Mutex<HashSet<String>>,
 }
 
 impl LocalizationManager {
     /// Create a new LocalizationManager for the specified locale
     ///
     /// # Arguments
-    /// * `locale` - Language code ("en" or "ja")
+    /// * `locale` - Locale ID ("ja-JP") or language code ("ja"), see `resolve_locale`
     ///
     /// # Returns
     /// * `Ok(LocalizationManager)` on success
-    /// * `Err` if locale files cannot be loaded
+    /// * `Err` if the locale is not available or its files cannot be loaded
     pub fn new(locale: &str) -> anyhow::Result<Self> {
-        // Map short codes to full locale identifiers
-        let locale_lower = locale.to_lowercase();
-        let locale_id = match locale_lower.as_str() {
-            "en" => "en-US",
-            "ja" => "ja-JP",
-            other => other,
-        };
+        let available = Self::available_locales();
+        let locale_id = resolve_locale(locale, &available).ok_or_else(|| {
+            anyhow::anyhow!("Locale '{}' is not available (available: {})", locale, available.join(", "))
+        })?;
 
         tracing::info!("Loading translations for locale: {}", locale_id);
 
         // Load the requested locale
-        let locale_id_owned = locale_id.to_string();
-        let bundle = Self::load_locale_bundle(&locale_id_owned)?;
+        let bundle = Self::load_locale_bundle(&locale_id)?;
 
         // Load fallback locale (en-US) if not already loaded
-        let fallback_bundle = if locale_id != "en-US" {
-            match Self::load_locale_bundle("en-US") {
+        let fallback_bundle = if locale_id != FALLBACK_LOCALE {
+            match Self::load_locale_bundle(FALLBACK_LOCALE) {
                 Ok(fallback) => {
-                    tracing::debug!("Loaded fallback locale: en-US");
+                    tracing::debug!("Loaded fallback locale: {}", FALLBACK_LOCALE);
                     Some(fallback)
                 }
                 Err(e) => {
-                    tracing::warn!("Failed to load fallback locale en-US: {}", e);
+                    tracing::warn!("Failed to load fallback locale {}: {}", FALLBACK_LOCALE, e);
                     None
                 }
             }
@@ -75,9 +81,21 @@ impl LocalizationManager {
             bundle,
             fallback_bundle,
             current_locale: locale.to_string(),
+            reported_missing: Mutex::new(HashSet::new()),
         })
     }
 
+    /// Locale IDs with translations, embedded or in the external locale directory (sorted)
+    pub fn available_locales() -> Vec<String> {
+        let mut locales: BTreeSet<String> = EmbeddedLocales::iter()
+            .filter_map(|path| path.split_once('/').map(|(dir, _)| dir.to_string()))
+            .collect();
+        if let Ok(dir) = super::paths::get_locale_data_dir() {
+            locales.extend(locales_in_dir(&dir));
+        }
+        locales.into_iter().collect()
+    }
+
     /// Load all .ftl files for a locale into a FluentBundle.
     ///
     /// Tries the external override directory first. If it contains a
@@ -85,6 +103,14 @@ impl LocalizationManager {
     /// falls back to embedded resources.
     fn load_locale_bundle(
         locale_id: &str,
+    ) -> anyhow::Result<FluentBundle<Arc<FluentResource>>> {
+        Self::bundle_from_resources(locale_id, Self::load_resources(locale_id))
+    }
+
+    /// Build the bundle of a locale from its parsed .ftl resources
+    fn bundle_from_resources(
+        locale_id: &str,
+        resources: Vec<Arc<FluentResource>>,
     ) -> anyhow::Result<FluentBundle<Arc<FluentResource>>> {
         let lang_id: LanguageIdentifier = locale_id
             .parse()
@@ -92,9 +118,6 @@ impl LocalizationManager {
 
         let mut bundle = FluentBundle::new(vec![lang_id]);
 
-        let resources = Self::load_from_external(locale_id)
-            .unwrap_or_else(|| Self::load_from_embedded(locale_id));
-
         if resources.is_empty() {
             anyhow::bail!(
                 "No .ftl files found for locale '{}'",
@@ -114,6 +137,41 @@ impl LocalizationManager {
         Ok(bundle)
     }
 
+    /// All .ftl resources of a locale: the external override if present, else embedded
+    fn load_resources(locale_id: &str) -> Vec<Arc<FluentResource>> {
+        Self::load_from_external(locale_id).unwrap_or_else(|| Self::load_from_embedded(locale_id))
+    }
+
+    /// Message keys defined by an available locale (ID or language code)
+    pub fn message_ids(locale: &str) -> anyhow::Result<BTreeSet<String>> {
+        let available = Self::available_locales();
+        let locale_id = resolve_locale(locale, &available).ok_or_else(|| {
+            anyhow::anyhow!("Locale '{}' is not available (available: {})", locale, available.join(", "))
+        })?;
+
+        Ok(Self::load_resources(&locale_id)
+            .iter()
+            .flat_map(|resource| {
+                resource
+                    .entries()
+                    .filter_map(|entry| match entry {
+                        ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    /// Keys defined in en-US but absent from `locale`, sorted
+    pub fn missing_keys(locale: &str) -> anyhow::Result<Vec<String>> {
+        let target = Self::message_ids(locale)?;
+        Ok(Self::message_ids(FALLBACK_LOCALE)?
+            .into_iter()
+            .filter(|key| !target.contains(key))
+            .collect())
+    }
+
     /// Attempt to load locale files from the external override directory.
     ///
     /// Returns `Some(resources)` if the locale directory exists and
@@ -295,26 +353,77 @@ impl LocalizationManager {
                         );
                     }
 
-                    tracing::debug!(
-                        "Using fallback translation for key: {}",
-                        key
-                    );
+                    if self.first_report(key) {
+                        tracing::debug!(
+                            "Translation key '{}' missing in {}, using {}",
+                            key,
+                            self.current_locale,
+                            FALLBACK_LOCALE
+                        );
+                    }
                     return value.to_string();
                 }
             }
         }
 
         // No translation found
-        tracing::warn!("Missing translation key: {}", key);
+        if self.first_report(key) {
+            tracing::warn!("Missing translation key: {}", key);
+        }
         format!("[missing: {}]", key)
     }
 
+    /// True the first time a missing `key` is seen (UI strings are looked up every frame)
+    fn first_report(&self, key: &str) -> bool {
+        self.reported_missing.lock().unwrap().insert(key.to_string())
+    }
+
     /// Get the current locale code
     pub fn current_locale(&self) -> &str {
         &self.current_locale
     }
 }
 
+/// Map a `general.language` value to one of the `available` locale IDs
+///
+/// Accepts a full ID (`ja-JP`) or a bare language code (`ja`, which picks
+/// the first available locale of that language), case-insensitively.
+pub fn resolve_locale(code: &str, available: &[String]) -> Option<String> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    available
+        .iter()
+        .find(|id| id.eq_ignore_ascii_case(code))
+        .or_else(|| {
+            available.iter().find(|id| {
+                id.split('-').next().is_some_and(|lang| lang.eq_ignore_ascii_case(code))
+            })
+        })
+        .cloned()
+}
+
+/// Subdirectories of `dir` that are valid locale IDs and contain .ftl files
+fn locales_in_dir(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| name.parse::<LanguageIdentifier>().is_ok())
+        .filter(|name| {
+            std::fs::read_dir(dir.join(name)).is_ok_and(|files| {
+                files
+                    .flatten()
+                    .any(|f| f.path().extension().and_then(|s| s.to_str()) == Some("ftl"))
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,6 +456,58 @@ mod tests {
         assert_eq!(manager.current_locale(), "en");
     }
 
+    #[test]
+    fn test_resolve_locale() {
+        let available = vec!["en-US".to_string(), "ja-JP".to_string(), "pt-BR".to_string()];
+        assert_eq!(resolve_locale("en", &available).as_deref(), Some("en-US"));
+        assert_eq!(resolve_locale("JA", &available).as_deref(), Some("ja-JP"));
+        assert_eq!(resolve_locale("pt-br", &available).as_deref(), Some("pt-BR"));
+        assert_eq!(resolve_locale("fr", &available), None);
+        assert_eq!(resolve_locale("", &available), None);
+
+        // Every directory under locales/ is embedded and selectable
+        let embedded = LocalizationManager::available_locales();
+        assert!(embedded.contains(&"en-US".to_string()));
+        assert!(embedded.contains(&"ja-JP".to_string()));
+        assert!(LocalizationManager::new("fr").is_err());
+    }
+
+    #[test]
+    fn test_missing_key_falls_back_to_en() {
+        // A deliberately partial locale: one key translated, everything else missing
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("main.ftl"), "help-title = Hilfe\n").unwrap();
+        let resources = LocalizationManager::load_ftl_files_from_dir(temp_dir.path()).unwrap();
+        let manager = LocalizationManager {
+            bundle: LocalizationManager::bundle_from_resources("de-DE", resources).unwrap(),
+            fallback_bundle: Some(LocalizationManager::load_locale_bundle(FALLBACK_LOCALE).unwrap()),
+            current_locale: "de-DE".to_string(),
+            reported_missing: Mutex::new(HashSet::new()),
+        };
+        let en = LocalizationManager::new("en").unwrap();
+
+        assert_eq!(manager.get("help-title"), "Hilfe");
+        // Keys absent from the locale still render, in English
+        assert_ne!(en.get("app-title"), "app-title");
+        assert_eq!(manager.get("app-title"), en.get("app-title"));
+        assert!(manager.first_report("some-key"));
+        assert!(!manager.first_report("some-key"));
+
+        assert!(LocalizationManager::missing_keys("en").unwrap().is_empty());
+        assert!(LocalizationManager::message_ids("en").unwrap().contains("app-title"));
+        assert!(LocalizationManager::missing_keys("xx").is_err());
+    }
+
+    #[test]
+    fn test_locales_in_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (dir, file) in [("de-DE", "main.ftl"), ("empty-dir", "notes.txt"), ("not a locale", "main.ftl")] {
+            std::fs::create_dir(temp_dir.path().join(dir)).unwrap();
+            std::fs::write(temp_dir.path().join(dir).join(file), "").unwrap();
+        }
+        assert_eq!(locales_in_dir(temp_dir.path()), ["de-DE"]);
+    }
+
     #[test]
     fn test_embedded_locales_contain_en_us() {
         // Verify that the rust-embed asset list includes en-US files
@@ -1,4 +1,10 @@
+pub mod file_lock;
+pub mod format;
+pub mod fs;
 pub mod i18n;
+pub mod notify;
 pub mod paths;
+pub mod power;
 pub mod sanitize;
+pub mod schedule;
 pub mod url_expansion;
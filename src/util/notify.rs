@@ -0,0 +1,75 @@
+//! Completion notifications
+//!
+//! Plays `general.completion_sound` when a download completes. Sound files
+//! are decoded with rodio (`completion-sound` feature); without the feature,
+//! or for an empty path, a short beep is played instead. Everything here is
+//! best-effort: failures are logged at debug level and never reach the
+//! download.
+
+use std::path::Path;
+
+/// Plays the configured completion sound on a background thread
+///
+/// `None` disables the sound; an empty path selects the built-in beep.
+pub fn play_completion_sound(sound: Option<&Path>) {
+    let Some(sound) = sound else {
+        return;
+    };
+    let sound = (!sound.as_os_str().is_empty()).then(|| sound.to_path_buf());
+
+    let spawned = std::thread::Builder::new()
+        .name("ggg-sound".to_string())
+        .spawn(move || {
+            if let Err(e) = play(sound.as_deref()) {
+                tracing::debug!("Completion sound not played: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::debug!("Failed to start sound thread: {}", e);
+    }
+}
+
+#[cfg(feature = "completion-sound")]
+fn play(sound: Option<&Path>) -> anyhow::Result<()> {
+    use rodio::{Decoder, OutputStream, Sink, Source};
+
+    let (_stream, handle) = OutputStream::try_default()?;
+    let sink = Sink::try_new(&handle)?;
+    match sound {
+        Some(path) => {
+            let file = std::io::BufReader::new(std::fs::File::open(path)?);
+            sink.append(Decoder::new(file)?);
+        }
+        None => {
+            let beep = rodio::source::SineWave::new(880.0)
+                .take_duration(std::time::Duration::from_millis(200))
+                .amplify(0.2);
+            sink.append(beep);
+        }
+    }
+    sink.sleep_until_end();
+    Ok(())
+}
+
+/// Without an audio backend, ring the terminal bell
+///
+/// The bell goes to stderr so it never ends up in piped or `--json` output,
+/// and is skipped entirely when stdout is not a terminal.
+#[cfg(not(feature = "completion-sound"))]
+fn play(sound: Option<&Path>) -> anyhow::Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    if let Some(path) = sound {
+        tracing::debug!(
+            "Built without the completion-sound feature; using the terminal bell instead of {}",
+            path.display()
+        );
+    }
+    if !std::io::stdout().is_terminal() {
+        return Ok(());
+    }
+    let mut stderr = std::io::stderr();
+    stderr.write_all(b"\x07")?;
+    stderr.flush()?;
+    Ok(())
+}
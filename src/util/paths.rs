@@ -108,6 +108,12 @@ pub fn get_folder_queue_path(folder_id: &str) -> Result<PathBuf> {
     Ok(folder_dir.join("queue.toml"))
 }
 
+/// Get absolute path to a folder's content_index.json (hashes for `download.on_duplicate_content`)
+pub fn get_folder_content_index_path(folder_id: &str) -> Result<PathBuf> {
+    let config_dir = find_config_directory()?;
+    Ok(config_dir.join(folder_id).join("content_index.json"))
+}
+
 /// Resolve the default download directory at runtime.
 ///
 /// Resolution order (mirrors config directory logic):
@@ -163,6 +169,30 @@ pub fn get_locale_data_dir() -> Result<PathBuf> {
     Ok(data_dir.join("ggg").join("locales"))
 }
 
+/// Get absolute path to trash.json (deleted downloads awaiting restore)
+pub fn get_trash_path() -> Result<PathBuf> {
+    let config_dir = find_config_directory()?;
+    Ok(config_dir.join("trash.json"))
+}
+
+/// Get absolute path to circuit_breaker.json (circuit breaker state shared between processes)
+pub fn get_circuit_breaker_path() -> Result<PathBuf> {
+    let config_dir = find_config_directory()?;
+    Ok(config_dir.join("circuit_breaker.json"))
+}
+
+/// Get absolute path to lifetime_stats.json (cumulative download totals)
+pub fn get_lifetime_stats_path() -> Result<PathBuf> {
+    let config_dir = find_config_directory()?;
+    Ok(config_dir.join("lifetime_stats.json"))
+}
+
+/// Get absolute path to the backups directory (files replaced by `ggg import`)
+pub fn get_backups_dir() -> Result<PathBuf> {
+    let config_dir = find_config_directory()?;
+    Ok(config_dir.join("backups"))
+}
+
 /// Get absolute path to application-wide logs directory
 pub fn get_logs_dir() -> Result<PathBuf> {
     let config_dir = find_config_directory()?;
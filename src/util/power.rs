@@ -0,0 +1,214 @@
+//! Power source and network cost monitoring
+//!
+//! When `general.pause_on_battery` or `general.pause_on_metered` is enabled, a
+//! background task polls the system state, pauses active downloads and holds
+//! back queued ones while the condition is met, and resumes them once the
+//! machine is back on AC power or an unmetered connection. Platforms without a
+//! detection API are a no-op.
+
+use crate::app::state::AppState;
+use crate::download::manager::DownloadManager;
+use crate::download::task::DownloadStatus;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the power/network state is polled
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Held reason shown on downloads kept from starting by the monitor
+const HOLD_REASON: &str = "waiting for AC power or an unmetered connection";
+
+/// Snapshot of the power source and network cost
+///
+/// `None` means the value could not be determined on this platform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerStatus {
+    pub on_battery: Option<bool>,
+    pub metered: Option<bool>,
+}
+
+impl PowerStatus {
+    /// Returns true if at least one value could be detected
+    pub fn is_supported(&self) -> bool {
+        self.on_battery.is_some() || self.metered.is_some()
+    }
+
+    /// Decide whether downloads should be paused for the given settings
+    pub fn should_pause(&self, pause_on_battery: bool, pause_on_metered: bool) -> bool {
+        (pause_on_battery && self.on_battery == Some(true))
+            || (pause_on_metered && self.metered == Some(true))
+    }
+}
+
+/// Query the current power source and network cost
+#[cfg(windows)]
+pub fn query_status() -> PowerStatus {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let on_battery = {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        match unsafe { GetSystemPowerStatus(&mut status) } {
+            // ACLineStatus: 0 = offline, 1 = online, 255 = unknown
+            Ok(()) => match status.ACLineStatus {
+                0 => Some(true),
+                1 => Some(false),
+                _ => None,
+            },
+            Err(_) => None,
+        }
+    };
+
+    let metered = NetworkInformation::GetInternetConnectionProfile()
+        .and_then(|profile| profile.GetConnectionCost())
+        .and_then(|cost| cost.NetworkCostType())
+        .ok()
+        .map(|cost_type| {
+            cost_type == NetworkCostType::Fixed || cost_type == NetworkCostType::Variable
+        });
+
+    PowerStatus { on_battery, metered }
+}
+
+/// Query the current power source and network cost
+///
+/// macOS exposes no simple metered-connection API, so only the power source is detected.
+#[cfg(target_os = "macos")]
+pub fn query_status() -> PowerStatus {
+    let on_battery = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            let text = String::from_utf8_lossy(&output.stdout);
+            if text.contains("'Battery Power'") {
+                Some(true)
+            } else if text.contains("'AC Power'") {
+                Some(false)
+            } else {
+                None
+            }
+        });
+
+    PowerStatus { on_battery, metered: None }
+}
+
+/// Query the current power source and network cost (unsupported platform)
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn query_status() -> PowerStatus {
+    PowerStatus::default()
+}
+
+/// Spawn the background monitor
+///
+/// Only tasks paused or held back by the monitor are resumed; tasks the user
+/// paused stay paused.
+pub fn spawn_monitor(manager: DownloadManager, app_state: AppState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut paused_by_monitor: Vec<Uuid> = Vec::new();
+        let mut holding = false;
+        let mut warned_unsupported = false;
+
+        loop {
+            interval.tick().await;
+
+            let (pause_on_battery, pause_on_metered) = {
+                let config = app_state.config.read().await;
+                (config.general.pause_on_battery, config.general.pause_on_metered)
+            };
+
+            let should_pause = if pause_on_battery || pause_on_metered {
+                let status = tokio::task::spawn_blocking(query_status)
+                    .await
+                    .unwrap_or_default();
+
+                if !status.is_supported() && !warned_unsupported {
+                    tracing::info!(
+                        "Battery/metered detection is not available on this platform; pause_on_battery/pause_on_metered have no effect"
+                    );
+                    warned_unsupported = true;
+                }
+
+                status.should_pause(pause_on_battery, pause_on_metered)
+            } else {
+                false
+            };
+
+            if should_pause {
+                // Queued downloads stay queued instead of starting between polls
+                if !holding {
+                    manager.hold_new_downloads(HOLD_REASON);
+                    holding = true;
+                }
+
+                // Pause what was running when the condition began
+                let active: Vec<Uuid> = manager
+                    .get_all_downloads()
+                    .await
+                    .iter()
+                    .filter(|t| t.status == DownloadStatus::Downloading)
+                    .map(|t| t.id)
+                    .collect();
+
+                let mut paused = 0;
+                for id in active {
+                    if manager.pause_download(id).await.is_ok() {
+                        paused_by_monitor.push(id);
+                        paused += 1;
+                    }
+                }
+                if paused > 0 {
+                    tracing::info!("Paused {} download(s) due to battery/metered connection", paused);
+                }
+            } else if holding || !paused_by_monitor.is_empty() {
+                holding = false;
+                let mut resumed = manager
+                    .release_held_downloads(HOLD_REASON, app_state.script_sender.clone(), app_state.config.clone())
+                    .await;
+                for id in paused_by_monitor.drain(..) {
+                    let still_paused = manager
+                        .get_by_id(id)
+                        .await
+                        .is_some_and(|t| t.status == DownloadStatus::Paused);
+                    if still_paused
+                        && manager
+                            .start_download(id, app_state.script_sender.clone(), app_state.config.clone())
+                            .await
+                            .is_ok()
+                    {
+                        resumed += 1;
+                    }
+                }
+                tracing::info!("Resumed {} download(s) after power/network conditions cleared", resumed);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_pause_on_battery() {
+        let status = PowerStatus { on_battery: Some(true), metered: Some(false) };
+        assert!(status.should_pause(true, false));
+        assert!(!status.should_pause(false, true));
+        assert!(!status.should_pause(false, false));
+    }
+
+    #[test]
+    fn test_should_pause_on_metered() {
+        let status = PowerStatus { on_battery: Some(false), metered: Some(true) };
+        assert!(status.should_pause(false, true));
+        assert!(!status.should_pause(true, false));
+    }
+
+    #[test]
+    fn test_unknown_status_never_pauses() {
+        let status = PowerStatus::default();
+        assert!(!status.is_supported());
+        assert!(!status.should_pause(true, true));
+    }
+}
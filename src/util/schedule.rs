@@ -0,0 +1,372 @@
+//! Quiet-window schedule
+//!
+//! `[schedule]` windows pause downloads or cap their combined bandwidth
+//! during set hours (local time), e.g. work hours on a shared network. A
+//! background task re-evaluates the windows periodically, applies the
+//! bandwidth limit (falling back to `download.bandwidth_limit` outside
+//! windows), pauses downloads and holds back queued ones while a pause window
+//! is active and resumes them once it ends. The current state is published for the TUI status bar.
+
+use crate::app::config::{Config, ScheduleAction, ScheduleWindow};
+use crate::app::state::AppState;
+use crate::download::manager::DownloadManager;
+use crate::download::task::DownloadStatus;
+use crate::script::message::ScriptRequest;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use std::sync::{mpsc, Arc, Mutex};
+use tokio::sync::RwLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the windows are re-evaluated
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Held reason shown on downloads kept from starting by a pause window
+const HOLD_REASON: &str = "waiting for the quiet window to end";
+
+/// Effect of the schedule at a point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScheduleMode {
+    /// No window is active
+    #[default]
+    Inactive,
+    /// Downloads are paused until `until`
+    Paused { until: NaiveTime },
+    /// Bandwidth is capped until `until`
+    Limited { bytes_per_sec: u64, until: NaiveTime },
+}
+
+/// Current schedule mode, shared between the monitor and the TUI (cheap to clone)
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleState {
+    inner: Arc<Mutex<ScheduleMode>>,
+}
+
+impl ScheduleState {
+    pub fn get(&self) -> ScheduleMode {
+        *self.inner.lock().unwrap()
+    }
+
+    pub fn set(&self, mode: ScheduleMode) {
+        *self.inner.lock().unwrap() = mode;
+    }
+}
+
+/// A validated schedule window
+#[derive(Debug)]
+struct Window {
+    start: NaiveTime,
+    end: NaiveTime,
+    days: Vec<Weekday>,
+    action: ScheduleAction,
+    bytes_per_sec: u64,
+}
+
+impl Window {
+    fn parse(window: &ScheduleWindow) -> Result<Self, String> {
+        let parse_time = |value: &str| {
+            NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                .map_err(|_| format!("invalid time '{}' (expected HH:MM)", value))
+        };
+        let days = window
+            .days
+            .iter()
+            .map(|day| day.trim().parse::<Weekday>().map_err(|_| format!("invalid day '{}'", day)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if window.action == ScheduleAction::Limit && window.max_bytes_per_sec == 0 {
+            return Err("action = \"limit\" requires max_bytes_per_sec > 0".to_string());
+        }
+
+        Ok(Self {
+            start: parse_time(&window.start)?,
+            end: parse_time(&window.end)?,
+            days,
+            action: window.action,
+            bytes_per_sec: window.max_bytes_per_sec,
+        })
+    }
+
+    fn mode(&self) -> ScheduleMode {
+        match self.action {
+            ScheduleAction::Pause => ScheduleMode::Paused { until: self.end },
+            ScheduleAction::Limit => ScheduleMode::Limited { bytes_per_sec: self.bytes_per_sec, until: self.end },
+        }
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    /// True if `now` falls inside the window
+    ///
+    /// Windows whose end is not after their start run past midnight into
+    /// the next day (`start == end` covers 24 hours).
+    fn is_active(&self, now: NaiveDateTime) -> bool {
+        let time = now.time();
+        let today = now.weekday();
+        if self.start < self.end {
+            self.starts_on(today) && time >= self.start && time < self.end
+        } else {
+            (self.starts_on(today) && time >= self.start) || (self.starts_on(today.pred()) && time < self.end)
+        }
+    }
+}
+
+/// Describe invalid windows (they are ignored by `evaluate`)
+pub fn validate(windows: &[ScheduleWindow]) -> Vec<String> {
+    windows
+        .iter()
+        .enumerate()
+        .filter_map(|(i, window)| Window::parse(window).err().map(|e| format!("window {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Combined effect of all windows at `now`
+///
+/// A pause window wins over limit windows; overlapping limits use the lowest cap.
+pub fn evaluate(windows: &[ScheduleWindow], now: NaiveDateTime) -> ScheduleMode {
+    windows
+        .iter()
+        .filter_map(|window| Window::parse(window).ok())
+        .filter(|window| window.is_active(now))
+        .map(|window| window.mode())
+        .min_by_key(|mode| match mode {
+            ScheduleMode::Paused { .. } => 0,
+            ScheduleMode::Limited { bytes_per_sec, .. } => *bytes_per_sec,
+            ScheduleMode::Inactive => u64::MAX,
+        })
+        .unwrap_or_default()
+}
+
+/// Bandwidth limit to apply for `mode` given `download.bandwidth_limit` (0 = unlimited)
+pub fn effective_limit(mode: ScheduleMode, base_limit: u64) -> u64 {
+    match mode {
+        ScheduleMode::Limited { bytes_per_sec, .. } if base_limit > 0 => bytes_per_sec.min(base_limit),
+        ScheduleMode::Limited { bytes_per_sec, .. } => bytes_per_sec,
+        _ => base_limit,
+    }
+}
+
+/// Spawn the background schedule monitor
+pub fn spawn_monitor(manager: DownloadManager, app_state: AppState) -> tokio::task::JoinHandle<()> {
+    // AppState itself is not Send (its localization manager is not thread-safe)
+    let config = app_state.config.clone();
+    let schedule = app_state.schedule.clone();
+    let script_sender = app_state.script_sender.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut paused_by_schedule: Vec<Uuid> = Vec::new();
+        let mut reported_errors: Vec<String> = Vec::new();
+
+        loop {
+            interval.tick().await;
+
+            let (windows, base_limit) = {
+                let config = config.read().await;
+                (config.schedule.windows.clone(), config.download.bandwidth_limit)
+            };
+
+            let errors = validate(&windows);
+            if errors != reported_errors {
+                for error in &errors {
+                    tracing::warn!("Ignoring schedule {}", error);
+                }
+                reported_errors = errors;
+            }
+
+            let mode = evaluate(&windows, chrono::Local::now().naive_local());
+            let previous = schedule.get();
+            if mode != previous {
+                match mode {
+                    ScheduleMode::Inactive => tracing::info!("Quiet window ended"),
+                    ScheduleMode::Paused { until } => {
+                        tracing::info!("Quiet window active: pausing downloads until {}", until.format("%H:%M"))
+                    }
+                    ScheduleMode::Limited { bytes_per_sec, until } => tracing::info!(
+                        "Quiet window active: limiting bandwidth to {} bytes/sec until {}",
+                        bytes_per_sec,
+                        until.format("%H:%M")
+                    ),
+                }
+                schedule.set(mode);
+            }
+
+            // Also picks up changes to download.bandwidth_limit
+            manager.set_bandwidth_limit(effective_limit(mode, base_limit));
+
+            let paused = matches!(mode, ScheduleMode::Paused { .. });
+            apply_pause(&manager, &script_sender, &config, paused, &mut paused_by_schedule).await;
+        }
+    })
+}
+
+/// Pause running downloads and hold back queued ones while `paused`;
+/// otherwise start what the pause window stopped
+///
+/// Only tasks paused or held by the schedule are resumed; tasks the user
+/// paused stay paused.
+async fn apply_pause(
+    manager: &DownloadManager,
+    script_sender: &Option<mpsc::Sender<ScriptRequest>>,
+    config: &Arc<RwLock<Config>>,
+    paused: bool,
+    paused_by_schedule: &mut Vec<Uuid>,
+) {
+    if paused {
+        // Queued downloads stay queued instead of starting between checks
+        manager.hold_new_downloads(HOLD_REASON);
+
+        let active: Vec<Uuid> = manager
+            .get_all_downloads()
+            .await
+            .iter()
+            .filter(|t| t.status == DownloadStatus::Downloading)
+            .map(|t| t.id)
+            .collect();
+
+        let mut paused = 0;
+        for id in active {
+            if manager.pause_download(id).await.is_ok() {
+                paused_by_schedule.push(id);
+                paused += 1;
+            }
+        }
+        if paused > 0 {
+            tracing::info!("Paused {} download(s) for the quiet window", paused);
+        }
+        return;
+    }
+
+    let mut resumed = manager
+        .release_held_downloads(HOLD_REASON, script_sender.clone(), config.clone())
+        .await;
+    for id in paused_by_schedule.drain(..) {
+        let still_paused = manager
+            .get_by_id(id)
+            .await
+            .is_some_and(|t| t.status == DownloadStatus::Paused);
+        if still_paused
+            && manager
+                .start_download(id, script_sender.clone(), config.clone())
+                .await
+                .is_ok()
+        {
+            resumed += 1;
+        }
+    }
+    if resumed > 0 {
+        tracing::info!("Resumed {} download(s) after the quiet window", resumed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn window(start: &str, end: &str, action: ScheduleAction, max_bytes_per_sec: u64) -> ScheduleWindow {
+        ScheduleWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: Vec::new(),
+            action,
+            max_bytes_per_sec,
+        }
+    }
+
+    /// 2026-01-05 is a Monday
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, day)
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_pause_window_holds_queued_downloads() {
+        use crate::download::task::DownloadTask;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = tempfile::tempdir().unwrap();
+        crate::util::paths::set_config_dir_override(Some(dir.path().to_path_buf()));
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let manager = DownloadManager::new();
+        let app_state = AppState::new(Config::default(), "en");
+        let task = DownloadTask::new(format!("{}/file.bin", mock_server.uri()), dir.path().to_path_buf());
+        let task_id = task.id;
+        manager.add_download(task).await;
+        let mut paused_by_schedule = Vec::new();
+
+        // Inside the window a queued download does not start
+        apply_pause(&manager, &None, &app_state.config, true, &mut paused_by_schedule).await;
+        manager.start_download(task_id, None, app_state.config.clone()).await.unwrap();
+        let held = manager.get_by_id(task_id).await.unwrap();
+        assert_eq!(held.status, DownloadStatus::Pending);
+        assert_eq!(held.held_reason.as_deref(), Some(HOLD_REASON));
+
+        // Once it ends the download starts
+        apply_pause(&manager, &None, &app_state.config, false, &mut paused_by_schedule).await;
+        let started = manager.get_by_id(task_id).await.unwrap();
+        crate::util::paths::set_config_dir_override(None);
+        assert_ne!(started.status, DownloadStatus::Pending);
+    }
+
+    #[test]
+    fn test_same_day_window() {
+        let windows = vec![window("09:00", "18:00", ScheduleAction::Limit, 1000)];
+        let until = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        assert_eq!(evaluate(&windows, at(5, "08:59")), ScheduleMode::Inactive);
+        assert_eq!(evaluate(&windows, at(5, "09:00")), ScheduleMode::Limited { bytes_per_sec: 1000, until });
+        assert_eq!(evaluate(&windows, at(5, "18:00")), ScheduleMode::Inactive);
+    }
+
+    #[test]
+    fn test_window_crossing_midnight_respects_start_day() {
+        let mut night = window("22:00", "06:00", ScheduleAction::Pause, 0);
+        night.days = vec!["fri".to_string()];
+        let windows = vec![night];
+        let until = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+
+        // Friday 2026-01-09 night into Saturday morning
+        assert_eq!(evaluate(&windows, at(9, "23:30")), ScheduleMode::Paused { until });
+        assert_eq!(evaluate(&windows, at(10, "05:59")), ScheduleMode::Paused { until });
+        assert_eq!(evaluate(&windows, at(10, "06:00")), ScheduleMode::Inactive);
+        // Saturday night is not covered, and neither is Friday early morning
+        assert_eq!(evaluate(&windows, at(10, "23:30")), ScheduleMode::Inactive);
+        assert_eq!(evaluate(&windows, at(9, "01:00")), ScheduleMode::Inactive);
+    }
+
+    #[test]
+    fn test_pause_wins_and_invalid_windows_are_ignored() {
+        let windows = vec![
+            window("09:00", "18:00", ScheduleAction::Limit, 5000),
+            window("12:00", "13:00", ScheduleAction::Pause, 0),
+            window("10:00", "11:00", ScheduleAction::Limit, 0),
+            window("9am", "5pm", ScheduleAction::Pause, 0),
+        ];
+        assert_eq!(validate(&windows).len(), 2);
+        assert!(matches!(evaluate(&windows, at(5, "12:30")), ScheduleMode::Paused { .. }));
+        assert!(matches!(
+            evaluate(&windows, at(5, "10:30")),
+            ScheduleMode::Limited { bytes_per_sec: 5000, .. }
+        ));
+    }
+
+    #[test]
+    fn test_effective_limit_uses_lower_cap() {
+        let until = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let limited = ScheduleMode::Limited { bytes_per_sec: 1000, until };
+        assert_eq!(effective_limit(limited, 0), 1000);
+        assert_eq!(effective_limit(limited, 500), 500);
+        assert_eq!(effective_limit(ScheduleMode::Inactive, 500), 500);
+        assert_eq!(effective_limit(ScheduleMode::Paused { until }, 0), 0);
+    }
+}